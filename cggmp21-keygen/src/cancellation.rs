@@ -0,0 +1,53 @@
+//! Cancellation of ongoing protocol execution
+//!
+//! [`CancellationToken`] can be given to the protocol builder (e.g. via
+//! [`set_cancellation`](crate::GenericKeygenBuilder::set_cancellation)). Once the token is
+//! [cancelled](CancellationToken::cancel), the protocol stops when the next round begins and returns
+//! an error which can be recognized via `is_cancelled` method (e.g.
+//! [`KeygenError::is_cancelled`](crate::KeygenError::is_cancelled)).
+//!
+//! Cancellation is checked before the round sends any messages, so the round in progress is never
+//! sent partially: other parties either receive all the messages of the round, or none of them (in
+//! which case they'll time out). Note that if the protocol is waiting for messages from other parties,
+//! it notices cancellation only once it receives them and proceeds to the next round.
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Token that signals the protocol to stop
+///
+/// Token can be cloned and sent to another thread. Cancelling any of the clones cancels all of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Constructs a token which is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the protocol
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release)
+    }
+
+    /// Checks whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Returns [`Cancelled`] error if the token was cancelled
+    pub fn ensure_not_cancelled(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Error indicating that protocol was cancelled via [`CancellationToken`]
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("protocol was cancelled")]
+pub struct Cancelled;
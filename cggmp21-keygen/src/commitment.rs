@@ -0,0 +1,77 @@
+//! Commitment schemes
+//!
+//! In the first round of keygen, each party commits to the data it reveals in the second round.
+//! By default, it's a hash commitment ([`HashCommitment`]), but another scheme can be
+//! [plugged in](crate::GenericKeygenBuilder::set_commitment_scheme) by implementing [`Commitment`]
+//! trait.
+//!
+//! Committed data always contains fresh randomness sampled by the protocol, so the scheme doesn't
+//! have to be hiding on its own for arbitrary inputs.
+//!
+//! All parties must use the same commitment scheme.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+use rand_core::CryptoRngCore;
+
+/// Commitment scheme
+pub trait Commitment: Send + Sync {
+    /// Commits to `data`
+    ///
+    /// Returns a commitment, which is sent in the first round, and an opening, which is sent
+    /// along with the data in the second round. `rng` is the
+    /// [secret rng](crate::GenericKeygenBuilder::set_secret_rng), if it was set.
+    fn commit(&self, rng: &mut dyn CryptoRngCore, data: &[u8]) -> (Vec<u8>, Vec<u8>);
+    /// Verifies that `commitment` opens to `data`
+    fn verify(&self, commitment: &[u8], data: &[u8], opening: &[u8]) -> bool;
+}
+
+/// Hash commitment
+///
+/// Commitment is a hash of the data, opening is empty. It's a default commitment scheme used
+/// by keygen.
+pub struct HashCommitment<D>(core::marker::PhantomData<fn() -> D>);
+
+impl<D> HashCommitment<D> {
+    /// Constructs a hash commitment scheme
+    pub const fn new() -> Self {
+        Self(core::marker::PhantomData)
+    }
+}
+
+impl<D> Default for HashCommitment<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> core::fmt::Debug for HashCommitment<D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("HashCommitment")
+    }
+}
+
+impl<D: Digest> Commitment for HashCommitment<D> {
+    fn commit(&self, _rng: &mut dyn CryptoRngCore, data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        (D::digest(data).to_vec(), Vec::new())
+    }
+
+    fn verify(&self, commitment: &[u8], data: &[u8], opening: &[u8]) -> bool {
+        opening.is_empty() && D::digest(data).as_slice() == commitment
+    }
+}
+
+/// Encodes `value` into bytes, so that hashing the bytes gives the same result as [`udigest::hash`]
+pub(crate) fn encode(value: &impl udigest::Digestable) -> Vec<u8> {
+    struct Buffer(Vec<u8>);
+    impl udigest::encoding::Buffer for Buffer {
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes)
+        }
+    }
+
+    let mut buffer = Buffer(Vec::new());
+    value.unambiguously_encode(udigest::encoding::EncodeValue::new(&mut buffer));
+    buffer.0
+}
@@ -0,0 +1,167 @@
+//! Party contribution to non-threshold keygen
+//!
+//! Functions in this module compute and verify messages of [non-threshold keygen](crate::keygen)
+//! exactly as the protocol does, but without running it. That allows a party of the keygen to be
+//! something else than a single process holding its secret share $x_i$. Notably, the party may
+//! itself be a group of signers that hold $x_i$ shared among them: the group never reconstructs
+//! $x_i$, but still produces messages that other parties can't tell apart from the messages of a
+//! regular party.
+//!
+//! ## Nested group
+//! Let the group consist of members that hold additive shares $x_{i,k}$ such that
+//! $x_i = \sum_k x_{i,k}$. The group takes part in keygen as follows:
+//!
+//! 1. Each member samples its share $x_{i,k}$ and an ephemeral schnorr secret
+//!    via [`schnorr_pok::prover_commits_ephemeral_secret`], and computes $X_{i,k} = x_{i,k} \cdot G$
+//!    and the schnorr commitment $A_{i,k}$. Members then share them within the group in two steps:
+//!    1. Each member broadcasts within the group a commitment to $(X_{i,k}, A_{i,k})$, e.g. a hash
+//!       of them along with fresh randomness.
+//!    2. Once commitments of all other members are received, each member reveals $X_{i,k}$ and
+//!       $A_{i,k}$, and others check them against the commitment.
+//!
+//!    Skipping the commitment is insecure: the member that reveals last could choose
+//!    $X_{i,k} = X^* - \sum_{k' \ne k} X_{i,k'}$ for $X^* = x^* \cdot G$ of its choice and take
+//!    control of $x_i$, and adapt $A_{i,k}$ in the same way.
+//! 2. The group forms its round 2 message: $X_i = \sum_k X_{i,k}$, `sch_commit` is
+//!    [combined](combine_schnorr_commits) $A_{i,k}$, `rid` and `decommit` are sampled at random.
+//!    Round 1 message is obtained by [committing](commit) to it.
+//! 3. Once commitments of all parties are received, the group sends the round 2 message.
+//! 4. Once decommitments are received, the group [verifies](verify_decommitment) them and derives
+//!    [the challenge](schnorr_challenge) from [combined](combine_rid) `rid`. Each member proves
+//!    knowledge of its share via [`schnorr_pok::prove`], and the partial proofs are
+//!    [combined](combine_schnorr_proofs) into round 3 message.
+//!
+//! Other parties output key shares as usual, public share of the group is $X_i$. Note that
+//! the group has to agree on messages it sends, how it's done is out of scope of this module.
+//!
+//! Only non-threshold keygen is supported.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+use generic_ec::{Curve, Point, Scalar};
+use generic_ec_zkp::schnorr_pok;
+use rand_core::CryptoRngCore;
+
+use crate::{
+    commitment::{self, Commitment, HashCommitment},
+    msg::non_threshold::{MsgRound1, MsgRound2, MsgRound3},
+    non_threshold::unambiguous,
+    ExecutionId, SecurityLevel,
+};
+
+/// Commits to round 2 message of party `i`, outputs round 1 message
+///
+/// `prove_knowledge` must be `true` unless [proof of knowledge](crate::GenericKeygenBuilder::set_prove_knowledge)
+/// is disabled at keygen. `commitment_scheme` must be the one that is
+/// [used at keygen](crate::GenericKeygenBuilder::set_commitment_scheme), or `None` for
+/// default [`HashCommitment`].
+///
+/// Sets [`opening`](MsgRound2::opening) of `decommitment`, so it must be called after all other
+/// fields of the decommitment are set. Returned message is not signed, so it can't be used when
+/// [equivocation detection](crate::GenericKeygenBuilder::detect_equivocation) is enabled.
+pub fn commit<E: Curve, L: SecurityLevel, D: Digest>(
+    rng: &mut dyn CryptoRngCore,
+    sid: ExecutionId,
+    i: u16,
+    prove_knowledge: bool,
+    commitment_scheme: Option<&dyn Commitment>,
+    decommitment: &mut MsgRound2<E, L>,
+) -> MsgRound1<D> {
+    let hash_commitment = HashCommitment::<D>::new();
+    let commitment_scheme = commitment_scheme.unwrap_or(&hash_commitment);
+
+    let (commitment, opening) =
+        commitment_scheme.commit(rng, &committed_data(sid, i, prove_knowledge, decommitment));
+    decommitment.opening = opening;
+    MsgRound1 {
+        commitment,
+        sid_hash: sid_hash::<D>(sid),
+        signature: None,
+    }
+}
+
+/// Verifies that round 2 message of party `j` matches its commitment
+///
+/// `prove_knowledge` and `commitment_scheme` must be the same as given to [`commit`].
+pub fn verify_decommitment<E: Curve, L: SecurityLevel, D: Digest>(
+    sid: ExecutionId,
+    j: u16,
+    prove_knowledge: bool,
+    commitment_scheme: Option<&dyn Commitment>,
+    commitment: &MsgRound1<D>,
+    decommitment: &MsgRound2<E, L>,
+) -> bool {
+    let hash_commitment = HashCommitment::<D>::new();
+    let commitment_scheme = commitment_scheme.unwrap_or(&hash_commitment);
+
+    commitment_scheme.verify(
+        &commitment.commitment,
+        &committed_data(sid, j, prove_knowledge, decommitment),
+        &decommitment.opening,
+    )
+}
+
+/// Combines [`rid`](MsgRound2::rid)s from round 2 messages of all parties (including own message)
+pub fn combine_rid<'a, E: Curve, L: SecurityLevel>(
+    decommitments: impl IntoIterator<Item = &'a MsgRound2<E, L>>,
+) -> L::Rid {
+    decommitments
+        .into_iter()
+        .map(|d| &d.rid)
+        .fold(L::Rid::default(), crate::utils::xor_array)
+}
+
+/// Derives a challenge that party `prover` has to answer in round 3
+///
+/// `rid` is [combined](combine_rid) `rid` of all parties.
+pub fn schnorr_challenge<E: Curve, D: Digest>(
+    sid: ExecutionId,
+    prover: u16,
+    rid: &[u8],
+) -> schnorr_pok::Challenge<E> {
+    let challenge = Scalar::from_hash::<D>(&unambiguous::SchnorrPok { sid, prover, rid });
+    schnorr_pok::Challenge { nonce: challenge }
+}
+
+/// Combines schnorr commitments of group members
+///
+/// Commitment to the sum of ephemeral secrets is a sum of commitments.
+pub fn combine_schnorr_commits<E: Curve>(
+    commits: impl IntoIterator<Item = schnorr_pok::Commit<E>>,
+) -> schnorr_pok::Commit<E> {
+    schnorr_pok::Commit(commits.into_iter().map(|c| c.0).sum::<Point<E>>())
+}
+
+/// Combines partial schnorr proofs of group members into round 3 message
+///
+/// Each member proves knowledge of its share $x_{i,k}$ using its ephemeral secret and the
+/// [challenge](schnorr_challenge) of the group. Sum of the proofs is a valid proof of knowledge
+/// of $x_i = \sum_k x_{i,k}$ w.r.t. [combined commitment](combine_schnorr_commits).
+pub fn combine_schnorr_proofs<E: Curve>(
+    partial_proofs: impl IntoIterator<Item = schnorr_pok::Proof<E>>,
+) -> MsgRound3<E> {
+    MsgRound3 {
+        sch_proof: schnorr_pok::Proof(partial_proofs.into_iter().map(|p| p.0).sum()),
+    }
+}
+
+/// Data that party `i` commits to in round 1
+fn committed_data<E: Curve, L: SecurityLevel>(
+    sid: ExecutionId,
+    i: u16,
+    prove_knowledge: bool,
+    decommitment: &MsgRound2<E, L>,
+) -> Vec<u8> {
+    commitment::encode(&unambiguous::HashCom {
+        sid,
+        party_index: i,
+        prove_knowledge,
+        decommitment,
+    })
+}
+
+/// Digest of execution id sent in round 1
+fn sid_hash<D: Digest>(sid: ExecutionId) -> digest::Output<D> {
+    udigest::hash::<D>(&unambiguous::SidHash { sid })
+}
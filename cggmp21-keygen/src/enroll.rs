@@ -0,0 +1,575 @@
+//! Adds a new party to an existing threshold key
+//!
+//! Holders of $t$-out-of-$n$ key shares can run this protocol together with a newcomer to obtain
+//! $t$-out-of-$(n+1)$ key shares of the same key, without running a DKG from scratch and without
+//! changing the public key. Each holder $j$ converts its share into additive share
+//! $\lambda_j x_j$ (where $\lambda_j$ is lagrange coefficient over all $n$ holders) and deals it via
+//! Feldman VSS: it samples a polynomial $f_j$ of degree $t-1$ such that $f_j(0) = \lambda_j x_j$,
+//! commits to it, and sends $f_j(k+1)$ to each party $k$, including the newcomer. Commitment to
+//! the polynomial is checked against $\lambda_j X_j$ known from the old key share, so a holder
+//! can't change the key, and a holder whose contribution is inconsistent is blamed.
+//!
+//! All $n$ holders of the old key must take part in the protocol. The newcomer receives index $n$,
+//! and only needs to know [public key info](crate::key_share::KeyInfo) of the key (see
+//! [`EnrollBuilder::new_party`]). Every party, including the old holders, obtains a fresh key
+//! share with [`VssSetup`] reflecting the new amount of parties.
+//!
+//! Note that old key shares remain valid after the protocol is completed. Old key shares should be
+//! erased once the new key shares are obtained.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::polynomial::{lagrange_coefficient_at_zero, Polynomial};
+use rand_core::{CryptoRng, RngCore};
+use round_based::{
+    rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
+    MsgId, Outgoing, PartyIndex, ProtocolMessage, SinkExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::progress::Tracer;
+use crate::{
+    errors::IoError,
+    key_share::{
+        CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, InvalidCoreShare, KeyInfo, Validate,
+        VssSetup,
+    },
+    utils, ExecutionId,
+};
+
+macro_rules! prefixed {
+    ($name:tt) => {
+        concat!("dfns.cggmp21.enroll_party.", $name)
+    };
+}
+
+/// Message of enrollment protocol
+#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum Msg<E: Curve, D: Digest> {
+    /// Round 1 message broadcasted to everyone
+    Round1Broad(MsgRound1Broad<E>),
+    /// Round 1 message unicasted to each party
+    Round1Uni(MsgRound1Uni<E>),
+    /// Reliability check message (optional additional round)
+    ReliabilityCheck(MsgReliabilityCheck<D>),
+}
+
+/// Message from round 1 broadcasted to everyone
+#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[serde(bound = "")]
+#[udigest(bound = "")]
+#[udigest(tag = prefixed!("round1_broad"))]
+pub struct MsgRound1Broad<E: Curve> {
+    /// Commitment to the polynomial $F_j$
+    ///
+    /// `None` if the sender is the newcomer
+    pub F: Option<Polynomial<Point<E>>>,
+}
+
+/// Message from round 1 unicasted to each party
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MsgRound1Uni<E: Curve> {
+    /// $\sigma_{j,k} = f_j(k+1)$
+    ///
+    /// `None` if the sender is the newcomer
+    pub sigma: Option<Scalar<E>>,
+}
+
+/// Message parties exchange to ensure reliability of broadcast channel
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+
+mod unambiguous {
+    use crate::ExecutionId;
+    use generic_ec::Curve;
+
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("echo_round"))]
+    #[udigest(bound = "")]
+    pub struct Echo<'a, E: Curve> {
+        pub sid: ExecutionId<'a>,
+        pub party_index: u16,
+        pub msg: &'a super::MsgRound1Broad<E>,
+    }
+}
+
+/// Role of the local party in the enrollment protocol
+enum Role<'a, E: Curve> {
+    /// Party holds a share of the key
+    Holder(&'a CoreKeyShare<E>),
+    /// Party is the newcomer and only knows the public key info
+    NewParty(&'a KeyInfo<E>),
+}
+
+/// Enrollment protocol builder
+pub struct EnrollBuilder<'a, E: Curve, D: Digest = crate::default_choice::Digest> {
+    i: u16,
+    role: Role<'a, E>,
+    new_party_index: u16,
+    new_n: u16,
+    execution_id: ExecutionId<'a>,
+    reliable_broadcast_enforced: bool,
+    tracer: Option<&'a mut dyn Tracer>,
+    _digest: core::marker::PhantomData<D>,
+}
+
+impl<'a, E: Curve> EnrollBuilder<'a, E> {
+    /// Constructs a builder for a party that holds a share of the key
+    ///
+    /// `new_party_index` is index of the newcomer, it must be equal to amount of key holders $n$,
+    /// and `new_n` must be equal to $n+1$.
+    pub fn new(
+        eid: ExecutionId<'a>,
+        key_share: &'a CoreKeyShare<E>,
+        new_party_index: u16,
+        new_n: u16,
+    ) -> Self {
+        Self {
+            i: key_share.i,
+            role: Role::Holder(key_share),
+            new_party_index,
+            new_n,
+            execution_id: eid,
+            reliable_broadcast_enforced: true,
+            tracer: None,
+            _digest: core::marker::PhantomData,
+        }
+    }
+
+    /// Constructs a builder for the newcomer that doesn't hold a share of the key
+    ///
+    /// Newcomer needs to know public key info of the key. Its index `new_party_index` must be
+    /// equal to amount of key holders $n$, and `new_n` must be equal to $n+1$.
+    pub fn new_party(
+        eid: ExecutionId<'a>,
+        key_info: &'a KeyInfo<E>,
+        new_party_index: u16,
+        new_n: u16,
+    ) -> Self {
+        Self {
+            i: new_party_index,
+            role: Role::NewParty(key_info),
+            new_party_index,
+            new_n,
+            execution_id: eid,
+            reliable_broadcast_enforced: true,
+            tracer: None,
+            _digest: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, E: Curve, D: Digest + Clone + 'static> EnrollBuilder<'a, E, D> {
+    /// Specifies another hash function to use
+    pub fn set_digest<D2: Digest + Clone + 'static>(self) -> EnrollBuilder<'a, E, D2> {
+        EnrollBuilder {
+            i: self.i,
+            role: self.role,
+            new_party_index: self.new_party_index,
+            new_n: self.new_n,
+            execution_id: self.execution_id,
+            reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            tracer: self.tracer,
+            _digest: core::marker::PhantomData,
+        }
+    }
+
+    /// Sets a tracer that tracks progress of protocol execution
+    pub fn set_progress_tracer(mut self, tracer: &'a mut dyn Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
+    pub fn enforce_reliable_broadcast(self, enforce: bool) -> Self {
+        Self {
+            reliable_broadcast_enforced: enforce,
+            ..self
+        }
+    }
+
+    /// Starts enrollment protocol
+    pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<CoreKeyShare<E>, EnrollError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        run_enroll(
+            self.tracer,
+            self.i,
+            self.role,
+            self.new_party_index,
+            self.new_n,
+            self.reliable_broadcast_enforced,
+            self.execution_id,
+            rng,
+            party,
+        )
+        .await
+    }
+
+    /// Returns a state machine that can be used to carry out the enrollment protocol
+    ///
+    /// See [`round_based::state_machine`] for details on how that can be done.
+    #[cfg(feature = "state-machine")]
+    pub fn into_state_machine<R>(
+        self,
+        rng: &'a mut R,
+    ) -> impl round_based::state_machine::StateMachine<
+        Output = Result<CoreKeyShare<E>, EnrollError>,
+        Msg = Msg<E, D>,
+    > + 'a
+    where
+        R: RngCore + CryptoRng,
+    {
+        round_based::state_machine::wrap_protocol(|party| self.start(rng, party))
+    }
+}
+
+async fn run_enroll<E, R, M, D>(
+    mut tracer: Option<&mut dyn Tracer>,
+    i: u16,
+    role: Role<'_, E>,
+    new_party_index: u16,
+    n: u16,
+    reliable_broadcast_enforced: bool,
+    sid: ExecutionId<'_>,
+    rng: &mut R,
+    party: M,
+) -> Result<CoreKeyShare<E>, EnrollError>
+where
+    E: Curve,
+    D: Digest + Clone + 'static,
+    R: RngCore + CryptoRng,
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Validate arguments");
+    let (key_info, my_share): (&DirtyKeyInfo<E>, _) = match &role {
+        Role::Holder(key_share) => (&key_share.key_info, Some(key_share)),
+        Role::NewParty(key_info) => (key_info, None),
+    };
+    let (t, old_I) = match &key_info.vss_setup {
+        Some(VssSetup {
+            min_signers,
+            I,
+            weights: None,
+        }) => (*min_signers, I),
+        Some(_) => return Err(InvalidArgs::Weighted.into()),
+        None => return Err(InvalidArgs::NotThreshold.into()),
+    };
+    let old_n = u16::try_from(key_info.public_shares.len()).map_err(|_| Bug::NOverflow)?;
+    if new_party_index != old_n || old_n.checked_add(1) != Some(n) {
+        return Err(InvalidArgs::InvalidNewParty.into());
+    }
+    if my_share.is_none() && i != new_party_index {
+        return Err(InvalidArgs::IndexMismatch.into());
+    }
+
+    tracer.stage("Compute lagrange coefficients");
+    let lambda = (0..old_n)
+        .map(|j| lagrange_coefficient_at_zero(usize::from(j), old_I))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Bug::LagrangeCoef)?;
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
+    let round1_broad = rounds.add_round(RoundInput::<MsgRound1Broad<E>>::broadcast(i, n));
+    let round1_uni = rounds.add_round(RoundInput::<MsgRound1Uni<E>>::p2p(i, n));
+    let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
+    let mut rounds = rounds.listen(incomings);
+
+    // Round 1
+    tracer.round_begins();
+
+    tracer.stage("Sample polynomial");
+    let f = my_share.map(|share| {
+        let additive_share = (lambda[usize::from(i)] * &share.x).into_secret();
+        Polynomial::sample_with_const_term(
+            rng,
+            usize::from(t) - 1,
+            SecretScalar::clone(&additive_share),
+        )
+    });
+    let sigmas = f.as_ref().map(|f| {
+        (0..n)
+            .map(|k| f.value::<_, Scalar<E>>(&Scalar::from(k + 1)))
+            .collect::<Vec<_>>()
+    });
+    let my_broad = MsgRound1Broad {
+        F: f.as_ref().map(|f| f * &Point::generator()),
+    };
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1Broad(my_broad.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    for j in utils::iter_peers(i, n) {
+        let sigma = sigmas.as_ref().map(|s| s[usize::from(j)]);
+        outgoings
+            .send(Outgoing::p2p(j, Msg::Round1Uni(MsgRound1Uni { sigma })))
+            .await
+            .map_err(IoError::send_message)?;
+    }
+    tracer.msg_sent();
+
+    // Round 2
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let commitments = rounds
+        .complete(round1_broad)
+        .await
+        .map_err(IoError::receive_message)?;
+    let sigmas_msg = rounds
+        .complete(round1_uni)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    // Optional reliability check
+    if reliable_broadcast_enforced {
+        tracer.stage("Hash received msgs (reliability check)");
+        let h_i =
+            udigest::hash_iter::<D>(commitments.iter_including_me(&my_broad).zip(0u16..).map(
+                |(msg, party_index)| unambiguous::Echo {
+                    sid,
+                    party_index,
+                    msg,
+                },
+            ));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
+                MsgReliabilityCheck(h_i.clone()),
+            )))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let hashes = rounds
+            .complete(round1_sync)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Assert other parties hashed messages (reliability check)");
+        let parties_have_different_hashes = hashes
+            .into_iter_indexed()
+            .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !parties_have_different_hashes.is_empty() {
+            return Err(EnrollAborted::Round1NotReliable(parties_have_different_hashes).into());
+        }
+    }
+
+    tracer.stage("Validate commitments");
+    // Key holders must deal their additive shares, the newcomer must not send anything
+    let blame = utils::collect_blame(&commitments, &sigmas_msg, |j, com, sigma| {
+        if j < old_n {
+            match (&com.F, sigma.sigma) {
+                (Some(F), Some(_)) => {
+                    F.degree() + 1 != usize::from(t)
+                        || F.coefs()[0]
+                            != lambda[usize::from(j)] * key_info.public_shares[usize::from(j)]
+                }
+                _ => true,
+            }
+        } else {
+            com.F.is_some() || sigma.sigma.is_some()
+        }
+    });
+    if !blame.is_empty() {
+        return Err(EnrollAborted::InvalidCommitment(blame).into());
+    }
+
+    tracer.stage("Validate Feldman VSS");
+    let blame = utils::collect_blame(&commitments, &sigmas_msg, |_j, com, sigma| {
+        match (&com.F, sigma.sigma) {
+            (Some(F), Some(sigma)) => {
+                F.value::<_, Point<E>>(&Scalar::from(i + 1)) != Point::generator() * sigma
+            }
+            _ => false,
+        }
+    });
+    if !blame.is_empty() {
+        return Err(EnrollAborted::FeldmanVerificationFailed(blame).into());
+    }
+
+    tracer.stage("Compute public shares");
+    let polynomial_sum = commitments
+        .iter_including_me(&my_broad)
+        .filter_map(|msg| msg.F.as_ref())
+        .sum::<Polynomial<_>>();
+    debug_assert_eq!(polynomial_sum.coefs()[0], *key_info.shared_public_key);
+    let mut ys = Vec::with_capacity(usize::from(n));
+    let mut zero_shares = Vec::new();
+    for k in 0..n {
+        let y_k: Point<E> = polynomial_sum.value(&Scalar::from(k + 1));
+        match NonZero::from_point(y_k) {
+            Some(y_k) => ys.push(y_k),
+            None => zero_shares.push(k),
+        }
+    }
+    if !zero_shares.is_empty() {
+        return Err(EnrollAborted::ZeroPublicShares {
+            shares: zero_shares,
+        }
+        .into());
+    }
+
+    tracer.stage("Compute secret share");
+    let mut sigma = sigmas_msg
+        .iter()
+        .filter_map(|msg| msg.sigma)
+        .sum::<Scalar<E>>();
+    if let Some(sigmas) = &sigmas {
+        sigma += sigmas[usize::from(i)];
+    }
+    let sigma = NonZero::from_secret_scalar(SecretScalar::new(&mut sigma)).ok_or(Bug::ZeroShare)?;
+    debug_assert_eq!(Point::generator() * &sigma, ys[usize::from(i)]);
+
+    tracer.stage("Derive resulting key share");
+    let key_shares_indexes = (1..=n)
+        .map(|k| NonZero::from_scalar(Scalar::from(k)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Bug::NonZeroScalar)?;
+    let key_share = DirtyCoreKeyShare {
+        i,
+        key_info: DirtyKeyInfo {
+            public_shares: ys,
+            vss_setup: Some(VssSetup {
+                min_signers: t,
+                I: key_shares_indexes,
+                weights: None,
+            }),
+            // Party set has changed, commitment to the old one doesn't apply anymore
+            party_set_commitment: None,
+            ..key_info.clone()
+        },
+        x: sigma,
+    }
+    .validate()
+    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?;
+
+    tracer.protocol_ends();
+    Ok(key_share)
+}
+
+/// Constructs a builder of enrollment protocol for a party that holds a share of the key
+///
+/// Alias to [`EnrollBuilder::new`]. See [module level documentation](crate::enroll) for details.
+pub fn enroll_party<'a, E: Curve>(
+    eid: ExecutionId<'a>,
+    existing_share: &'a CoreKeyShare<E>,
+    new_party_index: u16,
+    new_n: u16,
+) -> EnrollBuilder<'a, E> {
+    EnrollBuilder::new(eid, existing_share, new_party_index, new_n)
+}
+
+/// Enrollment protocol error
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("enrollment protocol is failed to complete")]
+pub struct EnrollError(#[cfg_attr(feature = "std", source)] Reason);
+
+crate::errors::impl_from! {
+    impl From for EnrollError {
+        err: InvalidArgs => EnrollError(Reason::InvalidArgs(err)),
+        err: EnrollAborted => EnrollError(Reason::Aborted(err)),
+        err: IoError => EnrollError(Reason::IoError(err)),
+        err: Bug => EnrollError(Reason::Bug(err)),
+    }
+}
+
+impl EnrollError {
+    /// Returns indexes of parties whose commitment doesn't match their public share, if the
+    /// protocol was aborted due to it
+    pub fn invalid_commitment(&self) -> Option<Vec<PartyIndex>> {
+        match &self.0 {
+            Reason::Aborted(EnrollAborted::InvalidCommitment(blame)) => {
+                Some(blame.iter().map(|b| b.faulty_party).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Reason {
+    /// Invalid arguments
+    #[displaydoc("invalid arguments")]
+    InvalidArgs(#[cfg_attr(feature = "std", source)] InvalidArgs),
+    /// Protocol was maliciously aborted by another party
+    #[displaydoc("protocol was aborted by malicious party")]
+    Aborted(#[cfg_attr(feature = "std", source)] EnrollAborted),
+    #[displaydoc("i/o error")]
+    IoError(#[cfg_attr(feature = "std", source)] IoError),
+    /// Bug occurred
+    #[displaydoc("bug occurred")]
+    Bug(Bug),
+}
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum InvalidArgs {
+    #[displaydoc("key is not threshold, use `reshare_to_threshold` instead")]
+    NotThreshold,
+    #[displaydoc("enrollment into weighted key is not supported")]
+    Weighted,
+    #[displaydoc("newcomer must have index `n_old` and `new_n` must be `n_old + 1`")]
+    InvalidNewParty,
+    #[displaydoc("index of the newcomer doesn't match `new_party_index`")]
+    IndexMismatch,
+}
+
+/// Error indicating that protocol was aborted by malicious party
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum EnrollAborted {
+    #[displaydoc("party commitment doesn't match its public share: {0:?}")]
+    InvalidCommitment(Vec<utils::AbortBlame>),
+    #[displaydoc("party secret share is not consistent: {0:?}")]
+    FeldmanVerificationFailed(Vec<utils::AbortBlame>),
+    #[displaydoc("round1 wasn't reliable")]
+    Round1NotReliable(Vec<(PartyIndex, MsgId)>),
+    /// Public shares of some parties are zero
+    ///
+    /// Polynomial dealt by an honest key holder is random, so that can only happen if other key
+    /// holders coordinated to cancel it out. Culprits can't be identified, `shares` lists indexes
+    /// of the affected parties.
+    #[displaydoc("resulting public shares are zero: {shares:?}")]
+    ZeroPublicShares { shares: Vec<u16> },
+}
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Bug {
+    #[displaydoc("resulting key share is not valid")]
+    InvalidKeyShare(#[cfg_attr(feature = "std", source)] InvalidCoreShare),
+    #[displaydoc("`n` overflows `u16`")]
+    NOverflow,
+    #[displaydoc("couldn't derive lagrange coefficient")]
+    LagrangeCoef,
+    #[displaydoc("unexpected zero value")]
+    NonZeroScalar,
+    #[displaydoc("secret share is zero although its public share is not")]
+    ZeroShare,
+}
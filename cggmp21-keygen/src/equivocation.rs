@@ -0,0 +1,188 @@
+//! Equivocation detection
+//!
+//! When broadcast channel is not reliable, a malicious party may send different commitments to
+//! different parties in the first round. [Reliability check](crate::GenericKeygenBuilder::enforce_reliable_broadcast)
+//! detects that parties received different messages, but can't point at the party who sent them.
+//!
+//! When [enabled](crate::GenericKeygenBuilder::detect_equivocation), each party signs its first round
+//! message using [`BroadcastSigner`] provided by transport layer, and parties forward all signed
+//! messages they received to each other in an additional round (replacing the reliability check round).
+//! If any party signed two different messages, the protocol aborts, and the two signed messages are
+//! returned as [evidence](KeygenError::equivocation) that can be presented to a third party, who
+//! can check it via [`verify_evidence`].
+//!
+//! All parties must enable equivocation detection.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use digest::Digest;
+use round_based::PartyIndex;
+
+#[cfg(doc)]
+use crate::KeygenError;
+use crate::{ExecutionId, KeygenAborted};
+
+/// Verifies signatures of broadcast messages
+///
+/// Only needs public keys of the parties, so it can be implemented by a third party who checks the
+/// [evidence](verify_evidence) of equivocation.
+pub trait BroadcastVerifier: Send + Sync {
+    /// Verifies that `signature` over `data` was produced by `j`-th party
+    fn verify(&self, j: PartyIndex, data: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Signs and verifies broadcast messages on behalf of the parties
+///
+/// Typically backed by long-term identity keys the parties already use at transport layer.
+pub trait BroadcastSigner: BroadcastVerifier {
+    /// Signs `data` with the key of the local party
+    fn sign(&self, data: &[u8]) -> Vec<u8>;
+}
+
+/// First round message signed by the party
+///
+/// One half of the equivocation evidence. Carries everything needed to recompute the
+/// [signed data](Self::signed_data), so it can be verified by a third party.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedCommitment {
+    /// Execution ID of the keygen
+    pub eid: Vec<u8>,
+    /// Index of the party who signed the message
+    pub party_index: PartyIndex,
+    /// Commitment $V_j$ sent by the party
+    pub commitment: Vec<u8>,
+    /// Digest of execution ID sent by the party
+    pub sid_hash: Vec<u8>,
+    /// Signature over [`signed_data`](Self::signed_data)
+    pub signature: Vec<u8>,
+}
+
+impl SignedCommitment {
+    pub(crate) fn new(
+        sid: ExecutionId,
+        party_index: PartyIndex,
+        commitment: &[u8],
+        sid_hash: &[u8],
+        signature: Option<&[u8]>,
+    ) -> Self {
+        Self {
+            eid: sid.as_bytes().to_vec(),
+            party_index,
+            commitment: commitment.to_vec(),
+            sid_hash: sid_hash.to_vec(),
+            signature: signature.unwrap_or_default().to_vec(),
+        }
+    }
+
+    /// Returns data signed by the party
+    ///
+    /// `D` must be the digest keygen was instantiated with.
+    pub fn signed_data<D: Digest>(&self) -> digest::Output<D> {
+        udigest::hash::<D>(&SignedRound1 {
+            sid: ExecutionId::new(&self.eid),
+            party_index: self.party_index,
+            commitment: &self.commitment,
+            sid_hash: &self.sid_hash,
+        })
+    }
+
+    /// Indicates whether both messages have the same content (signatures are not compared)
+    fn same_message(&self, other: &Self) -> bool {
+        self.eid == other.eid
+            && self.party_index == other.party_index
+            && self.commitment == other.commitment
+            && self.sid_hash == other.sid_hash
+    }
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.keygen.signed_round1")]
+struct SignedRound1<'a> {
+    sid: ExecutionId<'a>,
+    party_index: u16,
+    #[udigest(as_bytes)]
+    commitment: &'a [u8],
+    #[udigest(as_bytes)]
+    sid_hash: &'a [u8],
+}
+
+/// Verifies evidence that party `j` equivocated
+///
+/// Returns `true` if both messages were sent by `j`-th party in the same keygen, differ, and are
+/// signed by it. `D` must be the digest keygen was instantiated with. Evidence can be obtained
+/// via [`KeygenError::equivocation`].
+pub fn verify_evidence<D: Digest>(
+    verifier: &dyn BroadcastVerifier,
+    j: PartyIndex,
+    evidence: &(SignedCommitment, SignedCommitment),
+) -> bool {
+    let (a, b) = evidence;
+    a.party_index == j
+        && b.party_index == j
+        && a.eid == b.eid
+        && !a.same_message(b)
+        && verifier.verify(j, &a.signed_data::<D>(), &a.signature)
+        && verifier.verify(j, &b.signed_data::<D>(), &b.signature)
+}
+
+/// Checks that every party except `i` signed the message it sent to us
+pub(crate) fn verify_received<D: Digest>(
+    signer: &dyn BroadcastSigner,
+    i: PartyIndex,
+    received: &[SignedCommitment],
+) -> Result<(), KeygenAborted> {
+    let parties = (0..)
+        .zip(received)
+        .filter(|(j, msg)| *j != i && !signer.verify(*j, &msg.signed_data::<D>(), &msg.signature))
+        .map(|(j, _)| j)
+        .collect::<Vec<_>>();
+    if parties.is_empty() {
+        Ok(())
+    } else {
+        Err(KeygenAborted::InvalidBroadcastSignature { parties })
+    }
+}
+
+/// Compares messages forwarded by other parties with `received` ones
+///
+/// `received[j]` is a message that we received from `j`-th party (or sent, if `j` is index of the
+/// local party). `forwarded` contains messages forwarded by each other party, indexed the same way.
+pub(crate) fn check_forwarded<D: Digest>(
+    signer: &dyn BroadcastSigner,
+    received: &[SignedCommitment],
+    forwarded: impl IntoIterator<Item = (PartyIndex, Vec<SignedCommitment>)>,
+) -> Result<(), KeygenAborted> {
+    let mut invalid_size = Vec::new();
+    let mut invalid_signature = Vec::new();
+    for (k, forwarded_k) in forwarded {
+        if forwarded_k.len() != received.len() {
+            invalid_size.push(k);
+            continue;
+        }
+        for (j, (ours, theirs)) in (0..).zip(received.iter().zip(forwarded_k)) {
+            if ours.same_message(&theirs) {
+                continue;
+            }
+            if !signer.verify(j, &theirs.signed_data::<D>(), &theirs.signature) {
+                // Forwarder altered the message
+                invalid_signature.push(k);
+                break;
+            }
+            return Err(KeygenAborted::Equivocation {
+                party: j,
+                evidence: Box::new((ours.clone(), theirs)),
+            });
+        }
+    }
+    if !invalid_size.is_empty() {
+        return Err(KeygenAborted::InvalidDataSize {
+            parties: invalid_size,
+        });
+    }
+    if !invalid_signature.is_empty() {
+        return Err(KeygenAborted::InvalidBroadcastSignature {
+            parties: invalid_signature,
+        });
+    }
+    Ok(())
+}
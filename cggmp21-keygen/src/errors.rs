@@ -10,10 +10,9 @@ mod std_error {
     #[cfg(feature = "std")]
     pub use std::error::Error as StdError;
 
+    // `round-based` requires I/O errors to implement `core::error::Error` even without `std`
     #[cfg(not(feature = "std"))]
-    pub trait StdError: core::fmt::Display + core::fmt::Debug {}
-    #[cfg(not(feature = "std"))]
-    impl<E: core::fmt::Display + core::fmt::Debug> StdError for E {}
+    pub use core::error::Error as StdError;
 }
 pub use std_error::StdError;
 
@@ -19,4 +19,22 @@ impl<'id> ExecutionId<'id> {
     pub fn as_bytes(&self) -> &'id [u8] {
         self.id
     }
+
+    /// Returns a digest of execution ID
+    ///
+    /// Digest is a domain-separated hash of execution ID bytes computed with hash function `D`.
+    /// It's a fixed-size identifier of the protocol execution which is convenient for routing messages
+    /// and logging. The protocol itself always uses full execution ID.
+    pub fn digest<D>(&self) -> [u8; 32]
+    where
+        D: digest::Digest<OutputSize = digest::typenum::U32>,
+    {
+        udigest::hash::<D>(&Tagged { eid: *self }).into()
+    }
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.execution_id")]
+struct Tagged<'id> {
+    eid: ExecutionId<'id>,
 }
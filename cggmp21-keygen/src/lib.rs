@@ -13,8 +13,18 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod cancellation;
+pub mod commitment;
+pub mod contribution;
+pub mod enroll;
+pub mod equivocation;
 pub mod progress;
+pub mod reliable_broadcast;
+pub mod reshare;
+pub mod round_observer;
 pub mod security_level;
+pub mod timeout;
+pub mod version;
 
 /// Non-threshold DKG specific types
 mod non_threshold;
@@ -29,22 +39,43 @@ use alloc::vec::Vec;
 
 use digest::Digest;
 use generic_ec::Curve;
-use rand_core::{CryptoRng, RngCore};
-use round_based::{Mpc, MsgId, PartyIndex};
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
+use round_based::{Mpc, PartyIndex};
 
 #[doc(inline)]
 pub use key_share;
 
+use crate::cancellation::{CancellationToken, Cancelled};
+use crate::commitment::Commitment;
+use crate::equivocation::{BroadcastSigner, SignedCommitment};
 use crate::progress::Tracer;
+use crate::round_observer::RoundObserver;
+use crate::timeout::{BoxedTimer, RoundTimeout, TimedOut, Timer, TimerNotSet};
 use crate::{
     errors::IoError,
     key_share::{CoreKeyShare, InvalidCoreShare},
     security_level::SecurityLevel,
 };
 
+pub use self::enroll::enroll_party;
 pub use self::execution_id::ExecutionId;
 #[doc(no_inline)]
 pub use self::msg::{non_threshold::Msg as NonThresholdMsg, threshold::Msg as ThresholdMsg};
+pub use self::non_threshold::verify_schnorr_contribution;
+pub use self::reliable_broadcast::ReliabilityCheckFailure;
+pub use self::reshare::reshare_to_threshold;
+
+/// Maximum amount of parties supported by the protocols
+///
+/// In threshold keygen, every party receives a polynomial commitment of `t` points from each of
+/// `n` parties, so memory and computation grow as $O(n \cdot t) \le O(n^2)$. Interpolation and
+/// VSS verification have the same complexity. With `n = 1000`, it's already about a million
+/// points held in memory by every party; going beyond that is not practical, so larger `n`
+/// is most likely a misconfiguration. Protocols refuse to start with `n` exceeding this limit.
+///
+/// In [weighted](GenericKeygenBuilder::set_weights) keygen, each unit of weight is a sub-share
+/// that takes part in the math as a separate party, so the limit applies to total weight as well.
+pub const MAX_PARTIES: u16 = 1000;
 
 /// Defines default choice for digest and security level used across the crate
 mod default_choice {
@@ -56,13 +87,18 @@ mod default_choice {
 pub mod msg {
     /// Messages types related to non threshold DKG protocol
     pub mod non_threshold {
-        pub use crate::non_threshold::{Msg, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3};
+        pub use crate::non_threshold::{
+            Msg, MsgEquivocationCheck, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3,
+        };
+        pub use crate::version::MsgVersion;
     }
     /// Messages types related to threshold DKG protocol
     pub mod threshold {
         pub use crate::threshold::{
-            Msg, MsgReliabilityCheck, MsgRound1, MsgRound2Broad, MsgRound2Uni, MsgRound3,
+            Msg, MsgEquivocationCheck, MsgReliabilityCheck, MsgRound1, MsgRound2Broad,
+            MsgRound2Uni, MsgRound3,
         };
+        pub use crate::version::MsgVersion;
     }
 }
 
@@ -91,11 +127,25 @@ pub struct GenericKeygenBuilder<'a, E: Curve, M, L: SecurityLevel, D: Digest> {
     i: u16,
     n: u16,
     reliable_broadcast_enforced: bool,
+    prove_knowledge: bool,
+    require_production_level: bool,
+    negotiate_version: bool,
     optional_t: M,
     execution_id: ExecutionId<'a>,
     tracer: Option<&'a mut dyn Tracer>,
+    round_observer: Option<&'a mut dyn RoundObserver>,
+    cancellation: Option<CancellationToken>,
+    party_identity: Option<&'a [u8]>,
+    peer_identities: Option<&'a [&'a [u8]]>,
+    broadcast_signer: Option<&'a dyn BroadcastSigner>,
+    commitment_scheme: Option<&'a dyn Commitment>,
+    timer: Option<BoxedTimer<'a>>,
+    round_timeout: Option<core::time::Duration>,
+    secret_rng: Option<&'a mut (dyn CryptoRngCore + Send)>,
     #[cfg(feature = "hd-wallet")]
     hd_enabled: bool,
+    #[cfg(feature = "testing")]
+    fixed_rid: Option<&'a [u8]>,
     _params: core::marker::PhantomData<(E, L, D)>,
 }
 
@@ -103,6 +153,11 @@ pub struct GenericKeygenBuilder<'a, E: Curve, M, L: SecurityLevel, D: Digest> {
 pub struct NonThreshold;
 /// Indicates threshold DKG
 pub struct WithThreshold(u16);
+/// Indicates weighted threshold DKG
+pub struct WithWeights {
+    t: u16,
+    weights: Vec<u16>,
+}
 
 impl<'a, E, L, D> GenericKeygenBuilder<'a, E, NonThreshold, L, D>
 where
@@ -119,10 +174,24 @@ where
             n,
             optional_t: NonThreshold,
             reliable_broadcast_enforced: true,
+            prove_knowledge: true,
+            require_production_level: false,
+            negotiate_version: false,
             execution_id: eid,
             tracer: None,
+            round_observer: None,
+            cancellation: None,
+            party_identity: None,
+            peer_identities: None,
+            broadcast_signer: None,
+            commitment_scheme: None,
+            timer: None,
+            round_timeout: None,
+            secret_rng: None,
             #[cfg(feature = "hd-wallet")]
             hd_enabled: true,
+            #[cfg(feature = "testing")]
+            fixed_rid: None,
             _params: core::marker::PhantomData,
         }
     }
@@ -141,10 +210,68 @@ where
             n: self.n,
             optional_t: WithThreshold(t),
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            prove_knowledge: self.prove_knowledge,
+            require_production_level: self.require_production_level,
+            negotiate_version: self.negotiate_version,
+            execution_id: self.execution_id,
+            tracer: self.tracer,
+            round_observer: self.round_observer,
+            cancellation: self.cancellation,
+            party_identity: self.party_identity,
+            peer_identities: self.peer_identities,
+            broadcast_signer: self.broadcast_signer,
+            commitment_scheme: self.commitment_scheme,
+            timer: self.timer,
+            round_timeout: self.round_timeout,
+            secret_rng: self.secret_rng,
+            #[cfg(feature = "hd-wallet")]
+            hd_enabled: self.hd_enabled,
+            #[cfg(feature = "testing")]
+            fixed_rid: self.fixed_rid,
+            _params: core::marker::PhantomData,
+        }
+    }
+    /// Specifies weighted threshold scheme
+    ///
+    /// Takes weight threshold `t` and `weights`, where `weights[j]` is weight of j-th party. Any set of
+    /// parties whose weights sum up to at least `t` will be able to sign. Protocol returns an error
+    /// on start if `t` exceeds the total weight (see [`KeygenError::is_invalid_weights`]).
+    ///
+    /// Each party receives as many key shares as its weight, so the protocol outputs a list of key shares.
+    /// Note that key share size and amount of computation and communication grow linearly with the total
+    /// weight $\sum_j w_j$, which therefore must not exceed [`MAX_PARTIES`] (see
+    /// [`KeygenError::is_too_many_parties`]). See [`key_share::weighted`] module for more details.
+    pub fn set_weights(
+        self,
+        t: u16,
+        weights: &[u16],
+    ) -> GenericKeygenBuilder<'a, E, WithWeights, L, D> {
+        GenericKeygenBuilder {
+            i: self.i,
+            n: self.n,
+            optional_t: WithWeights {
+                t,
+                weights: weights.to_vec(),
+            },
+            reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            prove_knowledge: self.prove_knowledge,
+            require_production_level: self.require_production_level,
+            negotiate_version: self.negotiate_version,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            round_observer: self.round_observer,
+            cancellation: self.cancellation,
+            party_identity: self.party_identity,
+            peer_identities: self.peer_identities,
+            broadcast_signer: self.broadcast_signer,
+            commitment_scheme: self.commitment_scheme,
+            timer: self.timer,
+            round_timeout: self.round_timeout,
+            secret_rng: self.secret_rng,
             #[cfg(feature = "hd-wallet")]
             hd_enabled: self.hd_enabled,
+            #[cfg(feature = "testing")]
+            fixed_rid: self.fixed_rid,
             _params: core::marker::PhantomData,
         }
     }
@@ -158,10 +285,24 @@ where
             n: self.n,
             optional_t: self.optional_t,
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            prove_knowledge: self.prove_knowledge,
+            require_production_level: self.require_production_level,
+            negotiate_version: self.negotiate_version,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            round_observer: self.round_observer,
+            cancellation: self.cancellation,
+            party_identity: self.party_identity,
+            peer_identities: self.peer_identities,
+            broadcast_signer: self.broadcast_signer,
+            commitment_scheme: self.commitment_scheme,
+            timer: self.timer,
+            round_timeout: self.round_timeout,
+            secret_rng: self.secret_rng,
             #[cfg(feature = "hd-wallet")]
             hd_enabled: self.hd_enabled,
+            #[cfg(feature = "testing")]
+            fixed_rid: self.fixed_rid,
             _params: core::marker::PhantomData,
         }
     }
@@ -176,10 +317,24 @@ where
             n: self.n,
             optional_t: self.optional_t,
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            prove_knowledge: self.prove_knowledge,
+            require_production_level: self.require_production_level,
+            negotiate_version: self.negotiate_version,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            round_observer: self.round_observer,
+            cancellation: self.cancellation,
+            party_identity: self.party_identity,
+            peer_identities: self.peer_identities,
+            broadcast_signer: self.broadcast_signer,
+            commitment_scheme: self.commitment_scheme,
+            timer: self.timer,
+            round_timeout: self.round_timeout,
+            secret_rng: self.secret_rng,
             #[cfg(feature = "hd-wallet")]
             hd_enabled: self.hd_enabled,
+            #[cfg(feature = "testing")]
+            fixed_rid: self.fixed_rid,
             _params: core::marker::PhantomData,
         }
     }
@@ -190,6 +345,115 @@ where
         self
     }
 
+    /// Sets an observer that is notified about every received message
+    ///
+    /// See [`round_observer`] module for details.
+    pub fn set_round_observer(mut self, observer: &'a mut dyn RoundObserver) -> Self {
+        self.round_observer = Some(observer);
+        self
+    }
+
+    /// Sets a token that can be used to cancel the protocol
+    ///
+    /// Cancellation is checked when each round begins. See [`cancellation`] module for details.
+    pub fn set_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sets a timer used to enforce [round timeout](Self::set_round_timeout)
+    ///
+    /// See [`timeout`] module for details.
+    pub fn set_timer<T>(mut self, timer: T) -> Self
+    where
+        T: Timer + 'a,
+        T::Sleep: 'a,
+    {
+        self.timer = Some(BoxedTimer::new(timer));
+        self
+    }
+
+    /// Limits time the protocol waits for messages of each round
+    ///
+    /// If messages of a round are not received within `timeout`, the protocol aborts with an error
+    /// (see [`KeygenError::is_timed_out`]). Requires a [timer](Self::set_timer) to be set, otherwise
+    /// the protocol returns an error on start.
+    pub fn set_round_timeout(mut self, timeout: core::time::Duration) -> Self {
+        self.round_timeout = Some(timeout);
+        self
+    }
+
+    /// Binds party to its network identity
+    ///
+    /// `identity` is an identity of the local party at transport layer, e.g. fingerprint of its TLS
+    /// certificate. It's committed in the first round along with the rest of the party's data, and
+    /// revealed to other parties in the second round, so it can't be changed after seeing data of other
+    /// parties. Other parties may check it against their expectations via
+    /// [`set_peer_identities`](Self::set_peer_identities).
+    pub fn set_party_identity(mut self, identity: &'a [u8]) -> Self {
+        self.party_identity = Some(identity);
+        self
+    }
+
+    /// Specifies expected network identities of the parties
+    ///
+    /// `identities[j]` is an identity that j-th party must have set via
+    /// [`set_party_identity`](Self::set_party_identity). Identity of the local party is ignored.
+    /// If any party committed to a different identity (or didn't commit to any), protocol is
+    /// aborted and the party is blamed. This prevents, for instance, a malicious message relay from
+    /// impersonating the party.
+    pub fn set_peer_identities(mut self, identities: &'a [&'a [u8]]) -> Self {
+        self.peer_identities = Some(identities);
+        self
+    }
+
+    /// Enables detection of parties sending conflicting messages
+    ///
+    /// Each party signs its first round message with `signer`, and parties forward the signed
+    /// messages to each other in an additional round. If a party signed two different messages,
+    /// the protocol aborts and the signed messages are returned as evidence (see
+    /// [`KeygenError::equivocation`]). The round replaces the [reliability check](Self::enforce_reliable_broadcast),
+    /// as it detects everything the reliability check does.
+    ///
+    /// All parties must enable it. See [`equivocation`] module for details.
+    pub fn detect_equivocation(mut self, signer: &'a dyn BroadcastSigner) -> Self {
+        self.broadcast_signer = Some(signer);
+        self
+    }
+
+    /// Sets a commitment scheme used in the first round
+    ///
+    /// By default, parties commit to the data revealed in the second round using
+    /// [hash commitment](commitment::HashCommitment). All parties must use the same scheme.
+    /// See [`commitment`] module for details.
+    pub fn set_commitment_scheme(mut self, scheme: &'a dyn Commitment) -> Self {
+        self.commitment_scheme = Some(scheme);
+        self
+    }
+
+    /// Sets a source of randomness for long-term secrets
+    ///
+    /// By default, all randomness is drawn from the `rng` passed to `start` (or `into_state_machine`).
+    /// When secret rng is set, it becomes the only source of key material:
+    /// * In non-threshold keygen: secret share $x_i$ and chain code (if [HD wallets](Self::hd_wallet)
+    ///   are enabled) are sampled from `secret_rng`
+    /// * In threshold and weighted keygen: all coefficients of the polynomial $f_i$ (which determines
+    ///   the secret shares dealt by the party) and chain code are sampled from `secret_rng`
+    /// * Ephemeral secret of schnorr proof of knowledge is sampled from `secret_rng`: it's never
+    ///   revealed, and anyone who learns it can extract the secret the proof is about
+    /// * Randomness of the [commitment scheme](Self::set_commitment_scheme) is drawn from
+    ///   `secret_rng`
+    ///
+    /// Everything else is sampled from `rng`: `rid` contribution and decommitment nonce. These values
+    /// are revealed to other parties during the protocol and do not influence the resulting key.
+    ///
+    /// Useful, for instance, when key material must originate from certified hardware RNG (e.g. HSM),
+    /// whereas ephemeral values can be sampled from faster software CSPRNG.
+    pub fn set_secret_rng(mut self, secret_rng: &'a mut (dyn CryptoRngCore + Send)) -> Self {
+        self.secret_rng = Some(secret_rng);
+        self
+    }
+
     #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
     pub fn enforce_reliable_broadcast(self, enforce: bool) -> Self {
         Self {
@@ -198,12 +462,90 @@ where
         }
     }
 
+    /// Specifies whether parties prove knowledge of their secret shares
+    ///
+    /// By default, each party proves knowledge of the secret share it generated via schnorr proof
+    /// which takes an extra round of communication. Setting it to `false` omits that round: parties
+    /// don't send `MsgRound3` (see [`msg`](crate::msg)), and schnorr proofs are not verified.
+    ///
+    /// <div class="warning">
+    ///
+    /// **Disabling proof of knowledge reduces security of the protocol.** Without it, a malicious
+    /// party may choose its contribution to the public key depending on contributions of other parties
+    /// (rogue-key attack), and the security proof of the protocol no longer applies. Only consider
+    /// disabling it when all the parties are within one trust domain.
+    ///
+    /// </div>
+    ///
+    /// All parties must use the same setting. The setting is included into the commitment sent in
+    /// the first round, so parties that disagree on it will be blamed for invalid decommitment.
+    pub fn set_prove_knowledge(self, prove_knowledge: bool) -> Self {
+        Self {
+            prove_knowledge,
+            ..self
+        }
+    }
+
+    /// Refuses to run the protocol if security level is not suitable for production
+    ///
+    /// When set, protocol returns an error before sending any message if
+    /// [`SecurityLevel::IS_PRODUCTION_SAFE`] is `false`. It prevents accidentally generating a
+    /// real key with a security level intended for tests only.
+    pub fn require_production_level(self) -> Self {
+        Self {
+            require_production_level: true,
+            ..self
+        }
+    }
+
+    /// Makes parties negotiate protocol version before the protocol begins
+    ///
+    /// Adds a round in which each party broadcasts its [`ProtocolVersion`](version::ProtocolVersion).
+    /// If any party runs a different version, the protocol aborts with an error (see
+    /// [`KeygenError::version_mismatch`]). All parties must enable negotiation. See [`version`]
+    /// module for details.
+    pub fn negotiate_version(self) -> Self {
+        Self {
+            negotiate_version: true,
+            ..self
+        }
+    }
+
+    /// Checks that security level is production-safe if it's [required](Self::require_production_level)
+    fn check_production_level(&self) -> Result<(), KeygenError> {
+        if self.require_production_level && !L::IS_PRODUCTION_SAFE {
+            return Err(Reason::NotProductionSafe.into());
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "hd-wallet")]
     /// Specifies whether HD derivation is enabled for a key
     pub fn hd_wallet(mut self, v: bool) -> Self {
         self.hd_enabled = v;
         self
     }
+
+    /// Makes combined `rid` equal to `rid`
+    ///
+    /// <div class="warning">
+    ///
+    /// **Never use it in production.** Intended for differential testing and test vectors only.
+    /// `rid` is a source of randomness for schnorr challenges, fixing it breaks security of the
+    /// protocol.
+    ///
+    /// </div>
+    ///
+    /// Party 0 contributes `rid` and other parties contribute zeroes, so XOR of all contributions
+    /// equals to `rid`. All parties must set the same value. Length of `rid` must match the
+    /// security level, otherwise protocol returns an error.
+    ///
+    /// Requires `testing` feature.
+    #[cfg(feature = "testing")]
+    pub fn set_fixed_rid_for_testing(mut self, rid: &'a [u8]) -> Self {
+        self.fixed_rid = Some(rid);
+        self
+    }
 }
 
 impl<'a, E, L, D> GenericKeygenBuilder<'a, E, NonThreshold, L, D>
@@ -218,16 +560,30 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = non_threshold::Msg<E, L, D>>,
     {
+        self.check_production_level()?;
+        let round_timeout = RoundTimeout::new(self.timer, self.round_timeout)?;
         non_threshold::run_keygen(
             self.tracer,
+            self.cancellation.as_ref(),
             self.i,
             self.n,
             self.reliable_broadcast_enforced,
+            self.prove_knowledge,
+            self.negotiate_version,
+            self.party_identity,
+            self.peer_identities,
+            self.broadcast_signer,
+            self.commitment_scheme,
+            self.round_observer,
+            &round_timeout,
             self.execution_id,
             rng,
+            self.secret_rng,
             party,
             #[cfg(feature = "hd-wallet")]
             self.hd_enabled,
+            #[cfg(feature = "testing")]
+            self.fixed_rid,
         )
         .await
     }
@@ -262,19 +618,35 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = threshold::Msg<E, L, D>>,
     {
-        threshold::run_threshold_keygen(
+        self.check_production_level()?;
+        let round_timeout = RoundTimeout::new(self.timer, self.round_timeout)?;
+        let mut key_shares = threshold::run_threshold_keygen(
             self.tracer,
+            self.cancellation.as_ref(),
             self.i,
             self.optional_t.0,
             self.n,
+            None,
             self.reliable_broadcast_enforced,
+            self.prove_knowledge,
+            self.negotiate_version,
+            self.party_identity,
+            self.peer_identities,
+            self.broadcast_signer,
+            self.commitment_scheme,
+            self.round_observer,
+            &round_timeout,
             self.execution_id,
             rng,
+            self.secret_rng,
             party,
             #[cfg(feature = "hd-wallet")]
             self.hd_enabled,
+            #[cfg(feature = "testing")]
+            self.fixed_rid,
         )
-        .await
+        .await?;
+        Ok(key_shares.pop().ok_or(Bug::NoKeyShare)?)
     }
 
     /// Returns a state machine that can be used to carry out the key generation protocol
@@ -295,6 +667,84 @@ where
     }
 }
 
+impl<'a, E, L, D> GenericKeygenBuilder<'a, E, WithWeights, L, D>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone + 'static,
+{
+    /// Starts weighted threshold key generation
+    ///
+    /// Outputs key shares of the local party, the amount of key shares is equal to weight of the party.
+    pub async fn start<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<Vec<CoreKeyShare<E>>, KeygenError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = threshold::Msg<E, L, D>>,
+    {
+        self.check_production_level()?;
+        let WithWeights { t, weights } = self.optional_t;
+        let total_weight = weights.iter().map(|w| u32::from(*w)).sum::<u32>();
+        if weights.len() != usize::from(self.n)
+            || weights.contains(&0)
+            || total_weight > u32::from(u16::MAX)
+            || u32::from(t) > total_weight
+        {
+            return Err(Reason::InvalidWeights.into());
+        }
+        if total_weight > u32::from(MAX_PARTIES) {
+            return Err(Reason::TotalWeightTooLarge { total_weight }.into());
+        }
+        let round_timeout = RoundTimeout::new(self.timer, self.round_timeout)?;
+        threshold::run_threshold_keygen(
+            self.tracer,
+            self.cancellation.as_ref(),
+            self.i,
+            t,
+            self.n,
+            Some(&weights),
+            self.reliable_broadcast_enforced,
+            self.prove_knowledge,
+            self.negotiate_version,
+            self.party_identity,
+            self.peer_identities,
+            self.broadcast_signer,
+            self.commitment_scheme,
+            self.round_observer,
+            &round_timeout,
+            self.execution_id,
+            rng,
+            self.secret_rng,
+            party,
+            #[cfg(feature = "hd-wallet")]
+            self.hd_enabled,
+            #[cfg(feature = "testing")]
+            self.fixed_rid,
+        )
+        .await
+    }
+
+    /// Returns a state machine that can be used to carry out the key generation protocol
+    ///
+    /// See [`round_based::state_machine`] for details on how that can be done.
+    #[cfg(feature = "state-machine")]
+    pub fn into_state_machine<R>(
+        self,
+        rng: &'a mut R,
+    ) -> impl round_based::state_machine::StateMachine<
+        Output = Result<Vec<CoreKeyShare<E>>, KeygenError>,
+        Msg = threshold::Msg<E, L, D>,
+    > + 'a
+    where
+        R: RngCore + CryptoRng,
+    {
+        round_based::state_machine::wrap_protocol(|party| self.start(rng, party))
+    }
+}
+
 /// Keygen protocol error
 #[derive(Debug, displaydoc::Display)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
@@ -306,6 +756,152 @@ crate::errors::impl_from! {
         err: KeygenAborted => KeygenError(Reason::Aborted(err)),
         err: IoError => KeygenError(Reason::IoError(err)),
         err: Bug => KeygenError(Reason::Bug(err)),
+        err: Cancelled => KeygenError(Reason::Cancelled(err)),
+        err: TimedOut => KeygenError(Reason::TimedOut(err)),
+        err: TimerNotSet => KeygenError(Reason::TimerNotSet(err)),
+    }
+}
+
+impl KeygenError {
+    /// Indicates whether the protocol was [cancelled](cancellation)
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.0, Reason::Cancelled(_))
+    }
+
+    /// Indicates whether the protocol was aborted due to [round timeout](GenericKeygenBuilder::set_round_timeout)
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self.0, Reason::TimedOut(_))
+    }
+
+    /// Indicates whether the protocol refused to run as [round timeout](GenericKeygenBuilder::set_round_timeout)
+    /// was set without a [timer](GenericKeygenBuilder::set_timer)
+    pub fn is_timer_not_set(&self) -> bool {
+        matches!(self.0, Reason::TimerNotSet(_))
+    }
+
+    /// Indicates whether the protocol refused to run with a security level that's not
+    /// [production-safe](GenericKeygenBuilder::require_production_level)
+    pub fn is_not_production_safe(&self) -> bool {
+        matches!(self.0, Reason::NotProductionSafe)
+    }
+
+    /// Indicates whether the protocol refused to run as amount of parties (or total
+    /// [weight](GenericKeygenBuilder::set_weights) in weighted keygen) exceeds [`MAX_PARTIES`]
+    pub fn is_too_many_parties(&self) -> bool {
+        matches!(
+            self.0,
+            Reason::TooManyParties { .. } | Reason::TotalWeightTooLarge { .. }
+        )
+    }
+
+    /// Indicates whether the protocol refused to run with [weights](GenericKeygenBuilder::set_weights)
+    /// that are not valid
+    pub fn is_invalid_weights(&self) -> bool {
+        matches!(self.0, Reason::InvalidWeights)
+    }
+
+    /// Indicates whether the protocol refused to run as [fixed rid](GenericKeygenBuilder::set_fixed_rid_for_testing)
+    /// doesn't match the security level
+    ///
+    /// Requires `testing` feature.
+    #[cfg(feature = "testing")]
+    pub fn is_invalid_fixed_rid(&self) -> bool {
+        matches!(self.0, Reason::InvalidFixedRid)
+    }
+
+    /// Returns indexes of parties that run a different [protocol version](version::ProtocolVersion),
+    /// if the protocol was aborted due to it
+    ///
+    /// Only happens when [version negotiation](GenericKeygenBuilder::negotiate_version) is enabled.
+    pub fn version_mismatch(&self) -> Option<&[u16]> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::VersionMismatch { parties }) => Some(parties),
+            _ => None,
+        }
+    }
+
+    /// Returns indexes of parties that use a different [execution ID](ExecutionId), if the protocol
+    /// was aborted due to it
+    pub fn execution_id_mismatch(&self) -> Option<&[PartyIndex]> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::ExecutionIdMismatch { parties }) => Some(parties),
+            _ => None,
+        }
+    }
+
+    /// Returns indexes of parties that committed to an identity different from the one set via
+    /// [`set_peer_identities`](GenericKeygenBuilder::set_peer_identities), if the protocol was
+    /// aborted due to it
+    pub fn identity_mismatch(&self) -> Option<&[PartyIndex]> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::IdentityMismatch { parties }) => Some(parties),
+            _ => None,
+        }
+    }
+
+    /// Returns details of failed reliability check, if the protocol was aborted due to it
+    pub fn reliability_check_failure(&self) -> Option<&ReliabilityCheckFailure> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::Round1NotReliable(failure)) => Some(failure),
+            _ => None,
+        }
+    }
+
+    /// Returns index of the party who signed two different first round messages, and the messages,
+    /// if the protocol was aborted due to it
+    ///
+    /// Only happens when [equivocation detection](GenericKeygenBuilder::detect_equivocation) is
+    /// enabled.
+    pub fn equivocation(&self) -> Option<(PartyIndex, &(SignedCommitment, SignedCommitment))> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::Equivocation { party, evidence }) => {
+                Some((*party, &**evidence))
+            }
+            _ => None,
+        }
+    }
+
+    /// Indicates whether the protocol was aborted because contributions of the parties sum up to
+    /// zero public key
+    ///
+    /// Contribution of an honest party is random, so that can only happen if other parties
+    /// coordinated to cancel it out. Culprits can't be identified.
+    pub fn is_zero_public_key(&self) -> bool {
+        matches!(self.0, Reason::Aborted(KeygenAborted::ZeroPublicKey))
+    }
+
+    /// Returns indexes of (sub-)shares whose public shares are zero, if the protocol was aborted
+    /// due to it
+    ///
+    /// Similarly to [`is_zero_public_key`](Self::is_zero_public_key), it indicates a coordinated
+    /// attack, and culprits can't be identified.
+    pub fn zero_public_shares(&self) -> Option<&[u16]> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::ZeroPublicShares { shares }) => Some(shares),
+            _ => None,
+        }
+    }
+
+    /// Returns indexes of parties whose decommitment doesn't match their commitment, if the
+    /// protocol was aborted due to it
+    pub fn invalid_decommitment(&self) -> Option<Vec<PartyIndex>> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::InvalidDecommitment(blame)) => {
+                Some(blame.iter().map(|b| b.faulty_party).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns indexes of parties who provided invalid schnorr proof, if the protocol was aborted
+    /// due to it
+    pub fn invalid_schnorr_proof(&self) -> Option<Vec<PartyIndex>> {
+        match &self.0 {
+            Reason::Aborted(KeygenAborted::InvalidSchnorrProof(blame)) => {
+                Some(blame.iter().map(|b| b.faulty_party).collect())
+            }
+            _ => None,
+        }
     }
 }
 
@@ -317,11 +913,60 @@ enum Reason {
     Aborted(#[cfg_attr(feature = "std", source)] KeygenAborted),
     #[displaydoc("i/o error")]
     IoError(#[cfg_attr(feature = "std", source)] IoError),
+    /// Weights are not valid
+    #[displaydoc(
+        "weights must be non-zero, specified for each party, and sum up to at least threshold and at most 2^16-1"
+    )]
+    InvalidWeights,
+    /// Security level is not production-safe, but it was required to be
+    #[displaydoc("security level is not suitable for production")]
+    NotProductionSafe,
+    /// Amount of parties exceeds [`MAX_PARTIES`]
+    #[displaydoc("amount of parties {n} exceeds the limit {MAX_PARTIES}")]
+    TooManyParties { n: u16 },
+    /// Total weight exceeds [`MAX_PARTIES`]
+    #[displaydoc("total weight {total_weight} exceeds the limit {MAX_PARTIES}")]
+    TotalWeightTooLarge { total_weight: u32 },
+    /// Length of fixed rid doesn't match the security level
+    #[cfg(feature = "testing")]
+    #[displaydoc("fixed rid has invalid length")]
+    InvalidFixedRid,
+    /// Protocol was cancelled
+    #[displaydoc("protocol was cancelled")]
+    Cancelled(#[cfg_attr(feature = "std", source)] Cancelled),
+    /// Messages of a round were not received in time
+    #[displaydoc("round timed out")]
+    TimedOut(#[cfg_attr(feature = "std", source)] TimedOut),
+    /// Round timeout was set without a timer
+    #[displaydoc("timer is not set")]
+    TimerNotSet(#[cfg_attr(feature = "std", source)] TimerNotSet),
     /// Bug occurred
     #[displaydoc("bug occurred")]
     Bug(Bug),
 }
 
+impl<SendErr, RecvErr> From<reliable_broadcast::EchoRoundError<SendErr, RecvErr>> for KeygenError
+where
+    SendErr: errors::StdError + Send + Sync + 'static,
+    RecvErr: errors::StdError + Send + Sync + 'static,
+{
+    fn from(err: reliable_broadcast::EchoRoundError<SendErr, RecvErr>) -> Self {
+        use reliable_broadcast::EchoRoundError;
+        match err {
+            EchoRoundError::Send(err) => IoError::send_message(err).into(),
+            EchoRoundError::Receive(err) => IoError::receive_message(err).into(),
+            EchoRoundError::Cancelled(err) => err.into(),
+            EchoRoundError::TimedOut(err) => err.into(),
+        }
+    }
+}
+
+impl From<Reason> for KeygenError {
+    fn from(err: Reason) -> Self {
+        KeygenError(err)
+    }
+}
+
 impl From<KeygenAborted> for Reason {
     fn from(err: KeygenAborted) -> Self {
         Reason::Aborted(err)
@@ -343,10 +988,35 @@ enum KeygenAborted {
     #[displaydoc("party data size is not suitable for threshold parameters: {parties:?}")]
     InvalidDataSize { parties: Vec<u16> },
     #[displaydoc("round1 wasn't reliable")]
-    Round1NotReliable(Vec<(PartyIndex, MsgId)>),
+    Round1NotReliable(ReliabilityCheckFailure),
+    #[displaydoc("parties run different protocol version: {parties:?}")]
+    VersionMismatch { parties: Vec<u16> },
+    #[displaydoc("parties use different execution id: {parties:?}")]
+    ExecutionIdMismatch { parties: Vec<u16> },
+    #[displaydoc("parties committed to unexpected identity: {parties:?}")]
+    IdentityMismatch { parties: Vec<u16> },
+    #[displaydoc("party signed two different messages: {party}")]
+    Equivocation {
+        party: u16,
+        evidence: alloc::boxed::Box<(SignedCommitment, SignedCommitment)>,
+    },
+    #[displaydoc("parties sent message with invalid signature: {parties:?}")]
+    InvalidBroadcastSignature { parties: Vec<u16> },
     #[cfg(feature = "hd-wallet")]
     #[displaydoc("party did not generate chain code: {0:?}")]
     MissingChainCode(Vec<utils::AbortBlame>),
+    /// Contributions of the parties sum up to zero public key
+    ///
+    /// Contribution of an honest party is random, so that can only happen if other parties
+    /// coordinated to cancel it out. Culprits can't be identified.
+    #[displaydoc("resulting public key is zero")]
+    ZeroPublicKey,
+    /// Public shares of some (sub-)shares are zero
+    ///
+    /// Similarly to [`KeygenAborted::ZeroPublicKey`], it indicates a coordinated attack.
+    /// Culprits can't be identified, `shares` lists indexes of the affected shares.
+    #[displaydoc("resulting public shares are zero: {shares:?}")]
+    ZeroPublicShares { shares: Vec<u16> },
 }
 
 #[derive(Debug, displaydoc::Display)]
@@ -359,16 +1029,63 @@ enum Bug {
     #[cfg(feature = "hd-wallet")]
     #[displaydoc("chain code is missing although we checked that it should be present")]
     NoChainCode,
-    #[displaydoc("key share of one of the signers is zero - probability of that is negligible")]
+    #[displaydoc("secret share is zero although its public share is not")]
     ZeroShare,
-    #[displaydoc("shared public key is zero - probability of that is negligible")]
-    ZeroPk,
+    #[displaydoc("weights are not valid although we checked them")]
+    InvalidWeights,
+    #[displaydoc("protocol didn't output a key share")]
+    NoKeyShare,
+}
+
+/// Returns `rid_i` that party `i` contributes when [fixed rid](GenericKeygenBuilder::set_fixed_rid_for_testing)
+/// is set
+#[cfg(feature = "testing")]
+fn fixed_rid_contribution<L: SecurityLevel>(
+    i: u16,
+    fixed_rid: &[u8],
+) -> Result<L::Rid, KeygenError> {
+    let mut rid = L::Rid::default();
+    if rid.as_ref().len() != fixed_rid.len() {
+        return Err(Reason::InvalidFixedRid.into());
+    }
+    if i == 0 {
+        rid.as_mut().copy_from_slice(fixed_rid);
+    }
+    Ok(rid)
 }
 
 /// Distributed key generation protocol
 ///
 /// Each party of the protocol should have uniquely assigned index $i$ such that $0 \le i < n$
-/// (where $n$ is amount of parties in the protocol).
+/// (where $n$ is amount of parties in the protocol). $n$ must not exceed [`MAX_PARTIES`].
+///
+/// Unless [threshold is set](GenericKeygenBuilder::set_threshold), the protocol generates n-out-of-n key:
+/// the secret key is additively shared between the parties, and the output key share doesn't carry any
+/// VSS data ([`vss_setup`](key_share::DirtyKeyInfo::vss_setup) is `None`). This is the lightest
+/// mode for fixed quorums, such as 2-out-of-2. The protocol can't be made lighter than that: commitments
+/// to public shares must be published before they're revealed, otherwise a party who speaks last can
+/// bias the resulting public key.
 pub fn keygen<E: Curve>(eid: ExecutionId, i: u16, n: u16) -> KeygenBuilder<E> {
     KeygenBuilder::new(eid, i, n)
 }
+
+/// Computes commitment to network identities of the parties
+///
+/// `identities[j]` is an identity of $\jth$ party, as set via
+/// [`set_party_identity`](GenericKeygenBuilder::set_party_identity). When each party checked
+/// identities of others, keygen records this commitment into
+/// [key info](key_share::DirtyKeyInfo::party_set_commitment). Aux info generation records it
+/// as well, so it can be checked that both protocols were run by the same parties. All protocols
+/// must use the same digest `D` for commitments to be comparable.
+pub fn party_set_commitment<D: Digest>(identities: &[&[u8]]) -> Vec<u8> {
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = "dfns.cggmp21.party_set")]
+    struct PartySet<'a> {
+        identities: Vec<udigest::Bytes<&'a [u8]>>,
+    }
+
+    udigest::hash::<D>(&PartySet {
+        identities: identities.iter().copied().map(udigest::Bytes).collect(),
+    })
+    .to_vec()
+}
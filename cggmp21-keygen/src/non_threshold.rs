@@ -1,24 +1,33 @@
 use alloc::vec::Vec;
 
 use digest::Digest;
-use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec::{Curve, NonZero, Point, SecretScalar};
 use generic_ec_zkp::schnorr_pok;
-use rand_core::{CryptoRng, RngCore};
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
 use round_based::{
     rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
     Outgoing, ProtocolMessage, SinkExt,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::cancellation::CancellationToken;
+use crate::commitment::{Commitment, HashCommitment};
+use crate::contribution;
+use crate::equivocation::{self, BroadcastSigner, SignedCommitment};
 use crate::progress::Tracer;
+use crate::reliable_broadcast;
+use crate::round_observer::{ObservedIncomings, RoundObserver};
+use crate::timeout::RoundTimeout;
 use crate::{
     errors::IoError,
     key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate},
     security_level::SecurityLevel,
-    utils, ExecutionId,
+    utils,
+    version::{MsgVersion, ProtocolVersion},
+    ExecutionId,
 };
 
-use super::{Bug, KeygenAborted, KeygenError};
+use super::{Bug, KeygenAborted, KeygenError, Reason};
 
 macro_rules! prefixed {
     ($name:tt) => {
@@ -38,17 +47,47 @@ pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
     Round2(MsgRound2<E, L>),
     /// Round 3 message
     Round3(MsgRound3<E>),
+    /// Version negotiation message (optional additional round)
+    Version(MsgVersion),
+    /// Forwarded round 1 messages (optional additional round)
+    EquivocationCheck(MsgEquivocationCheck<D>),
+}
+
+impl<E: Curve, L: SecurityLevel, D: Digest> Msg<E, L, D> {
+    /// Name of the round the message belongs to
+    pub fn round_name(&self) -> &'static str {
+        match self {
+            Msg::Round1(_) => "round 1",
+            Msg::ReliabilityCheck(_) => "reliability check",
+            Msg::Round2(_) => "round 2",
+            Msg::Round3(_) => "round 3",
+            Msg::Version(_) => "version negotiation",
+            Msg::EquivocationCheck(_) => "equivocation check",
+        }
+    }
 }
 
 /// Message from round 1
+#[serde_with::serde_as]
 #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
 #[serde(bound = "")]
 #[udigest(bound = "")]
 #[udigest(tag = prefixed!("round1"))]
 pub struct MsgRound1<D: Digest> {
     /// $V_i$
+    #[serde_as(as = "serde_with::Bytes")]
+    #[udigest(as_bytes)]
+    pub commitment: Vec<u8>,
+    /// Digest of execution id
+    ///
+    /// Lets parties detect that they were given different execution ids
     #[udigest(as_bytes)]
-    pub commitment: digest::Output<D>,
+    pub sid_hash: digest::Output<D>,
+    /// Signature of the message, if [equivocation detection](crate::GenericKeygenBuilder::detect_equivocation)
+    /// is enabled
+    #[serde_as(as = "Option<serde_with::Bytes>")]
+    #[udigest(skip)]
+    pub signature: Option<Vec<u8>>,
 }
 /// Message from round 2
 #[serde_with::serde_as]
@@ -62,32 +101,46 @@ pub struct MsgRound2<E: Curve, L: SecurityLevel> {
     #[udigest(as_bytes)]
     pub rid: L::Rid,
     /// $X_i$
+    #[serde_as(as = "utils::Compact")]
     pub X: NonZero<Point<E>>,
     /// $A_i$
+    #[serde_as(as = "utils::Compact")]
     pub sch_commit: schnorr_pok::Commit<E>,
     /// Party contribution to chain code
     #[cfg(feature = "hd-wallet")]
     #[serde_as(as = "Option<utils::HexOrBin>")]
     #[udigest(as = Option<udigest::Bytes>)]
     pub chain_code: Option<hd_wallet::ChainCode>,
+    /// Network identity of the party, if it was set
+    #[serde_as(as = "Option<serde_with::Bytes>")]
+    #[udigest(as = Option<udigest::Bytes>)]
+    pub identity: Option<Vec<u8>>,
     /// $u_i$
     #[serde(with = "hex::serde")]
     #[udigest(as_bytes)]
     pub decommit: L::Rid,
+    /// Opening of the commitment, if [commitment scheme](crate::commitment) requires it
+    #[serde_as(as = "serde_with::Bytes")]
+    #[udigest(skip)]
+    pub opening: Vec<u8>,
 }
 /// Message from round 3
+#[serde_with::serde_as]
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct MsgRound3<E: Curve> {
     /// $\psi_i$
+    #[serde_as(as = "utils::Compact")]
     pub sch_proof: schnorr_pok::Proof<E>,
 }
-/// Message parties exchange to ensure reliability of broadcast channel
+#[doc(no_inline)]
+pub use crate::reliable_broadcast::MsgReliabilityCheck;
+/// Round 1 messages that party received from all parties, forwarded to detect equivocation
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+pub struct MsgEquivocationCheck<D: Digest>(pub Vec<MsgRound1<D>>);
 
-mod unambiguous {
+pub(crate) mod unambiguous {
     use crate::{ExecutionId, SecurityLevel};
     use generic_ec::Curve;
 
@@ -97,6 +150,7 @@ mod unambiguous {
     pub struct HashCom<'a, E: Curve, L: SecurityLevel> {
         pub sid: ExecutionId<'a>,
         pub party_index: u16,
+        pub prove_knowledge: bool,
         pub decommitment: &'a super::MsgRound2<E, L>,
     }
 
@@ -110,6 +164,12 @@ mod unambiguous {
         pub rid: &'a [u8],
     }
 
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("sid_hash"))]
+    pub struct SidHash<'a> {
+        pub sid: ExecutionId<'a>,
+    }
+
     #[derive(udigest::Digestable)]
     #[udigest(tag = prefixed!("echo_round"))]
     #[udigest(bound = "")]
@@ -119,15 +179,64 @@ mod unambiguous {
     }
 }
 
+/// Verifies that party proved knowledge of its secret share at non-threshold keygen
+///
+/// Takes round 2 and round 3 messages sent by party `prover`, and `rid` which is a XOR of
+/// [`rid`](MsgRound2::rid)s from round 2 messages of all parties. Challenge is derived exactly
+/// as in the protocol, so the function can be used to check, for instance, an archived protocol
+/// transcript. `sid` and digest `D` must be the same as were used at keygen.
+///
+/// Returns `true` if the proof is valid.
+pub fn verify_schnorr_contribution<E: Curve, L: SecurityLevel, D: Digest>(
+    sid: ExecutionId,
+    prover: u16,
+    rid: &[u8],
+    decommitment: &MsgRound2<E, L>,
+    sch_proof: &MsgRound3<E>,
+) -> bool {
+    let challenge = contribution::schnorr_challenge::<E, D>(sid, prover, rid);
+    sch_proof
+        .sch_proof
+        .verify(&decommitment.sch_commit, &challenge, &decommitment.X)
+        .is_ok()
+}
+
+/// Binds round 1 message to execution id and index of the party who sent it, which makes data signed
+/// when [equivocation detection](crate::GenericKeygenBuilder::detect_equivocation) is enabled
+fn signed_commitment<D: Digest>(
+    sid: ExecutionId,
+    party_index: u16,
+    commitment: &MsgRound1<D>,
+) -> SignedCommitment {
+    SignedCommitment::new(
+        sid,
+        party_index,
+        &commitment.commitment,
+        &commitment.sid_hash,
+        commitment.signature.as_deref(),
+    )
+}
+
 pub async fn run_keygen<E, R, M, L, D>(
     mut tracer: Option<&mut dyn Tracer>,
+    cancellation: Option<&CancellationToken>,
     i: u16,
     n: u16,
     reliable_broadcast_enforced: bool,
+    prove_knowledge: bool,
+    negotiate_version: bool,
+    party_identity: Option<&[u8]>,
+    peer_identities: Option<&[&[u8]]>,
+    broadcast_signer: Option<&dyn BroadcastSigner>,
+    commitment_scheme: Option<&dyn Commitment>,
+    round_observer: Option<&mut dyn RoundObserver>,
+    round_timeout: &RoundTimeout<'_>,
     sid: ExecutionId<'_>,
     rng: &mut R,
+    mut secret_rng: Option<&mut (dyn CryptoRngCore + Send)>,
     party: M,
     #[cfg(feature = "hd-wallet")] hd_enabled: bool,
+    #[cfg(feature = "testing")] fixed_rid: Option<&[u8]>,
 ) -> Result<CoreKeyShare<E>, KeygenError>
 where
     E: Curve,
@@ -138,60 +247,121 @@ where
 {
     tracer.protocol_begins();
 
+    let hash_commitment = HashCommitment::<D>::new();
+    let commitment_scheme = commitment_scheme.unwrap_or(&hash_commitment);
+
+    if n > crate::MAX_PARTIES {
+        return Err(Reason::TooManyParties { n }.into());
+    }
+
     tracer.stage("Setup networking");
     let MpcParty { delivery, .. } = party.into_party();
     let (incomings, mut outgoings) = delivery.split();
 
     let mut rounds = RoundsRouter::<Msg<E, L, D>>::builder();
+    let round0 = rounds.add_round(RoundInput::<MsgVersion>::broadcast(i, n));
     let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(i, n));
     let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
+    let round1_forward = rounds.add_round(RoundInput::<MsgEquivocationCheck<D>>::broadcast(i, n));
     let round2 = rounds.add_round(RoundInput::<MsgRound2<E, L>>::broadcast(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::broadcast(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let mut rounds = rounds.listen(ObservedIncomings::new(
+        incomings,
+        round_observer,
+        n,
+        Msg::round_name,
+    ));
+
+    // Optional version negotiation
+    if negotiate_version {
+        tracer.round_begins();
+        cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Version(MsgVersion(
+                ProtocolVersion::CURRENT,
+            ))))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.receive_msgs();
+        let versions = round_timeout
+            .race(rounds.complete(round0))
+            .await?
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Check that all parties run the same protocol version");
+        let parties = versions
+            .iter_indexed()
+            .filter(|(_j, _msg_id, version)| version.0 != ProtocolVersion::CURRENT)
+            .map(|(j, _msg_id, _)| j)
+            .collect::<Vec<_>>();
+        if !parties.is_empty() {
+            return Err(KeygenAborted::VersionMismatch { parties }.into());
+        }
+    }
 
     // Round 1
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
-    tracer.stage("Sample x_i, rid_i, chain_code");
-    let x_i = NonZero::<SecretScalar<E>>::random(rng);
+    tracer.stage("Sample x_i, chain_code");
+    let x_i = NonZero::<SecretScalar<E>>::random(&mut utils::secret_rng_or(&mut secret_rng, rng));
     let X_i = Point::generator() * &x_i;
 
-    let mut rid = L::Rid::default();
-    rng.fill_bytes(rid.as_mut());
-
     #[cfg(feature = "hd-wallet")]
     let chain_code_local = if hd_enabled {
         let mut chain_code = hd_wallet::ChainCode::default();
-        rng.fill_bytes(&mut chain_code);
+        utils::secret_rng_or(&mut secret_rng, rng).fill_bytes(&mut chain_code);
         Some(chain_code)
     } else {
         None
     };
 
+    tracer.stage("Sample rid_i");
+    let mut rid = L::Rid::default();
+    rng.fill_bytes(rid.as_mut());
+    #[cfg(feature = "testing")]
+    if let Some(fixed_rid) = fixed_rid {
+        rid = crate::fixed_rid_contribution::<L>(i, fixed_rid)?;
+    }
+
     tracer.stage("Sample schnorr commitment");
-    let (sch_secret, sch_commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+    let (sch_secret, sch_commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(
+        &mut utils::secret_rng_or(&mut secret_rng, rng),
+    );
 
     tracer.stage("Commit to public data");
-    let my_decommitment = MsgRound2 {
+    let mut my_decommitment = MsgRound2 {
         rid,
         X: X_i,
         sch_commit,
         #[cfg(feature = "hd-wallet")]
         chain_code: chain_code_local,
+        identity: party_identity.map(|identity| identity.to_vec()),
         decommit: {
             let mut nonce = L::Rid::default();
             rng.fill_bytes(nonce.as_mut());
             nonce
         },
+        opening: Vec::new(),
     };
-    let hash_commit = udigest::hash::<D>(&unambiguous::HashCom {
+    let mut my_commitment = contribution::commit::<E, L, D>(
+        utils::secret_rng_or(&mut secret_rng, rng),
         sid,
-        party_index: i,
-        decommitment: &my_decommitment,
-    });
-    let my_commitment = MsgRound1 {
-        commitment: hash_commit,
-    };
+        i,
+        prove_knowledge,
+        Some(commitment_scheme),
+        &mut my_decommitment,
+    );
+    if let Some(signer) = broadcast_signer {
+        tracer.stage("Sign commitment");
+        let signed_data = signed_commitment(sid, i, &my_commitment).signed_data::<D>();
+        my_commitment.signature = Some(signer.sign(&signed_data));
+    }
 
     tracer.send_msg();
     outgoings
@@ -202,49 +372,87 @@ where
 
     // Round 2
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
     tracer.receive_msgs();
-    let commitments = rounds
-        .complete(round1)
-        .await
+    let commitments = round_timeout
+        .race(rounds.complete(round1))
+        .await?
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
-    // Optional reliability check
-    if reliable_broadcast_enforced {
-        tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::hash_iter::<D>(
-            commitments
-                .iter_including_me(&my_commitment)
-                .map(|commitment| unambiguous::Echo { sid, commitment }),
-        );
+    tracer.stage("Check that all parties use the same execution id");
+    let parties = commitments
+        .iter_indexed()
+        .filter(|(_j, _msg_id, commitment)| commitment.sid_hash != my_commitment.sid_hash)
+        .map(|(j, _msg_id, _)| j)
+        .collect::<Vec<_>>();
+    if !parties.is_empty() {
+        return Err(KeygenAborted::ExecutionIdMismatch { parties }.into());
+    }
+
+    // Optional equivocation detection or reliability check
+    if let Some(signer) = broadcast_signer {
+        tracer.stage("Verify signatures of commitments");
+        let received = commitments
+            .iter_including_me(&my_commitment)
+            .zip(0..)
+            .map(|(commitment, j)| signed_commitment(sid, j, commitment))
+            .collect::<Vec<_>>();
+        equivocation::verify_received::<D>(signer, i, &received)?;
 
         tracer.send_msg();
         outgoings
-            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
-                MsgReliabilityCheck(h_i.clone()),
+            .send(Outgoing::broadcast(Msg::EquivocationCheck(
+                MsgEquivocationCheck(
+                    commitments
+                        .iter_including_me(&my_commitment)
+                        .cloned()
+                        .collect(),
+                ),
             )))
             .await
             .map_err(IoError::send_message)?;
         tracer.msg_sent();
 
         tracer.round_begins();
+        cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
         tracer.receive_msgs();
-        let round1_hashes = rounds
-            .complete(round1_sync)
-            .await
+        let forwarded = round_timeout
+            .race(rounds.complete(round1_forward))
+            .await?
             .map_err(IoError::receive_message)?;
         tracer.msgs_received();
 
-        tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = round1_hashes
-            .into_iter_indexed()
-            .filter(|(_j, _msg_id, hash_j)| hash_j.0 != h_i)
-            .map(|(j, msg_id, _)| (j, msg_id))
-            .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
-            return Err(KeygenAborted::Round1NotReliable(parties_have_different_hashes).into());
+        tracer.stage("Compare forwarded commitments (equivocation check)");
+        equivocation::check_forwarded::<D>(
+            signer,
+            &received,
+            forwarded.into_iter_indexed().map(|(k, _msg_id, msgs)| {
+                let msgs = (0..)
+                    .zip(&msgs.0)
+                    .map(|(j, commitment)| signed_commitment(sid, j, commitment))
+                    .collect();
+                (k, msgs)
+            }),
+        )?;
+    } else if reliable_broadcast_enforced {
+        let failure = reliable_broadcast::echo_round(
+            commitments
+                .iter_including_me(&my_commitment)
+                .map(|commitment| unambiguous::Echo { sid, commitment }),
+            Msg::ReliabilityCheck,
+            &mut outgoings,
+            &mut rounds,
+            round1_sync,
+            round_timeout,
+            cancellation,
+            &mut tracer,
+        )
+        .await?;
+        if let Some(failure) = failure {
+            return Err(KeygenAborted::Round1NotReliable(failure).into());
         }
     }
 
@@ -257,27 +465,53 @@ where
 
     // Round 3
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
     tracer.receive_msgs();
-    let decommitments = rounds
-        .complete(round2)
-        .await
+    let decommitments = round_timeout
+        .race(rounds.complete(round2))
+        .await?
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
     tracer.stage("Validate decommitments");
     let blame = utils::collect_blame(&commitments, &decommitments, |j, com, decom| {
-        let com_expected = udigest::hash::<D>(&unambiguous::HashCom {
+        !contribution::verify_decommitment(
             sid,
-            party_index: j,
-            decommitment: decom,
-        });
-        com.commitment != com_expected
+            j,
+            prove_knowledge,
+            Some(commitment_scheme),
+            com,
+            decom,
+        )
     });
     if !blame.is_empty() {
         return Err(KeygenAborted::InvalidDecommitment(blame).into());
     }
 
+    if let Some(peer_identities) = peer_identities {
+        tracer.stage("Validate identities");
+        let parties = decommitments
+            .iter_indexed()
+            .filter(|(j, _, decom)| {
+                decom.identity.as_deref() != peer_identities.get(usize::from(*j)).copied()
+            })
+            .map(|(j, _, _)| j)
+            .collect::<Vec<_>>();
+        if !parties.is_empty() {
+            return Err(KeygenAborted::IdentityMismatch { parties }.into());
+        }
+    }
+    let party_set_commitment = if peer_identities.is_some() {
+        decommitments
+            .iter_including_me(&my_decommitment)
+            .map(|decom| decom.identity.as_deref())
+            .collect::<Option<Vec<_>>>()
+            .map(|identities| crate::party_set_commitment::<D>(&identities))
+    } else {
+        None
+    };
+
     #[cfg(feature = "hd-wallet")]
     let chain_code = if hd_enabled {
         tracer.stage("Calculate chain_code");
@@ -285,67 +519,49 @@ where
         if !blame.is_empty() {
             return Err(KeygenAborted::MissingChainCode(blame).into());
         }
-        Some(decommitments.iter_including_me(&my_decommitment).try_fold(
-            hd_wallet::ChainCode::default(),
-            |acc, decom| {
-                Ok::<_, Bug>(utils::xor_array(
-                    acc,
-                    decom.chain_code.ok_or(Bug::NoChainCode)?,
-                ))
-            },
-        )?)
+        let contributions = decommitments
+            .iter_including_me(&my_decommitment)
+            .map(|decom| decom.chain_code.ok_or(Bug::NoChainCode))
+            .collect::<Result<Vec<_>, _>>()?;
+        Some(key_share::CombinedChainCode::combine(contributions))
     } else {
         None
     };
 
-    tracer.stage("Calculate challege rid");
-    let rid = decommitments
-        .iter_including_me(&my_decommitment)
-        .map(|d| &d.rid)
-        .fold(L::Rid::default(), utils::xor_array);
-    let challenge = Scalar::from_hash::<D>(&unambiguous::SchnorrPok {
-        sid,
-        prover: i,
-        rid: rid.as_ref(),
-    });
-    let challenge = schnorr_pok::Challenge { nonce: challenge };
+    if prove_knowledge {
+        tracer.stage("Calculate challege rid");
+        let rid = contribution::combine_rid(decommitments.iter_including_me(&my_decommitment));
+        let challenge = contribution::schnorr_challenge::<E, D>(sid, i, rid.as_ref());
 
-    tracer.stage("Prove knowledge of `x_i`");
-    let sch_proof = schnorr_pok::prove(&sch_secret, &challenge, &x_i);
+        tracer.stage("Prove knowledge of `x_i`");
+        let sch_proof = schnorr_pok::prove(&sch_secret, &challenge, &x_i);
 
-    tracer.send_msg();
-    let my_sch_proof = MsgRound3 { sch_proof };
-    outgoings
-        .send(Outgoing::broadcast(Msg::Round3(my_sch_proof.clone())))
-        .await
-        .map_err(IoError::send_message)?;
-    tracer.msg_sent();
+        tracer.send_msg();
+        let my_sch_proof = MsgRound3 { sch_proof };
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round3(my_sch_proof.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
 
-    // Round 4
-    tracer.round_begins();
+        // Round 4
+        tracer.round_begins();
+        cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
-    tracer.receive_msgs();
-    let sch_proofs = rounds
-        .complete(round3)
-        .await
-        .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+        tracer.receive_msgs();
+        let sch_proofs = round_timeout
+            .race(rounds.complete(round3))
+            .await?
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
 
-    tracer.stage("Validate schnorr proofs");
-    let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
-        let challenge = Scalar::from_hash::<D>(&unambiguous::SchnorrPok {
-            sid,
-            prover: j,
-            rid: rid.as_ref(),
+        tracer.stage("Validate schnorr proofs");
+        let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
+            !verify_schnorr_contribution::<E, L, D>(sid, j, rid.as_ref(), decom, sch_proof)
         });
-        let challenge = schnorr_pok::Challenge { nonce: challenge };
-        sch_proof
-            .sch_proof
-            .verify(&decom.sch_commit, &challenge, &decom.X)
-            .is_err()
-    });
-    if !blame.is_empty() {
-        return Err(KeygenAborted::InvalidSchnorrProof(blame).into());
+        if !blame.is_empty() {
+            return Err(KeygenAborted::InvalidSchnorrProof(blame).into());
+        }
     }
 
     tracer.protocol_ends();
@@ -360,7 +576,7 @@ where
                     .map(|d| d.X)
                     .sum(),
             )
-            .ok_or(Bug::ZeroPk)?,
+            .ok_or(KeygenAborted::ZeroPublicKey)?,
             public_shares: decommitments
                 .iter_including_me(&my_decommitment)
                 .map(|d| d.X)
@@ -368,6 +584,8 @@ where
             vss_setup: None,
             #[cfg(feature = "hd-wallet")]
             chain_code,
+            epoch: 0,
+            party_set_commitment,
         },
         x: x_i,
     }
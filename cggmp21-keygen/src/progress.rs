@@ -3,7 +3,8 @@
 //! Provides [`Tracer`] trait that can be used to trace progress of ongoing MPC protocol execution.
 //! For instance, it can be implemented to report progress to the end user.
 //!
-//! Out of box, there's [`PerfProfiler`] which can be used to bechmark a protocol.
+//! Out of box, there's [`PerfProfiler`] which can be used to bechmark a protocol. With `tracing` feature enabled,
+//! there's also `TracingTracer` which reports progress via [`tracing`](https://docs.rs/tracing) crate.
 
 /// Traces progress of protocol execution
 ///
@@ -108,11 +109,144 @@ impl<T: Tracer> Tracer for Option<T> {
     }
 }
 
+#[cfg(feature = "tracing")]
+pub use with_tracing::TracingTracer;
+#[cfg(feature = "tracing")]
+mod with_tracing {
+    use tracing::Span;
+
+    use super::{Event, Tracer};
+
+    /// Reports progress of the protocol via [`tracing`] crate
+    ///
+    /// Opens a span `protocol` when protocol begins, and a span `round` (child of `protocol` span)
+    /// for each round. Round span is closed when next round begins or protocol ends. Spans are
+    /// opened at `INFO` level.
+    ///
+    /// Stages, sending and receiving messages are reported as `DEBUG` events within round span.
+    /// Events carry round index, round name (if round is named), and amount of messages sent and
+    /// amount of times messages were received within the round so far.
+    pub struct TracingTracer {
+        protocol_name: &'static str,
+        protocol_span: Span,
+        round_span: Span,
+        round: Option<u16>,
+        round_name: &'static str,
+        msgs_sent: usize,
+        msgs_received: usize,
+    }
+
+    impl TracingTracer {
+        /// Constructs a tracer
+        ///
+        /// `protocol_name` is recorded as a field of `protocol` span
+        pub fn new(protocol_name: &'static str) -> Self {
+            Self {
+                protocol_name,
+                protocol_span: Span::none(),
+                round_span: Span::none(),
+                round: None,
+                round_name: "",
+                msgs_sent: 0,
+                msgs_received: 0,
+            }
+        }
+    }
+
+    impl Tracer for TracingTracer {
+        fn trace_event(&mut self, event: Event) {
+            match event {
+                Event::ProtocolBegins => {
+                    self.protocol_span = tracing::info_span!("protocol", name = self.protocol_name);
+                    self.round_span = Span::none();
+                    self.round = None;
+                    tracing::debug!(parent: &self.protocol_span, "protocol begins");
+                }
+                Event::RoundBegins { name } => {
+                    let round = self.round.map(|r| r.saturating_add(1)).unwrap_or(0);
+                    self.round = Some(round);
+                    self.round_name = name.unwrap_or("");
+                    self.msgs_sent = 0;
+                    self.msgs_received = 0;
+                    self.round_span = tracing::info_span!(
+                        parent: &self.protocol_span,
+                        "round",
+                        round,
+                        name = self.round_name,
+                    );
+                    tracing::debug!(parent: &self.round_span, round, name = self.round_name, "round begins");
+                }
+                Event::Stage { name } => {
+                    tracing::debug!(parent: self.current_span(), stage = name, "stage");
+                }
+                Event::ReceiveMsgs => {
+                    tracing::debug!(
+                        parent: self.current_span(),
+                        round = self.round,
+                        name = self.round_name,
+                        received = self.msgs_received,
+                        "receive msgs"
+                    );
+                }
+                Event::MsgsReceived => {
+                    self.msgs_received += 1;
+                    tracing::debug!(
+                        parent: self.current_span(),
+                        round = self.round,
+                        name = self.round_name,
+                        received = self.msgs_received,
+                        "msgs received"
+                    );
+                }
+                Event::SendMsg => {
+                    tracing::debug!(
+                        parent: self.current_span(),
+                        round = self.round,
+                        name = self.round_name,
+                        sent = self.msgs_sent,
+                        "send msg"
+                    );
+                }
+                Event::MsgSent => {
+                    self.msgs_sent += 1;
+                    tracing::debug!(
+                        parent: self.current_span(),
+                        round = self.round,
+                        name = self.round_name,
+                        sent = self.msgs_sent,
+                        "msg sent"
+                    );
+                }
+                Event::ProtocolEnds => {
+                    tracing::debug!(
+                        parent: &self.protocol_span,
+                        rounds = self.round.map(|r| r.saturating_add(1)).unwrap_or(0),
+                        "protocol ends"
+                    );
+                    self.round_span = Span::none();
+                    self.protocol_span = Span::none();
+                }
+            }
+        }
+    }
+
+    impl TracingTracer {
+        /// Returns round span if round has began, otherwise protocol span
+        fn current_span(&self) -> &Span {
+            if self.round.is_some() {
+                &self.round_span
+            } else {
+                &self.protocol_span
+            }
+        }
+    }
+}
+
 #[cfg(feature = "std")]
 pub use requires_std::*;
 #[cfg(feature = "std")]
 mod requires_std {
-    use alloc::{vec, vec::Vec};
+    use alloc::{string::String, vec, vec::Vec};
     use core::fmt;
     use std::time::{Duration, Instant};
 
@@ -359,6 +493,153 @@ mod requires_std {
         }
     }
 
+    impl PerfReport {
+        /// Renders the report as JSON
+        ///
+        /// Schema is stable, so reports can be compared across versions. All durations are
+        /// given in nanoseconds. Round `0` is a setup phase (before the first round began).
+        /// Time spent on i/o is always included, regardless of [`display_io`](Self::display_io).
+        ///
+        /// ```json
+        /// {
+        ///   "version": 1,
+        ///   "total_ns": 123,
+        ///   "setup": { "duration_ns": 12, "stages": [{ "name": "...", "duration_ns": 3 }] },
+        ///   "rounds": [{
+        ///     "round": 1,
+        ///     "name": null,
+        ///     "computation_ns": 10,
+        ///     "sending_ns": 1,
+        ///     "receiving_ns": 100,
+        ///     "stages": [{ "name": "...", "duration_ns": 5 }]
+        ///   }]
+        /// }
+        /// ```
+        pub fn to_json(&self) -> String {
+            use fmt::Write;
+
+            fn write_stages(out: &mut String, stages: &[StageDuration]) {
+                out.push('[');
+                for (i, stage) in stages.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str("{\"name\":");
+                    write_json_str(out, stage.name);
+                    let _ = write!(out, ",\"duration_ns\":{}}}", stage.duration.as_nanos());
+                }
+                out.push(']');
+            }
+
+            let mut out = String::new();
+            let _ = write!(
+                out,
+                "{{\"version\":1,\"total_ns\":{},\"setup\":{{\"duration_ns\":{},\"stages\":",
+                self.total().as_nanos(),
+                self.setup.as_nanos()
+            );
+            write_stages(&mut out, &self.setup_stages);
+            out.push_str("},\"rounds\":[");
+            for (i, round) in self.rounds.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{{\"round\":{},\"name\":", i + 1);
+                match round.round_name {
+                    Some(name) => write_json_str(&mut out, name),
+                    None => out.push_str("null"),
+                }
+                let _ = write!(
+                    out,
+                    ",\"computation_ns\":{},\"sending_ns\":{},\"receiving_ns\":{},\"stages\":",
+                    round.computation.as_nanos(),
+                    round.sending.as_nanos(),
+                    round.receiving.as_nanos()
+                );
+                write_stages(&mut out, &round.stages);
+                out.push('}');
+            }
+            out.push_str("]}");
+            out
+        }
+
+        /// Renders the report as CSV
+        ///
+        /// Each row is a single measurement: `round,round_name,kind,name,duration_ns`, where `kind`
+        /// is one of `setup`, `computation`, `sending`, `receiving`, or `stage` (in which case `name`
+        /// is the stage name). Round `0` is a setup phase. Output starts with the header row.
+        pub fn to_csv(&self) -> String {
+            use fmt::Write;
+
+            fn write_row(
+                out: &mut String,
+                round: usize,
+                round_name: Option<&str>,
+                kind: &str,
+                name: &str,
+                duration: Duration,
+            ) {
+                let _ = write!(out, "{round},");
+                write_csv_field(out, round_name.unwrap_or_default());
+                let _ = write!(out, ",{kind},");
+                write_csv_field(out, name);
+                let _ = writeln!(out, ",{}", duration.as_nanos());
+            }
+
+            let mut out = String::from("round,round_name,kind,name,duration_ns\n");
+            write_row(&mut out, 0, None, "setup", "", self.setup);
+            for stage in &self.setup_stages {
+                write_row(&mut out, 0, None, "stage", stage.name, stage.duration);
+            }
+            for (i, round) in self.rounds.iter().enumerate() {
+                let (i, name) = (i + 1, round.round_name);
+                write_row(&mut out, i, name, "computation", "", round.computation);
+                write_row(&mut out, i, name, "sending", "", round.sending);
+                write_row(&mut out, i, name, "receiving", "", round.receiving);
+                for stage in &round.stages {
+                    write_row(&mut out, i, name, "stage", stage.name, stage.duration);
+                }
+            }
+            out
+        }
+
+        /// Total duration of the protocol, including i/o
+        fn total(&self) -> Duration {
+            self.setup
+                + self
+                    .rounds
+                    .iter()
+                    .map(|r| r.computation + r.sending + r.receiving)
+                    .sum::<Duration>()
+        }
+    }
+
+    fn write_json_str(out: &mut String, s: &str) {
+        use fmt::Write;
+        out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if c.is_control() => {
+                    let _ = write!(out, "\\u{:04x}", u32::from(c));
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    fn write_csv_field(out: &mut String, s: &str) {
+        if s.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&s.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(s);
+        }
+    }
+
     impl fmt::Display for PerfReport {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             let total_computation =
@@ -0,0 +1,181 @@
+//! Reliability check of broadcast channel
+//!
+//! Protocols assume that broadcast channel is reliable: all parties receive the same messages.
+//! When the channel doesn't guarantee that, reliability can be enforced by an extra "echo" round:
+//! each party hashes the broadcast messages it received, sends the hash to other parties, and
+//! makes sure that all parties computed the same hash. Different hashes mean that parties
+//! received different messages, in which case the protocol must be aborted.
+//!
+//! [`echo_round`] carries out the echo round. It's used by keygen and signing when
+//! [reliable broadcast is enforced](crate::GenericKeygenBuilder::enforce_reliable_broadcast), and
+//! can be used by other protocols built on top of [`round_based`].
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+use round_based::{
+    rounds_router::{
+        simple_store::{RoundInput, RoundInputError},
+        CompleteRoundError, Round, RoundsRouter,
+    },
+    Incoming, MsgId, Outgoing, PartyIndex, ProtocolMessage, RoundMessage, Sink, SinkExt, Stream,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::cancellation::{CancellationToken, Cancelled};
+use crate::errors::StdError;
+use crate::progress::Tracer;
+use crate::timeout::{RoundTimeout, TimedOut};
+
+/// Message parties exchange to ensure reliability of broadcast channel
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+
+/// Carries out the echo round
+///
+/// Hashes `received` broadcast messages (including message of the local party), sends the hash
+/// to other parties wrapped into protocol message via `wrap`, receives hashes of other parties at
+/// `round`, and compares them with the local hash. Returns `Ok(None)` if all parties computed the
+/// same hash, or details of the failure otherwise.
+///
+/// `received` must be listed in the same order by all parties (e.g. ordered by sender index), and
+/// include session identifier and domain separation tag of the protocol, so that hashes from
+/// different sessions or protocols can't be mixed up.
+///
+/// `round_timeout`, `cancellation`, and `tracer` are applied as in any other round of the protocol.
+pub async fn echo_round<D, M, S, E, O>(
+    received: impl IntoIterator<Item = impl udigest::Digestable>,
+    wrap: impl FnOnce(MsgReliabilityCheck<D>) -> M,
+    outgoings: &mut O,
+    rounds: &mut RoundsRouter<M, S>,
+    round: Round<RoundInput<MsgReliabilityCheck<D>>>,
+    round_timeout: &RoundTimeout<'_>,
+    cancellation: Option<&CancellationToken>,
+    tracer: &mut dyn Tracer,
+) -> Result<Option<ReliabilityCheckFailure>, EchoRoundError<O::Error, E>>
+where
+    D: Digest + 'static,
+    M: ProtocolMessage + RoundMessage<MsgReliabilityCheck<D>>,
+    S: Stream<Item = Result<Incoming<M>, E>> + Unpin,
+    E: StdError,
+    O: Sink<Outgoing<M>> + Unpin,
+{
+    tracer.stage("Hash received msgs (reliability check)");
+    let h_i = udigest::hash_iter::<D>(received);
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(wrap(MsgReliabilityCheck(h_i.clone()))))
+        .await
+        .map_err(EchoRoundError::Send)?;
+    tracer.msg_sent();
+
+    tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
+
+    tracer.receive_msgs();
+    let hashes = round_timeout
+        .race(rounds.complete(round))
+        .await?
+        .map_err(EchoRoundError::Receive)?;
+    tracer.msgs_received();
+
+    tracer.stage("Assert other parties hashed messages (reliability check)");
+    Ok(ReliabilityCheckFailure::new::<D>(
+        &h_i,
+        hashes
+            .into_iter_indexed()
+            .map(|(j, msg_id, hash_j)| (j, msg_id, hash_j.0)),
+    ))
+}
+
+/// Details of failed reliability check
+///
+/// When [reliable broadcast is enforced](crate::GenericKeygenBuilder::enforce_reliable_broadcast),
+/// each party hashes the messages it received in the first round, and parties make sure that they
+/// all have the same hash. Different hashes mean that parties received different messages.
+#[derive(Debug, Clone)]
+pub struct ReliabilityCheckFailure {
+    /// Hash computed by local party
+    pub local_hash: Vec<u8>,
+    /// Parties whose hash matches [`local_hash`](Self::local_hash)
+    pub agreed: Vec<PartyIndex>,
+    /// Parties whose hash differs from [`local_hash`](Self::local_hash)
+    ///
+    /// Each entry contains index of the party, ID of the message that carried the hash, and the hash
+    /// reported by the party
+    pub mismatched: Vec<(PartyIndex, MsgId, Vec<u8>)>,
+}
+
+impl ReliabilityCheckFailure {
+    /// Indicates whether the failure is likely caused by equivocation
+    ///
+    /// Returns `false` if all other parties reported the same hash that differs from the local one:
+    /// local party received something else than everyone else, which may be an attack targeted on
+    /// local party, but also may be caused by local bug or faulty connection. Returns `true` if other
+    /// parties disagree among themselves, which means that someone sent different messages to
+    /// different parties (or the broadcast channel delivered them).
+    pub fn is_likely_equivocation(&self) -> bool {
+        let everyone_disagrees = self.agreed.is_empty();
+        let others_agree = self
+            .mismatched
+            .windows(2)
+            .all(|pair| pair[0].2 == pair[1].2);
+        !(everyone_disagrees && others_agree)
+    }
+
+    fn new<D: Digest>(
+        local_hash: &digest::Output<D>,
+        hashes: impl IntoIterator<Item = (PartyIndex, MsgId, digest::Output<D>)>,
+    ) -> Option<Self> {
+        let mut agreed = Vec::new();
+        let mut mismatched = Vec::new();
+        for (j, msg_id, hash_j) in hashes {
+            if hash_j == *local_hash {
+                agreed.push(j);
+            } else {
+                mismatched.push((j, msg_id, hash_j.to_vec()));
+            }
+        }
+        if mismatched.is_empty() {
+            None
+        } else {
+            Some(Self {
+                local_hash: local_hash.to_vec(),
+                agreed,
+                mismatched,
+            })
+        }
+    }
+}
+
+/// Error occurred while carrying out [`echo_round`]
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum EchoRoundError<SendErr, RecvErr> {
+    /// Couldn't send the hash
+    #[displaydoc("send message")]
+    Send(#[cfg_attr(feature = "std", source)] SendErr),
+    /// Couldn't receive hashes of other parties
+    #[displaydoc("receive messages")]
+    Receive(#[cfg_attr(feature = "std", source)] CompleteRoundError<RoundInputError, RecvErr>),
+    /// Protocol was cancelled
+    #[displaydoc("protocol was cancelled")]
+    Cancelled(#[cfg_attr(feature = "std", source)] Cancelled),
+    /// Hashes of other parties were not received in time
+    #[displaydoc("round timed out")]
+    TimedOut(#[cfg_attr(feature = "std", source)] TimedOut),
+}
+
+impl<SendErr, RecvErr> From<Cancelled> for EchoRoundError<SendErr, RecvErr> {
+    fn from(err: Cancelled) -> Self {
+        Self::Cancelled(err)
+    }
+}
+
+impl<SendErr, RecvErr> From<TimedOut> for EchoRoundError<SendErr, RecvErr> {
+    fn from(err: TimedOut) -> Self {
+        Self::TimedOut(err)
+    }
+}
@@ -0,0 +1,534 @@
+//! Converts non-threshold key into threshold key
+//!
+//! Holders of additive (non-threshold) key shares can run this protocol to obtain $t$-out-of-$n$
+//! key shares of the same key, without changing the public key. Each holder of additive share
+//! $x_j$ deals it via Feldman VSS: it samples a polynomial $f_j$ of degree $t-1$ such that
+//! $f_j(0) = x_j$, commits to it, and sends $f_j(k+1)$ to each party $k$. Commitment to the
+//! polynomial is checked against public share $X_j$ known from the old key share, so a dealer
+//! can't change the key.
+//!
+//! Resulting key may be shared between more parties than the old key: parties with indexes
+//! $n_{old} \le i < n$ are new parties that don't have a key share. They take part in the protocol
+//! by providing [public key info](crate::key_share::KeyInfo) of the old key (see
+//! [`ReshareBuilder::new_party`]).
+//!
+//! Note that old key shares remain valid after the protocol is completed. Old key shares should be
+//! erased once the new key shares are obtained.
+
+use alloc::vec::Vec;
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::polynomial::Polynomial;
+use rand_core::{CryptoRng, RngCore};
+use round_based::{
+    rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
+    MsgId, Outgoing, PartyIndex, ProtocolMessage, SinkExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::progress::Tracer;
+use crate::{
+    errors::IoError,
+    key_share::{
+        CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, InvalidCoreShare, KeyInfo, Validate,
+        VssSetup,
+    },
+    utils, ExecutionId,
+};
+
+macro_rules! prefixed {
+    ($name:tt) => {
+        concat!("dfns.cggmp21.reshare_to_threshold.", $name)
+    };
+}
+
+/// Message of resharing protocol
+#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum Msg<E: Curve, D: Digest> {
+    /// Round 1 message broadcasted to everyone
+    Round1Broad(MsgRound1Broad<E>),
+    /// Round 1 message unicasted to each party
+    Round1Uni(MsgRound1Uni<E>),
+    /// Reliability check message (optional additional round)
+    ReliabilityCheck(MsgReliabilityCheck<D>),
+}
+
+/// Message from round 1 broadcasted to everyone
+#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[serde(bound = "")]
+#[udigest(bound = "")]
+#[udigest(tag = prefixed!("round1_broad"))]
+pub struct MsgRound1Broad<E: Curve> {
+    /// Commitment to the polynomial $F_j$
+    ///
+    /// `None` if the sender is a new party which doesn't hold a share of the old key
+    pub F: Option<Polynomial<Point<E>>>,
+}
+
+/// Message from round 1 unicasted to each party
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MsgRound1Uni<E: Curve> {
+    /// $\sigma_{j,k} = f_j(k+1)$
+    ///
+    /// `None` if the sender is a new party which doesn't hold a share of the old key
+    pub sigma: Option<Scalar<E>>,
+}
+
+/// Message parties exchange to ensure reliability of broadcast channel
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+
+mod unambiguous {
+    use crate::ExecutionId;
+    use generic_ec::Curve;
+
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("echo_round"))]
+    #[udigest(bound = "")]
+    pub struct Echo<'a, E: Curve> {
+        pub sid: ExecutionId<'a>,
+        pub party_index: u16,
+        pub msg: &'a super::MsgRound1Broad<E>,
+    }
+}
+
+/// Role of the local party in the resharing protocol
+enum Role<'a, E: Curve> {
+    /// Party holds a share of the old key
+    Holder(&'a CoreKeyShare<E>),
+    /// Party is new and only knows the public key info
+    NewParty(&'a KeyInfo<E>),
+}
+
+/// Resharing protocol builder
+pub struct ReshareBuilder<'a, E: Curve, D: Digest = crate::default_choice::Digest> {
+    i: u16,
+    role: Role<'a, E>,
+    new_t: u16,
+    new_n: u16,
+    execution_id: ExecutionId<'a>,
+    reliable_broadcast_enforced: bool,
+    tracer: Option<&'a mut dyn Tracer>,
+    _digest: core::marker::PhantomData<D>,
+}
+
+impl<'a, E: Curve> ReshareBuilder<'a, E> {
+    /// Constructs a builder for a party that holds non-threshold key share
+    ///
+    /// Index of the party `i` must be the same as at keygen, i.e. `i == key_share.i`
+    pub fn new(
+        eid: ExecutionId<'a>,
+        i: u16,
+        key_share: &'a CoreKeyShare<E>,
+        new_t: u16,
+        new_n: u16,
+    ) -> Self {
+        Self {
+            i,
+            role: Role::Holder(key_share),
+            new_t,
+            new_n,
+            execution_id: eid,
+            reliable_broadcast_enforced: true,
+            tracer: None,
+            _digest: core::marker::PhantomData,
+        }
+    }
+
+    /// Constructs a builder for a new party that doesn't hold a share of the key
+    ///
+    /// New party must have index `i` such that $n_{old} \le i < n_{new}$, and it needs to know
+    /// public key info of the key being reshared.
+    pub fn new_party(
+        eid: ExecutionId<'a>,
+        i: u16,
+        key_info: &'a KeyInfo<E>,
+        new_t: u16,
+        new_n: u16,
+    ) -> Self {
+        Self {
+            i,
+            role: Role::NewParty(key_info),
+            new_t,
+            new_n,
+            execution_id: eid,
+            reliable_broadcast_enforced: true,
+            tracer: None,
+            _digest: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, E: Curve, D: Digest + Clone + 'static> ReshareBuilder<'a, E, D> {
+    /// Specifies another hash function to use
+    pub fn set_digest<D2: Digest + Clone + 'static>(self) -> ReshareBuilder<'a, E, D2> {
+        ReshareBuilder {
+            i: self.i,
+            role: self.role,
+            new_t: self.new_t,
+            new_n: self.new_n,
+            execution_id: self.execution_id,
+            reliable_broadcast_enforced: self.reliable_broadcast_enforced,
+            tracer: self.tracer,
+            _digest: core::marker::PhantomData,
+        }
+    }
+
+    /// Sets a tracer that tracks progress of protocol execution
+    pub fn set_progress_tracer(mut self, tracer: &'a mut dyn Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
+    pub fn enforce_reliable_broadcast(self, enforce: bool) -> Self {
+        Self {
+            reliable_broadcast_enforced: enforce,
+            ..self
+        }
+    }
+
+    /// Starts resharing protocol
+    pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<CoreKeyShare<E>, ReshareError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        run_reshare(
+            self.tracer,
+            self.i,
+            self.role,
+            self.new_t,
+            self.new_n,
+            self.reliable_broadcast_enforced,
+            self.execution_id,
+            rng,
+            party,
+        )
+        .await
+    }
+
+    /// Returns a state machine that can be used to carry out the resharing protocol
+    ///
+    /// See [`round_based::state_machine`] for details on how that can be done.
+    #[cfg(feature = "state-machine")]
+    pub fn into_state_machine<R>(
+        self,
+        rng: &'a mut R,
+    ) -> impl round_based::state_machine::StateMachine<
+        Output = Result<CoreKeyShare<E>, ReshareError>,
+        Msg = Msg<E, D>,
+    > + 'a
+    where
+        R: RngCore + CryptoRng,
+    {
+        round_based::state_machine::wrap_protocol(|party| self.start(rng, party))
+    }
+}
+
+async fn run_reshare<E, R, M, D>(
+    mut tracer: Option<&mut dyn Tracer>,
+    i: u16,
+    role: Role<'_, E>,
+    t: u16,
+    n: u16,
+    reliable_broadcast_enforced: bool,
+    sid: ExecutionId<'_>,
+    rng: &mut R,
+    party: M,
+) -> Result<CoreKeyShare<E>, ReshareError>
+where
+    E: Curve,
+    D: Digest + Clone + 'static,
+    R: RngCore + CryptoRng,
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Validate arguments");
+    let (key_info, my_share): (&DirtyKeyInfo<E>, _) = match &role {
+        Role::Holder(key_share) => (&key_share.key_info, Some(key_share)),
+        Role::NewParty(key_info) => (key_info, None),
+    };
+    if key_info.vss_setup.is_some() {
+        return Err(InvalidArgs::AlreadyThreshold.into());
+    }
+    let old_n = u16::try_from(key_info.public_shares.len()).map_err(|_| Bug::NOverflow)?;
+    if !(2 <= t && t <= n) || n < old_n {
+        return Err(InvalidArgs::InvalidThreshold.into());
+    }
+    match my_share {
+        Some(share) if share.i != i || i >= old_n => return Err(InvalidArgs::IndexMismatch.into()),
+        None if i < old_n || i >= n => return Err(InvalidArgs::IndexMismatch.into()),
+        _ => (),
+    }
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
+    let round1_broad = rounds.add_round(RoundInput::<MsgRound1Broad<E>>::broadcast(i, n));
+    let round1_uni = rounds.add_round(RoundInput::<MsgRound1Uni<E>>::p2p(i, n));
+    let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
+    let mut rounds = rounds.listen(incomings);
+
+    // Round 1
+    tracer.round_begins();
+
+    tracer.stage("Sample polynomial");
+    let f = my_share.map(|share| {
+        Polynomial::sample_with_const_term(rng, usize::from(t) - 1, SecretScalar::clone(&share.x))
+    });
+    let sigmas = f.as_ref().map(|f| {
+        (0..n)
+            .map(|k| f.value::<_, Scalar<E>>(&Scalar::from(k + 1)))
+            .collect::<Vec<_>>()
+    });
+    let my_broad = MsgRound1Broad {
+        F: f.as_ref().map(|f| f * &Point::generator()),
+    };
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1Broad(my_broad.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    for j in utils::iter_peers(i, n) {
+        let sigma = sigmas.as_ref().map(|s| s[usize::from(j)]);
+        outgoings
+            .send(Outgoing::p2p(j, Msg::Round1Uni(MsgRound1Uni { sigma })))
+            .await
+            .map_err(IoError::send_message)?;
+    }
+    tracer.msg_sent();
+
+    // Round 2
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let commitments = rounds
+        .complete(round1_broad)
+        .await
+        .map_err(IoError::receive_message)?;
+    let sigmas_msg = rounds
+        .complete(round1_uni)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    // Optional reliability check
+    if reliable_broadcast_enforced {
+        tracer.stage("Hash received msgs (reliability check)");
+        let h_i =
+            udigest::hash_iter::<D>(commitments.iter_including_me(&my_broad).zip(0u16..).map(
+                |(msg, party_index)| unambiguous::Echo {
+                    sid,
+                    party_index,
+                    msg,
+                },
+            ));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
+                MsgReliabilityCheck(h_i.clone()),
+            )))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let hashes = rounds
+            .complete(round1_sync)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Assert other parties hashed messages (reliability check)");
+        let parties_have_different_hashes = hashes
+            .into_iter_indexed()
+            .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !parties_have_different_hashes.is_empty() {
+            return Err(ReshareAborted::Round1NotReliable(parties_have_different_hashes).into());
+        }
+    }
+
+    tracer.stage("Validate commitments");
+    // Parties with index < old_n must deal their shares, new parties must not send anything
+    let blame = utils::collect_blame(&commitments, &sigmas_msg, |j, com, sigma| {
+        if j < old_n {
+            match (&com.F, sigma.sigma) {
+                (Some(F), Some(_)) => {
+                    F.degree() + 1 != usize::from(t)
+                        || F.coefs()[0] != *key_info.public_shares[usize::from(j)]
+                }
+                _ => true,
+            }
+        } else {
+            com.F.is_some() || sigma.sigma.is_some()
+        }
+    });
+    if !blame.is_empty() {
+        return Err(ReshareAborted::InvalidCommitment(blame).into());
+    }
+
+    tracer.stage("Validate Feldman VSS");
+    let blame = utils::collect_blame(&commitments, &sigmas_msg, |_j, com, sigma| {
+        match (&com.F, sigma.sigma) {
+            (Some(F), Some(sigma)) => {
+                F.value::<_, Point<E>>(&Scalar::from(i + 1)) != Point::generator() * sigma
+            }
+            _ => false,
+        }
+    });
+    if !blame.is_empty() {
+        return Err(ReshareAborted::FeldmanVerificationFailed(blame).into());
+    }
+
+    tracer.stage("Compute public shares");
+    let polynomial_sum = commitments
+        .iter_including_me(&my_broad)
+        .filter_map(|msg| msg.F.as_ref())
+        .sum::<Polynomial<_>>();
+    let ys = (0..n)
+        .map(|k| polynomial_sum.value(&Scalar::from(k + 1)))
+        .map(|y_k: Point<E>| NonZero::from_point(y_k).ok_or(Bug::ZeroShare))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    tracer.stage("Compute secret share");
+    let mut sigma = sigmas_msg
+        .iter()
+        .filter_map(|msg| msg.sigma)
+        .sum::<Scalar<E>>();
+    if let Some(sigmas) = &sigmas {
+        sigma += sigmas[usize::from(i)];
+    }
+    let sigma = NonZero::from_secret_scalar(SecretScalar::new(&mut sigma)).ok_or(Bug::ZeroShare)?;
+    debug_assert_eq!(Point::generator() * &sigma, ys[usize::from(i)]);
+
+    tracer.stage("Derive resulting key share");
+    let key_shares_indexes = (1..=n)
+        .map(|k| NonZero::from_scalar(Scalar::from(k)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Bug::NonZeroScalar)?;
+    let key_share = DirtyCoreKeyShare {
+        i,
+        key_info: DirtyKeyInfo {
+            public_shares: ys,
+            vss_setup: Some(VssSetup {
+                min_signers: t,
+                I: key_shares_indexes,
+                weights: None,
+            }),
+            ..key_info.clone()
+        },
+        x: sigma,
+    }
+    .validate()
+    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?;
+
+    tracer.protocol_ends();
+    Ok(key_share)
+}
+
+/// Constructs a builder of resharing protocol for a party that holds non-threshold key share
+///
+/// Alias to [`ReshareBuilder::new`]. See [module level documentation](crate::reshare) for details.
+pub fn reshare_to_threshold<'a, E: Curve>(
+    eid: ExecutionId<'a>,
+    i: u16,
+    key_share: &'a CoreKeyShare<E>,
+    new_t: u16,
+    new_n: u16,
+) -> ReshareBuilder<'a, E> {
+    ReshareBuilder::new(eid, i, key_share, new_t, new_n)
+}
+
+/// Resharing protocol error
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("resharing protocol is failed to complete")]
+pub struct ReshareError(#[cfg_attr(feature = "std", source)] Reason);
+
+crate::errors::impl_from! {
+    impl From for ReshareError {
+        err: InvalidArgs => ReshareError(Reason::InvalidArgs(err)),
+        err: ReshareAborted => ReshareError(Reason::Aborted(err)),
+        err: IoError => ReshareError(Reason::IoError(err)),
+        err: Bug => ReshareError(Reason::Bug(err)),
+    }
+}
+
+impl ReshareError {
+    /// Returns indexes of parties who dealt a share inconsistent with their commitment, if the
+    /// protocol was aborted due to it
+    pub fn invalid_secret_share(&self) -> Option<Vec<PartyIndex>> {
+        match &self.0 {
+            Reason::Aborted(ReshareAborted::FeldmanVerificationFailed(blame)) => {
+                Some(blame.iter().map(|b| b.faulty_party).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Reason {
+    /// Invalid arguments
+    #[displaydoc("invalid arguments")]
+    InvalidArgs(#[cfg_attr(feature = "std", source)] InvalidArgs),
+    /// Protocol was maliciously aborted by another party
+    #[displaydoc("protocol was aborted by malicious party")]
+    Aborted(#[cfg_attr(feature = "std", source)] ReshareAborted),
+    #[displaydoc("i/o error")]
+    IoError(#[cfg_attr(feature = "std", source)] IoError),
+    /// Bug occurred
+    #[displaydoc("bug occurred")]
+    Bug(Bug),
+}
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum InvalidArgs {
+    #[displaydoc("key is already threshold")]
+    AlreadyThreshold,
+    #[displaydoc("threshold must be in range `2 <= t <= n` and `n` can't be less than amount of old key holders")]
+    InvalidThreshold,
+    #[displaydoc("index of the party doesn't match its role: key holders must keep their index, new parties must have index `n_old <= i < n`")]
+    IndexMismatch,
+}
+
+/// Error indicating that protocol was aborted by malicious party
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum ReshareAborted {
+    #[displaydoc("party commitment doesn't match its public share: {0:?}")]
+    InvalidCommitment(Vec<utils::AbortBlame>),
+    #[displaydoc("party secret share is not consistent: {0:?}")]
+    FeldmanVerificationFailed(Vec<utils::AbortBlame>),
+    #[displaydoc("round1 wasn't reliable")]
+    Round1NotReliable(Vec<(PartyIndex, MsgId)>),
+}
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Bug {
+    #[displaydoc("resulting key share is not valid")]
+    InvalidKeyShare(#[cfg_attr(feature = "std", source)] InvalidCoreShare),
+    #[displaydoc("`n` overflows `u16`")]
+    NOverflow,
+    #[displaydoc("unexpected zero value")]
+    NonZeroScalar,
+    #[displaydoc("key share of one of the signers is zero - probability of that is negligible")]
+    ZeroShare,
+}
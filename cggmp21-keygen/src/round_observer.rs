@@ -0,0 +1,120 @@
+//! Observes messages received within protocol rounds
+//!
+//! [`RoundObserver`] is notified each time the protocol receives a message, and learns how many
+//! parties have already delivered their message within the round. It gives real-time visibility
+//! into parties that hold the round back, e.g. to render a progress UI. Observer is set via
+//! `set_round_observer` method of the [keygen builder](crate::GenericKeygenBuilder::set_round_observer),
+//! as well as signing and key refresh builders of `cggmp21` crate.
+//!
+//! Observer is purely informational: it can't affect the protocol execution.
+//!
+//! ## Example
+//! ```rust
+//! use cggmp21_keygen::round_observer::RoundProgress;
+//!
+//! let mut observer = |progress: RoundProgress| {
+//!     println!(
+//!         "{}: received message from party {} ({}/{})",
+//!         progress.round, progress.from, progress.received, progress.expected,
+//!     )
+//! };
+//! # fn use_observer(_: &mut dyn cggmp21_keygen::round_observer::RoundObserver) {}
+//! # use_observer(&mut observer);
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures_core::Stream;
+use round_based::{Incoming, PartyIndex, ProtocolMessage};
+
+/// Observes messages received within protocol rounds
+///
+/// See [module level documentation](self) for more details. It's implemented for any closure
+/// `FnMut(RoundProgress)`.
+pub trait RoundObserver: Send + Sync {
+    /// Called each time a message is received
+    fn message_received(&mut self, progress: RoundProgress);
+}
+
+impl<F: FnMut(RoundProgress) + Send + Sync> RoundObserver for F {
+    fn message_received(&mut self, progress: RoundProgress) {
+        self(progress)
+    }
+}
+
+/// Progress of the round, reported when a message is received
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundProgress {
+    /// Name of the round the message belongs to
+    pub round: &'static str,
+    /// Index of party who sent the message
+    pub from: PartyIndex,
+    /// Amount of parties who delivered their message within the round so far
+    pub received: u16,
+    /// Amount of parties who are expected to send a message within the round
+    pub expected: u16,
+}
+
+/// Wraps a stream of incoming messages and reports every received message to the observer
+///
+/// Used by protocols to report progress to the observer set in the builder.
+pub struct ObservedIncomings<'o, S, M> {
+    incomings: S,
+    observer: Option<&'o mut dyn RoundObserver>,
+    round_name: fn(&M) -> &'static str,
+    expected: u16,
+    senders: BTreeMap<u16, Vec<PartyIndex>>,
+}
+
+impl<'o, S, M> ObservedIncomings<'o, S, M> {
+    /// Wraps `incomings` of a protocol with `n` parties
+    ///
+    /// Each round expects a message from every party except the local one. `round_name` maps
+    /// a message onto the name of its round.
+    pub fn new(
+        incomings: S,
+        observer: Option<&'o mut dyn RoundObserver>,
+        n: u16,
+        round_name: fn(&M) -> &'static str,
+    ) -> Self {
+        Self {
+            incomings,
+            observer,
+            round_name,
+            expected: n.saturating_sub(1),
+            senders: BTreeMap::new(),
+        }
+    }
+}
+
+impl<S, M, E> Stream for ObservedIncomings<'_, S, M>
+where
+    S: Stream<Item = Result<Incoming<M>, E>> + Unpin,
+    M: ProtocolMessage,
+{
+    type Item = Result<Incoming<M>, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let item = match Pin::new(&mut this.incomings).poll_next(cx) {
+            Poll::Ready(item) => item,
+            Poll::Pending => return Poll::Pending,
+        };
+        if let (Some(observer), Some(Ok(incoming))) = (this.observer.as_mut(), &item) {
+            let senders = this.senders.entry(incoming.msg.round()).or_default();
+            if !senders.contains(&incoming.sender) {
+                senders.push(incoming.sender);
+            }
+            observer.message_received(RoundProgress {
+                round: (this.round_name)(&incoming.msg),
+                from: incoming.sender,
+                received: u16::try_from(senders.len()).unwrap_or(u16::MAX),
+                expected: this.expected,
+            });
+        }
+        Poll::Ready(item)
+    }
+}
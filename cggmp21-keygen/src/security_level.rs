@@ -17,6 +17,13 @@ pub trait SecurityLevel: Clone + Sync + Send + 'static {
     const SECURITY_BITS: u32;
     /// $\kappa/8$ bytes of security
     const SECURITY_BYTES: usize;
+    /// Indicates whether security level is suitable for production use
+    ///
+    /// `false` for security levels that are intentionally weak, e.g. to speed up tests. Protocols
+    /// can be set to refuse running with such levels (see
+    /// [`require_production_level`](crate::GenericKeygenBuilder::require_production_level)).
+    /// Set via `production_safe` parameter of [define_security_level] macro, `true` by default.
+    const IS_PRODUCTION_SAFE: bool = true;
 
     /// Static array of $\kappa/8$ bytes
     type Rid: AsRef<[u8]>
@@ -82,14 +89,29 @@ pub mod _internal {
 ///     security_bits = 1024,
 /// });
 /// ```
+///
+/// Security level that's not suitable for production (e.g. used in tests only) should be marked
+/// as such via `production_safe = false`, see [`SecurityLevel::IS_PRODUCTION_SAFE`]:
+/// ```rust
+/// use cggmp21_keygen::security_level::define_security_level;
+///
+/// #[derive(Clone)]
+/// pub struct DevelopmentOnly;
+/// define_security_level!(DevelopmentOnly{
+///     security_bits = 64,
+///     production_safe = false,
+/// });
+/// ```
 #[macro_export]
 macro_rules! define_security_level {
     ($struct_name:ident {
-        security_bits = $k:expr$(,)?
+        security_bits = $k:expr
+        $(, production_safe = $safe:expr)?$(,)?
     }) => {
         impl $crate::security_level::SecurityLevel for $struct_name {
             const SECURITY_BITS: u32 = $k;
             const SECURITY_BYTES: usize = $k / 8;
+            $(const IS_PRODUCTION_SAFE: bool = $safe;)?
             type Rid = $crate::security_level::_internal::Rid<{ $k / 8 }>;
         }
     };
@@ -1,9 +1,10 @@
 use alloc::vec::Vec;
+use core::iter;
 
 use digest::Digest;
 use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
 use generic_ec_zkp::{polynomial::Polynomial, schnorr_pok};
-use rand_core::{CryptoRng, RngCore};
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
 use round_based::{
     rounds_router::simple_store::RoundInput, rounds_router::RoundsRouter, Delivery, Mpc, MpcParty,
     Outgoing, ProtocolMessage, SinkExt,
@@ -11,15 +12,23 @@ use round_based::{
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use crate::cancellation::CancellationToken;
+use crate::commitment::{self, Commitment, HashCommitment};
+use crate::equivocation::{self, BroadcastSigner, SignedCommitment};
 use crate::progress::Tracer;
+use crate::reliable_broadcast;
+use crate::round_observer::{ObservedIncomings, RoundObserver};
+use crate::timeout::RoundTimeout;
 use crate::{
     errors::IoError,
     key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate, VssSetup},
     security_level::SecurityLevel,
-    utils, ExecutionId,
+    utils,
+    version::{MsgVersion, ProtocolVersion},
+    ExecutionId,
 };
 
-use super::{Bug, KeygenAborted, KeygenError};
+use super::{Bug, KeygenAborted, KeygenError, Reason};
 
 macro_rules! prefixed {
     ($name:tt) => {
@@ -41,17 +50,48 @@ pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
     Round3(MsgRound3<E>),
     /// Reliability check message (optional additional round)
     ReliabilityCheck(MsgReliabilityCheck<D>),
+    /// Version negotiation message (optional additional round)
+    Version(MsgVersion),
+    /// Forwarded round 1 messages (optional additional round)
+    EquivocationCheck(MsgEquivocationCheck<D>),
+}
+
+impl<E: Curve, L: SecurityLevel, D: Digest> Msg<E, L, D> {
+    /// Name of the round the message belongs to
+    pub fn round_name(&self) -> &'static str {
+        match self {
+            Msg::Round1(_) => "round 1",
+            Msg::Round2Broad(_) => "round 2a",
+            Msg::Round2Uni(_) => "round 2b",
+            Msg::Round3(_) => "round 3",
+            Msg::ReliabilityCheck(_) => "reliability check",
+            Msg::Version(_) => "version negotiation",
+            Msg::EquivocationCheck(_) => "equivocation check",
+        }
+    }
 }
 
 /// Message from round 1
+#[serde_with::serde_as]
 #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
 #[serde(bound = "")]
 #[udigest(bound = "")]
 #[udigest(tag = prefixed!("round1"))]
 pub struct MsgRound1<D: Digest> {
     /// $V_i$
+    #[serde_as(as = "serde_with::Bytes")]
     #[udigest(as_bytes)]
-    pub commitment: digest::Output<D>,
+    pub commitment: Vec<u8>,
+    /// Digest of execution id
+    ///
+    /// Lets parties detect that they were given different execution ids
+    #[udigest(as_bytes)]
+    pub sid_hash: digest::Output<D>,
+    /// Signature of the message, if [equivocation detection](crate::GenericKeygenBuilder::detect_equivocation)
+    /// is enabled
+    #[serde_as(as = "Option<serde_with::Bytes>")]
+    #[udigest(skip)]
+    pub signature: Option<Vec<u8>>,
 }
 /// Message from round 2 broadcasted to everyone
 #[serde_as]
@@ -65,37 +105,58 @@ pub struct MsgRound2Broad<E: Curve, L: SecurityLevel> {
     #[udigest(as_bytes)]
     pub rid: L::Rid,
     /// $\vec S_i$
+    #[serde_as(as = "utils::Compact")]
     pub F: Polynomial<Point<E>>,
     /// $A_i$
+    #[serde_as(as = "utils::Compact")]
     pub sch_commit: schnorr_pok::Commit<E>,
     /// Party contribution to chain code
     #[cfg(feature = "hd-wallet")]
     #[serde_as(as = "Option<utils::HexOrBin>")]
     #[udigest(as = Option<udigest::Bytes>)]
     pub chain_code: Option<hd_wallet::ChainCode>,
+    /// Network identity of the party, if it was set
+    #[serde_as(as = "Option<serde_with::Bytes>")]
+    #[udigest(as = Option<udigest::Bytes>)]
+    pub identity: Option<Vec<u8>>,
     /// $u_i$
     #[serde(with = "hex::serde")]
     #[udigest(as_bytes)]
     pub decommit: L::Rid,
+    /// Opening of the commitment, if [commitment scheme](crate::commitment) requires it
+    #[serde_as(as = "serde_with::Bytes")]
+    #[udigest(skip)]
+    pub opening: Vec<u8>,
 }
 /// Message from round 2 unicasted to each party
+#[serde_as]
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct MsgRound2Uni<E: Curve> {
     /// $\sigma_{i,j}$
+    #[serde_as(as = "utils::Compact")]
     pub sigma: Scalar<E>,
+    /// $\sigma$ for the remaining sub-shares of the recipient
+    ///
+    /// Only present in weighted keygen, when recipient holds more than one sub-share
+    #[serde_as(as = "Vec<utils::Compact>")]
+    pub extra_sigmas: Vec<Scalar<E>>,
 }
 /// Message from round 3
+#[serde_as]
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct MsgRound3<E: Curve> {
     /// $\psi_i$
+    #[serde_as(as = "utils::Compact")]
     pub sch_proof: schnorr_pok::Proof<E>,
 }
-/// Message parties exchange to ensure reliability of broadcast channel
+#[doc(no_inline)]
+pub use crate::reliable_broadcast::MsgReliabilityCheck;
+/// Round 1 messages that party received from all parties, forwarded to detect equivocation
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+pub struct MsgEquivocationCheck<D: Digest>(pub Vec<MsgRound1<D>>);
 
 mod unambiguous {
     use generic_ec::{Curve, NonZero, Point};
@@ -108,6 +169,7 @@ mod unambiguous {
     pub struct HashCom<'a, E: Curve, L: SecurityLevel> {
         pub sid: ExecutionId<'a>,
         pub party_index: u16,
+        pub prove_knowledge: bool,
         pub decommitment: &'a super::MsgRound2Broad<E, L>,
     }
 
@@ -123,6 +185,12 @@ mod unambiguous {
         pub h: Point<E>,
     }
 
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("sid_hash"))]
+    pub struct SidHash<'a> {
+        pub sid: ExecutionId<'a>,
+    }
+
     #[derive(udigest::Digestable)]
     #[udigest(tag = prefixed!("echo_round"))]
     #[udigest(bound = "")]
@@ -132,17 +200,45 @@ mod unambiguous {
     }
 }
 
+/// Binds round 1 message to execution id and index of the party who sent it, which makes data signed
+/// when [equivocation detection](crate::GenericKeygenBuilder::detect_equivocation) is enabled
+fn signed_commitment<D: Digest>(
+    sid: ExecutionId,
+    party_index: u16,
+    commitment: &MsgRound1<D>,
+) -> SignedCommitment {
+    SignedCommitment::new(
+        sid,
+        party_index,
+        &commitment.commitment,
+        &commitment.sid_hash,
+        commitment.signature.as_deref(),
+    )
+}
+
 pub async fn run_threshold_keygen<E, R, M, L, D>(
     mut tracer: Option<&mut dyn Tracer>,
+    cancellation: Option<&CancellationToken>,
     i: u16,
     t: u16,
     n: u16,
+    weights: Option<&[u16]>,
     reliable_broadcast_enforced: bool,
+    prove_knowledge: bool,
+    negotiate_version: bool,
+    party_identity: Option<&[u8]>,
+    peer_identities: Option<&[&[u8]]>,
+    broadcast_signer: Option<&dyn BroadcastSigner>,
+    commitment_scheme: Option<&dyn Commitment>,
+    round_observer: Option<&mut dyn RoundObserver>,
+    round_timeout: &RoundTimeout<'_>,
     sid: ExecutionId<'_>,
     rng: &mut R,
+    mut secret_rng: Option<&mut (dyn CryptoRngCore + Send)>,
     party: M,
     #[cfg(feature = "hd-wallet")] hd_enabled: bool,
-) -> Result<CoreKeyShare<E>, KeygenError>
+    #[cfg(feature = "testing")] fixed_rid: Option<&[u8]>,
+) -> Result<Vec<CoreKeyShare<E>>, KeygenError>
 where
     E: Curve,
     L: SecurityLevel,
@@ -152,69 +248,154 @@ where
 {
     tracer.protocol_begins();
 
+    let hash_commitment = HashCommitment::<D>::new();
+    let commitment_scheme = commitment_scheme.unwrap_or(&hash_commitment);
+
+    if n > crate::MAX_PARTIES {
+        return Err(Reason::TooManyParties { n }.into());
+    }
+
+    // Indexes of sub-shares held by each party. In non-weighted keygen, each party
+    // holds exactly one sub-share.
+    let sub_shares = match weights {
+        Some(weights) => (0..n)
+            .map(|j| key_share::weighted::sub_shares(weights, j))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Bug::InvalidWeights)?,
+        None => (0..n).map(|j| j..j + 1).collect(),
+    };
+    let total_weight = sub_shares.last().ok_or(Bug::InvalidWeights)?.end;
+    let my_sub_shares = sub_shares[usize::from(i)].clone();
+
     tracer.stage("Setup networking");
     let MpcParty { delivery, .. } = party.into_party();
     let (incomings, mut outgoings) = delivery.split();
 
     let mut rounds = RoundsRouter::<Msg<E, L, D>>::builder();
+    let round0 = rounds.add_round(RoundInput::<MsgVersion>::broadcast(i, n));
     let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(i, n));
     let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
+    let round1_forward = rounds.add_round(RoundInput::<MsgEquivocationCheck<D>>::broadcast(i, n));
     let round2_broad = rounds.add_round(RoundInput::<MsgRound2Broad<E, L>>::broadcast(i, n));
     let round2_uni = rounds.add_round(RoundInput::<MsgRound2Uni<E>>::p2p(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::broadcast(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let mut rounds = rounds.listen(ObservedIncomings::new(
+        incomings,
+        round_observer,
+        n,
+        Msg::round_name,
+    ));
+
+    // Optional version negotiation
+    if negotiate_version {
+        tracer.round_begins();
+        cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
-    // Round 1
-    tracer.round_begins();
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Version(MsgVersion(
+                ProtocolVersion::CURRENT,
+            ))))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
 
-    tracer.stage("Sample rid_i, schnorr commitment, polynomial, chain_code");
-    let mut rid = L::Rid::default();
-    rng.fill_bytes(rid.as_mut());
+        tracer.receive_msgs();
+        let versions = round_timeout
+            .race(rounds.complete(round0))
+            .await?
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Check that all parties run the same protocol version");
+        let parties = versions
+            .iter_indexed()
+            .filter(|(_j, _msg_id, version)| version.0 != ProtocolVersion::CURRENT)
+            .map(|(j, _msg_id, _)| j)
+            .collect::<Vec<_>>();
+        if !parties.is_empty() {
+            return Err(KeygenAborted::VersionMismatch { parties }.into());
+        }
+    }
 
-    let (r, h) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+    // Round 1
+    tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
-    let f = Polynomial::<SecretScalar<E>>::sample(rng, usize::from(t) - 1);
+    tracer.stage("Sample polynomial, chain_code");
+    let f = Polynomial::<SecretScalar<E>>::sample(
+        &mut utils::secret_rng_or(&mut secret_rng, rng),
+        usize::from(t) - 1,
+    );
     let F = &f * &Point::generator();
-    let sigmas = (0..n)
+    let sigmas = (0..total_weight)
         .map(|j| {
             let x = Scalar::from(j + 1);
             f.value(&x)
         })
         .collect::<Vec<_>>();
-    debug_assert_eq!(sigmas.len(), usize::from(n));
+    debug_assert_eq!(sigmas.len(), usize::from(total_weight));
 
     #[cfg(feature = "hd-wallet")]
     let chain_code_local = if hd_enabled {
         let mut chain_code = hd_wallet::ChainCode::default();
-        rng.fill_bytes(&mut chain_code);
+        utils::secret_rng_or(&mut secret_rng, rng).fill_bytes(&mut chain_code);
         Some(chain_code)
     } else {
         None
     };
 
+    tracer.stage("Sample rid_i, schnorr commitment");
+    let mut rid = L::Rid::default();
+    rng.fill_bytes(rid.as_mut());
+    #[cfg(feature = "testing")]
+    if let Some(fixed_rid) = fixed_rid {
+        rid = crate::fixed_rid_contribution::<L>(i, fixed_rid)?;
+    }
+
+    let (r, h) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(&mut utils::secret_rng_or(
+        &mut secret_rng,
+        rng,
+    ));
+
     tracer.stage("Commit to public data");
-    let my_decommitment = MsgRound2Broad {
+    let mut my_decommitment = MsgRound2Broad {
         rid,
         F: F.clone(),
         sch_commit: h,
         #[cfg(feature = "hd-wallet")]
         chain_code: chain_code_local,
+        identity: party_identity.map(|identity| identity.to_vec()),
         decommit: {
             let mut nonce = L::Rid::default();
             rng.fill_bytes(nonce.as_mut());
             nonce
         },
+        opening: Vec::new(),
     };
-    let hash_commit = udigest::hash::<D>(&unambiguous::HashCom {
-        sid,
-        party_index: i,
-        decommitment: &my_decommitment,
-    });
+    let (commitment, opening) = commitment_scheme.commit(
+        utils::secret_rng_or(&mut secret_rng, rng),
+        &commitment::encode(&unambiguous::HashCom {
+            sid,
+            party_index: i,
+            prove_knowledge,
+            decommitment: &my_decommitment,
+        }),
+    );
+    my_decommitment.opening = opening;
+
+    let mut my_commitment = MsgRound1 {
+        commitment,
+        sid_hash: udigest::hash::<D>(&unambiguous::SidHash { sid }),
+        signature: None,
+    };
+    if let Some(signer) = broadcast_signer {
+        tracer.stage("Sign commitment");
+        let signed_data = signed_commitment(sid, i, &my_commitment).signed_data::<D>();
+        my_commitment.signature = Some(signer.sign(&signed_data));
+    }
 
     tracer.send_msg();
-    let my_commitment = MsgRound1 {
-        commitment: hash_commit,
-    };
     outgoings
         .send(Outgoing::broadcast(Msg::Round1(my_commitment.clone())))
         .await
@@ -223,49 +404,87 @@ where
 
     // Round 2
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
     tracer.receive_msgs();
-    let commitments = rounds
-        .complete(round1)
-        .await
+    let commitments = round_timeout
+        .race(rounds.complete(round1))
+        .await?
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
-    // Optional reliability check
-    if reliable_broadcast_enforced {
-        tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::hash_iter::<D>(
-            commitments
-                .iter_including_me(&my_commitment)
-                .map(|commitment| unambiguous::Echo { sid, commitment }),
-        );
+    tracer.stage("Check that all parties use the same execution id");
+    let parties = commitments
+        .iter_indexed()
+        .filter(|(_j, _msg_id, commitment)| commitment.sid_hash != my_commitment.sid_hash)
+        .map(|(j, _msg_id, _)| j)
+        .collect::<Vec<_>>();
+    if !parties.is_empty() {
+        return Err(KeygenAborted::ExecutionIdMismatch { parties }.into());
+    }
+
+    // Optional equivocation detection or reliability check
+    if let Some(signer) = broadcast_signer {
+        tracer.stage("Verify signatures of commitments");
+        let received = commitments
+            .iter_including_me(&my_commitment)
+            .zip(0..)
+            .map(|(commitment, j)| signed_commitment(sid, j, commitment))
+            .collect::<Vec<_>>();
+        equivocation::verify_received::<D>(signer, i, &received)?;
 
         tracer.send_msg();
         outgoings
-            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
-                MsgReliabilityCheck(h_i.clone()),
+            .send(Outgoing::broadcast(Msg::EquivocationCheck(
+                MsgEquivocationCheck(
+                    commitments
+                        .iter_including_me(&my_commitment)
+                        .cloned()
+                        .collect(),
+                ),
             )))
             .await
             .map_err(IoError::send_message)?;
         tracer.msg_sent();
 
         tracer.round_begins();
+        cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
         tracer.receive_msgs();
-        let hashes = rounds
-            .complete(round1_sync)
-            .await
+        let forwarded = round_timeout
+            .race(rounds.complete(round1_forward))
+            .await?
             .map_err(IoError::receive_message)?;
         tracer.msgs_received();
 
-        tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = hashes
-            .into_iter_indexed()
-            .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
-            .map(|(j, msg_id, _)| (j, msg_id))
-            .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
-            return Err(KeygenAborted::Round1NotReliable(parties_have_different_hashes).into());
+        tracer.stage("Compare forwarded commitments (equivocation check)");
+        equivocation::check_forwarded::<D>(
+            signer,
+            &received,
+            forwarded.into_iter_indexed().map(|(k, _msg_id, msgs)| {
+                let msgs = (0..)
+                    .zip(&msgs.0)
+                    .map(|(j, commitment)| signed_commitment(sid, j, commitment))
+                    .collect();
+                (k, msgs)
+            }),
+        )?;
+    } else if reliable_broadcast_enforced {
+        let failure = reliable_broadcast::echo_round(
+            commitments
+                .iter_including_me(&my_commitment)
+                .map(|commitment| unambiguous::Echo { sid, commitment }),
+            Msg::ReliabilityCheck,
+            &mut outgoings,
+            &mut rounds,
+            round1_sync,
+            round_timeout,
+            cancellation,
+            &mut tracer,
+        )
+        .await?;
+        if let Some(failure) = failure {
+            return Err(KeygenAborted::Round1NotReliable(failure).into());
         }
     }
 
@@ -278,8 +497,10 @@ where
         .map_err(IoError::send_message)?;
 
     for j in utils::iter_peers(i, n) {
+        let range = &sub_shares[usize::from(j)];
         let message = MsgRound2Uni {
-            sigma: sigmas[usize::from(j)],
+            sigma: sigmas[usize::from(range.start)],
+            extra_sigmas: sigmas[usize::from(range.start) + 1..usize::from(range.end)].to_vec(),
         };
         outgoings
             .send(Outgoing::p2p(j, Msg::Round2Uni(message)))
@@ -290,36 +511,64 @@ where
 
     // Round 3
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
     tracer.receive_msgs();
-    let decommitments = rounds
-        .complete(round2_broad)
-        .await
+    let decommitments = round_timeout
+        .race(rounds.complete(round2_broad))
+        .await?
         .map_err(IoError::receive_message)?;
-    let sigmas_msg = rounds
-        .complete(round2_uni)
-        .await
+    let sigmas_msg = round_timeout
+        .race(rounds.complete(round2_uni))
+        .await?
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
     tracer.stage("Validate decommitments");
     let blame = utils::collect_blame(&commitments, &decommitments, |j, com, decom| {
-        let com_expected = udigest::hash::<D>(&unambiguous::HashCom {
+        let data = commitment::encode(&unambiguous::HashCom {
             sid,
             party_index: j,
+            prove_knowledge,
             decommitment: decom,
         });
-        com.commitment != com_expected
+        !commitment_scheme.verify(&com.commitment, &data, &decom.opening)
     });
     if !blame.is_empty() {
         return Err(KeygenAborted::InvalidDecommitment(blame).into());
     }
 
+    if let Some(peer_identities) = peer_identities {
+        tracer.stage("Validate identities");
+        let parties = decommitments
+            .iter_indexed()
+            .filter(|(j, _, decom)| {
+                decom.identity.as_deref() != peer_identities.get(usize::from(*j)).copied()
+            })
+            .map(|(j, _, _)| j)
+            .collect::<Vec<_>>();
+        if !parties.is_empty() {
+            return Err(KeygenAborted::IdentityMismatch { parties }.into());
+        }
+    }
+    let party_set_commitment = if peer_identities.is_some() {
+        decommitments
+            .iter_including_me(&my_decommitment)
+            .map(|decom| decom.identity.as_deref())
+            .collect::<Option<Vec<_>>>()
+            .map(|identities| crate::party_set_commitment::<D>(&identities))
+    } else {
+        None
+    };
+
     tracer.stage("Validate data size");
     let blame = decommitments
         .iter_indexed()
-        .filter(|(_, _, d)| d.F.degree() + 1 != usize::from(t))
-        .map(|t| t.0)
+        .zip(sigmas_msg.iter())
+        .filter(|((_, _, d), s)| {
+            d.F.degree() + 1 != usize::from(t) || s.extra_sigmas.len() + 1 != my_sub_shares.len()
+        })
+        .map(|t| t.0 .0)
         .collect::<Vec<_>>();
     if !blame.is_empty() {
         return Err(KeygenAborted::InvalidDataSize { parties: blame }.into());
@@ -330,7 +579,12 @@ where
         .iter_indexed()
         .zip(sigmas_msg.iter())
         .filter(|((_, _, d), s)| {
-            d.F.value::<_, Point<_>>(&Scalar::from(i + 1)) != Point::generator() * s.sigma
+            my_sub_shares
+                .clone()
+                .zip(iter::once(&s.sigma).chain(&s.extra_sigmas))
+                .any(|(k, sigma)| {
+                    d.F.value::<_, Point<_>>(&Scalar::from(k + 1)) != Point::generator() * sigma
+                })
         })
         .map(|t| t.0 .0)
         .collect::<Vec<_>>();
@@ -350,15 +604,11 @@ where
         if !blame.is_empty() {
             return Err(KeygenAborted::MissingChainCode(blame).into());
         }
-        Some(decommitments.iter_including_me(&my_decommitment).try_fold(
-            hd_wallet::ChainCode::default(),
-            |acc, decom| {
-                Ok::<_, Bug>(utils::xor_array(
-                    acc,
-                    decom.chain_code.ok_or(Bug::NoChainCode)?,
-                ))
-            },
-        )?)
+        let contributions = decommitments
+            .iter_including_me(&my_decommitment)
+            .map(|decom| decom.chain_code.ok_or(Bug::NoChainCode))
+            .collect::<Result<Vec<_>, _>>()?;
+        Some(key_share::CombinedChainCode::combine(contributions))
     } else {
         None
     };
@@ -367,64 +617,90 @@ where
         .iter_including_me(&my_decommitment)
         .map(|d| &d.F)
         .sum::<Polynomial<_>>();
-    let ys = (0..n)
-        .map(|l| polynomial_sum.value(&Scalar::from(l + 1)))
-        .map(|y_j: Point<E>| NonZero::from_point(y_j).ok_or(Bug::ZeroShare))
-        .collect::<Result<Vec<_>, _>>()?;
+    let ys = non_zero_public_shares(
+        (0..total_weight).map(|l| polynomial_sum.value(&Scalar::from(l + 1))),
+    )?;
+    // Public share of each party, which is a sum of all its sub-shares. In non-weighted
+    // keygen, it's the same as `ys`.
+    let party_ys = non_zero_public_shares(sub_shares.iter().map(|range| {
+        ys[usize::from(range.start)..usize::from(range.end)]
+            .iter()
+            .sum::<Point<E>>()
+    }))?;
     tracer.stage("Compute sigma");
-    let sigma: Scalar<E> = sigmas_msg.iter().map(|msg| msg.sigma).sum();
-    let mut sigma = sigma + sigmas[usize::from(i)];
+    let my_sigmas = my_sub_shares
+        .clone()
+        .enumerate()
+        .map(|(offset, k)| {
+            let sigma: Scalar<E> = sigmas_msg
+                .iter()
+                .map(|msg| match offset {
+                    0 => msg.sigma,
+                    _ => msg.extra_sigmas[offset - 1],
+                })
+                .sum();
+            let mut sigma = sigma + sigmas[usize::from(k)];
+            let sigma =
+                NonZero::from_secret_scalar(SecretScalar::new(&mut sigma)).ok_or(Bug::ZeroShare)?;
+            debug_assert_eq!(Point::generator() * &sigma, ys[usize::from(k)]);
+            Ok::<_, Bug>(sigma)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut sigma = my_sigmas.iter().fold(Scalar::zero(), |acc, s| acc + s);
     let sigma = NonZero::from_secret_scalar(SecretScalar::new(&mut sigma)).ok_or(Bug::ZeroShare)?;
-    debug_assert_eq!(Point::generator() * &sigma, ys[usize::from(i)]);
+    debug_assert_eq!(Point::generator() * &sigma, party_ys[usize::from(i)]);
 
-    tracer.stage("Calculate challenge");
-    let challenge = Scalar::from_hash::<D>(&unambiguous::SchnorrPok {
-        sid,
-        prover: i,
-        rid: rid.as_ref(),
-        y: ys[usize::from(i)],
-        h: my_decommitment.sch_commit.0,
-    });
-    let challenge = schnorr_pok::Challenge { nonce: challenge };
+    if prove_knowledge {
+        tracer.stage("Calculate challenge");
+        let challenge = Scalar::from_hash::<D>(&unambiguous::SchnorrPok {
+            sid,
+            prover: i,
+            rid: rid.as_ref(),
+            y: party_ys[usize::from(i)],
+            h: my_decommitment.sch_commit.0,
+        });
+        let challenge = schnorr_pok::Challenge { nonce: challenge };
 
-    tracer.stage("Prove knowledge of `sigma_i`");
-    let z = schnorr_pok::prove(&r, &challenge, &sigma);
+        tracer.stage("Prove knowledge of `sigma_i`");
+        let z = schnorr_pok::prove(&r, &challenge, &sigma);
 
-    tracer.send_msg();
-    let my_sch_proof = MsgRound3 { sch_proof: z };
-    outgoings
-        .send(Outgoing::broadcast(Msg::Round3(my_sch_proof.clone())))
-        .await
-        .map_err(IoError::send_message)?;
-    tracer.msg_sent();
+        tracer.send_msg();
+        let my_sch_proof = MsgRound3 { sch_proof: z };
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round3(my_sch_proof.clone())))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
 
-    // Output round
-    tracer.round_begins();
+        // Output round
+        tracer.round_begins();
+        cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
-    tracer.receive_msgs();
-    let sch_proofs = rounds
-        .complete(round3)
-        .await
-        .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
+        tracer.receive_msgs();
+        let sch_proofs = round_timeout
+            .race(rounds.complete(round3))
+            .await?
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
 
-    tracer.stage("Validate schnorr proofs");
-    let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
-        let challenge = Scalar::from_hash::<D>(&unambiguous::SchnorrPok {
-            sid,
-            prover: j,
-            rid: rid.as_ref(),
-            y: ys[usize::from(j)],
-            h: decom.sch_commit.0,
+        tracer.stage("Validate schnorr proofs");
+        let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
+            let challenge = Scalar::from_hash::<D>(&unambiguous::SchnorrPok {
+                sid,
+                prover: j,
+                rid: rid.as_ref(),
+                y: party_ys[usize::from(j)],
+                h: decom.sch_commit.0,
+            });
+            let challenge = schnorr_pok::Challenge { nonce: challenge };
+            sch_proof
+                .sch_proof
+                .verify(&decom.sch_commit, &challenge, &party_ys[usize::from(j)])
+                .is_err()
         });
-        let challenge = schnorr_pok::Challenge { nonce: challenge };
-        sch_proof
-            .sch_proof
-            .verify(&decom.sch_commit, &challenge, &ys[usize::from(j)])
-            .is_err()
-    });
-    if !blame.is_empty() {
-        return Err(KeygenAborted::InvalidSchnorrProof(blame).into());
+        if !blame.is_empty() {
+            return Err(KeygenAborted::InvalidSchnorrProof(blame).into());
+        }
     }
 
     tracer.stage("Derive resulting public key and other data");
@@ -432,28 +708,60 @@ where
         .iter_including_me(&my_decommitment)
         .map(|d| d.F.coefs()[0])
         .sum();
-    let key_shares_indexes = (1..=n)
+    let key_shares_indexes = (1..=total_weight)
         .map(|i| NonZero::from_scalar(Scalar::from(i)))
         .collect::<Option<Vec<_>>>()
         .ok_or(Bug::NonZeroScalar)?;
 
+    let key_info = DirtyKeyInfo {
+        curve: Default::default(),
+        shared_public_key: NonZero::from_point(y).ok_or(KeygenAborted::ZeroPublicKey)?,
+        public_shares: ys,
+        vss_setup: Some(VssSetup {
+            min_signers: t,
+            I: key_shares_indexes,
+            weights: weights.map(|w| w.to_vec()),
+        }),
+        #[cfg(feature = "hd-wallet")]
+        chain_code,
+        epoch: 0,
+        party_set_commitment,
+    };
+    let key_shares = my_sub_shares
+        .zip(my_sigmas)
+        .map(|(k, x)| {
+            DirtyCoreKeyShare {
+                i: k,
+                key_info: key_info.clone(),
+                x,
+            }
+            .validate()
+            .map_err(|err| Bug::InvalidKeyShare(err.into_error()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
     tracer.protocol_ends();
 
-    Ok(DirtyCoreKeyShare {
-        i,
-        key_info: DirtyKeyInfo {
-            curve: Default::default(),
-            shared_public_key: NonZero::from_point(y).ok_or(Bug::ZeroPk)?,
-            public_shares: ys,
-            vss_setup: Some(VssSetup {
-                min_signers: t,
-                I: key_shares_indexes,
-            }),
-            #[cfg(feature = "hd-wallet")]
-            chain_code,
-        },
-        x: sigma,
+    Ok(key_shares)
+}
+
+/// Converts public shares into non-zero points
+///
+/// Returns error listing indexes of the shares that are zero.
+fn non_zero_public_shares<E: Curve>(
+    ys: impl IntoIterator<Item = Point<E>>,
+) -> Result<Vec<NonZero<Point<E>>>, KeygenAborted> {
+    let mut non_zero = Vec::new();
+    let mut zero = Vec::new();
+    for (j, y_j) in (0u16..).zip(ys) {
+        match NonZero::from_point(y_j) {
+            Some(y_j) => non_zero.push(y_j),
+            None => zero.push(j),
+        }
+    }
+    if zero.is_empty() {
+        Ok(non_zero)
+    } else {
+        Err(KeygenAborted::ZeroPublicShares { shares: zero })
     }
-    .validate()
-    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?)
 }
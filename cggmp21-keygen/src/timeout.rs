@@ -0,0 +1,152 @@
+//! Per-round timeouts
+//!
+//! Protocols can be given a [`Timer`] (e.g. via [`set_timer`](crate::GenericKeygenBuilder::set_timer))
+//! and a [round timeout](crate::GenericKeygenBuilder::set_round_timeout). Then, every time the protocol
+//! waits for messages of the next round, it races receiving them against [`Timer::sleep`]. If the timer
+//! fires first, the protocol returns an error which can be recognized via `is_timed_out` method (e.g.
+//! [`KeygenError::is_timed_out`](crate::KeygenError::is_timed_out)).
+//!
+//! The protocols are not tied to any async runtime: [`Timer`] is implemented by the caller. Adapters
+//! for popular runtimes are provided behind features:
+//! * [`TokioTimer`] requires `tokio` feature
+//! * [`AsyncStdTimer`] requires `async-std` feature
+//!
+//! Timeouts are not supported when the protocol is carried out via state machine interface: the state
+//! machine can't be woken up by the timer.
+
+use alloc::boxed::Box;
+use core::future::{poll_fn, Future};
+use core::pin::{pin, Pin};
+use core::task::Poll;
+use core::time::Duration;
+
+/// Timer provided by async runtime
+///
+/// Timer and its [`Sleep`](Self::Sleep) future are required to be `Send`, so protocols that use the
+/// timer can be spawned on multi-threaded runtimes.
+pub trait Timer: Send + Sync {
+    /// Future returned by [`sleep`](Self::sleep)
+    type Sleep: Future<Output = ()> + Send;
+
+    /// Returns a future that resolves once `duration` elapsed
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+}
+
+/// Type-erased [`Timer`]
+///
+/// Protocol builders store the timer in this form, so setting a timer doesn't change the type
+/// of the builder.
+pub struct BoxedTimer<'a>(Box<dyn ErasedTimer + 'a>);
+
+impl<'a> BoxedTimer<'a> {
+    /// Erases type of the timer
+    pub fn new<T>(timer: T) -> Self
+    where
+        T: Timer + 'a,
+        T::Sleep: 'a,
+    {
+        Self(Box::new(timer))
+    }
+}
+
+impl core::fmt::Debug for BoxedTimer<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("BoxedTimer")
+    }
+}
+
+trait ErasedTimer: Send + Sync {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>>;
+}
+
+impl<T: Timer> ErasedTimer for T {
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(Timer::sleep(self, duration))
+    }
+}
+
+/// Timeout applied to each round of the protocol
+///
+/// Constructed by protocols from the timer and the timeout set in the builder.
+#[derive(Debug, Default)]
+pub struct RoundTimeout<'a>(Option<(BoxedTimer<'a>, Duration)>);
+
+impl<'a> RoundTimeout<'a> {
+    /// Constructs a round timeout
+    ///
+    /// Timeout is disabled if `timeout` is not set. Returns an error if `timeout` is set, but `timer`
+    /// is not.
+    pub fn new(
+        timer: Option<BoxedTimer<'a>>,
+        timeout: Option<Duration>,
+    ) -> Result<Self, TimerNotSet> {
+        match (timer, timeout) {
+            (Some(timer), Some(timeout)) => Ok(Self(Some((timer, timeout)))),
+            (None, Some(_)) => Err(TimerNotSet),
+            (_, None) => Ok(Self(None)),
+        }
+    }
+
+    /// Awaits `fut`, unless the timeout elapses first
+    pub async fn race<F: Future>(&self, fut: F) -> Result<F::Output, TimedOut> {
+        let Some((timer, timeout)) = &self.0 else {
+            return Ok(fut.await);
+        };
+        let mut fut = pin!(fut);
+        let mut sleep = timer.0.sleep(*timeout);
+        poll_fn(|cx| {
+            if let Poll::Ready(output) = fut.as_mut().poll(cx) {
+                return Poll::Ready(Ok(output));
+            }
+            if sleep.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(TimedOut));
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// Error indicating that the protocol didn't receive messages of the round in time
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("round timed out")]
+pub struct TimedOut;
+
+/// Error indicating that round timeout was set without a timer
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("round timeout is set, but timer is not")]
+pub struct TimerNotSet;
+
+/// [`Timer`] backed by tokio runtime
+///
+/// Requires `tokio` feature.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioTimer;
+
+#[cfg(feature = "tokio")]
+impl Timer for TokioTimer {
+    type Sleep = tokio::time::Sleep;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// [`Timer`] backed by async-std runtime
+///
+/// Requires `async-std` feature.
+#[cfg(feature = "async-std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdTimer;
+
+#[cfg(feature = "async-std")]
+impl Timer for AsyncStdTimer {
+    type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
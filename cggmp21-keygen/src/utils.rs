@@ -1,9 +1,12 @@
 use alloc::vec::Vec;
 
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
 use round_based::rounds_router::simple_store::RoundMsgs;
 use round_based::{MsgId, PartyIndex};
 
+mod compact;
 mod hex_or_bin;
+pub use compact::Compact;
 pub use hex_or_bin::HexOrBin;
 
 pub fn xor_array<A, B>(mut a: A, b: B) -> A
@@ -90,3 +93,14 @@ where
 pub fn iter_peers(i: u16, n: u16) -> impl Iterator<Item = u16> {
     (0..n).filter(move |x| *x != i)
 }
+
+/// Returns `secret_rng` if it's set, or `rng` otherwise
+pub fn secret_rng_or<'r, R: RngCore + CryptoRng>(
+    secret_rng: &'r mut Option<&mut (dyn CryptoRngCore + Send)>,
+    rng: &'r mut R,
+) -> &'r mut dyn CryptoRngCore {
+    match secret_rng {
+        Some(secret_rng) => *secret_rng,
+        None => rng,
+    }
+}
@@ -0,0 +1,131 @@
+use alloc::vec::Vec;
+
+use generic_ec::{Curve, Point, Scalar};
+use generic_ec_zkp::{polynomial::Polynomial, schnorr_pok};
+use serde_with::{DeserializeAs, SerializeAs};
+
+/// (De)serializes points in compressed form and scalars as fixed-size big-endian bytes
+///
+/// # Motivation
+/// Default serialization of points and scalars is a struct that contains curve name and the
+/// value. Structs are encoded differently by different serde backends (e.g. as a map in json and
+/// cbor, as a list in bincode), so the same message gets different layouts depending on codec.
+/// `Compact` encodes a point as its compressed SEC1 bytes and a scalar as its big-endian bytes,
+/// without any wrapping struct, so the encoded bytes are the same regardless of the codec (human
+/// readable formats get them hex-encoded).
+///
+/// Same as [`generic_ec::serde::Compact`], but additionally supports schnorr proof of knowledge
+/// and polynomials with point coefficients.
+pub struct Compact;
+
+impl<E: Curve> SerializeAs<Point<E>> for Compact {
+    fn serialize_as<S>(source: &Point<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        generic_ec::serde::Compact::serialize_as(source, serializer)
+    }
+}
+
+impl<'de, E: Curve> DeserializeAs<'de, Point<E>> for Compact {
+    fn deserialize_as<D>(deserializer: D) -> Result<Point<E>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        generic_ec::serde::Compact::deserialize_as(deserializer)
+    }
+}
+
+impl<E: Curve> SerializeAs<Scalar<E>> for Compact {
+    fn serialize_as<S>(source: &Scalar<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        generic_ec::serde::Compact::serialize_as(source, serializer)
+    }
+}
+
+impl<'de, E: Curve> DeserializeAs<'de, Scalar<E>> for Compact {
+    fn deserialize_as<D>(deserializer: D) -> Result<Scalar<E>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        generic_ec::serde::Compact::deserialize_as(deserializer)
+    }
+}
+
+impl<E: Curve> SerializeAs<generic_ec::NonZero<Point<E>>> for Compact {
+    fn serialize_as<S>(
+        source: &generic_ec::NonZero<Point<E>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        generic_ec::serde::Compact::serialize_as(source, serializer)
+    }
+}
+
+impl<'de, E: Curve> DeserializeAs<'de, generic_ec::NonZero<Point<E>>> for Compact {
+    fn deserialize_as<D>(deserializer: D) -> Result<generic_ec::NonZero<Point<E>>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        generic_ec::serde::Compact::deserialize_as(deserializer)
+    }
+}
+
+impl<E: Curve> SerializeAs<schnorr_pok::Commit<E>> for Compact {
+    fn serialize_as<S>(source: &schnorr_pok::Commit<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Compact::serialize_as(&source.0, serializer)
+    }
+}
+
+impl<'de, E: Curve> DeserializeAs<'de, schnorr_pok::Commit<E>> for Compact {
+    fn deserialize_as<D>(deserializer: D) -> Result<schnorr_pok::Commit<E>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Compact::deserialize_as(deserializer).map(schnorr_pok::Commit)
+    }
+}
+
+impl<E: Curve> SerializeAs<schnorr_pok::Proof<E>> for Compact {
+    fn serialize_as<S>(source: &schnorr_pok::Proof<E>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        Compact::serialize_as(&source.0, serializer)
+    }
+}
+
+impl<'de, E: Curve> DeserializeAs<'de, schnorr_pok::Proof<E>> for Compact {
+    fn deserialize_as<D>(deserializer: D) -> Result<schnorr_pok::Proof<E>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Compact::deserialize_as(deserializer).map(schnorr_pok::Proof)
+    }
+}
+
+impl<E: Curve> SerializeAs<Polynomial<Point<E>>> for Compact {
+    fn serialize_as<S>(source: &Polynomial<Point<E>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        <[Compact]>::serialize_as(source.coefs(), serializer)
+    }
+}
+
+impl<'de, E: Curve> DeserializeAs<'de, Polynomial<Point<E>>> for Compact {
+    fn deserialize_as<D>(deserializer: D) -> Result<Polynomial<Point<E>>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let coefs: Vec<Point<E>> = Vec::<Compact>::deserialize_as(deserializer)?;
+        Ok(Polynomial::from_coefs(coefs))
+    }
+}
@@ -0,0 +1,56 @@
+//! Protocol version negotiation
+//!
+//! When [enabled](crate::GenericKeygenBuilder::negotiate_version), parties exchange their
+//! [`ProtocolVersion`] in an additional round before the protocol begins, and the protocol aborts
+//! if any of them disagree. It's useful for deployments doing rolling upgrades: parties running
+//! incompatible versions of the library fail with an explicit error instead of a hash mismatch
+//! somewhere down the protocol.
+//!
+//! All parties must enable negotiation, otherwise those who enabled it will be waiting for
+//! the version message forever.
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the protocol implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersion {
+    /// Semver-major version of the crate
+    ///
+    /// For `0.x.y` versions, it's `x`, as that's what breaking releases bump
+    pub major: u16,
+    /// Epoch of the wire format
+    ///
+    /// Bumped whenever messages format changes within the same major version
+    pub format_epoch: u16,
+}
+
+impl ProtocolVersion {
+    /// Version implemented by this crate
+    pub const CURRENT: Self = Self {
+        major: {
+            let major = parse_u16(env!("CARGO_PKG_VERSION_MAJOR"));
+            if major == 0 {
+                parse_u16(env!("CARGO_PKG_VERSION_MINOR"))
+            } else {
+                major
+            }
+        },
+        format_epoch: 7,
+    };
+}
+
+/// Message parties exchange to negotiate protocol version
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MsgVersion(pub ProtocolVersion);
+
+/// Parses a decimal number, used to parse crate version at compile time
+const fn parse_u16(s: &str) -> u16 {
+    let s = s.as_bytes();
+    let mut n = 0u16;
+    let mut i = 0;
+    while i < s.len() {
+        n = n * 10 + (s[i] - b'0') as u16;
+        i += 1;
+    }
+    n
+}
@@ -0,0 +1,149 @@
+//! Derivation of blockchain addresses from a public key
+//!
+//! Helpers take a [shared public key](crate::key_share::DirtyKeyInfo::shared_public_key) (or a public key
+//! derived from it via HD derivation) and produce an address it corresponds to.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn main() {
+//! # let key_share: cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1> = unimplemented!();
+//! use cggmp21::addresses;
+//!
+//! let btc_address = addresses::bitcoin_p2wpkh(
+//!     &key_share.shared_public_key,
+//!     addresses::BitcoinNetwork::Mainnet,
+//! );
+//! let eth_address = addresses::ethereum(&key_share.shared_public_key);
+//! # }
+//! ```
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point};
+
+/// Computes `RIPEMD160(SHA256(public_key))`
+///
+/// Public key is encoded in compressed form. Hash160 is used to derive addresses in Bitcoin and
+/// many other blockchains.
+pub fn hash160<E: Curve>(public_key: &NonZero<Point<E>>) -> [u8; 20] {
+    let sha256 = sha2::Sha256::digest(public_key.to_bytes(true));
+    ripemd::Ripemd160::digest(sha256).into()
+}
+
+/// Bitcoin network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitcoinNetwork {
+    /// Bitcoin mainnet, addresses start with `bc1`
+    Mainnet,
+    /// Bitcoin testnet and signet, addresses start with `tb1`
+    Testnet,
+    /// Bitcoin regtest, addresses start with `bcrt1`
+    Regtest,
+}
+
+impl BitcoinNetwork {
+    /// Human-readable part of bech32 address
+    fn hrp(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "bc",
+            Self::Testnet => "tb",
+            Self::Regtest => "bcrt",
+        }
+    }
+}
+
+/// Derives Bitcoin native SegWit (P2WPKH) address
+///
+/// Address is a bech32-encoded version 0 witness program, which is [hash160] of the public key,
+/// as specified in [BIP-173](https://github.com/bitcoin/bips/blob/master/bip-0173.mediawiki).
+pub fn bitcoin_p2wpkh(
+    public_key: &NonZero<Point<crate::supported_curves::Secp256k1>>,
+    network: BitcoinNetwork,
+) -> String {
+    bech32::encode_segwit_v0(network.hrp(), &hash160(public_key))
+}
+
+/// Derives Ethereum address
+///
+/// Address is the last 20 bytes of keccak256 hash of uncompressed public key (without `0x04`
+/// prefix). It's returned as hex string prefixed with `0x`, with mixed-case checksum as
+/// specified in [EIP-55](https://eips.ethereum.org/EIPS/eip-55).
+pub fn ethereum(public_key: &NonZero<Point<crate::supported_curves::Secp256k1>>) -> String {
+    let public_key = public_key.to_bytes(false);
+    let hash = sha3::Keccak256::digest(&public_key[1..]);
+    let address = hex::encode(&hash[12..]);
+
+    // EIP-55 checksum: uppercase i-th letter if i-th nibble of hash of the address is >= 8
+    let checksum = sha3::Keccak256::digest(address.as_bytes());
+    let address = address
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (checksum[i / 2] >> (4 * (1 - i % 2))) & 0xf;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    format!("0x{address}")
+}
+
+/// Minimal bech32 encoder as specified in BIP-173
+mod bech32 {
+    const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+        let mut chk = 1u32;
+        for v in values {
+            let b = chk >> 25;
+            chk = ((chk & 0x1ffffff) << 5) ^ u32::from(v);
+            for (i, g) in GENERATOR.iter().enumerate() {
+                if (b >> i) & 1 == 1 {
+                    chk ^= g;
+                }
+            }
+        }
+        chk
+    }
+
+    /// Regroups 8-bit bytes into 5-bit groups, pads the last group with zeroes
+    fn to_base32(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity((data.len() * 8).div_ceil(5));
+        let mut acc = 0u32;
+        let mut bits = 0;
+        for b in data {
+            acc = (acc << 8) | u32::from(*b);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(((acc >> bits) & 0x1f) as u8);
+            }
+        }
+        if bits > 0 {
+            out.push(((acc << (5 - bits)) & 0x1f) as u8);
+        }
+        out
+    }
+
+    /// Encodes version 0 witness program
+    pub fn encode_segwit_v0(hrp: &str, program: &[u8]) -> String {
+        let mut data = vec![0u8];
+        data.extend(to_base32(program));
+
+        let hrp_expanded = hrp
+            .bytes()
+            .map(|c| c >> 5)
+            .chain([0])
+            .chain(hrp.bytes().map(|c| c & 0x1f));
+        let checksum = polymod(hrp_expanded.chain(data.iter().copied()).chain([0; 6])) ^ 1;
+        data.extend((0..6).map(|i| ((checksum >> (5 * (5 - i))) & 0x1f) as u8));
+
+        let mut out = String::with_capacity(hrp.len() + 1 + data.len());
+        out.push_str(hrp);
+        out.push('1');
+        out.extend(data.iter().map(|d| char::from(CHARSET[usize::from(*d)])));
+        out
+    }
+}
@@ -0,0 +1,244 @@
+//! Encrypted backup of key share
+//!
+//! [`export_encrypted`] serializes the key share and encrypts it with a key derived from a passphrase,
+//! producing a self-contained blob that can be stored in cold storage. [`import_encrypted`] restores
+//! the key share from the blob, given the same passphrase.
+//!
+//! Key is derived from the passphrase via [scrypt] with a random salt. Serialized key share is
+//! encrypted with AES-256 in CTR mode and authenticated with HMAC-SHA256 (encrypt-then-MAC).
+//! Salt, IV and KDF parameters are stored in the header of the backup, which is authenticated as well.
+//! Plaintext and derived keys are zeroized once they're no longer needed.
+//!
+//! Security of the backup is bounded by the strength of the passphrase: scrypt makes each guess
+//! expensive, but it doesn't help if the passphrase is weak.
+//!
+//! ## Choice of primitives
+//! Backup uses scrypt, AES-256-CTR and HMAC-SHA256, the primitives of the widely deployed Web3
+//! keystore format, rather than Argon2 and XChaCha20-Poly1305. Implementations come from RustCrypto
+//! crates `scrypt`, `aes`, `ctr` and `hmac`, which are only pulled in by `backup` feature.
+//! Security-wise the constructions are equivalent for this purpose: scrypt is a memory-hard KDF
+//! like Argon2, and AES-256-CTR with HMAC-SHA256 over the header and ciphertext, keyed
+//! independently (encrypt-then-MAC), is an authenticated encryption like XChaCha20-Poly1305. Random
+//! 128-bit IV is safe since every backup is encrypted under a fresh key derived with a random salt.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn main() -> Result<(), cggmp21::backup::BackupError> {
+//! # type E = cggmp21::supported_curves::Secp256k1;
+//! # let key_share: cggmp21::KeyShare<E> = unimplemented!();
+//! # let mut rng = rand::rngs::OsRng;
+//! let backup = cggmp21::backup::export_encrypted(&mut rng, &key_share, b"passphrase")?;
+//! let restored: cggmp21::KeyShare<E> = cggmp21::backup::import_encrypted(&backup, b"passphrase")?;
+//! # Ok(()) }
+//! ```
+//!
+//! [scrypt]: https://www.rfc-editor.org/rfc/rfc7914
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use generic_ec::Curve;
+use hmac::{digest::KeyInit, Mac};
+use rand_core::{CryptoRng, RngCore};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::{
+    codec::CodecError,
+    key_share::{DirtyKeyShare, InvalidKeyShare, KeyShare, Validate},
+    security_level::SecurityLevel,
+};
+
+type Aes256Ctr = ctr::Ctr128BE<aes::Aes256>;
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+
+/// Magic bytes that backup starts with
+const MAGIC: &[u8] = b"cgbk";
+/// Current version of backup format
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const IV_LEN: usize = 16;
+const TAG_LEN: usize = 32;
+/// Header: magic, version, KDF params (`log_n`, `r`, `p`), salt, IV
+const HEADER_LEN: usize = MAGIC.len() + 1 + 1 + 4 + 4 + SALT_LEN + IV_LEN;
+
+/// Parameters of scrypt key derivation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl KdfParams {
+    /// Parameters used by [`export_encrypted`]: $N = 2^{17}$, $r = 8$, $p = 1$
+    ///
+    /// Key derivation takes about 128MiB of memory.
+    pub const RECOMMENDED: Self = Self {
+        log_n: 17,
+        r: 8,
+        p: 1,
+    };
+    /// Upper bound on memory (in bytes) that key derivation may take, $128 \cdot r \cdot N$
+    ///
+    /// Prevents a malformed backup from making key derivation consume unbounded memory. Twice
+    /// as much as [recommended](Self::RECOMMENDED) parameters take.
+    const MAX_MEMORY: u64 = 256 * 1024 * 1024;
+    /// Upper bound on parallelization parameter, bounds time that key derivation may take
+    const MAX_P: u32 = 4;
+
+    /// Constructs scrypt parameters
+    ///
+    /// Returns `None` if parameters are not valid or exceed the limits accepted by
+    /// [`import_encrypted`]: key derivation must take at most 256MiB of memory
+    /// ($128 \cdot r \cdot N \le 2^{28}$), and $p \le 4$
+    pub fn new(log_n: u8, r: u32, p: u32) -> Option<Self> {
+        let params = Self { log_n, r, p };
+        let memory = 1u64
+            .checked_shl(log_n.into())?
+            .checked_mul(128 * u64::from(r))?;
+        if memory > Self::MAX_MEMORY || p > Self::MAX_P {
+            return None;
+        }
+        params.to_scrypt().ok()?;
+        Some(params)
+    }
+
+    fn to_scrypt(self) -> Result<scrypt::Params, BackupError> {
+        scrypt::Params::new(self.log_n, self.r, self.p).map_err(|_| BackupError::InvalidKdfParams)
+    }
+
+    /// Derives encryption key (32 bytes) and MAC key (64 bytes) from the passphrase
+    fn derive_keys(
+        self,
+        passphrase: &[u8],
+        salt: &[u8],
+    ) -> Result<Zeroizing<[u8; 96]>, BackupError> {
+        let mut keys = Zeroizing::new([0u8; 96]);
+        scrypt::scrypt(passphrase, salt, &self.to_scrypt()?, keys.as_mut())
+            .map_err(|_| BackupError::InvalidKdfParams)?;
+        Ok(keys)
+    }
+}
+
+/// Encrypts the key share with the passphrase
+///
+/// Uses [recommended](KdfParams::RECOMMENDED) KDF parameters. See [module-level](self) docs for details.
+pub fn export_encrypted<E: Curve, L: SecurityLevel>(
+    rng: &mut (impl RngCore + CryptoRng),
+    key_share: &KeyShare<E, L>,
+    passphrase: &[u8],
+) -> Result<Vec<u8>, BackupError> {
+    export_encrypted_with_params(rng, key_share, passphrase, KdfParams::RECOMMENDED)
+}
+
+/// Encrypts the key share with the passphrase, using given KDF parameters
+pub fn export_encrypted_with_params<E: Curve, L: SecurityLevel>(
+    rng: &mut (impl RngCore + CryptoRng),
+    key_share: &KeyShare<E, L>,
+    passphrase: &[u8],
+    kdf_params: KdfParams,
+) -> Result<Vec<u8>, BackupError> {
+    let plaintext = Zeroizing::new(
+        crate::codec::encode_message(key_share, usize::MAX).map_err(BackupError::Encode)?,
+    );
+
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    let mut iv = [0u8; IV_LEN];
+    rng.fill_bytes(&mut iv);
+    let keys = kdf_params.derive_keys(passphrase, &salt)?;
+    let (enc_key, mac_key) = keys.split_at(32);
+
+    let mut backup = Vec::with_capacity(HEADER_LEN + plaintext.len() + TAG_LEN);
+    backup.extend_from_slice(MAGIC);
+    backup.push(VERSION);
+    backup.push(kdf_params.log_n);
+    backup.extend_from_slice(&kdf_params.r.to_be_bytes());
+    backup.extend_from_slice(&kdf_params.p.to_be_bytes());
+    backup.extend_from_slice(&salt);
+    backup.extend_from_slice(&iv);
+    backup.extend_from_slice(&plaintext);
+
+    Aes256Ctr::new(enc_key.into(), (&iv).into()).apply_keystream(&mut backup[HEADER_LEN..]);
+
+    let mut mac = <HmacSha256 as KeyInit>::new(mac_key.into());
+    mac.update(&backup);
+    backup.extend_from_slice(&mac.finalize().into_bytes());
+
+    Ok(backup)
+}
+
+/// Decrypts the key share encrypted via [`export_encrypted`]
+///
+/// Returns [`BackupError::WrongPassphrase`] if passphrase doesn't match, or if backup was modified.
+/// Restored key share is validated.
+pub fn import_encrypted<E: Curve, L: SecurityLevel>(
+    backup: &[u8],
+    passphrase: &[u8],
+) -> Result<KeyShare<E, L>, BackupError> {
+    let header = backup
+        .get(..HEADER_LEN)
+        .filter(|_| backup.len() >= HEADER_LEN + TAG_LEN)
+        .ok_or(BackupError::InvalidHeader)?;
+    let (magic, header) = header.split_at(MAGIC.len());
+    if magic != MAGIC {
+        return Err(BackupError::InvalidHeader);
+    }
+    let (&version, header) = header.split_first().ok_or(BackupError::InvalidHeader)?;
+    if version != VERSION {
+        return Err(BackupError::UnknownVersion(version));
+    }
+    let (&log_n, header) = header.split_first().ok_or(BackupError::InvalidHeader)?;
+    let (r, header) = header.split_at(4);
+    let (p, header) = header.split_at(4);
+    let (salt, iv) = header.split_at(SALT_LEN);
+    let kdf_params = KdfParams::new(
+        log_n,
+        u32::from_be_bytes(r.try_into().map_err(|_| BackupError::InvalidHeader)?),
+        u32::from_be_bytes(p.try_into().map_err(|_| BackupError::InvalidHeader)?),
+    )
+    .ok_or(BackupError::InvalidKdfParams)?;
+
+    let keys = kdf_params.derive_keys(passphrase, salt)?;
+    let (enc_key, mac_key) = keys.split_at(32);
+
+    let (authenticated, tag) = backup.split_at(backup.len() - TAG_LEN);
+    let mut mac = <HmacSha256 as KeyInit>::new(mac_key.into());
+    mac.update(authenticated);
+    mac.verify_slice(tag)
+        .map_err(|_| BackupError::WrongPassphrase)?;
+
+    let mut plaintext = Zeroizing::new(authenticated[HEADER_LEN..].to_vec());
+    Aes256Ctr::new(enc_key.into(), iv.into()).apply_keystream(&mut plaintext);
+
+    let key_share: DirtyKeyShare<E, L> =
+        crate::codec::decode_message(&plaintext, usize::MAX).map_err(BackupError::Decode)?;
+    key_share
+        .validate()
+        .map_err(|err| BackupError::InvalidKeyShare(err.into_error()))
+}
+
+/// Error returned by functions in [backup module](self)
+#[derive(Debug, Error)]
+pub enum BackupError {
+    /// Backup is too short or doesn't start with expected magic bytes
+    #[error("backup header is invalid")]
+    InvalidHeader,
+    /// Backup was created by a version of the crate that uses unknown format
+    #[error("unknown version of backup format: {0}")]
+    UnknownVersion(u8),
+    /// KDF parameters are not valid or exceed the limits
+    #[error("invalid KDF parameters")]
+    InvalidKdfParams,
+    /// Passphrase doesn't match, or backup was modified
+    #[error("wrong passphrase or backup is corrupted")]
+    WrongPassphrase,
+    /// Key share couldn't be serialized
+    #[error("couldn't encode key share")]
+    Encode(#[source] CodecError),
+    /// Key share couldn't be deserialized
+    #[error("couldn't decode key share")]
+    Decode(#[source] CodecError),
+    /// Restored key share is not valid
+    #[error("restored key share is not valid")]
+    InvalidKeyShare(#[source] InvalidKeyShare),
+}
@@ -0,0 +1,290 @@
+//! Serialization of protocol messages with a limit on message size
+//!
+//! Protocol messages (e.g. [`signing::msg::Msg`](crate::signing::msg::Msg)) implement serde traits,
+//! so they can be serialized in any format. This module provides a fixed codec (CBOR) that can be
+//! used by the transport layer, so all parties agree on the encoding of messages.
+//!
+//! Both [`encode_message`] and [`decode_message`] take `max_len` and refuse to process messages
+//! which exceed it. Transport should check the size of received message before buffering it
+//! entirely, but [`decode_message`] makes sure that an oversized message is never parsed.
+//! Decoder also rejects the message if there are any trailing bytes after it.
+//!
+//! Decoding never panics on malformed input, which is checked by a fuzz target in `fuzz/`
+//! directory of the repository.
+//!
+//! ## Framing
+//! When several protocol executions are multiplexed over one connection, messages need to be routed
+//! to the right execution before they're decoded. [`frame`] prepends encoded message with a
+//! fixed-size [`FrameHeader`] that identifies the protocol, the execution, and the round the message
+//! belongs to. Router can read the header via [`FrameHeader::parse`] and dispatch the message by
+//! [`execution_id_digest`](FrameHeader::execution_id_digest); the message itself is decoded by the
+//! protocol execution via [`deframe`].
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn main() -> Result<(), cggmp21::codec::CodecError> {
+//! # type Msg = cggmp21::signing::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>;
+//! # let msg: Msg = unimplemented!();
+//! use cggmp21::codec;
+//!
+//! const MAX_LEN: usize = 1 << 20;
+//!
+//! let bytes = codec::encode_message(&msg, MAX_LEN)?;
+//! let decoded: Msg = codec::decode_message(&bytes, MAX_LEN)?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use generic_ec::Curve;
+use round_based::ProtocolMessage;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+use crate::{
+    security_level::{KeygenSecurityLevel, SecurityLevel},
+    ExecutionId,
+};
+
+/// Encodes a message
+///
+/// Returns [`CodecError::TooLarge`] if the encoded message is longer than `max_len` bytes.
+pub fn encode_message<M: Serialize>(msg: &M, max_len: usize) -> Result<Vec<u8>, CodecError> {
+    let mut bytes = vec![];
+    ciborium::into_writer(msg, &mut bytes)
+        .map_err(|err| CodecError::Encode(CborError(err.into())))?;
+    if bytes.len() > max_len {
+        return Err(CodecError::TooLarge {
+            len: bytes.len(),
+            max_len,
+        });
+    }
+    Ok(bytes)
+}
+
+/// Decodes a message
+///
+/// Returns [`CodecError::TooLarge`] if `bytes` is longer than `max_len`, and
+/// [`CodecError::TrailingBytes`] if `bytes` has anything after the encoded message.
+pub fn decode_message<M: DeserializeOwned>(bytes: &[u8], max_len: usize) -> Result<M, CodecError> {
+    if bytes.len() > max_len {
+        return Err(CodecError::TooLarge {
+            len: bytes.len(),
+            max_len,
+        });
+    }
+    let mut reader = bytes;
+    let msg = ciborium::from_reader(&mut reader)
+        .map_err(|err: ciborium::de::Error<_>| CodecError::Decode(CborError(err.into())))?;
+    if !reader.is_empty() {
+        return Err(CodecError::TrailingBytes(reader.len()));
+    }
+    Ok(msg)
+}
+
+/// Protocol which message belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+#[non_exhaustive]
+pub enum ProtocolKind {
+    /// Non-threshold key generation
+    NonThresholdKeygen = 1,
+    /// Threshold key generation
+    ThresholdKeygen = 2,
+    /// Aux info generation
+    AuxInfoGen = 3,
+    /// Non-threshold key refresh
+    KeyRefresh = 4,
+    /// Signing
+    Signing = 5,
+    /// Enrollment of a new party
+    Enroll = 6,
+    /// Conversion of non-threshold key into threshold key
+    Reshare = 7,
+}
+
+impl ProtocolKind {
+    fn from_byte(byte: u8) -> Option<Self> {
+        [
+            Self::NonThresholdKeygen,
+            Self::ThresholdKeygen,
+            Self::AuxInfoGen,
+            Self::KeyRefresh,
+            Self::Signing,
+            Self::Enroll,
+            Self::Reshare,
+        ]
+        .into_iter()
+        .find(|kind| *kind as u8 == byte)
+    }
+}
+
+/// Protocol message that can be [framed](frame)
+pub trait FramedMessage: ProtocolMessage + Serialize + DeserializeOwned {
+    /// Protocol which message belongs to
+    const PROTOCOL_KIND: ProtocolKind;
+
+    /// Decodes a message received from the network
+    ///
+    /// Same as [`decode_message`]. Never panics: any malformed input (truncated message, invalid
+    /// point or scalar encoding, wrong length of fixed-size fields, etc.) results into an error.
+    fn try_decode(bytes: &[u8], max_len: usize) -> Result<Self, CodecError> {
+        decode_message(bytes, max_len)
+    }
+}
+
+impl<E: Curve, L: KeygenSecurityLevel, D: Digest> FramedMessage
+    for crate::keygen::NonThresholdMsg<E, L, D>
+{
+    const PROTOCOL_KIND: ProtocolKind = ProtocolKind::NonThresholdKeygen;
+}
+impl<E: Curve, L: KeygenSecurityLevel, D: Digest> FramedMessage
+    for crate::keygen::ThresholdMsg<E, L, D>
+{
+    const PROTOCOL_KIND: ProtocolKind = ProtocolKind::ThresholdKeygen;
+}
+impl<D: Digest, L: SecurityLevel> FramedMessage for crate::key_refresh::AuxOnlyMsg<D, L> {
+    const PROTOCOL_KIND: ProtocolKind = ProtocolKind::AuxInfoGen;
+}
+impl<E: Curve, D: Digest, L: SecurityLevel> FramedMessage
+    for crate::key_refresh::NonThresholdMsg<E, D, L>
+{
+    const PROTOCOL_KIND: ProtocolKind = ProtocolKind::KeyRefresh;
+}
+impl<E: Curve, D: Digest> FramedMessage for crate::signing::msg::Msg<E, D> {
+    const PROTOCOL_KIND: ProtocolKind = ProtocolKind::Signing;
+}
+impl<E: Curve, D: Digest> FramedMessage for crate::enroll::Msg<E, D> {
+    const PROTOCOL_KIND: ProtocolKind = ProtocolKind::Enroll;
+}
+impl<E: Curve, D: Digest> FramedMessage for crate::reshare::Msg<E, D> {
+    const PROTOCOL_KIND: ProtocolKind = ProtocolKind::Reshare;
+}
+
+/// Header of [framed](frame) message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameHeader {
+    /// Protocol which message belongs to
+    pub protocol_kind: ProtocolKind,
+    /// [Digest](ExecutionId::digest) of execution ID computed with SHA256
+    pub execution_id_digest: [u8; 32],
+    /// Round which message belongs to
+    pub round: u16,
+}
+
+impl FrameHeader {
+    /// Size of encoded header in bytes
+    pub const SIZE: usize = 1 + 32 + 2;
+
+    /// Constructs a header of the message
+    pub fn new<M: FramedMessage>(msg: &M, eid: ExecutionId) -> Self {
+        Self {
+            protocol_kind: M::PROTOCOL_KIND,
+            execution_id_digest: eid.digest::<sha2::Sha256>(),
+            round: msg.round(),
+        }
+    }
+
+    /// Encodes the header
+    ///
+    /// Header is encoded as protocol kind (1 byte), execution ID digest (32 bytes), and round
+    /// (2 bytes, big-endian)
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0] = self.protocol_kind as u8;
+        bytes[1..33].copy_from_slice(&self.execution_id_digest);
+        bytes[33..].copy_from_slice(&self.round.to_be_bytes());
+        bytes
+    }
+
+    /// Parses a header of framed message
+    ///
+    /// Only the header is parsed, the message that follows it is left untouched.
+    pub fn parse(frame: &[u8]) -> Result<Self, CodecError> {
+        let header = frame.get(..Self::SIZE).ok_or(CodecError::InvalidHeader)?;
+        Ok(Self {
+            protocol_kind: ProtocolKind::from_byte(header[0]).ok_or(CodecError::InvalidHeader)?,
+            execution_id_digest: header[1..33]
+                .try_into()
+                .map_err(|_| CodecError::InvalidHeader)?,
+            round: u16::from_be_bytes(
+                header[33..]
+                    .try_into()
+                    .map_err(|_| CodecError::InvalidHeader)?,
+            ),
+        })
+    }
+}
+
+/// Encodes a message and prepends it with [`FrameHeader`]
+///
+/// Returns [`CodecError::TooLarge`] if the whole frame is longer than `max_len` bytes.
+pub fn frame<M: FramedMessage>(
+    msg: &M,
+    eid: ExecutionId,
+    max_len: usize,
+) -> Result<Vec<u8>, CodecError> {
+    let header = FrameHeader::new(msg, eid);
+    let body = encode_message(msg, max_len.saturating_sub(FrameHeader::SIZE))?;
+    let mut frame = Vec::with_capacity(FrameHeader::SIZE + body.len());
+    frame.extend_from_slice(&header.to_bytes());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+/// Decodes a message framed via [`frame`]
+///
+/// Returns [`CodecError::TooLarge`] if `frame` is longer than `max_len`, and
+/// [`CodecError::HeaderMismatch`] if the header doesn't correspond to the decoded message.
+pub fn deframe<M: FramedMessage>(
+    frame: &[u8],
+    max_len: usize,
+) -> Result<(FrameHeader, M), CodecError> {
+    if frame.len() > max_len {
+        return Err(CodecError::TooLarge {
+            len: frame.len(),
+            max_len,
+        });
+    }
+    let header = FrameHeader::parse(frame)?;
+    if header.protocol_kind != M::PROTOCOL_KIND {
+        return Err(CodecError::HeaderMismatch);
+    }
+    let msg: M = decode_message(&frame[FrameHeader::SIZE..], max_len)?;
+    if msg.round() != header.round {
+        return Err(CodecError::HeaderMismatch);
+    }
+    Ok((header, msg))
+}
+
+/// Error returned by functions in [codec module](self)
+#[derive(Debug, Error)]
+pub enum CodecError {
+    /// Message exceeds size limit
+    #[error("message is too large: {len} bytes, limit is {max_len} bytes")]
+    TooLarge {
+        /// Size of the message
+        len: usize,
+        /// Size limit
+        max_len: usize,
+    },
+    /// Encoded message is followed by trailing bytes
+    #[error("message is followed by {0} trailing bytes")]
+    TrailingBytes(usize),
+    /// Frame is too short or has unknown protocol kind
+    #[error("frame header is invalid")]
+    InvalidHeader,
+    /// Frame header doesn't correspond to the message
+    #[error("frame header doesn't match the message")]
+    HeaderMismatch,
+    /// Message couldn't be encoded
+    #[error("couldn't encode message")]
+    Encode(#[source] CborError),
+    /// Message couldn't be decoded
+    #[error("couldn't decode message")]
+    Decode(#[source] CborError),
+}
+
+/// Error returned by underlying CBOR implementation
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct CborError(Box<dyn std::error::Error + Send + Sync>);
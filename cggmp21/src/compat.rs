@@ -0,0 +1,5 @@
+//! Compatibility with other threshold ECDSA implementations
+//!
+//! Requires `compat-gg20` feature.
+
+pub mod gg20;
@@ -0,0 +1,212 @@
+//! Public portion of key share in GG20 format
+//!
+//! Converts public data of the key share into the layout used by GG20 implementation of
+//! [multi-party-ecdsa] (`LocalKey` struct), so tools built for GG20 keys (e.g. monitoring)
+//! keep working during the migration. Only public data is mapped, secret shares are not
+//! interoperable.
+//!
+//! ## Mapping
+//! | GG20 `LocalKey` field             | cggmp21                                                           |
+//! |-----------------------------------|-------------------------------------------------------------------|
+//! | `y_sum_s`                         | [`shared_public_key`](crate::key_share::DirtyKeyInfo::shared_public_key) |
+//! | `pk_vec[j]`                       | [`public_shares[j]`](crate::key_share::DirtyKeyInfo::public_shares), see below |
+//! | `vss_scheme.commitments`          | [`commitment_polynomial`](crate::key_share::KeyInfo::commitment_polynomial) |
+//! | `t`, `vss_scheme.parameters.threshold` | [`min_signers`](crate::key_share::KeyInfo::min_signers) minus one |
+//! | `n`, `vss_scheme.parameters.share_count` | [`n`](crate::key_share::KeyInfo::n)                      |
+//!
+//! GG20 shares the key with a polynomial evaluated at points $1, \dots, n$, and the party with index
+//! `j` in cggmp21 has index `j+1` in GG20.
+//! * Polynomial key shares are mapped as is, but only if share preimages are $I_j = j+1$ (which is
+//!   the case for keys generated by [keygen](mod@crate::keygen) and [trusted dealer](crate::trusted_dealer)).
+//! * Additive (non-threshold) key shares are mapped to $n$-out-of-$n$ polynomial shares:
+//!   `pk_vec[j]` is $\lambda_j^{-1} \cdot X_j$, where $X_j$ is the public share and $\lambda_j$ is
+//!   the Lagrange coefficient of point $j+1$. Such public shares lie on the same polynomial of degree
+//!   $n-1$ that evaluates to the shared public key at zero.
+//! * Weighted key shares are not supported.
+//!
+//! Following `LocalKey` fields are not mapped: `i` (index of the local party), `keys_linear` (secret
+//! share), `paillier_dk`, `paillier_key_vec` and `h1_h2_n_tilde_vec` (auxiliary data, CGGMP21 uses
+//! different parameters). HD wallet chain code has no GG20 counterpart.
+//!
+//! Points are serialized the same way as `curv` does: a struct with curve name and hex-encoded
+//! compressed point.
+//!
+//! [multi-party-ecdsa]: https://github.com/ZenGo-X/multi-party-ecdsa
+
+use generic_ec::{Curve, NonZero, Point, Scalar};
+use generic_ec_zkp::polynomial::lagrange_coefficient_at_zero;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::key_share::{KeyInfo, Validate, VssSetup};
+
+/// Public portion of GG20 `LocalKey`
+///
+/// See [module level documentation](self) for details on how fields are mapped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PublicKeys<E: Curve> {
+    /// Shared public key
+    pub y_sum_s: CurvPoint<E>,
+    /// Public shares of parties
+    pub pk_vec: Vec<CurvPoint<E>>,
+    /// Feldman commitment to the polynomial that secret key is shared with
+    pub vss_scheme: VerifiableSS<E>,
+    /// GG20 threshold: signing requires `t+1` parties
+    pub t: u16,
+    /// Amount of parties
+    pub n: u16,
+}
+
+/// Feldman VSS scheme as defined in `curv`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct VerifiableSS<E: Curve> {
+    /// Parameters of secret sharing
+    pub parameters: ShamirSecretSharing,
+    /// Commitments to the polynomial coefficients, from the lowest to the highest degree
+    pub commitments: Vec<CurvPoint<E>>,
+}
+
+/// Parameters of Shamir secret sharing as defined in `curv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShamirSecretSharing {
+    /// Degree of the polynomial, i.e. `t`
+    pub threshold: u16,
+    /// Amount of shares, i.e. `n`
+    pub share_count: u16,
+}
+
+/// Point serialized as in `curv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "", try_from = "CurvPointRepr", into = "CurvPointRepr")]
+pub struct CurvPoint<E: Curve>(pub Point<E>);
+
+#[derive(Serialize, Deserialize)]
+struct CurvPointRepr {
+    curve: String,
+    #[serde(with = "hex::serde")]
+    point: Vec<u8>,
+}
+
+impl<E: Curve> From<CurvPoint<E>> for CurvPointRepr {
+    fn from(p: CurvPoint<E>) -> Self {
+        Self {
+            curve: E::CURVE_NAME.into(),
+            point: p.0.to_bytes(true).to_vec(),
+        }
+    }
+}
+
+impl<E: Curve> TryFrom<CurvPointRepr> for CurvPoint<E> {
+    type Error = String;
+    fn try_from(p: CurvPointRepr) -> Result<Self, Self::Error> {
+        if p.curve != E::CURVE_NAME {
+            return Err(format!("expected curve {}, got {}", E::CURVE_NAME, p.curve));
+        }
+        Point::from_bytes(&p.point)
+            .map(CurvPoint)
+            .map_err(|_| "invalid point".into())
+    }
+}
+
+impl<E: Curve> From<NonZero<Point<E>>> for CurvPoint<E> {
+    fn from(p: NonZero<Point<E>>) -> Self {
+        Self(*p)
+    }
+}
+
+/// Converts public data of the key share into GG20 format
+///
+/// Accepts [`IncompleteKeyShare`](crate::key_share::IncompleteKeyShare) or [`KeyInfo`]. Returns an error if key can not be represented in GG20 format.
+/// See [module level documentation](self) for details.
+pub fn public_keys<E: Curve>(
+    key_share: &impl AsRef<KeyInfo<E>>,
+) -> Result<PublicKeys<E>, IncompatibleKey> {
+    let key_info = key_share.as_ref();
+    let n = key_info.n();
+    let gg20_indexes = (1..=n).map(Scalar::<E>::from).collect::<Vec<_>>();
+
+    let polynomial_key = match &key_info.vss_setup {
+        Some(vss_setup) => {
+            if vss_setup.weights.is_some() {
+                return Err(Reason::Weighted.into());
+            }
+            if !vss_setup
+                .I
+                .iter()
+                .zip(&gg20_indexes)
+                .all(|(I_j, index)| **I_j == *index)
+            {
+                return Err(Reason::NonStandardIndexes.into());
+            }
+            key_info.clone()
+        }
+        None => {
+            // Map additive shares onto polynomial of degree n-1
+            let public_shares = key_info
+                .public_shares
+                .iter()
+                .enumerate()
+                .map(|(j, X_j)| {
+                    let lambda_j = lagrange_coefficient_at_zero(j, &gg20_indexes)?;
+                    Some(X_j * lambda_j.invert())
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or(Reason::Interpolation)?;
+            let mut key_info = key_info.clone().into_inner();
+            key_info.public_shares = public_shares;
+            key_info.vss_setup = Some(VssSetup {
+                min_signers: n,
+                I: gg20_indexes
+                    .iter()
+                    .map(|index| NonZero::from_scalar(*index))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(Reason::Interpolation)?,
+                weights: None,
+            });
+            key_info
+                .validate()
+                .map_err(|err| Reason::InvalidMappedKey(err.into_error()))?
+        }
+    };
+
+    let commitments = polynomial_key
+        .commitment_polynomial()
+        .ok_or(Reason::Interpolation)?;
+    let t = polynomial_key.min_signers() - 1;
+    Ok(PublicKeys {
+        y_sum_s: polynomial_key.shared_public_key.into(),
+        pk_vec: polynomial_key
+            .public_shares
+            .iter()
+            .map(|X_j| CurvPoint::from(*X_j))
+            .collect(),
+        vss_scheme: VerifiableSS {
+            parameters: ShamirSecretSharing {
+                threshold: t,
+                share_count: n,
+            },
+            commitments: commitments.into_iter().map(CurvPoint).collect(),
+        },
+        t,
+        n,
+    })
+}
+
+/// Error indicating that key can not be represented in GG20 format
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct IncompatibleKey(#[from] Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("weighted key shares are not supported")]
+    Weighted,
+    #[error("share preimages are not 1, ..., n")]
+    NonStandardIndexes,
+    #[error("interpolation failed")]
+    Interpolation,
+    #[error("mapped key is invalid")]
+    InvalidMappedKey(#[source] crate::key_share::InvalidIncompleteKeyShare),
+}
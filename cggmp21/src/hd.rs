@@ -0,0 +1,60 @@
+//! HD derivation helpers
+//!
+//! Helpers work with [extended public key](crate::key_share::DirtyKeyInfo::extended_public_key) of
+//! the key share (or any other extended public key), they don't require any interaction between
+//! the signers.
+
+use core::ops::Range;
+
+use generic_ec::{Curve, Point};
+use hd_wallet::{errors::OutOfRange, ExtendedPublicKey, HdWallet, NonHardenedIndex};
+
+/// Derives public keys of children with indexes in `range`
+///
+/// It's a convenience wrapper that derives each child independently, exactly as
+/// [`HdWallet::derive_child_public_key`] does. No state is shared between the children, so it's
+/// not any faster than deriving them one by one.
+///
+/// Each `i`-th output equals to public key derived at path `[range.start + i]` from `xpub`, i.e.
+/// the same key that would be used to verify a signature produced with derivation path set to
+/// `[range.start + i]`. To derive children of a longer path (e.g. `m/0/*`), derive the parent
+/// via [`HdWallet::derive_child_public_key_with_path`] and pass it as `xpub`.
+///
+/// Returns error if `range` contains hardened indexes (i.e. `range.end > 2^31`).
+///
+/// ## Derivation algorithm
+/// This function uses [`hd_wallet::Slip10`] derivation algorithm, which can only be used with secp256k1
+/// and secp256r1 curves. If you need to use another one, see
+/// [`derive_child_public_keys_with_algo`]
+#[cfg(feature = "hd-slip10")]
+pub fn derive_child_public_keys<E: Curve>(
+    xpub: &ExtendedPublicKey<E>,
+    range: Range<u32>,
+) -> Result<Vec<Point<E>>, OutOfRange>
+where
+    hd_wallet::Slip10: HdWallet<E>,
+{
+    derive_child_public_keys_with_algo::<hd_wallet::Slip10, E>(xpub, range)
+}
+
+/// Derives public keys of children with indexes in `range`, using HD derivation algorithm
+/// [`HdWallet`]
+///
+/// See [`derive_child_public_keys`] for details.
+pub fn derive_child_public_keys_with_algo<Hd: HdWallet<E>, E: Curve>(
+    xpub: &ExtendedPublicKey<E>,
+    range: Range<u32>,
+) -> Result<Vec<Point<E>>, OutOfRange> {
+    // Validate the whole range before doing any computation
+    if !range.is_empty() {
+        NonHardenedIndex::try_from(range.end - 1)?;
+    }
+    range
+        .map(|index| {
+            let index = NonHardenedIndex::try_from(index)?;
+            Ok(Hd::derive_public_shift(xpub, index)
+                .child_public_key
+                .public_key)
+        })
+        .collect()
+}
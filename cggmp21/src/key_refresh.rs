@@ -7,19 +7,27 @@ mod non_threshold;
 
 use digest::Digest;
 use generic_ec::Curve;
+use paillier_zk::IntegerExt;
 use rand_core::{CryptoRng, RngCore};
 use round_based::Mpc;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
     errors::IoError,
     key_share::{AnyKeyShare, AuxInfo, DirtyIncompleteKeyShare, KeyShare},
+    modexp::{ModExp, ModExpBackend},
     progress::Tracer,
+    round_observer::RoundObserver,
     security_level::SecurityLevel,
     utils::AbortBlame,
     ExecutionId,
 };
-use crate::{fast_paillier, rug::Integer};
+use crate::{
+    fast_paillier,
+    rug::{Complete, Integer},
+    zk::ring_pedersen_parameters as π_prm,
+};
 
 #[doc(no_inline)]
 pub use self::msg::{aux_only::Msg as AuxOnlyMsg, non_threshold::Msg as NonThresholdMsg};
@@ -74,6 +82,8 @@ impl<L: SecurityLevel> PregeneratedPrimes<L> {
     }
 
     /// Generates primes. Takes some time.
+    ///
+    /// Candidates are tested for primality with [`DEFAULT_MR_ROUNDS`] Miller-Rabin rounds.
     pub fn generate<R: RngCore>(rng: &mut R) -> Self {
         Self {
             p: fast_paillier::utils::generate_safe_prime(rng, 4 * L::SECURITY_BITS),
@@ -81,6 +91,357 @@ impl<L: SecurityLevel> PregeneratedPrimes<L> {
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Generates primes, testing at most `max_candidates` candidates for primality
+    ///
+    /// Primality testing of candidates takes most of the time of primes generation, so limiting
+    /// the amount of candidates bounds the time spent in one call. If primes were not found within
+    /// the budget, returns [`BudgetExhausted`] which keeps the progress made so far and can be
+    /// [resumed](BudgetExhausted::resume) later. It lets a scheduler run primes generation as a
+    /// cooperative background task.
+    ///
+    /// Finding a safe prime takes, on average, a few thousands of candidates for the default security
+    /// level.
+    pub fn generate_with_budget<R: RngCore>(
+        rng: &mut R,
+        max_candidates: usize,
+    ) -> Result<Self, BudgetExhausted<L>> {
+        BudgetExhausted {
+            p: None,
+            _phantom: std::marker::PhantomData,
+        }
+        .resume(rng, max_candidates)
+    }
+
+    /// Generates primes in batches of candidates, backing off between the batches
+    ///
+    /// Tests at most [`candidates_per_attempt`](PrimeGenRetry::candidates_per_attempt) candidates
+    /// for primality in each attempt. If primes were not found yet, the progress is kept, and the
+    /// thread sleeps before the next attempt. The delay starts at
+    /// [`initial_backoff`](PrimeGenRetry::initial_backoff) and is doubled after each attempt (up to
+    /// [`max_backoff`](PrimeGenRetry::max_backoff)), which gives a starving entropy source time to
+    /// recover. Each attempt and backoff is reported to the `tracer`.
+    ///
+    /// Unlike [`generate`](Self::generate), never runs indefinitely: returns
+    /// [`PrimeGenError::ExhaustedAttempts`] if primes were not found within
+    /// [`max_attempts`](PrimeGenRetry::max_attempts) attempts.
+    pub fn generate_with_retries<R: RngCore>(
+        rng: &mut R,
+        retry: PrimeGenRetry,
+        mut tracer: Option<&mut dyn Tracer>,
+    ) -> Result<Self, PrimeGenError> {
+        let mut exhausted = BudgetExhausted {
+            p: None,
+            _phantom: std::marker::PhantomData,
+        };
+        let mut backoff = retry.initial_backoff;
+        for attempt in 0..retry.max_attempts {
+            if attempt > 0 {
+                tracer.stage("Back off before the next batch of candidates");
+                std::thread::sleep(backoff);
+                backoff = backoff.saturating_mul(2).min(retry.max_backoff);
+            }
+            tracer.stage("Test a batch of candidates for primality");
+            match exhausted.resume(rng, retry.candidates_per_attempt) {
+                Ok(primes) => return Ok(primes),
+                Err(progress) => exhausted = progress,
+            }
+        }
+        Err(PrimeGenError::ExhaustedAttempts {
+            attempts: retry.max_attempts,
+        })
+    }
+
+    /// Generates primes, testing candidates for primality with `rounds` Miller-Rabin rounds
+    ///
+    /// More rounds give stronger confidence that generated numbers are primes, at the cost of
+    /// speed. [`generate`](Self::generate) uses [`DEFAULT_MR_ROUNDS`].
+    ///
+    /// Returns `None` if `rounds` is less than [`MIN_MR_ROUNDS`].
+    pub fn generate_with_mr_rounds<R: RngCore>(rng: &mut R, rounds: u32) -> Option<Self> {
+        if rounds < MIN_MR_ROUNDS {
+            return None;
+        }
+        let bits = 4 * L::SECURITY_BITS;
+        let mut budget = usize::MAX;
+        let p = generate_safe_prime_with_budget(rng, bits, rounds, &mut budget)?;
+        let q = generate_safe_prime_with_budget(rng, bits, rounds, &mut budget)?;
+        Some(Self {
+            p,
+            q,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+}
+
+/// Amount of Miller-Rabin rounds used by [`PregeneratedPrimes::generate`]
+pub const DEFAULT_MR_ROUNDS: u32 = 25;
+/// Minimal amount of Miller-Rabin rounds accepted by [`PregeneratedPrimes::generate_with_mr_rounds`]
+///
+/// Lower bound of the range recommended by GMP for its primality test.
+pub const MIN_MR_ROUNDS: u32 = 15;
+
+/// Retry policy of [`PregeneratedPrimes::generate_with_retries`]
+#[derive(Debug, Clone, Copy)]
+pub struct PrimeGenRetry {
+    /// Maximum amount of attempts (batches of candidates)
+    pub max_attempts: u32,
+    /// Amount of candidates tested for primality in one attempt
+    pub candidates_per_attempt: usize,
+    /// Delay between the first and the second attempts
+    pub initial_backoff: std::time::Duration,
+    /// Upper bound of the delay between attempts
+    pub max_backoff: std::time::Duration,
+}
+
+impl Default for PrimeGenRetry {
+    /// 16 attempts of 10 000 candidates each, backoff starts at 10ms and is capped at 1s
+    fn default() -> Self {
+        Self {
+            max_attempts: 16,
+            candidates_per_attempt: 10_000,
+            initial_backoff: std::time::Duration::from_millis(10),
+            max_backoff: std::time::Duration::from_secs(1),
+        }
+    }
+}
+
+/// Error indicating that primes generation failed
+#[derive(Debug, Error)]
+pub enum PrimeGenError {
+    /// Primes were not found within allowed amount of attempts
+    #[error("primes were not found within {attempts} attempts")]
+    ExhaustedAttempts {
+        /// Amount of attempts made
+        attempts: u32,
+    },
+}
+
+/// Error indicating that primes were not found within given budget
+///
+/// Returned by [`PregeneratedPrimes::generate_with_budget`]. Keeps the progress made so far, so
+/// generation can be [resumed](Self::resume).
+///
+/// **Contains secret data** once the first prime is found.
+#[derive(Error)]
+#[error("primes generation budget is exhausted")]
+pub struct BudgetExhausted<L = crate::default_choice::SecurityLevel> {
+    p: Option<Integer>,
+    _phantom: std::marker::PhantomData<L>,
+}
+
+impl<L: SecurityLevel> BudgetExhausted<L> {
+    /// Continues primes generation, testing at most `max_candidates` candidates for primality
+    pub fn resume<R: RngCore>(
+        mut self,
+        rng: &mut R,
+        max_candidates: usize,
+    ) -> Result<PregeneratedPrimes<L>, Self> {
+        let mut budget = max_candidates;
+        let bits = 4 * L::SECURITY_BITS;
+        let p = match self.p.take() {
+            Some(p) => p,
+            None => {
+                match generate_safe_prime_with_budget(rng, bits, DEFAULT_MR_ROUNDS, &mut budget) {
+                    Some(p) => p,
+                    None => return Err(self),
+                }
+            }
+        };
+        match generate_safe_prime_with_budget(rng, bits, DEFAULT_MR_ROUNDS, &mut budget) {
+            Some(q) => Ok(PregeneratedPrimes {
+                p,
+                q,
+                _phantom: std::marker::PhantomData,
+            }),
+            None => {
+                self.p = Some(p);
+                Err(self)
+            }
+        }
+    }
+
+    /// Returns amount of primes found so far (out of 2)
+    pub fn found_primes(&self) -> usize {
+        usize::from(self.p.is_some())
+    }
+}
+
+impl<L> std::fmt::Debug for BudgetExhausted<L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BudgetExhausted")
+            .field("found_primes", &usize::from(self.p.is_some()))
+            .finish()
+    }
+}
+
+/// Generates a safe prime of `bits` bits, testing at most `budget` candidates for primality
+///
+/// Follows [`fast_paillier::utils::generate_safe_prime`]: candidates are sieved by small primes
+/// before they're tested with `mr_rounds` Miller-Rabin rounds. Decreases `budget` by amount of
+/// tested candidates. Returns `None` if the budget is exhausted.
+fn generate_safe_prime_with_budget<R: RngCore>(
+    rng: &mut R,
+    bits: u32,
+    mr_rounds: u32,
+    budget: &mut usize,
+) -> Option<Integer> {
+    use crate::rug::{integer::IsPrime, Assign};
+
+    // Same amount of small primes as used by `fast_paillier::utils::generate_safe_prime`
+    let small_primes = (3u32..)
+        .step_by(2)
+        .filter(|n| {
+            (3..)
+                .step_by(2)
+                .take_while(|d| d * d <= *n)
+                .all(|d| n % d != 0)
+        })
+        .take(135)
+        .collect::<Vec<_>>();
+
+    let mut rng = crate::utils::external_rand(rng);
+    let mut x = Integer::new();
+    while *budget > 0 {
+        // Generate an odd number `x` of length `bits - 1`, so that `p = 2x + 1` has length `bits`
+        x.assign(Integer::random_bits(bits - 1, &mut rng));
+        x.set_bit(bits - 2, true);
+        x |= 1u32;
+
+        // Skip `x` if either `x` or `2x + 1` is divisible by a small prime
+        if small_primes.iter().any(|&small_prime| {
+            let r = x.mod_u(small_prime);
+            r == 0 || r == (small_prime - 1) / 2
+        }) {
+            continue;
+        }
+
+        *budget -= 1;
+        if x.is_probably_prime(mr_rounds) != IsPrime::No {
+            let p = (x.clone() << 1u32) + 1u32;
+            if p.is_probably_prime(mr_rounds) != IsPrime::No {
+                return Some(p);
+            }
+        }
+    }
+    None
+}
+
+/// Part of aux info generation that can be computed ahead of time
+///
+/// Generating auxiliary parameters and proving their correctness takes a significant part
+/// of aux info generation. It doesn't depend on other parties, so it can be done before
+/// interactive protocol begins via [`precompute_aux_info`], and then provided to the protocol
+/// via [`set_precomputed`](GenericKeyRefreshBuilder::set_precomputed).
+///
+/// Precomputed data is bound to pregenerated primes it was computed from: protocol refuses to
+/// start if it's used with different primes.
+///
+/// **Contains secret data.** Keep it as secret as the primes. Never use the same precomputed
+/// data in more than one protocol execution: it leaks the secret exponent $\lambda$ otherwise.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct AuxPrecompute<L = crate::default_choice::SecurityLevel> {
+    N: Integer,
+    s: Integer,
+    t: Integer,
+    lambda: Integer,
+    prm_commitment: π_prm::Commitment<{ crate::security_level::M }>,
+    _phantom: std::marker::PhantomData<L>,
+}
+
+/// Computes part of aux info generation ahead of time
+///
+/// Takes the same primes that will be given to aux info generation. See [`AuxPrecompute`].
+pub fn precompute_aux_info<L, R>(
+    rng: &mut R,
+    pregenerated: &PregeneratedPrimes<L>,
+) -> Result<AuxPrecompute<L>, KeyRefreshError>
+where
+    L: SecurityLevel,
+    R: RngCore + CryptoRng,
+{
+    let PregeneratedPrimes { p, q, .. } = pregenerated;
+    let N = (p * q).complete();
+    let phi_N = (p - 1u8).complete() * (q - 1u8).complete();
+
+    let r = Integer::gen_invertible(&N, rng);
+    let lambda = phi_N
+        .random_below_ref(&mut crate::utils::external_rand(rng))
+        .into();
+    let t = r.square().modulo(&N);
+    let s = t.pow_mod_ref(&lambda, &N).ok_or(Bug::PowMod)?.into();
+
+    let prm_commitment = π_prm::commit(
+        rng,
+        π_prm::Data {
+            N: &N,
+            s: &s,
+            t: &t,
+        },
+        &phi_N,
+        ModExp::default(),
+    )
+    .map_err(Bug::PiPrm)?;
+
+    Ok(AuxPrecompute {
+        N,
+        s,
+        t,
+        lambda,
+        prm_commitment,
+        _phantom: std::marker::PhantomData,
+    })
+}
+
+/// Estimates how long aux info generation takes for `n` parties
+///
+/// Estimation is based on amount of modular exponentiations performed by a party during the protocol
+/// (mostly when proving and verifying ZK proofs), multiplied by the time that one exponentiation takes
+/// on current hardware. It's only meant to give an order of magnitude: it doesn't account for network
+/// latency, and it assumes that primes are [pregenerated](PregeneratedPrimes::generate) in advance.
+///
+/// The first call measures time of modular exponentiation which takes few dozens of milliseconds.
+/// The measurement is cached, so subsequent calls are cheap.
+pub fn estimate_aux_info_gen_duration<L: SecurityLevel>(n: u16) -> std::time::Duration {
+    // Amount of exponentiations that π_fac takes to prove and to verify (roughly)
+    const FAC_EXPS: u32 = 14;
+
+    let m = crate::security_level::M as u32;
+    let others = u32::from(n.saturating_sub(1));
+    // In terms of exponentiations modulo N with |N|-bit exponent, where N is paillier modulus:
+    // * π_prm takes M exponentiations to prove, and M to verify each proof of other parties
+    // * π_mod takes about M/4 to prove (fourth roots are computed modulo p and q), and M to
+    //   verify each proof of other parties
+    // * π_fac is proven and verified for each other party
+    let exps = m + m / 4 + others * (2 * m + FAC_EXPS);
+
+    modexp_duration(8 * L::SECURITY_BITS) * exps
+}
+
+/// Estimates time of computing `x^e mod N` where `x`, `e` and `N` are `bits` long
+fn modexp_duration(bits: u32) -> std::time::Duration {
+    const REFERENCE_BITS: u32 = 2048;
+    const ROUNDS: u32 = 10;
+    static REFERENCE: std::sync::OnceLock<std::time::Duration> = std::sync::OnceLock::new();
+
+    let reference = *REFERENCE.get_or_init(|| {
+        let mut rand = crate::rug::rand::RandState::new();
+        let mut modulus = Integer::from(Integer::random_bits(REFERENCE_BITS, &mut rand));
+        modulus.set_bit(REFERENCE_BITS - 1, true);
+        modulus.set_bit(0, true);
+        let base = Integer::from(modulus.random_below_ref(&mut rand));
+        let exponent = Integer::from(Integer::random_bits(REFERENCE_BITS, &mut rand));
+
+        let start = std::time::Instant::now();
+        for _ in 0..ROUNDS {
+            std::hint::black_box(base.pow_mod_ref(&exponent, &modulus).map(Integer::from));
+        }
+        start.elapsed() / ROUNDS
+    });
+
+    // Modular exponentiation takes cubic time in size of the modulus
+    reference.mul_f64((f64::from(bits) / f64::from(REFERENCE_BITS)).powi(3))
 }
 
 /// A variant of [`GenericKeyRefreshBuilder`] that performs key refresh
@@ -109,9 +470,14 @@ where
     execution_id: ExecutionId<'a>,
     pregenerated: PregeneratedPrimes<L>,
     tracer: Option<&'a mut dyn Tracer>,
+    round_observer: Option<&'a mut dyn RoundObserver>,
     enforce_reliable_broadcast: bool,
     precompute_multiexp_tables: bool,
     precompute_crt: bool,
+    precomputed: Option<AuxPrecompute<L>>,
+    modexp: Option<&'a dyn ModExpBackend>,
+    party_identity: Option<&'a [u8]>,
+    peer_identities: Option<&'a [&'a [u8]]>,
     _digest: std::marker::PhantomData<D>,
 }
 
@@ -142,9 +508,14 @@ where
             execution_id: eid,
             pregenerated,
             tracer: None,
+            round_observer: None,
             enforce_reliable_broadcast: true,
             precompute_multiexp_tables: false,
             precompute_crt: false,
+            precomputed: None,
+            modexp: None,
+            party_identity: None,
+            peer_identities: None,
             _digest: std::marker::PhantomData,
         }
     }
@@ -158,15 +529,23 @@ where
         L: SecurityLevel,
         D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
     {
+        if !crate::security_level::is_compatible_with_curve::<L, E>() {
+            return Err(KeyRefreshError(Reason::IncompatibleLevelForCurve {
+                ell: L::ELL,
+                curve_order_bits: crate::security_level::curve_order_bits::<E>(),
+            }));
+        }
         non_threshold::run_refresh(
             rng,
             party,
             self.execution_id,
             self.pregenerated,
             self.tracer,
+            self.round_observer,
             self.enforce_reliable_broadcast,
             self.precompute_multiexp_tables,
             self.precompute_crt,
+            ModExp::new(self.modexp),
             self.target.0,
         )
         .await
@@ -212,9 +591,14 @@ where
             execution_id: eid,
             pregenerated,
             tracer: None,
+            round_observer: None,
             enforce_reliable_broadcast: true,
             precompute_multiexp_tables: false,
             precompute_crt: false,
+            precomputed: None,
+            modexp: None,
+            party_identity: None,
+            peer_identities: None,
             _digest: std::marker::PhantomData,
         }
     }
@@ -235,13 +619,55 @@ where
             self.execution_id,
             self.pregenerated,
             self.tracer,
+            self.round_observer,
             self.enforce_reliable_broadcast,
             self.precompute_multiexp_tables,
             self.precompute_crt,
+            self.precomputed,
+            ModExp::new(self.modexp),
+            self.party_identity,
+            self.peer_identities,
         )
         .await
     }
 
+    /// Provides data precomputed via [`precompute_aux_info`]
+    ///
+    /// Speeds up the protocol: the most expensive part of round 1 is skipped. Precomputed data
+    /// must be computed from the same primes that were given to the builder, otherwise protocol
+    /// returns an error without sending any messages.
+    pub fn set_precomputed(mut self, precomputed: AuxPrecompute<L>) -> Self {
+        self.precomputed = Some(precomputed);
+        self
+    }
+
+    /// Binds party to its network identity
+    ///
+    /// Works the same way as [in keygen](crate::keygen::GenericKeygenBuilder::set_party_identity):
+    /// `identity` is committed in the first round and revealed in the second one. Other parties
+    /// may check it via [`set_peer_identities`](Self::set_peer_identities).
+    pub fn set_party_identity(mut self, identity: &'a [u8]) -> Self {
+        self.party_identity = Some(identity);
+        self
+    }
+
+    /// Specifies expected network identities of the parties
+    ///
+    /// `identities[j]` is an identity that j-th party must have set via
+    /// [`set_party_identity`](Self::set_party_identity). Identity of the local party is ignored.
+    /// If any party committed to a different identity (or didn't commit to any), protocol is
+    /// aborted and the party is blamed.
+    ///
+    /// If local party has set its identity as well, output aux info records
+    /// [commitment](crate::key_share::DirtyAuxInfo::party_set_commitment) to identities of all
+    /// parties. When aux info is combined with key info via [`KeyShare::from_parts`], the
+    /// commitment is checked against the one recorded at keygen (if any), which assures that both
+    /// protocols were run by the same parties. Both protocols must use the same digest.
+    pub fn set_peer_identities(mut self, identities: &'a [&'a [u8]]) -> Self {
+        self.peer_identities = Some(identities);
+        self
+    }
+
     /// Returns a state machine that can be used to carry out the aux info generation protocol
     ///
     /// See [`round_based::state_machine`] for details on how that can be done.
@@ -274,9 +700,14 @@ where
             execution_id: self.execution_id,
             pregenerated: self.pregenerated,
             tracer: self.tracer,
+            round_observer: self.round_observer,
             enforce_reliable_broadcast: self.enforce_reliable_broadcast,
             precompute_multiexp_tables: self.precompute_multiexp_tables,
             precompute_crt: self.precompute_crt,
+            precomputed: self.precomputed,
+            modexp: self.modexp,
+            party_identity: self.party_identity,
+            peer_identities: self.peer_identities,
             _digest: std::marker::PhantomData,
         }
     }
@@ -287,6 +718,14 @@ where
         self
     }
 
+    /// Sets an observer that is notified each time a message is received
+    ///
+    /// See [`round_observer`](crate::round_observer) module for details.
+    pub fn set_round_observer(mut self, observer: &'a mut dyn RoundObserver) -> Self {
+        self.round_observer = Some(observer);
+        self
+    }
+
     #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
     pub fn enforce_reliable_broadcast(self, v: bool) -> Self {
         Self {
@@ -316,6 +755,15 @@ where
         self.precompute_crt = v;
         self
     }
+
+    /// Offloads modular exponentiations to the `backend`
+    ///
+    /// See [`modexp`](crate::modexp) module for the list of operations that are routed through
+    /// the backend.
+    pub fn set_modexp_backend(mut self, backend: &'a dyn ModExpBackend) -> Self {
+        self.modexp = Some(backend);
+        self
+    }
 }
 
 /// Error of key refresh and aux info generation protocols
@@ -323,6 +771,20 @@ where
 #[error("key refresh protocol failed to complete")]
 pub struct KeyRefreshError(#[source] Reason);
 
+impl KeyRefreshError {
+    /// Indicates whether the protocol refused to run as security level is not
+    /// [compatible](crate::security_level::is_compatible_with_curve) with the curve
+    pub fn is_incompatible_level_for_curve(&self) -> bool {
+        matches!(self.0, Reason::IncompatibleLevelForCurve { .. })
+    }
+
+    /// Indicates whether the protocol refused to run as [precomputed data](GenericKeyRefreshBuilder::set_precomputed)
+    /// was computed from different primes
+    pub fn is_precomputed_mismatch(&self) -> bool {
+        matches!(self.0, Reason::PrecomputedMismatch)
+    }
+}
+
 crate::errors::impl_from! {
     impl From for KeyRefreshError {
         err: ProtocolAborted => KeyRefreshError(Reason::Aborted(err)),
@@ -336,6 +798,10 @@ enum Reason {
     /// Protocol was maliciously aborted by another party
     #[error("protocol was aborted by malicious party")]
     Aborted(#[source] ProtocolAborted),
+    #[error("precomputed data was computed from different primes")]
+    PrecomputedMismatch,
+    #[error("security level (ell = {ell}) is not compatible with curve order of {curve_order_bits} bits")]
+    IncompatibleLevelForCurve { ell: usize, curve_order_bits: usize },
     #[error("i/o error")]
     IoError(#[source] IoError),
     #[error("internal error")]
@@ -402,6 +868,8 @@ enum ProtocolAbortReason {
     PaillierDec,
     #[error("round 1 was not reliable")]
     Round1NotReliable,
+    #[error("party committed to unexpected identity")]
+    IdentityMismatch,
 }
 
 macro_rules! make_factory {
@@ -428,4 +896,5 @@ impl ProtocolAborted {
     make_factory!(invalid_data_size, InvalidDataSize);
     make_factory!(paillier_dec, PaillierDec);
     make_factory!(round1_not_reliable, Round1NotReliable);
+    make_factory!(identity_mismatch, IdentityMismatch);
 }
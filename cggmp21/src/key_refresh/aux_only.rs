@@ -16,7 +16,9 @@ use serde::{Deserialize, Serialize};
 use crate::{
     errors::IoError,
     key_share::{AuxInfo, DirtyAuxInfo, PartyAux, Validate},
+    modexp::ModExp,
     progress::Tracer,
+    round_observer::{ObservedIncomings, RoundObserver},
     security_level::SecurityLevel,
     utils,
     utils::{collect_blame, AbortBlame},
@@ -24,7 +26,7 @@ use crate::{
     ExecutionId,
 };
 
-use super::{Bug, KeyRefreshError, PregeneratedPrimes, ProtocolAborted};
+use super::{AuxPrecompute, Bug, KeyRefreshError, PregeneratedPrimes, ProtocolAborted, Reason};
 
 macro_rules! prefixed {
     ($name:tt) => {
@@ -48,6 +50,18 @@ pub enum Msg<D: Digest, L: SecurityLevel> {
     ReliabilityCheck(MsgReliabilityCheck<D>),
 }
 
+impl<D: Digest, L: SecurityLevel> Msg<D, L> {
+    /// Name of the round the message belongs to
+    pub fn round_name(&self) -> &'static str {
+        match self {
+            Msg::Round1(_) => "round 1",
+            Msg::Round2(_) => "round 2",
+            Msg::Round3(_) => "round 3",
+            Msg::ReliabilityCheck(_) => "reliability check",
+        }
+    }
+}
+
 /// Message from round 1
 #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
 #[udigest(tag = prefixed!("round1"))]
@@ -59,6 +73,7 @@ pub struct MsgRound1<D: Digest> {
     pub commitment: digest::Output<D>,
 }
 /// Message from round 2
+#[serde_with::serde_as]
 #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
 #[udigest(tag = prefixed!("round2"))]
 #[udigest(bound = "")]
@@ -81,6 +96,10 @@ pub struct MsgRound2<L: SecurityLevel> {
     #[serde(with = "hex")]
     #[udigest(as_bytes)]
     pub rho_bytes: L::Rid,
+    /// Network identity of the party, if it was set
+    #[serde_as(as = "Option<serde_with::Bytes>")]
+    #[udigest(as = Option<udigest::Bytes>)]
+    pub identity: Option<Vec<u8>>,
     /// $u_i$
     #[serde(with = "hex")]
     #[udigest(as_bytes)]
@@ -161,9 +180,14 @@ pub async fn run_aux_gen<R, M, L, D>(
     sid: ExecutionId<'_>,
     pregenerated: PregeneratedPrimes<L>,
     mut tracer: Option<&mut dyn Tracer>,
+    round_observer: Option<&mut dyn RoundObserver>,
     reliable_broadcast_enforced: bool,
     compute_multiexp_table: bool,
     compute_crt: bool,
+    precomputed: Option<AuxPrecompute<L>>,
+    modexp: ModExp<'_>,
+    party_identity: Option<&[u8]>,
+    peer_identities: Option<&[&[u8]]>,
 ) -> Result<AuxInfo<L>, KeyRefreshError>
 where
     R: RngCore + CryptoRng,
@@ -174,6 +198,11 @@ where
     tracer.protocol_begins();
 
     tracer.stage("Retrieve auxiliary data");
+    let PregeneratedPrimes { p, q, .. } = pregenerated;
+    let N = (&p * &q).complete();
+    if precomputed.as_ref().is_some_and(|pre| pre.N != N) {
+        return Err(KeyRefreshError(Reason::PrecomputedMismatch));
+    }
 
     tracer.stage("Setup networking");
     let MpcParty { delivery, .. } = party.into_party();
@@ -184,38 +213,65 @@ where
     let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
     let round2 = rounds.add_round(RoundInput::<MsgRound2<L>>::broadcast(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3>::p2p(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let mut rounds = rounds.listen(ObservedIncomings::new(
+        incomings,
+        round_observer,
+        n,
+        Msg::round_name,
+    ));
 
     // Round 1
     tracer.round_begins();
 
-    tracer.stage("Retrieve primes (p and q)");
-    let PregeneratedPrimes { p, q, .. } = pregenerated;
-    tracer.stage("Compute paillier decryption key (N)");
-    let N = (&p * &q).complete();
+    tracer.stage("Compute φ(N)");
     let phi_N = (&p - 1u8).complete() * (&q - 1u8).complete();
 
-    tracer.stage("Generate auxiliary params r, λ, t, s");
-    let r = Integer::gen_invertible(&N, rng);
-    let lambda = phi_N
-        .random_below_ref(&mut utils::external_rand(rng))
-        .into();
-    let t = r.square().modulo(&N);
-    let s = t.pow_mod_ref(&lambda, &N).ok_or(Bug::PowMod)?.into();
-
-    tracer.stage("Prove Πprm (ψˆ_i)");
-    let hat_psi = π_prm::prove::<{ crate::security_level::M }, D>(
-        &unambiguous::ProofPrm { sid, prover: i },
-        &mut rng,
-        π_prm::Data {
-            N: &N,
-            s: &s,
-            t: &t,
-        },
-        &phi_N,
-        &lambda,
-    )
-    .map_err(Bug::PiPrm)?;
+    let (s, t, hat_psi) = if let Some(precomputed) = precomputed {
+        tracer.stage("Prove Πprm (ψˆ_i) using precomputed commitment");
+        let AuxPrecompute {
+            s,
+            t,
+            lambda,
+            prm_commitment,
+            ..
+        } = precomputed;
+        let hat_psi = π_prm::prove_with_commitment::<{ crate::security_level::M }, D>(
+            &unambiguous::ProofPrm { sid, prover: i },
+            π_prm::Data {
+                N: &N,
+                s: &s,
+                t: &t,
+            },
+            &phi_N,
+            &lambda,
+            prm_commitment,
+        );
+        (s, t, hat_psi)
+    } else {
+        tracer.stage("Generate auxiliary params r, λ, t, s");
+        let r = Integer::gen_invertible(&N, rng);
+        let lambda = phi_N
+            .random_below_ref(&mut utils::external_rand(rng))
+            .into();
+        let t = r.square().modulo(&N);
+        let s = modexp.pow_mod(&t, &lambda, &N).ok_or(Bug::PowMod)?;
+
+        tracer.stage("Prove Πprm (ψˆ_i)");
+        let hat_psi = π_prm::prove::<{ crate::security_level::M }, D>(
+            &unambiguous::ProofPrm { sid, prover: i },
+            &mut rng,
+            π_prm::Data {
+                N: &N,
+                s: &s,
+                t: &t,
+            },
+            &phi_N,
+            &lambda,
+            modexp,
+        )
+        .map_err(Bug::PiPrm)?;
+        (s, t, hat_psi)
+    };
 
     tracer.stage("Sample random bytes");
     // rho_i in paper, this signer's share of bytes
@@ -230,6 +286,7 @@ where
         t: t.clone(),
         params_proof: hat_psi,
         rho_bytes: rho_bytes.clone(),
+        identity: party_identity.map(|identity| identity.to_vec()),
         decommit: {
             let mut nonce = L::Rid::default();
             rng.fill_bytes(nonce.as_mut());
@@ -330,6 +387,15 @@ where
     if !blame.is_empty() {
         return Err(ProtocolAborted::invalid_decommitment(blame).into());
     }
+    if let Some(peer_identities) = peer_identities {
+        tracer.stage("Validate identities");
+        let blame = collect_blame(&decommitments, &decommitments, |j, d, _| {
+            d.identity.as_deref() != peer_identities.get(usize::from(j)).copied()
+        });
+        if !blame.is_empty() {
+            return Err(ProtocolAborted::identity_mismatch(blame).into());
+        }
+    }
     // validate parameters and param_proofs
     tracer.stage("Validate П_prm (ψ_i)");
     let blame = collect_blame(&decommitments, &decommitments, |j, d, _| {
@@ -345,6 +411,7 @@ where
                 &unambiguous::ProofPrm { sid, prover: j },
                 data,
                 &d.params_proof,
+                modexp,
             )
             .is_err()
         }
@@ -520,6 +587,13 @@ where
         p,
         q,
         parties: party_auxes,
+        party_set_commitment: peer_identities.and_then(|_| {
+            decommitments
+                .iter_including_me(&decommitment)
+                .map(|d| d.identity.as_deref())
+                .collect::<Option<Vec<_>>>()
+                .map(|identities| crate::party_set_commitment::<D>(&identities))
+        }),
         security_level: std::marker::PhantomData,
     };
 
@@ -23,12 +23,14 @@ use crate::{
     key_share::{
         DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, KeyShare, PartyAux, Validate,
     },
+    modexp::ModExp,
     progress::Tracer,
+    round_observer::{ObservedIncomings, RoundObserver},
     security_level::{SecurityLevel, M},
     utils,
     utils::{
-        but_nth, collect_blame, collect_simple_blame, iter_peers, scalar_to_bignumber, xor_array,
-        AbortBlame,
+        but_nth, collect_blame, collect_simple_blame, iter_peers, scalar_to_bignumber_ct,
+        xor_array, AbortBlame,
     },
     zk::ring_pedersen_parameters as π_prm,
     ExecutionId, IncompleteKeyShare,
@@ -56,6 +58,18 @@ pub enum Msg<E: Curve, D: Digest, L: SecurityLevel> {
     ReliabilityCheck(MsgReliabilityCheck<D>),
 }
 
+impl<E: Curve, D: Digest, L: SecurityLevel> Msg<E, D, L> {
+    /// Name of the round the message belongs to
+    pub fn round_name(&self) -> &'static str {
+        match self {
+            Msg::Round1(_) => "round 1",
+            Msg::Round2(_) => "round 2",
+            Msg::Round3(_) => "round 3",
+            Msg::ReliabilityCheck(_) => "reliability check",
+        }
+    }
+}
+
 /// Message from round 1
 #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
 #[udigest(tag = prefixed!("round1"))]
@@ -189,9 +203,11 @@ pub async fn run_refresh<R, M, E, L, D>(
     sid: ExecutionId<'_>,
     pregenerated: PregeneratedPrimes<L>,
     mut tracer: Option<&mut dyn Tracer>,
+    round_observer: Option<&mut dyn RoundObserver>,
     reliable_broadcast_enforced: bool,
     build_multiexp_tables: bool,
     build_crt: bool,
+    modexp: ModExp<'_>,
     core_share: &DirtyIncompleteKeyShare<E>,
 ) -> Result<KeyShare<E, L>, KeyRefreshError>
 where
@@ -216,7 +232,12 @@ where
     let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
     let round2 = rounds.add_round(RoundInput::<MsgRound2<E, L>>::broadcast(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::p2p(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let mut rounds = rounds.listen(ObservedIncomings::new(
+        incomings,
+        round_observer,
+        n,
+        Msg::round_name,
+    ));
 
     // Round 1
     tracer.round_begins();
@@ -252,7 +273,7 @@ where
         .random_below_ref(&mut utils::external_rand(rng))
         .into();
     let t = r.square().modulo(&N);
-    let s = t.pow_mod_ref(&lambda, &N).ok_or(Bug::PowMod)?.into();
+    let s = modexp.pow_mod(&t, &lambda, &N).ok_or(Bug::PowMod)?;
 
     tracer.stage("Prove Πprm (ψˆ_i)");
     let hat_psi = π_prm::prove::<{ M }, D>(
@@ -265,6 +286,7 @@ where
         },
         &phi_N,
         &lambda,
+        modexp,
     )
     .map_err(Bug::PiPrm)?;
 
@@ -413,6 +435,7 @@ where
                 &unambiguous::ProofPrm { sid, prover: j },
                 data,
                 &d.params_proof,
+                modexp,
             )
             .is_err()
         }
@@ -488,9 +511,10 @@ where
         .zip(iter_peers(i, n));
     for (((x, enc), d), j) in iterator {
         tracer.stage("Paillier encryption of x_i^j");
-        let (C, _) = enc
-            .encrypt_with_random(&mut rng, &scalar_to_bignumber(x))
-            .map_err(|_| Bug::PaillierEnc)?;
+        let nonce = fast_paillier::utils::sample_in_mult_group(&mut rng, enc.n());
+        let C = modexp
+            .encrypt_with(enc, &scalar_to_bignumber_ct(x), &nonce)
+            .ok_or(Bug::PaillierEnc)?;
         tracer.stage("Compute П_fac (ф_i^j)");
         let phi = π_fac::prove::<D>(
             &unambiguous::ProofFac {
@@ -707,6 +731,7 @@ where
     let new_core_share: IncompleteKeyShare<E> = DirtyIncompleteKeyShare {
         key_info: DirtyKeyInfo {
             public_shares: X_stars,
+            epoch: old_core_share.key_info.epoch.saturating_add(1),
             ..old_core_share.key_info
         },
         x: NonZero::from_secret_scalar(SecretScalar::new(&mut x_star)).ok_or(Bug::ZeroShare)?,
@@ -730,6 +755,7 @@ where
         p,
         q,
         parties: party_auxes,
+        party_set_commitment: None,
         security_level: std::marker::PhantomData,
     };
 
@@ -1,9 +1,10 @@
 //! Key share
 
-use std::ops;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::{iter, ops};
 
-use generic_ec::{Curve, NonZero, Point};
+use generic_ec::{Curve, NonZero, Point, Scalar};
 use paillier_zk::paillier_encryption_in_range as π_enc;
 use paillier_zk::rug::{Complete, Integer};
 use serde::{Deserialize, Serialize};
@@ -13,11 +14,24 @@ use crate::security_level::SecurityLevel;
 
 #[doc(inline)]
 pub use cggmp21_keygen::key_share::{
+    AdditiveShareError, CompactCoreKeyShare as CompactIncompleteKeyShare,
     CoreKeyShare as IncompleteKeyShare, DirtyCoreKeyShare as DirtyIncompleteKeyShare, DirtyKeyInfo,
-    HdError, InvalidCoreShare as InvalidIncompleteKeyShare, KeyInfo, Valid, Validate,
-    ValidateError, ValidateFromParts, VssSetup,
+    HdError, InvalidCoreShare as InvalidIncompleteKeyShare, KeyInfo, PublicKeyShare, RogueKeyError,
+    TwoPartyAdditiveShare, Valid, Validate, ValidateError, ValidateFromParts, VssSetup,
 };
 
+#[doc(inline)]
+pub use cggmp21_keygen::enroll_party;
+#[doc(inline)]
+pub use cggmp21_keygen::key_share::weighted;
+#[cfg(feature = "hd-wallet")]
+#[doc(inline)]
+pub use cggmp21_keygen::key_share::CombinedChainCode;
+#[doc(inline)]
+pub use cggmp21_keygen::reshare_to_threshold;
+#[doc(inline)]
+pub use key_share::tweak;
+
 /// Key share
 ///
 #[doc = include_str!("../docs/key_share.md")]
@@ -30,6 +44,7 @@ pub type KeyShare<E, L = crate::default_choice::SecurityLevel> = Valid<DirtyKeyS
 pub type AuxInfo<L = crate::default_choice::SecurityLevel> = Valid<DirtyAuxInfo<L>>;
 
 /// Dirty aux info
+#[serde_with::serde_as]
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct DirtyAuxInfo<L: SecurityLevel = crate::default_choice::SecurityLevel> {
@@ -41,6 +56,14 @@ pub struct DirtyAuxInfo<L: SecurityLevel = crate::default_choice::SecurityLevel>
     ///
     /// `parties[i]` corresponds to public auxiliary data of $\ith$ party
     pub parties: Vec<PartyAux>,
+    /// Commitment to network identities of the parties that generated aux info, if they were set
+    ///
+    /// Recorded by [aux info generation](crate::aux_info_gen) when each party checked identities
+    /// of others. If both aux info and [key info](DirtyKeyInfo::party_set_commitment) have it,
+    /// they must match to be combined into [`KeyShare`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<serde_with::Bytes>")]
+    pub party_set_commitment: Option<Vec<u8>>,
     /// Security level that was used to generate aux info
     #[serde(skip)]
     pub security_level: std::marker::PhantomData<L>,
@@ -56,6 +79,18 @@ pub struct DirtyKeyShare<E: Curve, L: SecurityLevel = crate::default_choice::Sec
     pub core: DirtyIncompleteKeyShare<E>,
     /// Auxiliary info
     pub aux: DirtyAuxInfo<L>,
+    /// Arbitrary metadata attached to the key share by its holder (e.g. label, creation time)
+    ///
+    /// **Unauthenticated**: metadata is serialized along with the key share, but it's not
+    /// validated, not hashed, and not used by any protocol, so it can't affect the cryptography.
+    /// Anyone who can modify stored key share can modify its metadata as well. It's not carried
+    /// over to key shares produced by the protocols (e.g. by key refresh).
+    ///
+    /// See [`with_metadata`](Self::with_metadata), [`metadata`](Self::metadata()), and
+    /// [`set_metadata_field`](Self::set_metadata_field). Metadata of a validated [`KeyShare`] can be
+    /// modified via [`Valid::modify`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
 }
 
 /// Party public auxiliary data
@@ -78,6 +113,20 @@ pub struct PartyAux {
     pub crt: Option<paillier_zk::fast_paillier::utils::CrtExp>,
 }
 
+/// Public auxiliary parameters of a party
+///
+/// Read-only view of [`PartyAux`] that exposes only public data: Paillier modulus and
+/// ring-Pedersen parameters. Obtained via [`DirtyAuxInfo::party_public_params`].
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PublicAuxParams<'a> {
+    /// Paillier public key $N_j$
+    pub N: &'a Integer,
+    /// Ring-Pedersen parameter $s_j$
+    pub s: &'a Integer,
+    /// Ring-Pedersen parameter $t_j$
+    pub t: &'a Integer,
+}
+
 impl<L: SecurityLevel> Validate for DirtyAuxInfo<L> {
     type Error = InvalidKeyShare;
 
@@ -165,9 +214,113 @@ impl<L: SecurityLevel> DirtyAuxInfo<L> {
             .ok_or(InvalidKeyShareReason::CrtINotInRange)?;
         aux_i.precompute_crt(&self.p, &self.q)
     }
+
+    /// Returns public auxiliary parameters of $j$-th party
+    ///
+    /// Exposes Paillier modulus $N_j$ and ring-Pedersen parameters $s_j, t_j$ that are needed to
+    /// verify ZK proofs produced by the party, for instance, by an external auditor. Secret primes
+    /// and CRT parameters are never exposed.
+    ///
+    /// Returns `None` if `j` is out of bounds.
+    pub fn party_public_params(&self, j: u16) -> Option<PublicAuxParams<'_>> {
+        self.parties
+            .get(usize::from(j))
+            .map(PartyAux::public_params)
+    }
+
+    /// Verifies public auxiliary data of every party
+    ///
+    /// Intended to be used on aux info received from elsewhere (e.g. out of band) before it's
+    /// combined into a key share. For each party, checks that:
+    /// * $N_j$ is odd, not a perfect square, not a prime, and meets security level `L`
+    /// * ring-Pedersen parameters $s_j, t_j$ lie in $(1, N_j)$ and are coprime with $N_j$
+    ///
+    /// Returns error indicating index of the first party whose data is malformed.
+    ///
+    /// Note that aux info doesn't contain ZK proofs ($\pi^\text{mod}$, $\pi^\text{prm}$, $\pi^\text{fac}$)
+    /// that parties exchange during aux info generation: they're verified by the protocol and not
+    /// persisted afterwards. This method therefore can't prove that $N_j$ is a product of two safe
+    /// primes or that $s_j$ is in the group generated by $t_j$. It only rejects parameters that are
+    /// evidently invalid.
+    pub fn verify(&self) -> Result<(), AuxVerifyError> {
+        for (j, aux_j) in (0u16..).zip(&self.parties) {
+            aux_j
+                .verify::<L>()
+                .map_err(|reason| AuxVerifyError { party: j, reason })?;
+        }
+        Ok(())
+    }
 }
 
 impl PartyAux {
+    /// Returns public parameters of the party
+    pub fn public_params(&self) -> PublicAuxParams<'_> {
+        PublicAuxParams {
+            N: &self.N,
+            s: &self.s,
+            t: &self.t,
+        }
+    }
+
+    /// Reports how many bytes auxiliary data of the party takes
+    ///
+    /// `p` and `q` are secret primes of the key share holder: CRT parameters are only present
+    /// for the holder's own entry, and their size is estimated from the primes.
+    fn memory_footprint(&self, p: &Integer, q: &Integer) -> PartyAuxFootprint {
+        let multiexp = self
+            .multiexp
+            .as_ref()
+            .map(|table| Footprint {
+                in_memory: core::mem::size_of_val(&**table)
+                    + 2 * core::mem::size_of::<usize>()
+                    + table.size_in_bytes(),
+                serialized: table.size_in_bytes(),
+            })
+            .unwrap_or_default();
+        // CRT params consist of N, p, phi(p), q, phi(q), and p^-1 mod q
+        let crt = if self.crt.is_some() {
+            Footprint::of_integer(&self.N)
+                + Footprint::of_integer(p)
+                + Footprint::of_integer(p)
+                + Footprint::of_integer(q)
+                + Footprint::of_integer(q)
+                + Footprint::of_integer(q)
+        } else {
+            Footprint::default()
+        };
+        PartyAuxFootprint {
+            modulus: Footprint::of_integer(&self.N),
+            ring_pedersen: Footprint::of_integer(&self.s) + Footprint::of_integer(&self.t),
+            multiexp,
+            crt,
+        }
+    }
+
+    fn verify<L: SecurityLevel>(&self) -> Result<(), AuxVerifyReason> {
+        if !crate::security_level::validate_public_paillier_key_size::<L>(&self.N) {
+            return Err(AuxVerifyReason::PaillierPkTooSmall {
+                required: 8 * L::SECURITY_BITS - 1,
+                actual: self.N.significant_bits(),
+            });
+        }
+        if self.N.is_even()
+            || self.N.is_perfect_square()
+            || self.N.is_probably_prime(25) != paillier_zk::rug::integer::IsPrime::No
+        {
+            return Err(AuxVerifyReason::InvalidModulus);
+        }
+        let in_range = |x: &Integer| *x > *Integer::ONE && *x < self.N;
+        if !in_range(&self.s) || !in_range(&self.t) {
+            return Err(AuxVerifyReason::StOutOfRange);
+        }
+        if self.s.gcd_ref(&self.N).complete() != *Integer::ONE
+            || self.t.gcd_ref(&self.N).complete() != *Integer::ONE
+        {
+            return Err(AuxVerifyReason::StGcdN);
+        }
+        Ok(())
+    }
+
     /// Precompute multiexponentiation table
     ///
     /// Enables optimization that makes signing and presigning faster. Precomputation may take a while.
@@ -240,11 +393,46 @@ impl<E: Curve, L: SecurityLevel> ValidateFromParts<(IncompleteKeyShare<E>, AuxIn
         Self {
             core: core.into_inner(),
             aux: aux.into_inner(),
+            metadata: BTreeMap::new(),
         }
     }
 }
 
 impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
+    /// Returns a builder that assembles key share from core share and aux info
+    ///
+    /// Alternative to [`KeyShare::from_parts`] that allows providing core share and aux info
+    /// separately. See [`KeyShareBuilder`].
+    pub fn builder() -> KeyShareBuilder<E, L> {
+        KeyShareBuilder::new()
+    }
+
+    /// Replaces metadata of the key share
+    ///
+    /// Metadata is unauthenticated. Metadata of a validated [`KeyShare`] can be modified via
+    /// [`Valid::modify`], which validates the key share again.
+    pub fn with_metadata(self, metadata: BTreeMap<String, String>) -> Self {
+        Self { metadata, ..self }
+    }
+
+    /// Returns metadata attached to the key share
+    ///
+    /// Metadata is unauthenticated, see docs of `metadata` field.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Sets a metadata field, returns its previous value if it was present
+    ///
+    /// Metadata is unauthenticated, see docs of `metadata` field.
+    pub fn set_metadata_field(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Option<String> {
+        self.metadata.insert(key.into(), value.into())
+    }
+
     /// Perform consistency check between core and aux
     fn validate_consistency(
         core: &DirtyIncompleteKeyShare<E>,
@@ -253,6 +441,13 @@ impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
         if core.public_shares.len() != aux.parties.len() {
             return Err(InvalidKeyShareReason::AuxLen.into());
         }
+        if let (Some(core_parties), Some(aux_parties)) =
+            (&core.party_set_commitment, &aux.party_set_commitment)
+        {
+            if core_parties != aux_parties {
+                return Err(InvalidKeyShareReason::PartySetMismatch.into());
+            }
+        }
 
         let N_i = &aux.parties[usize::from(core.i)].N;
         if *N_i != (&aux.p * &aux.q).complete() {
@@ -261,6 +456,89 @@ impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
 
         Ok(())
     }
+
+    /// Checks whether `self` and `other` are shares of the same key with the same aux info
+    ///
+    /// In addition to [`IncompleteKeyShare::same_key`] which
+    /// compares public key info, it checks that both shares carry the same public aux data of all
+    /// parties (i.e. they were produced by the same aux info generation or key refresh). Party-specific
+    /// data (index `i`, secret share, Paillier secret key) and precomputed tables are ignored.
+    pub fn same_key(&self, other: &DirtyKeyShare<E, L>) -> bool {
+        self.core.key_info.same_key(&other.core.key_info)
+            && self.aux.parties.len() == other.aux.parties.len()
+            && self
+                .aux
+                .parties
+                .iter()
+                .zip(&other.aux.parties)
+                .all(|(a, b)| a.N == b.N && a.s == b.s && a.t == b.t)
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
+    /// Reports how many bytes the key share takes
+    ///
+    /// Breaks down the size of key share by its components. For each component, it reports size in
+    /// RAM (including heap allocations) and size of raw encoding of the values (see [`Footprint`]).
+    ///
+    /// Note that ZK proofs exchanged during aux info generation are not part of the key share, so
+    /// they don't take any space.
+    pub fn memory_footprint(&self) -> FootprintReport {
+        let key_info = &self.core.key_info;
+
+        let mut vss_setup = Footprint::default();
+        if let Some(setup) = &key_info.vss_setup {
+            vss_setup += Footprint::of_vec(&setup.I)
+                + Footprint {
+                    in_memory: 0,
+                    serialized: 2 + setup.I.len() * Scalar::<E>::serialized_len(),
+                };
+            if let Some(weights) = &setup.weights {
+                vss_setup += Footprint::of_vec(weights)
+                    + Footprint {
+                        in_memory: 0,
+                        serialized: 2 * weights.len(),
+                    };
+            }
+        }
+
+        #[cfg(feature = "hd-wallet")]
+        let chain_code = key_info
+            .chain_code
+            .map(|chain_code| Footprint {
+                in_memory: 0,
+                serialized: core::mem::size_of_val(&chain_code),
+            })
+            .unwrap_or_default();
+        #[cfg(not(feature = "hd-wallet"))]
+        let chain_code = Footprint::default();
+
+        FootprintReport {
+            key_info: Footprint::of_point(&key_info.shared_public_key) + vss_setup + chain_code,
+            public_shares: Footprint::of_vec(&key_info.public_shares)
+                + key_info
+                    .public_shares
+                    .iter()
+                    .map(|X| Footprint {
+                        in_memory: 0,
+                        serialized: Footprint::of_point(X).serialized,
+                    })
+                    .sum(),
+            secret_share: Footprint {
+                // Secret scalar is stored on the heap
+                in_memory: core::mem::size_of_val(&self.core.x) + core::mem::size_of::<Scalar<E>>(),
+                serialized: Scalar::<E>::serialized_len(),
+            },
+            paillier_secret_key: Footprint::of_integer(&self.aux.p)
+                + Footprint::of_integer(&self.aux.q),
+            parties: self
+                .aux
+                .parties
+                .iter()
+                .map(|aux_j| aux_j.memory_footprint(&self.aux.p, &self.aux.q))
+                .collect(),
+        }
+    }
 }
 
 impl<E: Curve> DirtyKeyShare<E> {
@@ -280,6 +558,71 @@ impl<E: Curve> DirtyKeyShare<E> {
     }
 }
 
+/// Builder of [`KeyShare`]
+///
+/// Assembles key share from [core share](IncompleteKeyShare) and [aux info](AuxInfo) that can be
+/// provided at different times, e.g. when they are loaded from different stores. Both parts are
+/// validated on their own, [`build`](Self::build) checks that they are consistent with each other.
+///
+/// ## Example
+/// ```rust,no_run
+/// # fn load<E: generic_ec::Curve>() -> Result<(), cggmp21::key_share::BuildKeyShareError> {
+/// # let incomplete_share: cggmp21::IncompleteKeyShare<E> = unimplemented!();
+/// # let aux_info: cggmp21::key_share::AuxInfo = unimplemented!();
+/// let key_share: cggmp21::KeyShare<E> = cggmp21::key_share::DirtyKeyShare::builder()
+///     .core(incomplete_share)
+///     .aux(aux_info)
+///     .build()?;
+/// # Ok(()) }
+/// ```
+pub struct KeyShareBuilder<E: Curve, L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    core: Option<IncompleteKeyShare<E>>,
+    aux: Option<AuxInfo<L>>,
+}
+
+impl<E: Curve, L: SecurityLevel> KeyShareBuilder<E, L> {
+    /// Constructs an empty builder
+    pub fn new() -> Self {
+        Self {
+            core: None,
+            aux: None,
+        }
+    }
+
+    /// Sets core share
+    pub fn core(self, core: IncompleteKeyShare<E>) -> Self {
+        Self {
+            core: Some(core),
+            ..self
+        }
+    }
+
+    /// Sets aux info
+    pub fn aux(self, aux: AuxInfo<L>) -> Self {
+        Self {
+            aux: Some(aux),
+            ..self
+        }
+    }
+
+    /// Assembles the key share
+    ///
+    /// Returns an error if any of the parts is missing, or if parts are not consistent with each
+    /// other. Performs the same checks as [`KeyShare::from_parts`].
+    pub fn build(self) -> Result<KeyShare<E, L>, BuildKeyShareError> {
+        let core = self.core.ok_or(BuildKeyShareError::MissingCore)?;
+        let aux = self.aux.ok_or(BuildKeyShareError::MissingAux)?;
+        KeyShare::from_parts((core, aux))
+            .map_err(|err| BuildKeyShareError::Inconsistent(err.into_error()))
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> Default for KeyShareBuilder<E, L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<E: Curve, L: SecurityLevel> AsRef<DirtyIncompleteKeyShare<E>> for DirtyKeyShare<E, L> {
     fn as_ref(&self) -> &DirtyIncompleteKeyShare<E> {
         &self.core
@@ -326,14 +669,109 @@ pub trait AnyKeyShare<E: Curve>: AsRef<IncompleteKeyShare<E>> {
             .unwrap_or_else(|| self.n())
     }
 
+    /// Returns amount of parties holding the key
+    ///
+    /// Same as [`n`](Self::n), except for weighted keys: [`n`](Self::n) is the amount of sub-shares,
+    /// whereas this method returns the amount of parties.
+    fn parties_count(&self) -> u16 {
+        match self
+            .as_ref()
+            .vss_setup
+            .as_ref()
+            .and_then(|s| s.weights.as_ref())
+        {
+            #[allow(clippy::expect_used)]
+            Some(weights) => weights
+                .len()
+                .try_into()
+                .expect("valid key share is guaranteed to have amount of parties fitting into u16"),
+            None => self.n(),
+        }
+    }
+
+    /// Returns threshold, or `None` if the key is non-threshold
+    ///
+    /// Use [`min_signers`](Self::min_signers) to get the minimal amount of signers regardless of
+    /// the key type.
+    fn threshold(&self) -> Option<u16> {
+        self.as_ref().vss_setup.as_ref().map(|s| s.min_signers)
+    }
+
     /// Returns public key shared by signers
     fn shared_public_key(&self) -> NonZero<Point<E>> {
         self.as_ref().shared_public_key
     }
+
+    /// Returns public shares of all key co-holders
+    ///
+    /// `public_shares()[j]` is a public share of j-th co-holder
+    fn public_shares(&self) -> &[NonZero<Point<E>>] {
+        &self.as_ref().public_shares
+    }
+
+    /// Returns index of the party that holds the key share
+    fn party_index(&self) -> u16 {
+        self.as_ref().i
+    }
+
+    /// Enumerates all minimal sets of signers that can sign with this key
+    ///
+    /// Yields every set of [`min_signers`](Self::min_signers) distinct party indexes out of
+    /// [`n`](Self::n), sorted in ascending order. Sets are generated lazily in lexicographic order, so
+    /// it's fine to use it with large `n` as long as you don't collect all of them: amount of sets is
+    /// $\binom{n}{t}$.
+    ///
+    /// For weighted keys, indexes refer to sub-shares rather than parties.
+    fn valid_signer_sets(&self) -> SignerSets {
+        SignerSets::new(self.n(), self.min_signers())
+    }
 }
 
 impl<E: Curve, T: AsRef<IncompleteKeyShare<E>>> AnyKeyShare<E> for T {}
 
+/// Iterator over sets of signers
+///
+/// Returned by [`AnyKeyShare::valid_signer_sets`]
+#[derive(Debug, Clone)]
+pub struct SignerSets {
+    n: u16,
+    next: Option<Vec<u16>>,
+}
+
+impl SignerSets {
+    pub(crate) fn new(n: u16, t: u16) -> Self {
+        Self {
+            n,
+            next: (t <= n).then(|| (0..t).collect()),
+        }
+    }
+}
+
+impl Iterator for SignerSets {
+    type Item = Vec<u16>;
+
+    fn next(&mut self) -> Option<Vec<u16>> {
+        let current = self.next.take()?;
+
+        // Next combination: find the rightmost index that can be incremented, increment
+        // it, and reset all the indexes to the right of it
+        let t = current.len();
+        let mut next = current.clone();
+        if let Some(k) = (0..t)
+            .rev()
+            .find(|&k| usize::from(next[k]) < usize::from(self.n) - t + k)
+        {
+            next[k] += 1;
+            for l in k + 1..t {
+                next[l] = next[l - 1] + 1;
+            }
+            self.next = Some(next);
+        }
+
+        Some(current)
+    }
+}
+
 /// Reconstructs a secret key from set of at least [`min_signers`](KeyShare::min_signers) key shares
 ///
 /// Requires at least [`min_signers`](KeyShare::min_signers) distinct key shares from the same generation
@@ -350,6 +788,101 @@ pub fn reconstruct_secret_key<E: Curve>(
     key_share::reconstruct_secret_key(key_shares)
 }
 
+/// Converts sub-shares of weighted key into a key share of specific set of signers
+///
+/// Takes all sub-shares of the local party obtained from weighted keygen, aux info generated
+/// for all parties (in the same order as at keygen), and list of `signers`, where `signers[j]`
+/// is index of j-th signer at keygen. Returns an error if total weight of signers is less than
+/// the threshold.
+///
+/// Resulting key share is n-out-of-n key share of the signers, so the signing must be carried
+/// out with `parties_indexes_at_keygen = [0, 1, ..., signers.len() - 1]`, and the local party
+/// has index equal to its position in `signers`. See [`weighted`] module for more details.
+pub fn weighted_signing_share<E: Curve, L: SecurityLevel>(
+    sub_shares: &[IncompleteKeyShare<E>],
+    aux: &AuxInfo<L>,
+    signers: &[u16],
+) -> Result<KeyShare<E, L>, InvalidKeyShare> {
+    let core =
+        weighted::signing_share(sub_shares, signers).map_err(InvalidKeyShareReason::Weighted)?;
+    let parties = signers
+        .iter()
+        .map(|j| aux.parties.get(usize::from(*j)).cloned())
+        .collect::<Option<Vec<_>>>()
+        .ok_or(InvalidKeyShareReason::AuxLen)?;
+    let aux = DirtyAuxInfo {
+        p: aux.p.clone(),
+        q: aux.q.clone(),
+        parties,
+        party_set_commitment: aux.party_set_commitment.clone(),
+        security_level: std::marker::PhantomData,
+    }
+    .validate()?;
+    KeyShare::from_parts((core, aux)).map_err(|err| err.into_error())
+}
+
+/// Returns key share of the key tweaked by the `domain`
+///
+/// Tweaks [core key share](IncompleteKeyShare::tweak) and keeps aux info as is, so tweaked key
+/// share can be used in signing right away. All signers must use the same `domain` and digest `D`.
+/// Metadata is not carried over to the tweaked key share.
+///
+/// Returns `None` if tweaked key is degenerate, which happens with negligible probability.
+/// See [`tweak`] module for more details.
+pub fn tweaked_key_share<E: Curve, L: SecurityLevel, D: digest::Digest>(
+    key_share: &KeyShare<E, L>,
+    domain: &[u8],
+) -> Option<KeyShare<E, L>> {
+    let core: &IncompleteKeyShare<E> = key_share.as_ref();
+    let aux: &AuxInfo<L> = key_share.as_ref();
+    KeyShare::from_parts((core.tweak::<D>(domain)?, aux.clone())).ok()
+}
+
+/// Magic bytes that [versioned encoding](to_bytes) of key share starts with
+#[cfg(feature = "codec")]
+const FORMAT_MAGIC: &[u8] = b"cgks";
+/// Current version of [key share encoding](to_bytes)
+#[cfg(feature = "codec")]
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Encodes key share in versioned binary format
+///
+/// Output consists of a header (magic bytes followed by [`FORMAT_VERSION`]) and key share
+/// encoded in CBOR. It can be decoded via [`migrate`] by this or any later version of the crate.
+#[cfg(feature = "codec")]
+pub fn to_bytes<E: Curve>(
+    key_share: &IncompleteKeyShare<E>,
+) -> Result<Vec<u8>, crate::codec::CodecError> {
+    let body = crate::codec::encode_message(key_share, usize::MAX)?;
+    let mut bytes = Vec::with_capacity(FORMAT_MAGIC.len() + 1 + body.len());
+    bytes.extend_from_slice(FORMAT_MAGIC);
+    bytes.push(FORMAT_VERSION);
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Decodes key share encoded via [`to_bytes`], upgrading it to the current layout
+///
+/// Version of the encoding is detected from the header. Key share is validated after it's decoded.
+///
+/// Returns [`MigrationError::UnknownVersion`] if the key share was encoded by a newer version of the
+/// crate.
+#[cfg(feature = "codec")]
+pub fn migrate<E: Curve>(bytes: &[u8]) -> Result<IncompleteKeyShare<E>, MigrationError> {
+    let (&version, body) = bytes
+        .strip_prefix(FORMAT_MAGIC)
+        .and_then(|bytes| bytes.split_first())
+        .ok_or(MigrationError::MissingHeader)?;
+    let key_share: DirtyIncompleteKeyShare<E> = match version {
+        // Deserialization of key share accepts all the layouts produced by earlier releases
+        1 => crate::codec::decode_message(body, usize::MAX).map_err(MigrationError::Decode)?,
+        _ => return Err(MigrationError::UnknownVersion(version)),
+    };
+    key_share
+        .validate()
+        .map_err(|err| MigrationError::Invalid(err.into_error()))
+}
+
 impl From<&PartyAux> for π_enc::Aux {
     fn from(aux: &PartyAux) -> Self {
         Self {
@@ -362,17 +895,140 @@ impl From<&PartyAux> for π_enc::Aux {
     }
 }
 
+/// Amount of bytes taken by a component of key share
+///
+/// See [`DirtyKeyShare::memory_footprint`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Footprint {
+    /// Size in RAM, including heap allocations and allocation overhead (e.g. unused capacity of
+    /// big integers)
+    pub in_memory: usize,
+    /// Size of raw encoding of the values
+    ///
+    /// Points are counted in compressed form, integers take as many bytes as needed to
+    /// represent them. Overhead of serialization format (e.g. lengths, field names) isn't
+    /// counted, so the actual size of serialized key share depends on the format.
+    pub serialized: usize,
+}
+
+impl Footprint {
+    fn of_integer(x: &Integer) -> Self {
+        Self {
+            in_memory: core::mem::size_of::<Integer>() + x.capacity().div_ceil(8),
+            serialized: usize::try_from(x.significant_bits().div_ceil(8)).unwrap_or(usize::MAX),
+        }
+    }
+
+    fn of_point<E: Curve>(point: &NonZero<Point<E>>) -> Self {
+        Self {
+            in_memory: core::mem::size_of_val(point),
+            serialized: point.to_bytes(true).len(),
+        }
+    }
+
+    /// Size of a vector in RAM, not counting heap allocations of its elements
+    fn of_vec<T>(vec: &Vec<T>) -> Self {
+        Self {
+            in_memory: core::mem::size_of::<Vec<T>>() + vec.capacity() * core::mem::size_of::<T>(),
+            serialized: 0,
+        }
+    }
+}
+
+impl ops::Add for Footprint {
+    type Output = Footprint;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            in_memory: self.in_memory + rhs.in_memory,
+            serialized: self.serialized + rhs.serialized,
+        }
+    }
+}
+
+impl ops::AddAssign for Footprint {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl iter::Sum for Footprint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, x| acc + x)
+    }
+}
+
+/// Breakdown of key share size
+///
+/// Returned by [`DirtyKeyShare::memory_footprint`]
+#[derive(Debug, Clone)]
+pub struct FootprintReport {
+    /// Shared public key, chain code, and VSS setup
+    pub key_info: Footprint,
+    /// Public shares of all signers
+    pub public_shares: Footprint,
+    /// Secret share $x_i$
+    pub secret_share: Footprint,
+    /// Secret Paillier key $p, q$
+    pub paillier_secret_key: Footprint,
+    /// Auxiliary data of each party
+    ///
+    /// `parties[j]` corresponds to auxiliary data of $j$-th party
+    pub parties: Vec<PartyAuxFootprint>,
+}
+
+impl FootprintReport {
+    /// Total size of the key share
+    pub fn total(&self) -> Footprint {
+        self.key_info
+            + self.public_shares
+            + self.secret_share
+            + self.paillier_secret_key
+            + self.parties.iter().map(PartyAuxFootprint::total).sum()
+    }
+}
+
+/// Breakdown of party auxiliary data size
+#[derive(Debug, Clone, Copy)]
+pub struct PartyAuxFootprint {
+    /// Paillier modulus $N_j$
+    pub modulus: Footprint,
+    /// Ring-Pedersen parameters $s_j, t_j$
+    pub ring_pedersen: Footprint,
+    /// [Multiexp table](PartyAux::precompute_multiexp_table), zero if it's not precomputed
+    pub multiexp: Footprint,
+    /// [CRT parameters](PartyAux::precompute_crt) (estimated), zero if they're not precomputed
+    pub crt: Footprint,
+}
+
+impl PartyAuxFootprint {
+    /// Total size of party auxiliary data
+    pub fn total(&self) -> Footprint {
+        self.modulus + self.ring_pedersen + self.multiexp + self.crt
+    }
+}
+
 /// Error indicating that key share is not valid
 #[derive(Debug, Error)]
 #[error(transparent)]
 pub struct InvalidKeyShare(#[from] InvalidKeyShareReason);
 
+impl InvalidKeyShare {
+    /// Indicates whether key info and aux info were generated by different sets of parties
+    ///
+    /// Only detected when both of them record [party set commitment](DirtyAuxInfo::party_set_commitment).
+    pub fn is_party_set_mismatch(&self) -> bool {
+        matches!(self.0, InvalidKeyShareReason::PartySetMismatch)
+    }
+}
+
 #[derive(Debug, Error)]
 enum InvalidKeyShareReason {
     #[error(transparent)]
     InvalidCoreShare(InvalidIncompleteKeyShare),
     #[error("size of parties auxiliary data list doesn't match `n`: n != parties.len()")]
     AuxLen,
+    #[error("key info and aux info were generated by different sets of parties")]
+    PartySetMismatch,
     #[error("N_i != p q")]
     PrimesMul,
     #[error("gcd(s_j, N_j) != 1 or gcd(t_j, N_j) != 1")]
@@ -389,6 +1045,68 @@ enum InvalidKeyShareReason {
     CrtInvalidPq,
     #[error("couldn't build CRT parameters")]
     BuildCrt,
+    #[error(transparent)]
+    Weighted(weighted::WeightedError),
+}
+
+/// Error indicating that [aux info verification](DirtyAuxInfo::verify) failed
+#[derive(Debug, Error)]
+#[error("auxiliary data of party {party} is invalid")]
+pub struct AuxVerifyError {
+    party: u16,
+    #[source]
+    reason: AuxVerifyReason,
+}
+
+impl AuxVerifyError {
+    /// Index of the party whose auxiliary data is invalid
+    pub fn party(&self) -> u16 {
+        self.party
+    }
+}
+
+#[derive(Debug, Error)]
+enum AuxVerifyReason {
+    #[error("paillier public key doesn't match security level: required bit length = {required}, actual = {actual}")]
+    PaillierPkTooSmall { required: u32, actual: u32 },
+    #[error("N is not a valid paillier modulus")]
+    InvalidModulus,
+    #[error("ring-pedersen parameters s, t are not in range (1, N)")]
+    StOutOfRange,
+    #[error("gcd(s, N) != 1 or gcd(t, N) != 1")]
+    StGcdN,
+}
+
+/// Error indicating that [key share builder](KeyShareBuilder) couldn't assemble the key share
+#[derive(Debug, Error)]
+pub enum BuildKeyShareError {
+    /// Core share was not provided
+    #[error("core share is missing")]
+    MissingCore,
+    /// Aux info was not provided
+    #[error("aux info is missing")]
+    MissingAux,
+    /// Core share and aux info are not consistent with each other
+    #[error("core share and aux info are inconsistent")]
+    Inconsistent(#[source] InvalidKeyShare),
+}
+
+/// Error indicating that key share couldn't be [migrated](migrate)
+#[cfg(feature = "codec")]
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    /// Bytes don't start with a header of versioned key share encoding
+    #[error("key share header is missing")]
+    MissingHeader,
+    /// Key share was encoded in a version that's unknown to this version of the crate
+    #[error("unknown version of key share encoding: {0}")]
+    UnknownVersion(u8),
+    /// Key share couldn't be decoded
+    #[error("couldn't decode key share")]
+    Decode(#[source] crate::codec::CodecError),
+    /// Decoded key share is not valid
+    #[error("key share is not valid")]
+    Invalid(#[source] InvalidIncompleteKeyShare),
 }
 
 /// Error indicating that [key reconstruction](reconstruct_secret_key) failed
@@ -0,0 +1,403 @@
+//! Key generation combined with aux info generation
+//!
+//! When the same set of parties runs [keygen](crate::keygen()) and then [aux info
+//! generation](crate::aux_info_gen) back-to-back, both protocols can be carried out within a single
+//! session: [`keygen_with_aux`](crate::keygen_with_aux()) runs them concurrently over the same network
+//! connection and outputs a complete [`KeyShare`] right away. Rounds of both protocols are
+//! interleaved, so the session takes as many round trips as the longest of the two protocols.
+//!
+//! Messages of both protocols are wrapped into [`Msg`]. Protocols remain cryptographically separated:
+//! every hash computed by keygen and aux info generation is tagged with a protocol-specific domain
+//! tag, so transcripts of one protocol can't be confused with transcripts of another, even though
+//! both are bound to the same execution ID.
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::pin;
+use std::task::Poll;
+
+use digest::Digest;
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use generic_ec::Curve;
+use rand_core::{CryptoRng, RngCore};
+use round_based::{Delivery, Incoming, Mpc, MpcParty, Outgoing, ProtocolMessage};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    errors::IoError,
+    key_refresh::{msg::aux_only, KeyRefreshError, PregeneratedPrimes},
+    key_share::{AuxInfo, IncompleteKeyShare, InvalidKeyShare, KeyShare},
+    keygen::{KeygenError, NonThresholdMsg, ThresholdMsg},
+    security_level::SecurityLevel,
+    ExecutionId,
+};
+
+/// Message of combined keygen and aux info generation protocol
+#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+#[allow(clippy::large_enum_variant)]
+pub enum Msg<E: Curve, L: SecurityLevel, D: Digest> {
+    /// Message of non-threshold keygen
+    NonThresholdKeygen(NonThresholdMsg<E, L, D>),
+    /// Message of threshold keygen
+    ThresholdKeygen(ThresholdMsg<E, L, D>),
+    /// Message of aux info generation
+    AuxInfo(aux_only::Msg<D, L>),
+}
+
+/// Combined keygen and aux info generation protocol builder
+pub struct KeygenWithAuxBuilder<
+    'a,
+    E,
+    L = crate::default_choice::SecurityLevel,
+    D = crate::default_choice::Digest,
+> where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest,
+{
+    eid: ExecutionId<'a>,
+    i: u16,
+    n: u16,
+    t: Option<u16>,
+    pregenerated: PregeneratedPrimes<L>,
+    enforce_reliable_broadcast: bool,
+    #[cfg(feature = "hd-wallet")]
+    hd_enabled: bool,
+    _ph: std::marker::PhantomData<(E, D)>,
+}
+
+impl<'a, E, L, D> KeygenWithAuxBuilder<'a, E, L, D>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+{
+    /// Constructs a builder
+    ///
+    /// PregeneratedPrimes can be obtained with [`PregeneratedPrimes::generate`]
+    pub fn new(eid: ExecutionId<'a>, i: u16, n: u16, pregenerated: PregeneratedPrimes<L>) -> Self {
+        Self {
+            eid,
+            i,
+            n,
+            t: None,
+            pregenerated,
+            enforce_reliable_broadcast: true,
+            #[cfg(feature = "hd-wallet")]
+            hd_enabled: true,
+            _ph: std::marker::PhantomData,
+        }
+    }
+
+    /// Generates a threshold key
+    ///
+    /// See [`GenericKeygenBuilder::set_threshold`](crate::keygen::GenericKeygenBuilder::set_threshold)
+    pub fn set_threshold(self, t: u16) -> Self {
+        Self { t: Some(t), ..self }
+    }
+
+    /// Specifies another hash function to use
+    pub fn set_digest<D2>(self) -> KeygenWithAuxBuilder<'a, E, L, D2>
+    where
+        D2: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    {
+        KeygenWithAuxBuilder {
+            eid: self.eid,
+            i: self.i,
+            n: self.n,
+            t: self.t,
+            pregenerated: self.pregenerated,
+            enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            #[cfg(feature = "hd-wallet")]
+            hd_enabled: self.hd_enabled,
+            _ph: std::marker::PhantomData,
+        }
+    }
+
+    /// Enforces reliable broadcast in both protocols
+    ///
+    /// Enabled by default
+    pub fn enforce_reliable_broadcast(self, v: bool) -> Self {
+        Self {
+            enforce_reliable_broadcast: v,
+            ..self
+        }
+    }
+
+    /// Specifies whether HD derivation is enabled for the generated key
+    ///
+    /// See [`GenericKeygenBuilder::hd_wallet`](crate::keygen::GenericKeygenBuilder::hd_wallet)
+    #[cfg(feature = "hd-wallet")]
+    pub fn hd_wallet(self, v: bool) -> Self {
+        Self {
+            hd_enabled: v,
+            ..self
+        }
+    }
+
+    /// Carries out both protocols
+    pub async fn start<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+    ) -> Result<KeyShare<E, L>, KeygenWithAuxError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, L, D>>,
+    {
+        let t = self.t;
+        let MpcParty { delivery, .. } = party.into_party();
+        let (mut incomings, mut outgoings) = delivery.split();
+
+        let (non_threshold_incomings, non_threshold_rx) = mpsc::unbounded();
+        let (threshold_incomings, threshold_rx) = mpsc::unbounded();
+        let (aux_incomings, aux_rx) = mpsc::unbounded();
+        let mut non_threshold_incomings = Some(non_threshold_incomings);
+        let mut threshold_incomings = Some(threshold_incomings);
+        let mut aux_incomings = Some(aux_incomings);
+        let (outgoings_tx, mut outgoings_rx) = mpsc::unbounded();
+
+        // Both protocols are polled within the same task, so they never borrow the rng at the same time
+        let rng = RefCell::new(rng);
+
+        let keygen = {
+            let keygen_builder = crate::keygen::<E>(self.eid, self.i, self.n)
+                .set_security_level::<L>()
+                .set_digest::<D>()
+                .enforce_reliable_broadcast(self.enforce_reliable_broadcast);
+            #[cfg(feature = "hd-wallet")]
+            let keygen_builder = keygen_builder.hd_wallet(self.hd_enabled);
+            let rng = &rng;
+            let outgoings_tx = outgoings_tx.clone();
+            async move {
+                let mut rng = SharedRng(rng);
+                match t {
+                    Some(t) => {
+                        let party = sub_party(threshold_rx, outgoings_tx, Msg::ThresholdKeygen);
+                        keygen_builder.set_threshold(t).start(&mut rng, party).await
+                    }
+                    None => {
+                        let party =
+                            sub_party(non_threshold_rx, outgoings_tx, Msg::NonThresholdKeygen);
+                        keygen_builder.start(&mut rng, party).await
+                    }
+                }
+            }
+        };
+        let aux = {
+            let aux_builder = crate::aux_info_gen(self.eid, self.i, self.n, self.pregenerated)
+                .set_digest::<D>()
+                .enforce_reliable_broadcast(self.enforce_reliable_broadcast);
+            let rng = &rng;
+            async move {
+                let party = sub_party(aux_rx, outgoings_tx, Msg::AuxInfo);
+                aux_builder.start(&mut SharedRng(rng), party).await
+            }
+        };
+        let mut keygen = pin!(keygen);
+        let mut aux = pin!(aux);
+
+        let mut key_share = None;
+        let mut aux_info = None;
+        let mut to_send: Option<Outgoing<Msg<E, L, D>>> = None;
+
+        futures::future::poll_fn(|cx| -> Poll<Result<(), Reason>> {
+            loop {
+                let mut progressed = false;
+
+                if key_share.is_none() {
+                    if let Poll::Ready(result) = keygen.as_mut().poll(cx) {
+                        key_share = Some(result.map_err(Reason::Keygen)?);
+                    }
+                }
+                if aux_info.is_none() {
+                    if let Poll::Ready(result) = aux.as_mut().poll(cx) {
+                        aux_info = Some(result.map_err(Reason::AuxInfo)?);
+                    }
+                }
+
+                // Forward messages sent by the protocols
+                loop {
+                    if to_send.is_none() {
+                        match outgoings_rx.poll_next_unpin(cx) {
+                            Poll::Ready(Some(msg)) => to_send = Some(msg),
+                            Poll::Ready(None) | Poll::Pending => break,
+                        }
+                    }
+                    match outgoings.poll_ready_unpin(cx) {
+                        Poll::Ready(Ok(())) => {
+                            if let Some(msg) = to_send.take() {
+                                outgoings
+                                    .start_send_unpin(msg)
+                                    .map_err(IoError::send_message)?;
+                            }
+                            progressed = true;
+                        }
+                        Poll::Ready(Err(err)) => {
+                            return Poll::Ready(Err(IoError::send_message(err).into()))
+                        }
+                        Poll::Pending => break,
+                    }
+                }
+                let flushed = match outgoings.poll_flush_unpin(cx) {
+                    Poll::Ready(Ok(())) => true,
+                    Poll::Ready(Err(err)) => {
+                        return Poll::Ready(Err(IoError::send_message(err).into()))
+                    }
+                    Poll::Pending => false,
+                };
+
+                if key_share.is_some() && aux_info.is_some() && to_send.is_none() && flushed {
+                    return Poll::Ready(Ok(()));
+                }
+
+                // Route received messages to the protocols
+                if non_threshold_incomings.is_some()
+                    || threshold_incomings.is_some()
+                    || aux_incomings.is_some()
+                {
+                    match incomings.poll_next_unpin(cx) {
+                        Poll::Ready(Some(Ok(incoming))) => {
+                            let Incoming {
+                                id,
+                                sender,
+                                msg_type,
+                                msg,
+                            } = incoming;
+                            // Protocol that has already completed doesn't need any more messages
+                            let _ = match msg {
+                                Msg::NonThresholdKeygen(msg) if t.is_none() => {
+                                    forward(&non_threshold_incomings, id, sender, msg_type, msg)
+                                }
+                                Msg::ThresholdKeygen(msg) if t.is_some() => {
+                                    forward(&threshold_incomings, id, sender, msg_type, msg)
+                                }
+                                Msg::AuxInfo(msg) => {
+                                    forward(&aux_incomings, id, sender, msg_type, msg)
+                                }
+                                // Message of keygen that doesn't match the threshold we run
+                                _ => return Poll::Ready(Err(Reason::UnexpectedMessage { sender })),
+                            };
+                            progressed = true;
+                        }
+                        Poll::Ready(Some(Err(err))) => {
+                            return Poll::Ready(Err(IoError::ReceiveMessage(Box::new(err)).into()))
+                        }
+                        Poll::Ready(None) => {
+                            // Protocols will fail with unexpected eof error
+                            non_threshold_incomings = None;
+                            threshold_incomings = None;
+                            aux_incomings = None;
+                            progressed = true;
+                        }
+                        Poll::Pending => {}
+                    }
+                }
+
+                if !progressed {
+                    return Poll::Pending;
+                }
+            }
+        })
+        .await?;
+
+        let key_share: IncompleteKeyShare<E> = key_share.ok_or(Reason::Bug)?;
+        let aux_info: AuxInfo<L> = aux_info.ok_or(Reason::Bug)?;
+        KeyShare::from_parts((key_share, aux_info))
+            .map_err(|err| Reason::InvalidKeyShare(err.into_error()).into())
+    }
+}
+
+/// Constructs a party of sub-protocol which messages are wrapped into [`Msg`]
+fn sub_party<E, L, D, M>(
+    incomings: mpsc::UnboundedReceiver<Result<Incoming<M>, Infallible>>,
+    outgoings: mpsc::UnboundedSender<Outgoing<Msg<E, L, D>>>,
+    wrap: fn(M) -> Msg<E, L, D>,
+) -> MpcParty<M, impl Delivery<M>>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest,
+{
+    let outgoings = outgoings.with(move |msg: Outgoing<M>| {
+        futures::future::ready(Ok::<_, mpsc::SendError>(Outgoing {
+            recipient: msg.recipient,
+            msg: wrap(msg.msg),
+        }))
+    });
+    MpcParty::connected((incomings, outgoings))
+}
+
+fn forward<M>(
+    incomings: &Option<mpsc::UnboundedSender<Result<Incoming<M>, Infallible>>>,
+    id: round_based::MsgId,
+    sender: round_based::PartyIndex,
+    msg_type: round_based::MessageType,
+    msg: M,
+) -> Result<(), ()> {
+    let incomings = incomings.as_ref().ok_or(())?;
+    incomings
+        .unbounded_send(Ok(Incoming {
+            id,
+            sender,
+            msg_type,
+            msg,
+        }))
+        .map_err(|_| ())
+}
+
+/// Rng shared between protocols that are polled within the same task
+struct SharedRng<'r, R>(&'r RefCell<&'r mut R>);
+
+impl<R: RngCore> RngCore for SharedRng<'_, R> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.borrow_mut().next_u32()
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.0.borrow_mut().next_u64()
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.borrow_mut().fill_bytes(dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.0.borrow_mut().try_fill_bytes(dest)
+    }
+}
+
+impl<R: CryptoRng> CryptoRng for SharedRng<'_, R> {}
+
+/// Error of combined keygen and aux info generation protocol
+#[derive(Debug, Error)]
+#[error("keygen with aux info generation failed")]
+pub struct KeygenWithAuxError(#[source] Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("keygen failed")]
+    Keygen(#[source] KeygenError),
+    #[error("aux info generation failed")]
+    AuxInfo(#[source] KeyRefreshError),
+    #[error("party {sender} sent message of keygen with different threshold")]
+    UnexpectedMessage { sender: round_based::PartyIndex },
+    #[error("i/o error")]
+    IoError(#[source] IoError),
+    #[error("generated key share is invalid")]
+    InvalidKeyShare(#[source] InvalidKeyShare),
+    #[error("protocol completed without output")]
+    Bug,
+}
+
+crate::errors::impl_from! {
+    impl From for KeygenWithAuxError {
+        err: Reason => KeygenWithAuxError(err),
+    }
+}
+
+crate::errors::impl_from! {
+    impl From for Reason {
+        err: IoError => Reason::IoError(err),
+    }
+}
@@ -17,6 +17,7 @@
 //! * (3+1)-round general threshold and non-threshold signing
 //! * Auxiliary info generation protocol
 //! * Key refresh for non-threshold keys
+//! * [Keygen combined with aux info generation](crate::keygen_with_aux()) within a single session
 //! * HD-wallets support based on [slip10] standard (compatible with [bip32]) \
 //!   Requires `hd-wallets` feature
 //!
@@ -25,11 +26,42 @@
 //! We also provide auxiliary tools like:
 //! * [Secret key reconstruction](crate::key_share::reconstruct_secret_key) (exporting key from TSS)
 //! * [Trusted dealer](crate::trusted_dealer) (importing key into TSS)
+//! * [Key tweaking](crate::key_share::tweak) deriving many independent keys from a single keygen
+//! * [Address derivation](crate::addresses) for Bitcoin and Ethereum \
+//!   Requires `addresses` feature
+//! * [Message codec](crate::codec) with a limit on message size, and versioned
+//!   [key share encoding](crate::key_share::to_bytes) \
+//!   Requires `codec` feature
+//! * [Transcripts](crate::transcript) of broadcast messages and their comparison, to find where
+//!   parties diverged \
+//!   Requires `codec` feature
+//! * [Encrypted backup](crate::backup) of key share \
+//!   Requires `backup` feature
+//! * [Export of public key data in GG20 format](crate::compat::gg20) for migration from GG20 \
+//!   Requires `compat-gg20` feature
+//! * [Presignature pool](crate::presignature_pool) with expiry and single-use enforcement
+//! * [Signing preflight](crate::signing::preflight) checking that signers' key shares fit together
+//! * [Signing guard](crate::signing_guard) limiting amount of concurrent signing sessions per key share
+//! * [Proof of participation](crate::participation) attesting which parties took part in signing
+//! * [Signing with over-provisioned signers](crate::overprovisioning) that tolerates signers dropping
+//! * [Round observer](crate::round_observer) reporting which parties delivered their messages in keygen,
+//!   signing and key refresh
+//! * [Per-round timeouts](crate::timeout) in keygen and signing, not tied to any async runtime \
+//!   Timers for tokio and async-std require `tokio` and `async-std` features
+//! * [Pluggable modular exponentiation](crate::modexp) to offload the heaviest operations to an accelerator
+//! * [Prime cache](crate::prime_cache) (persistent pool of pregenerated primes) \
+//!   Requires `prime-cache` feature
+//! * [Local simulation](crate::testing) of the protocols for tests \
+//!   Requires `testing` feature
 //!
 //! This crate **does not** (currently) support:
 //! * Key refresh for threshold keys (i.e., t-out-of-n)
 //! * Identifiable abort
 //! * The (5+1)-round signing protocol
+//! * Schnorr signatures (e.g. [BIP340] for Taproot): presignatures of CGGMP21 are specific to ECDSA, and
+//!   threshold Schnorr requires a different signing protocol. Key shares produced by `cggmp21-keygen`
+//!   are not tied to ECDSA and can be used with threshold Schnorr implementations built on `key-share` crate
+//! * Curves without an affine $x$ coordinate, such as Ristretto255 (see [`supported_curves`])
 //!
 //! Our implementation has been audited by Kudelski. Report can be found [here][report].
 //!
@@ -225,6 +257,12 @@
 //! 3. A threshold number of partial signatures can be combined using [`PartialSignature::combine`] to
 //!    obtain a full signature
 //!
+//! If partial signatures are collected by a coordinator rather than broadcast to all signers, use
+//! [`Presignature::issue_signature_share`] and [`Signature::from_shares`] instead. Each signer sends
+//! [`Presignature::commitment`] to the coordinator right after presigning, before the message is
+//! known; the coordinator verifies each share against the commitment and identifies signers who
+//! sent invalid ones.
+//!
 //! **Never reuse presignatures!** If you use the same presignature to sign two different messages,
 //! the private key may be leaked.
 //!
@@ -260,6 +298,28 @@
 //! However, you may opt for them by enabling `spof` feature, then you can use [`trusted_dealer`]
 //! for key import and [`key_share::reconstruct_secret_key`] for key export.
 //!
+//! ## `no_std` support
+//! Key generation and key share primitives live in [`cggmp21-keygen`] and [`key-share`] crates which
+//! are `#![no_std]` compatible (they only require `alloc`). Disable their default `std` feature to use
+//! them on embedded targets, e.g. hardware wallets. [`Tracer`](progress::Tracer) trait works without `std`, only
+//! `PerfProfiler` requires it as it relies on `std::time::Instant`.
+//!
+//! Other protocols (aux info generation, key refresh, signing) rely on Paillier encryption backed by
+//! GMP, and therefore require `std`. This crate always requires `std`, and enables `std` feature of
+//! `cggmp21-keygen` and `key-share`.
+//!
+//! There's currently no way to swap GMP for a pure-Rust big integer backend: Paillier encryption
+//! and ZK proofs are implemented in [`paillier-zk`] and [`fast-paillier`] crates which depend on
+//! [`rug`](crate::rug) unconditionally, and [`rug::Integer`](crate::rug::Integer) is part of public API
+//! (e.g. [`SecurityLevel::q`](security_level::SecurityLevel::q)). Targets where GMP can't be built
+//! (e.g. wasm) are limited to key generation and key share primitives. JavaScript bindings for
+//! key generation can be found in `wasm/bindings` directory of the repository.
+//!
+//! [`cggmp21-keygen`]: https://docs.rs/cggmp21-keygen
+//! [`key-share`]: https://docs.rs/key-share
+//! [`paillier-zk`]: https://docs.rs/paillier-zk
+//! [`fast-paillier`]: https://docs.rs/fast-paillier
+//!
 //! ## Differences between the implementation and CGGMP21
 //! [CGGMP21] only defines a non-threshold protocol. To support general thresholds,
 //! we defined our own CGGMP21-like key generation and threshold signing
@@ -275,6 +335,7 @@
 //! [security guidelines]: #security-guidelines
 //! [slip10]: https://github.com/satoshilabs/slips/blob/master/slip-0010.md
 //! [bip32]: https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki
+//! [BIP340]: https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki
 //! [report]: https://github.com/LFDT-Lockness/cggmp21/blob/m/docs/audit_report.pdf
 //! [serde]: https://serde.rs/
 //!
@@ -309,7 +370,10 @@ pub use {
 };
 
 #[doc(inline)]
-pub use cggmp21_keygen::{keygen, progress, ExecutionId};
+pub use cggmp21_keygen::{
+    cancellation, keygen, party_set_commitment, progress, reliable_broadcast, round_observer,
+    timeout, ExecutionId,
+};
 
 use generic_ec::{coords::HasAffineX, Curve, Point};
 use key_share::AnyKeyShare;
@@ -317,15 +381,37 @@ use round_based::PartyIndex;
 use security_level::SecurityLevel;
 use signing::SigningBuilder;
 
+#[cfg(feature = "addresses")]
+pub mod addresses;
+#[cfg(feature = "backup")]
+pub mod backup;
+#[cfg(feature = "codec")]
+pub mod codec;
+#[cfg(feature = "compat-gg20")]
+pub mod compat;
 mod errors;
+#[cfg(feature = "hd-wallet")]
+pub mod hd;
 pub mod key_refresh;
 pub mod key_share;
+pub mod keygen_with_aux;
+pub mod modexp;
+pub mod overprovisioning;
+pub mod participation;
+pub mod presignature_pool;
 pub mod security_level;
 pub mod signing;
+pub mod signing_guard;
 pub mod supported_curves;
 mod utils;
 mod zk;
 
+#[cfg(feature = "prime-cache")]
+pub mod prime_cache;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "codec")]
+pub mod transcript;
 #[cfg(feature = "spof")]
 pub mod trusted_dealer;
 
@@ -339,19 +425,53 @@ mod default_choice {
 pub mod keygen {
     #[doc(inline)]
     pub use cggmp21_keygen::{
-        msg, GenericKeygenBuilder, KeygenBuilder, KeygenError, NonThreshold,
-        ThresholdKeygenBuilder, WithThreshold,
+        commitment, contribution, equivocation, msg, verify_schnorr_contribution, version,
+        GenericKeygenBuilder, KeygenBuilder, KeygenError, NonThreshold, ReliabilityCheckFailure,
+        ThresholdKeygenBuilder, WithThreshold, WithWeights,
     };
 
     pub use msg::non_threshold::Msg as NonThresholdMsg;
     pub use msg::threshold::Msg as ThresholdMsg;
 }
 
+/// Utilities that the protocols use internally
+///
+/// Exposed for external tooling that needs to reproduce computations done by the protocols, e.g.
+/// recompute chain code or cross-check Paillier arithmetic. Behavior of these functions is covered
+/// by semver guarantees of the crate.
+pub mod utilities {
+    #[doc(inline)]
+    pub use crate::utils::{
+        bignumber_to_scalar, scalar_to_bignumber, scalar_to_bignumber_ct, xor_array,
+    };
+}
+
+/// Adds a new party to an existing threshold key
+pub mod enroll {
+    #[doc(inline)]
+    pub use cggmp21_keygen::enroll::*;
+}
+
+/// Converts non-threshold key into threshold key
+pub mod reshare {
+    #[doc(inline)]
+    pub use cggmp21_keygen::reshare::*;
+}
+
+#[doc(inline)]
+pub use cggmp21_keygen::MAX_PARTIES;
+
+#[cfg(feature = "codec")]
+pub use self::codec::{deframe, frame};
+
 pub use self::{
     key_refresh::{KeyRefreshError, PregeneratedPrimes},
     key_share::{IncompleteKeyShare, KeyShare},
     keygen::KeygenError,
-    signing::{DataToSign, PartialSignature, Presignature, Signature, SigningError},
+    signing::{
+        DataToSign, PartialSignature, Presignature, PresignatureCommitment, Signature,
+        SignatureShare, SigningError,
+    },
 };
 
 /// Protocol for finalizing the keygen by generating aux info.
@@ -379,6 +499,25 @@ where
     key_refresh::GenericKeyRefreshBuilder::new_aux_gen(eid, i, n, pregenerated)
 }
 
+/// Protocol that carries out keygen and aux info generation within a single session
+///
+/// Interleaves both protocols over the same network connection and outputs a complete [`KeyShare`].
+/// See [`keygen_with_aux`](mod@keygen_with_aux) module for details.
+///
+/// PregeneratedPrimes can be obtained with [`key_refresh::PregeneratedPrimes::generate`]
+pub fn keygen_with_aux<E, L>(
+    eid: ExecutionId,
+    i: u16,
+    n: u16,
+    pregenerated: key_refresh::PregeneratedPrimes<L>,
+) -> keygen_with_aux::KeygenWithAuxBuilder<E, L>
+where
+    E: Curve,
+    L: SecurityLevel,
+{
+    keygen_with_aux::KeygenWithAuxBuilder::new(eid, i, n, pregenerated)
+}
+
 /// Protocol for performing key refresh. Can be used to perform initial refresh
 /// with aux info generation, or for a refresh of a complete key share.
 ///
@@ -446,9 +585,13 @@ mod tests {
         crate::key_refresh::msg::aux_only::Msg<D, L>,
         crate::key_refresh::msg::non_threshold::Msg<E, D, L>,
 
+        crate::keygen_with_aux::Msg<E, L, D>,
+
         crate::signing::msg::Msg<E, D>,
         crate::signing::Presignature<E>,
         crate::signing::PartialSignature<E>,
+        crate::signing::PresignatureCommitment<E>,
+        crate::signing::SignatureShare<E>,
         crate::signing::Signature<E>,
     }
 }
@@ -0,0 +1,176 @@
+//! Pluggable modular exponentiation
+//!
+//! Exponentiation modulo Paillier modulus $N$ (or $N^2$) is the dominant cost of signing and
+//! aux info generation. Nodes equipped with a crypto accelerator or GPU may offload it by
+//! implementing [`ModExpBackend`] and setting it via
+//! [`SigningBuilder::set_modexp_backend`](crate::signing::SigningBuilder::set_modexp_backend) or
+//! [`GenericKeyRefreshBuilder::set_modexp_backend`](crate::key_refresh::GenericKeyRefreshBuilder::set_modexp_backend).
+//!
+//! When backend is set, the following operations are routed through it:
+//! * Signing: Paillier encryptions and homomorphic multiplications ($\gamma_i \cdot K_j$,
+//!   $x_i \cdot K_j$)
+//! * Aux info generation and key refresh: derivation of ring-Pedersen parameters, proving and
+//!   verifying $\Pi^\text{prm}$, and encryption of secret shares at key refresh
+//!
+//! Exponentiations performed within other ZK proofs (e.g. $\Pi^\text{enc}$, $\Pi^\text{aff-g}$,
+//! $\Pi^\text{mod}$, $\Pi^\text{fac}$) are implemented in [`paillier_zk`](crate::paillier_zk)
+//! crate and always computed in software.
+//!
+//! When backend is not set, exponentiations are computed by [`Software`] backend, except that
+//! encryptions under own Paillier key use CRT optimization.
+
+use paillier_zk::{
+    fast_paillier::{utils, Ciphertext, DecryptionKey, EncryptionKey, Nonce, Plaintext},
+    rug::{Complete, Integer},
+};
+
+/// Modular exponentiation backend
+///
+/// See [module-level](self) docs.
+pub trait ModExpBackend: Send + Sync {
+    /// Computes $\text{base}^\text{exponent} \bmod \text{modulus}$
+    ///
+    /// `modulus` is positive. `exponent` may be negative, in which case inverse of `base` is raised
+    /// to $-\text{exponent}$. Returns `None` if `exponent` is negative and `base` is not invertible
+    /// modulo `modulus`.
+    ///
+    /// Result must be in range $[0; \text{modulus})$. Implementation must be constant time if it
+    /// handles secret exponents.
+    fn pow_mod(&self, base: &Integer, exponent: &Integer, modulus: &Integer) -> Option<Integer>;
+}
+
+/// Software implementation of modular exponentiation backed by GMP
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Software;
+
+impl ModExpBackend for Software {
+    fn pow_mod(&self, base: &Integer, exponent: &Integer, modulus: &Integer) -> Option<Integer> {
+        base.pow_mod_ref(exponent, modulus).map(Integer::from)
+    }
+}
+
+/// Routes operations to the backend if it's set, or computes them in software otherwise
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ModExp<'a>(Option<&'a dyn ModExpBackend>);
+
+impl<'a> ModExp<'a> {
+    pub fn new(backend: Option<&'a dyn ModExpBackend>) -> Self {
+        Self(backend)
+    }
+
+    /// Computes `base^exponent mod modulus`
+    pub fn pow_mod(
+        &self,
+        base: &Integer,
+        exponent: &Integer,
+        modulus: &Integer,
+    ) -> Option<Integer> {
+        match self.0 {
+            Some(backend) => backend.pow_mod(base, exponent, modulus),
+            None => Software.pow_mod(base, exponent, modulus),
+        }
+    }
+
+    /// Encrypts `x` with `nonce`, same as [`EncryptionKey::encrypt_with`]
+    pub fn encrypt_with(
+        &self,
+        enc: &EncryptionKey,
+        x: &Plaintext,
+        nonce: &Nonce,
+    ) -> Option<Ciphertext> {
+        let Some(backend) = self.0 else {
+            return enc.encrypt_with(x, nonce).ok();
+        };
+        if !enc.in_signed_group(x) || !utils::in_mult_group(nonce, enc.n()) {
+            return None;
+        }
+        // (1 + N)^x = 1 + xN mod N^2
+        let a = (Integer::ONE + (x * enc.n()).complete()).modulo(enc.nn());
+        let b = backend.pow_mod(nonce, enc.n(), enc.nn())?;
+        Some((a * b).modulo(enc.nn()))
+    }
+
+    /// Encrypts `x` with `nonce` under own Paillier key, same as [`DecryptionKey::encrypt_with`]
+    pub fn encrypt_with_dec(
+        &self,
+        dec: &DecryptionKey,
+        x: &Plaintext,
+        nonce: &Nonce,
+    ) -> Option<Ciphertext> {
+        match self.0 {
+            Some(_) => self.encrypt_with(dec.encryption_key(), x, nonce),
+            None => dec.encrypt_with(x, nonce).ok(),
+        }
+    }
+
+    /// Homomorphic multiplication of `ciphertext` by `scalar`, same as [`EncryptionKey::omul`]
+    pub fn omul(
+        &self,
+        enc: &EncryptionKey,
+        scalar: &Integer,
+        ciphertext: &Ciphertext,
+    ) -> Option<Ciphertext> {
+        let Some(backend) = self.0 else {
+            return enc.omul(scalar, ciphertext).ok();
+        };
+        if !utils::in_mult_group_abs(scalar, enc.n()) || !utils::in_mult_group(ciphertext, enc.nn())
+        {
+            return None;
+        }
+        backend.pow_mod(ciphertext, scalar, enc.nn())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use paillier_zk::{
+        fast_paillier::{utils as paillier_utils, DecryptionKey},
+        rug::Integer,
+    };
+
+    use super::{ModExp, ModExpBackend, Software};
+
+    /// Backend that computes results in software, but counts how many times it was called
+    #[derive(Default)]
+    struct Counting(std::sync::atomic::AtomicUsize);
+
+    impl ModExpBackend for Counting {
+        fn pow_mod(
+            &self,
+            base: &Integer,
+            exponent: &Integer,
+            modulus: &Integer,
+        ) -> Option<Integer> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Software.pow_mod(base, exponent, modulus)
+        }
+    }
+
+    #[test]
+    fn backend_matches_software() {
+        let mut rng = rand_dev::DevRng::new();
+        let p = crate::utils::generate_blum_prime(&mut rng, 256);
+        let q = crate::utils::generate_blum_prime(&mut rng, 256);
+        let dec = DecryptionKey::from_primes(p, q).unwrap();
+        let enc = dec.encryption_key();
+
+        let backend = Counting::default();
+        let software = ModExp::default();
+        let accelerated = ModExp::new(Some(&backend));
+
+        let x = Integer::from(-1234567);
+        let k = Integer::from(7654321);
+        let nonce = paillier_utils::sample_in_mult_group(&mut rng, enc.n());
+
+        let c = software.encrypt_with(enc, &x, &nonce).unwrap();
+        assert_eq!(accelerated.encrypt_with(enc, &x, &nonce).unwrap(), c);
+        assert_eq!(accelerated.encrypt_with_dec(&dec, &x, &nonce).unwrap(), c);
+        assert_eq!(software.encrypt_with_dec(&dec, &x, &nonce).unwrap(), c);
+
+        let kc = software.omul(enc, &k, &c).unwrap();
+        assert_eq!(accelerated.omul(enc, &k, &c).unwrap(), kc);
+        assert_eq!(dec.decrypt(&kc).unwrap(), x * k);
+
+        assert_eq!(backend.0.load(std::sync::atomic::Ordering::Relaxed), 3);
+    }
+}
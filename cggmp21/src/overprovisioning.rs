@@ -0,0 +1,179 @@
+//! Signing with over-provisioned signers
+//!
+//! Signing protocol requires every chosen signer to complete: presignature is generated jointly by all
+//! of them, and a signature can only be obtained from signature shares of all of them. If exactly
+//! $t$ signers were chosen and one of them drops, the session has to be restarted.
+//!
+//! To tolerate up to $k$ signers dropping, start with $t+k$ candidate signers and generate presignatures
+//! for several [quorums](quorums) of $t$ signers out of them, each quorum within its own execution ID.
+//! Once presignatures are generated, each signer sends [commitments](crate::signing::Presignature::commitment)
+//! to its presignatures to the coordinator, who registers them via [`QuorumCombiner::add_quorum`]. When message needs to be signed, each signer issues [signature shares](crate::signing::SignatureShare)
+//! for every quorum it belongs to, and sends them to the coordinator. [`QuorumCombiner`] collects the
+//! shares and produces a signature as soon as any quorum delivered all of its shares. Signers that didn't
+//! deliver any share are [reported](QuorumSignature::dropped).
+//!
+//! Note that each signer takes part in generating presignatures for every quorum it belongs to, so
+//! presigning cost grows with amount of quorums. Choosing all $\binom{t+k}{t}$ quorums tolerates any $k$
+//! signers dropping; a smaller selection (e.g. $t+1$ quorums for $k=1$) is usually enough.
+//!
+//! **Each presignature must be used at most once**, in particular, presignatures of all the quorums
+//! must be discarded once the message is signed, regardless of which quorum completed.
+
+use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point};
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::{
+    key_share::SignerSets,
+    signing::{DataToSign, PresignatureCommitment, Signature, SignatureShare},
+};
+
+/// Enumerates all quorums of `t` signers out of `candidates`
+///
+/// Quorums are yielded lazily in lexicographic order of positions in `candidates`.
+pub fn quorums(candidates: &[PartyIndex], t: u16) -> impl Iterator<Item = Vec<PartyIndex>> + '_ {
+    let n = u16::try_from(candidates.len()).unwrap_or(u16::MAX);
+    SignerSets::new(n, t).map(move |positions| {
+        positions
+            .into_iter()
+            .map(|j| candidates[usize::from(j)])
+            .collect()
+    })
+}
+
+/// Collects signature shares issued by over-provisioned signers
+///
+/// See [module-level](self) docs.
+pub struct QuorumCombiner<E: Curve> {
+    public_key: Point<E>,
+    message: DataToSign<E>,
+    quorums: Vec<Quorum<E>>,
+}
+
+struct Quorum<E: Curve> {
+    signers: Vec<PartyIndex>,
+    commitments: Vec<PresignatureCommitment<E>>,
+    shares: Vec<Option<SignatureShare<E>>>,
+}
+
+/// Signature produced by [`QuorumCombiner`]
+#[derive(Debug, Clone)]
+pub struct QuorumSignature<E: Curve> {
+    /// Resulting signature
+    pub signature: Signature<E>,
+    /// Signers of the quorum that completed the signature
+    pub quorum: Vec<PartyIndex>,
+    /// Signers that didn't deliver any signature share
+    pub dropped: Vec<PartyIndex>,
+}
+
+impl<E: Curve> QuorumCombiner<E>
+where
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+{
+    /// Constructs a combiner for signing `message` with `public_key`
+    ///
+    /// If presignatures were derived via HD derivation, `public_key` must be the child public key.
+    pub fn new(public_key: Point<E>, message: DataToSign<E>) -> Self {
+        Self {
+            public_key,
+            message,
+            quorums: Vec::new(),
+        }
+    }
+
+    /// Registers a quorum that generated a presignature
+    ///
+    /// `signers` must be the same list of signers that was used to generate the presignature,
+    /// `commitments[i]` is the [commitment](crate::signing::Presignature::commitment) received
+    /// from `signers[i]`. Returns ID of the quorum that needs to be specified when shares are
+    /// [received](Self::receive).
+    ///
+    /// Returns an error if amount of commitments doesn't match amount of signers.
+    pub fn add_quorum(
+        &mut self,
+        signers: &[PartyIndex],
+        commitments: Vec<PresignatureCommitment<E>>,
+    ) -> Result<usize, MismatchedCommitments> {
+        if commitments.len() != signers.len() {
+            return Err(MismatchedCommitments);
+        }
+        self.quorums.push(Quorum {
+            signers: signers.to_vec(),
+            commitments,
+            shares: vec![None; signers.len()],
+        });
+        Ok(self.quorums.len() - 1)
+    }
+
+    /// Saves a signature share issued by `signer` for quorum `quorum`
+    ///
+    /// Returns an error if there's no such quorum, or if signer is not in the quorum.
+    pub fn receive(
+        &mut self,
+        quorum: usize,
+        signer: PartyIndex,
+        share: SignatureShare<E>,
+    ) -> Result<(), UnknownSigner> {
+        let quorum = self.quorums.get_mut(quorum).ok_or(UnknownSigner)?;
+        let position = quorum
+            .signers
+            .iter()
+            .position(|s| *s == signer)
+            .ok_or(UnknownSigner)?;
+        quorum.shares[position] = Some(share);
+        Ok(())
+    }
+
+    /// Produces a signature from the first quorum that delivered valid shares from all of its signers
+    ///
+    /// Returns `None` if no quorum has completed yet. Quorums in which some shares are invalid are
+    /// skipped; invalid shares can be identified by combining them via [`Signature::from_shares`].
+    pub fn try_combine(&self) -> Option<QuorumSignature<E>> {
+        self.quorums.iter().find_map(|quorum| {
+            let shares = quorum.shares.iter().cloned().collect::<Option<Vec<_>>>()?;
+            let signature = Signature::from_shares(
+                &self.public_key,
+                &quorum.commitments,
+                &shares,
+                &self.message,
+            )
+            .ok()?;
+            Some(QuorumSignature {
+                signature,
+                quorum: quorum.signers.clone(),
+                dropped: self.dropped(),
+            })
+        })
+    }
+
+    /// Returns signers that haven't delivered any signature share so far
+    pub fn dropped(&self) -> Vec<PartyIndex> {
+        let mut delivered = Vec::new();
+        let mut candidates = Vec::new();
+        for quorum in &self.quorums {
+            for (signer, share) in quorum.signers.iter().zip(&quorum.shares) {
+                candidates.push(*signer);
+                if share.is_some() {
+                    delivered.push(*signer);
+                }
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates.retain(|signer| !delivered.contains(signer));
+        candidates
+    }
+}
+
+/// Error indicating that signature share was received for unknown quorum or from a signer who is
+/// not in the quorum
+#[derive(Debug, Error)]
+#[error("signer is not a member of the quorum")]
+pub struct UnknownSigner;
+
+/// Error indicating that amount of presignature commitments doesn't match amount of signers in the
+/// quorum
+#[derive(Debug, Error)]
+#[error("amount of commitments doesn't match amount of signers")]
+pub struct MismatchedCommitments;
@@ -0,0 +1,100 @@
+//! Proof of participation in signing
+//!
+//! ECDSA signature doesn't reveal which signers produced it: any `t` parties holding key shares
+//! could have done that. When [enabled](crate::signing::SigningBuilder::prove_participation), each
+//! signer signs the [transcript hash](crate::signing::SignatureWithTranscript::transcript_hash) of
+//! the session with its long-term key using [`BroadcastSigner`] provided by transport layer, and
+//! signers exchange these signatures in an additional round once the ECDSA signature is obtained.
+//! Signatures of all signers form a [`ParticipationProof`] that is output along with the ECDSA
+//! signature by [`sign_with_transcript`](crate::signing::SigningBuilder::sign_with_transcript),
+//! and can be checked later by anyone who knows verification keys of the parties (see
+//! [`BroadcastVerifier`]) via [`verify_participation`].
+//!
+//! All signers must enable proof of participation.
+
+use digest::Digest;
+use round_based::PartyIndex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[doc(no_inline)]
+pub use crate::keygen::equivocation::{BroadcastSigner, BroadcastVerifier};
+
+/// Proof that a specific set of signers took part in the signing session
+///
+/// See [module-level](self) docs.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipationProof {
+    /// Hash of the session transcript signed by each signer
+    #[serde(with = "hex::serde")]
+    pub transcript_hash: [u8; 32],
+    /// Signatures of the signers, in the same order as signers are listed in the session
+    #[serde_as(as = "Vec<serde_with::Bytes>")]
+    pub signatures: Vec<Vec<u8>>,
+}
+
+/// Verifies that every party in `signers` took part in the session
+///
+/// `signers` are indexes of the parties at keygen, listed in the same order as at signing.
+/// `verification_keys` verifies signature of the party with given index: it only needs public
+/// keys of the parties, so the proof can be checked by an auditor who can't sign on behalf of any
+/// party. `D` must be the same digest as used at signing.
+///
+/// Returns an error listing signers whose signatures are invalid.
+pub fn verify_participation<D: Digest>(
+    proof: &ParticipationProof,
+    signers: &[PartyIndex],
+    verification_keys: &dyn BroadcastVerifier,
+) -> Result<(), InvalidParticipationProof> {
+    if proof.signatures.len() != signers.len() {
+        return Err(InvalidParticipationProof {
+            parties: signers.to_vec(),
+        });
+    }
+    let parties = signers
+        .iter()
+        .zip(&proof.signatures)
+        .filter(|(j, signature)| {
+            let data = signed_data::<D>(&proof.transcript_hash, signers, **j);
+            !verification_keys.verify(**j, &data, signature)
+        })
+        .map(|(j, _)| *j)
+        .collect::<Vec<_>>();
+    if parties.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidParticipationProof { parties })
+    }
+}
+
+/// Error indicating that [participation proof](ParticipationProof) is invalid
+#[derive(Debug, Error)]
+#[error("participation proof is invalid for parties {parties:?}")]
+pub struct InvalidParticipationProof {
+    /// Signers (indexes at keygen) whose signatures are invalid or missing
+    pub parties: Vec<PartyIndex>,
+}
+
+/// Data signed by `signer` (index at keygen)
+pub(crate) fn signed_data<D: Digest>(
+    transcript_hash: &[u8; 32],
+    signers: &[PartyIndex],
+    signer: PartyIndex,
+) -> Vec<u8> {
+    udigest::hash::<D>(&Participation {
+        transcript_hash,
+        signers,
+        signer,
+    })
+    .to_vec()
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.signing.participation")]
+struct Participation<'a> {
+    #[udigest(as_bytes)]
+    transcript_hash: &'a [u8; 32],
+    signers: &'a [PartyIndex],
+    signer: PartyIndex,
+}
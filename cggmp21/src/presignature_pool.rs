@@ -0,0 +1,262 @@
+//! In-memory pool of presignatures
+//!
+//! [Presignatures](Presignature) can be generated ahead of time, so that signing a message later
+//! doesn't require interaction between signers (see [`SigningBuilder::generate_presignature`]).
+//! [`PresignaturePool`] stores generated presignatures grouped by the set of signers that generated
+//! them, hands them out for signing, and evicts presignatures that weren't used within configured TTL.
+//!
+//! **Each presignature must be used at most once.** Issuing partial signatures for two different
+//! messages with the same presignature leaks the secret key. [`PresignaturePool::take`] and
+//! [`PresignaturePool::take_by_id`] remove the presignature from the pool, and the pool refuses to accept a presignature generated within
+//! execution ID that it has already seen, or a presignature with the same nonce $R$ as one it has
+//! already seen. Seen execution IDs and nonces are remembered even after the presignature was
+//! taken or evicted, until they're explicitly [forgotten](PresignaturePool::forget), so memory
+//! used by the pool grows with amount of inserted presignatures.
+//!
+//! Note that the pool only knows about presignatures inserted into it. It can't detect a
+//! presignature that was already used outside of the pool, or that was inserted into another
+//! pool instance (e.g. before the process was restarted).
+//!
+//! Signers must issue partial signatures with the same presignature, which they can't tell from
+//! their pools alone: pools of different signers may hand out presignatures in different order,
+//! e.g. if some presignature expired at one signer but not at another. Each presignature is
+//! therefore identified by [`PresignatureId`] derived from execution ID it was generated within.
+//! Typically, one of the signers takes a presignature via [`PresignaturePool::take`] and tells
+//! its ID to the others, who take the same presignature via [`PresignaturePool::take_by_id`].
+//!
+//! Pool is not persisted. Presignatures are secret and contain ephemeral data that must never be
+//! restored from a backup, so persisting them is not recommended.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn example<E, M>(
+//! #     eid: cggmp21::ExecutionId<'_>,
+//! #     i: u16,
+//! #     key_share: cggmp21::KeyShare<E>,
+//! #     party: M,
+//! #     message: cggmp21::DataToSign<E>,
+//! # ) -> Result<(), Box<dyn std::error::Error>>
+//! # where
+//! #     E: generic_ec::Curve,
+//! #     generic_ec::Point<E>: generic_ec::coords::HasAffineX<E>,
+//! #     M: round_based::Mpc<ProtocolMessage = cggmp21::signing::msg::Msg<E, sha2::Sha256>>,
+//! # {
+//! # let mut rng = rand::rngs::OsRng;
+//! use cggmp21::presignature_pool::PresignaturePool;
+//!
+//! let mut pool = PresignaturePool::<E>::new(std::time::Duration::from_secs(3600));
+//!
+//! let signers = [0, 2];
+//! let presignature = cggmp21::signing(eid, i, &signers, &key_share)
+//!     .generate_presignature(&mut rng, party)
+//!     .await?;
+//! pool.insert(&signers, eid, presignature)?;
+//!
+//! // later, on one of the signers
+//! if let Some((id, presignature)) = pool.take(&signers) {
+//!     let partial_signature = presignature.issue_partial_signature(message);
+//!     // send `id` to other signers
+//! #   let _ = (id, partial_signature);
+//! }
+//! // on other signers, after receiving `id`
+//! # let id = cggmp21::presignature_pool::PresignatureId::from_eid(eid);
+//! if let Some(presignature) = pool.take_by_id(&signers, id) {
+//!     let partial_signature = presignature.issue_partial_signature(message);
+//! #   let _ = partial_signature;
+//! }
+//! # Ok(()) }
+//! ```
+//!
+//! [`SigningBuilder::generate_presignature`]: crate::signing::SigningBuilder::generate_presignature
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use generic_ec::{Curve, NonZero, Point};
+use round_based::PartyIndex;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{signing::Presignature, ExecutionId};
+
+/// In-memory pool of presignatures
+///
+/// See [module-level](self) docs.
+pub struct PresignaturePool<E: Curve> {
+    ttl: Duration,
+    presignatures: HashMap<Vec<PartyIndex>, VecDeque<Entry<E>>>,
+    seen_eids: HashSet<PresignatureId>,
+    seen_nonces: HashSet<NonZero<Point<E>>>,
+}
+
+struct Entry<E: Curve> {
+    inserted_at: Instant,
+    id: PresignatureId,
+    presignature: Presignature<E>,
+}
+
+/// Identifier of presignature in the [pool](PresignaturePool)
+///
+/// It's a digest of execution ID that presignature was generated within, so all signers derive the
+/// same identifier for the same presignature, and it can be sent over the network to tell other
+/// signers which presignature to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PresignatureId([u8; 32]);
+
+impl PresignatureId {
+    /// Derives identifier of presignature generated within `eid`
+    pub fn from_eid(eid: ExecutionId) -> Self {
+        Self(eid.digest::<sha2::Sha256>())
+    }
+
+    /// Returns bytes representation of the identifier
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl<E: Curve> PresignaturePool<E> {
+    /// Constructs an empty pool
+    ///
+    /// Presignatures are evicted once they've been in the pool for longer than `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            presignatures: HashMap::new(),
+            seen_eids: HashSet::new(),
+            seen_nonces: HashSet::new(),
+        }
+    }
+
+    /// Puts a presignature into the pool
+    ///
+    /// `signers` must be the same list of signers indexes (`parties_indexes_at_keygen`) and `eid`
+    /// must be the same execution ID that were used to generate the presignature.
+    ///
+    /// Returns an error if the pool has already seen a presignature generated within `eid`, or a
+    /// presignature with the same nonce $R$, unless it was [forgotten](Self::forget).
+    pub fn insert(
+        &mut self,
+        signers: &[PartyIndex],
+        eid: ExecutionId,
+        presignature: Presignature<E>,
+    ) -> Result<(), DuplicatePresignature> {
+        let now = Instant::now();
+        self.evict_expired_at(now);
+
+        let id = PresignatureId::from_eid(eid);
+        if self.seen_eids.contains(&id) || self.seen_nonces.contains(&presignature.R) {
+            return Err(DuplicatePresignature);
+        }
+        self.seen_eids.insert(id);
+        self.seen_nonces.insert(presignature.R);
+        self.presignatures
+            .entry(signers.to_vec())
+            .or_default()
+            .push_back(Entry {
+                inserted_at: now,
+                id,
+                presignature,
+            });
+        Ok(())
+    }
+
+    /// Removes from the pool and returns the oldest presignature generated by `signers`, along
+    /// with its identifier
+    ///
+    /// Returns `None` if there are no such presignatures in the pool, or if all of them are expired.
+    /// Returned presignature is never handed out again. Other signers must use the same
+    /// presignature, so the identifier needs to be sent to them (see [`take_by_id`](Self::take_by_id)).
+    pub fn take(&mut self, signers: &[PartyIndex]) -> Option<(PresignatureId, Presignature<E>)> {
+        self.evict_expired_at(Instant::now());
+        let presignatures = self.presignatures.get_mut(signers)?;
+        let entry = presignatures.pop_front();
+        if presignatures.is_empty() {
+            self.presignatures.remove(signers);
+        }
+        entry.map(|entry| (entry.id, entry.presignature))
+    }
+
+    /// Removes from the pool and returns presignature generated by `signers` with identifier `id`
+    ///
+    /// Returns `None` if there's no such presignature in the pool, or if it's expired. Returned
+    /// presignature is never handed out again.
+    pub fn take_by_id(
+        &mut self,
+        signers: &[PartyIndex],
+        id: PresignatureId,
+    ) -> Option<Presignature<E>> {
+        self.evict_expired_at(Instant::now());
+        let presignatures = self.presignatures.get_mut(signers)?;
+        let position = presignatures.iter().position(|entry| entry.id == id)?;
+        let entry = presignatures.remove(position);
+        if presignatures.is_empty() {
+            self.presignatures.remove(signers);
+        }
+        entry.map(|entry| entry.presignature)
+    }
+
+    /// Forgets that a presignature generated within `eid` with nonce `R` was seen
+    ///
+    /// Presignature within `eid` and with nonce `R` can be inserted into the pool again afterwards,
+    /// so it must only be called when such presignature can never show up again, e.g. when it's
+    /// known to be destroyed by all the signers.
+    pub fn forget(&mut self, eid: ExecutionId, R: &NonZero<Point<E>>) {
+        self.seen_eids.remove(&PresignatureId::from_eid(eid));
+        self.seen_nonces.remove(R);
+    }
+
+    /// Evicts expired presignatures from the pool
+    ///
+    /// Expired presignatures are also evicted on [`insert`](Self::insert), [`take`](Self::take) and
+    /// [`take_by_id`](Self::take_by_id), this method can be used to drop them without waiting for the next call. Evicted presignatures
+    /// are still remembered as seen, see [`forget`](Self::forget). Returns amount of evicted
+    /// presignatures.
+    pub fn evict_expired(&mut self) -> usize {
+        self.evict_expired_at(Instant::now())
+    }
+
+    /// Returns amount of presignatures generated by `signers` that are in the pool
+    ///
+    /// Expired presignatures that haven't been evicted yet are counted as well.
+    pub fn available(&self, signers: &[PartyIndex]) -> usize {
+        self.presignatures.get(signers).map_or(0, VecDeque::len)
+    }
+
+    /// Returns total amount of presignatures in the pool
+    ///
+    /// Expired presignatures that haven't been evicted yet are counted as well.
+    pub fn len(&self) -> usize {
+        self.presignatures.values().map(VecDeque::len).sum()
+    }
+
+    /// Checks whether the pool is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn evict_expired_at(&mut self, now: Instant) -> usize {
+        let ttl = self.ttl;
+        let is_expired = |inserted_at: Instant| now.saturating_duration_since(inserted_at) >= ttl;
+
+        let mut evicted = 0;
+        self.presignatures.retain(|_, presignatures| {
+            // Presignatures are ordered by insertion time, so expired ones are at the front
+            while presignatures
+                .front()
+                .is_some_and(|entry| is_expired(entry.inserted_at))
+            {
+                presignatures.pop_front();
+                evicted += 1;
+            }
+            !presignatures.is_empty()
+        });
+        evicted
+    }
+}
+
+/// Error indicating that presignature generated within the same execution ID, or presignature with
+/// the same nonce, was already put into the [pool](PresignaturePool)
+#[derive(Debug, Error)]
+#[error("presignature with the same execution id or nonce was already put into the pool")]
+pub struct DuplicatePresignature;
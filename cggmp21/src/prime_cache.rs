@@ -0,0 +1,213 @@
+//! Persistent pool of pregenerated primes
+//!
+//! Generating [`PregeneratedPrimes`] takes a while, which makes aux info generation and key refresh
+//! slow to start. [`PrimeCache`] lets you generate primes ahead of time (e.g. in background), persist them
+//! in a store of your choice, and take a fresh pair for each protocol run.
+//!
+//! **Each pair of primes must be used at most once.** Reusing the same primes in two protocol runs
+//! results in two parties (or two key refreshes) sharing the same Paillier key, which breaks security
+//! of the protocol. [`PrimeCache`] removes primes from the store as they're handed out, and the
+//! [`PrimeStore`] implementation must guarantee that removed entry is never returned again.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn main() -> Result<(), cggmp21::prime_cache::PrimeCacheError> {
+//! use cggmp21::prime_cache::{CachedPrimes, PrimeCache};
+//! use cggmp21::security_level::SecurityLevel128;
+//! # let mut rng = rand::rngs::OsRng;
+//!
+//! // `Vec` is an in-memory store, in production you'd want store backed by disk or database
+//! let mut cache = PrimeCache::new(Vec::<CachedPrimes>::new());
+//! cache.fill::<SecurityLevel128, _>(&mut rng, 2)?;
+//!
+//! let primes = cache.pop::<SecurityLevel128>()?.expect("cache is not empty");
+//! // use `primes` in aux info generation
+//! # let _ = primes;
+//! # Ok(()) }
+//! ```
+
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{rug::Integer, security_level::SecurityLevel, PregeneratedPrimes};
+
+/// Pair of primes stored in [`PrimeStore`]
+///
+/// Tagged with security level the primes were generated for. Can be serialized and persisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPrimes {
+    security_bits: u32,
+    p: Integer,
+    q: Integer,
+}
+
+impl CachedPrimes {
+    /// Tags primes with security level `L`
+    pub fn new<L: SecurityLevel>(primes: PregeneratedPrimes<L>) -> Self {
+        let (p, q) = primes.split();
+        Self {
+            security_bits: L::SECURITY_BITS,
+            p,
+            q,
+        }
+    }
+
+    /// Returns [`SECURITY_BITS`](crate::security_level::KeygenSecurityLevel::SECURITY_BITS)
+    /// of security level the primes were generated for
+    pub fn security_bits(&self) -> u32 {
+        self.security_bits
+    }
+}
+
+/// Storage for [`PrimeCache`]
+///
+/// Implementation decides where primes are persisted: in memory, on disk, in database, etc.
+pub trait PrimeStore {
+    /// Store error
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Saves primes in the store
+    fn put(&mut self, primes: CachedPrimes) -> Result<(), Self::Error>;
+
+    /// Removes from the store and returns primes generated for `security_bits`
+    ///
+    /// Returns `Ok(None)` if there are no such primes in the store.
+    ///
+    /// Removal must be atomic: once primes are returned, the store must never return them
+    /// again, even if the process crashes right after this method returns. For instance,
+    /// database-backed store should delete the entry in the same transaction that reads it.
+    fn take(&mut self, security_bits: u32) -> Result<Option<CachedPrimes>, Self::Error>;
+
+    /// Returns amount of primes generated for `security_bits` in the store
+    fn count(&self, security_bits: u32) -> Result<usize, Self::Error>;
+}
+
+/// In-memory store
+impl PrimeStore for Vec<CachedPrimes> {
+    type Error = std::convert::Infallible;
+
+    fn put(&mut self, primes: CachedPrimes) -> Result<(), Self::Error> {
+        self.push(primes);
+        Ok(())
+    }
+
+    fn take(&mut self, security_bits: u32) -> Result<Option<CachedPrimes>, Self::Error> {
+        Ok(self
+            .iter()
+            .position(|p| p.security_bits == security_bits)
+            .map(|i| self.remove(i)))
+    }
+
+    fn count(&self, security_bits: u32) -> Result<usize, Self::Error> {
+        Ok(self
+            .iter()
+            .filter(|p| p.security_bits == security_bits)
+            .count())
+    }
+}
+
+/// Pool of pregenerated primes backed by [`PrimeStore`]
+///
+/// See [module-level docs](self) for details.
+pub struct PrimeCache<S> {
+    store: S,
+}
+
+impl<S: PrimeStore> PrimeCache<S> {
+    /// Constructs a cache on top of the store
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Adds primes to the cache
+    ///
+    /// Primes can be generated elsewhere (e.g. in a background thread) via [`PregeneratedPrimes::generate`]
+    pub fn put<L: SecurityLevel>(
+        &mut self,
+        primes: PregeneratedPrimes<L>,
+    ) -> Result<(), PrimeCacheError> {
+        self.store
+            .put(CachedPrimes::new(primes))
+            .map_err(|err| Reason::Store(Box::new(err)).into())
+    }
+
+    /// Generates `amount` pairs of primes and adds them to the cache. Takes some time.
+    pub fn fill<L: SecurityLevel, R: RngCore>(
+        &mut self,
+        rng: &mut R,
+        amount: usize,
+    ) -> Result<(), PrimeCacheError> {
+        for _ in 0..amount {
+            self.put(PregeneratedPrimes::<L>::generate(rng))?;
+        }
+        Ok(())
+    }
+
+    /// Takes primes for security level `L` from the cache
+    ///
+    /// Returned primes are removed from the cache, so they're never handed out twice. Returns
+    /// `Ok(None)` if the cache doesn't have primes for `L`.
+    ///
+    /// Returns error if the store returned primes generated for another security level or of
+    /// invalid size. Such primes are removed from the store anyway.
+    pub fn pop<L: SecurityLevel>(
+        &mut self,
+    ) -> Result<Option<PregeneratedPrimes<L>>, PrimeCacheError> {
+        let Some(primes) = self
+            .store
+            .take(L::SECURITY_BITS)
+            .map_err(|err| Reason::Store(Box::new(err)))?
+        else {
+            return Ok(None);
+        };
+        if primes.security_bits != L::SECURITY_BITS {
+            return Err(Reason::MismatchedSecurityLevel {
+                expected: L::SECURITY_BITS,
+                actual: primes.security_bits,
+            }
+            .into());
+        }
+        PregeneratedPrimes::new(primes.p, primes.q)
+            .map(Some)
+            .ok_or_else(|| Reason::InvalidPrimes.into())
+    }
+
+    /// Returns amount of primes for security level `L` in the cache
+    pub fn count<L: SecurityLevel>(&self) -> Result<usize, PrimeCacheError> {
+        self.store
+            .count(L::SECURITY_BITS)
+            .map_err(|err| Reason::Store(Box::new(err)).into())
+    }
+
+    /// Returns reference to the underlying store
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the underlying store
+    pub fn into_store(self) -> S {
+        self.store
+    }
+}
+
+/// Prime cache error
+#[derive(Debug, Error)]
+#[error("prime cache error")]
+pub struct PrimeCacheError(#[source] Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("store error")]
+    Store(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("store returned primes for security level of {actual} bits, expected {expected} bits")]
+    MismatchedSecurityLevel { expected: u32, actual: u32 },
+    #[error("store returned primes of invalid size")]
+    InvalidPrimes,
+}
+
+impl From<Reason> for PrimeCacheError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
@@ -67,6 +67,23 @@ pub fn max_exponents_size<L: SecurityLevel>() -> (u32, u32) {
     (x_bits, y_bits)
 }
 
+/// Checks that security level is compatible with curve `E`
+///
+/// Range proofs used in signing and key refresh prove that secret scalars (nonces, secret shares)
+/// lie in range $\pm 2^\ell$, so $\ell$ must be at least bit size of the curve order. Security level
+/// tuned for a smaller curve makes honest parties fail to prove statements about their secrets.
+///
+/// Protocols check it before they start and return an error if the level is not compatible.
+pub fn is_compatible_with_curve<L: SecurityLevel, E: generic_ec::Curve>() -> bool {
+    L::ELL >= curve_order_bits::<E>()
+}
+
+/// Returns bit size of order of curve `E`
+pub(crate) fn curve_order_bits<E: generic_ec::Curve>() -> usize {
+    let order_minus_one = crate::utils::scalar_to_bignumber(-generic_ec::Scalar::<E>::one());
+    (order_minus_one + 1u8).significant_bits() as usize
+}
+
 /// Internal module that's powers `define_security_level` macro
 #[doc(hidden)]
 pub mod _internal {
@@ -132,6 +149,9 @@ pub mod _internal {
 /// });
 /// ```
 ///
+/// Security level that's not suitable for production should be marked by appending
+/// `production_safe = false,` after `q`. See [`KeygenSecurityLevel::IS_PRODUCTION_SAFE`].
+///
 /// **Note:** currently, security parameter $m$ is hardcoded to the [`M = 128`](M) due to compiler limitations.
 /// Setting any other value of $m$ results into compilation error. We're going to fix that once `generic_const_exprs`
 /// feature is stable.
@@ -144,6 +164,7 @@ macro_rules! define_security_level {
         ell_prime = $ell_prime:expr,
         m = $m:tt,
         q = $q:expr,
+        $(production_safe = $safe:expr,)?
     }) => {
         $crate::define_security_level! {
             $struct_name {
@@ -157,6 +178,7 @@ macro_rules! define_security_level {
         $crate::security_level::_internal::define_keygen_security_level! {
             $struct_name {
                 security_bits = $k,
+                $(production_safe = $safe,)?
             }
         }
     };
@@ -1,11 +1,20 @@
 //! Signing protocol
 
+use std::iter;
+use std::time::Duration;
+
 use digest::Digest;
 use futures::SinkExt;
-use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point, Scalar, SecretScalar};
-use generic_ec_zkp::polynomial::lagrange_coefficient_at_zero;
+use generic_ec::{
+    coords::{AlwaysHasAffineX, Coordinate, HasAffineX, HasAffineXAndParity, Parity},
+    Curve, NonZero, Point, Scalar, SecretScalar,
+};
+use generic_ec_zkp::{polynomial::lagrange_coefficient_at_zero, schnorr_pok};
 use paillier_zk::rug::Complete;
-use paillier_zk::{fast_paillier, rug::Integer};
+use paillier_zk::{
+    fast_paillier,
+    rug::{self, Integer},
+};
 use paillier_zk::{
     group_element_vs_paillier_encryption_in_range as pi_log,
     paillier_affine_operation_in_range as pi_aff, paillier_encryption_in_range as pi_enc,
@@ -20,13 +29,28 @@ use round_based::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::cancellation::{CancellationToken, Cancelled};
 use crate::errors::IoError;
-use crate::key_share::{KeyShare, PartyAux, VssSetup};
+use crate::key_share::{KeyShare, VssSetup};
+use crate::modexp::{ModExp, ModExpBackend};
+use crate::participation::{BroadcastSigner, ParticipationProof};
 use crate::progress::Tracer;
+use crate::reliable_broadcast;
+use crate::round_observer::{ObservedIncomings, RoundObserver};
+use crate::signing_guard::SessionPermit;
+use crate::timeout::{BoxedTimer, RoundTimeout, TimedOut, Timer, TimerNotSet};
 use crate::{key_share::InvalidKeyShare, security_level::SecurityLevel, utils, ExecutionId};
 
 use self::msg::*;
 
+pub use self::arena::SigningArena;
+pub use self::batch_verify::BatchVerifier;
+pub use self::preflight::{preflight, PreflightError};
+
+mod arena;
+mod batch_verify;
+mod preflight;
+
 /// A (prehashed) data to be signed
 ///
 /// `DataToSign` holds a scalar that represents data to be signed. Different ECDSA schemes define different
@@ -34,6 +58,22 @@ use self::msg::*;
 /// cryptographic hash functions. Most commonly, original data is hashed using SHA2-256, then output is parsed
 /// as big-endian integer and taken modulo curve order. This exact functionality is implemented in
 /// [DataToSign::digest] and [DataToSign::from_digest] constructors.
+///
+/// ## Hash-to-scalar reduction
+/// Hash output may be longer than the curve order. There are two ways to map it into a scalar:
+/// * [Reduction](DataToSign::digest_reducing): the whole hash is taken modulo curve order. That's
+///   what [DataToSign::digest] and [DataToSign::from_digest] do.
+/// * [Truncation](DataToSign::digest_truncating): hash is truncated to its leftmost bits, as many
+///   as the bit length of the curve order, and then taken modulo curve order. That's how ECDSA is
+///   defined in SEC1, FIPS 186 and RFC 6979 (`bits2int`), and what most verifiers implement.
+///
+/// When hash is not longer than the curve order, both ways give the same scalar. That's the case for
+/// Bitcoin and Ethereum, which use 256-bit hashes (double SHA2-256 and Keccak-256) with secp256k1, and
+/// for SHA2-256 with secp256r1. Otherwise, e.g. SHA2-512 with secp256k1 or secp256r1, or any 256-bit
+/// hash with Stark curve (its order is 252 bits long), the results differ: the signature would be
+/// valid for one of the scalars only. Standard ECDSA verifiers (e.g. NIST P-256 verifiers used in
+/// WebAuthn) expect truncation. Stark-based chains define their own hashing into the scalar field,
+/// use [DataToSign::from_scalar] for them.
 #[derive(Debug, Clone, Copy)]
 pub struct DataToSign<E: Curve>(Scalar<E>);
 
@@ -41,22 +81,62 @@ impl<E: Curve> DataToSign<E> {
     /// Construct a `DataToSign` by hashing `data` with algorithm `D`
     ///
     /// `data_to_sign = hash(data) mod q`
+    ///
+    /// Same as [`digest_reducing`](Self::digest_reducing). See [hash-to-scalar reduction](Self#hash-to-scalar-reduction).
     pub fn digest<D: Digest>(data: &[u8]) -> Self {
-        DataToSign(Scalar::from_be_bytes_mod_order(D::digest(data)))
+        Self::digest_reducing::<D>(data)
     }
 
     /// Constructs a `DataToSign` from output of given digest
     ///
     /// `data_to_sign = hash(data) mod q`
+    ///
+    /// See [hash-to-scalar reduction](Self#hash-to-scalar-reduction).
     pub fn from_digest<D: Digest>(hash: D) -> Self {
         DataToSign(Scalar::from_be_bytes_mod_order(hash.finalize()))
     }
 
+    /// Constructs a `DataToSign` by hashing `data` with algorithm `D`, taking the whole hash
+    /// modulo curve order
+    ///
+    /// `data_to_sign = hash(data) mod q`
+    ///
+    /// See [hash-to-scalar reduction](Self#hash-to-scalar-reduction).
+    pub fn digest_reducing<D: Digest>(data: &[u8]) -> Self {
+        DataToSign(Scalar::from_be_bytes_mod_order(D::digest(data)))
+    }
+
+    /// Constructs a `DataToSign` by hashing `data` with algorithm `D`, truncating the hash to the
+    /// bit length of curve order
+    ///
+    /// `data_to_sign = (hash(data) >> max(0, hash_len - q_len)) mod q`, where `hash_len` and `q_len`
+    /// are bit lengths of the hash and the curve order
+    ///
+    /// See [hash-to-scalar reduction](Self#hash-to-scalar-reduction).
+    pub fn digest_truncating<D: Digest>(data: &[u8]) -> Self {
+        let hash = D::digest(data);
+        let excess =
+            (8 * hash.len()).saturating_sub(crate::security_level::curve_order_bits::<E>());
+        if excess == 0 {
+            return DataToSign(Scalar::from_be_bytes_mod_order(hash));
+        }
+        let truncated = Integer::from_digits(&hash, rug::integer::Order::Msf) >> excess;
+        DataToSign(Scalar::from_be_bytes_mod_order(
+            truncated.to_digits::<u8>(rug::integer::Order::Msf),
+        ))
+    }
+
     /// Constructs a `DataToSign` from scalar
     ///
     /// ** Note: [DataToSign::digest] and [DataToSign::from_digest] are preferred way to construct the `DataToSign` **
     ///
-    /// `scalar` must be output of cryptographic hash function applied to original message to be signed
+    /// Any scalar is accepted and signed as is, no hashing is applied. It's intended for integrations
+    /// where message is already a field element, e.g. it was computed by a zk circuit or by
+    /// chain-specific hashing.
+    ///
+    /// Caller is responsible for the way messages are mapped onto scalars: a signature of the scalar
+    /// is valid for every message that maps onto it, so the mapping must be collision resistant and
+    /// domain separated, e.g. messages of different kinds must never map onto the same scalar.
     pub fn from_scalar(scalar: Scalar<E>) -> Self {
         Self(scalar)
     }
@@ -95,6 +175,80 @@ pub struct PartialSignature<E: Curve> {
     pub sigma: Scalar<E>,
 }
 
+/// Public commitments to signer's presignature
+///
+/// Can be obtained using [`Presignature::commitment`]. A coordinator who's going to collect
+/// [signature shares](SignatureShare) receives commitment from each signer right after the
+/// presignature is generated, **before** the message to sign is known, and uses them to verify
+/// the shares in [`Signature::from_shares`]. Since commitments are fixed before signers learn the
+/// message, a signer can't adjust them to make an invalid share pass the verification.
+/// Commitment doesn't carry any sensitive information.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PresignatureCommitment<E: Curve> {
+    /// $R$ component of presignature
+    pub R: NonZero<Point<E>>,
+    /// $K_i = k_i \cdot R$
+    pub K: Point<E>,
+    /// $S_i = \chi_i \cdot R$
+    pub S: Point<E>,
+}
+
+/// Signer's contribution to the signature, verifiable by a coordinator
+///
+/// Can be obtained using [`Presignature::issue_signature_share`]. Unlike [`PartialSignature`],
+/// signature share can be verified by a coordinator holding [commitment](PresignatureCommitment)
+/// of the signer, which lets the coordinator identify the signers who sent invalid shares.
+/// Signature share doesn't carry any sensitive information.
+///
+/// Threshold amount of signature shares can be combined into a regular signature using [`Signature::from_shares`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SignatureShare<E: Curve> {
+    /// $R$ component of presignature
+    pub R: NonZero<Point<E>>,
+    /// $\sigma_i$ component of partial signature
+    pub sigma: Scalar<E>,
+}
+
+/// Signature share issued in advance for a specific message and signing session
+///
+/// Obtained via [`Presignature::delegate_contribution`]. Lets a signer who's normally offline
+/// authorize an online aggregator to complete a signature on its behalf: the aggregator
+/// [redeems](Self::redeem) the contribution into a [`SignatureShare`] and combines it with shares
+/// of other signers via [`Signature::from_shares`].
+///
+/// Signature share is cryptographically bound to the message it was issued for, so it can't be
+/// used to sign any other message. Message, execution ID, expiration time and the share are
+/// signed by the delegator under its public share $X_i$, so none of them can be altered without
+/// invalidating the contribution. The aggregator verifies the signature and checks that the
+/// contribution is not expired when it's redeemed. Delegated contribution doesn't carry any
+/// sensitive information.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct DelegatedContribution<E: Curve> {
+    /// Signature share of the signer
+    pub share: SignatureShare<E>,
+    /// Message the share was issued for
+    pub message: Scalar<E>,
+    /// Execution ID of signing session the share was issued for
+    pub execution_id: Vec<u8>,
+    /// Expiration time, in seconds since Unix epoch
+    pub expires_at: u64,
+    /// Delegator's signature of the contribution
+    pub authorization: ContributionAuthorization<E>,
+}
+
+/// Schnorr signature of [delegated contribution](DelegatedContribution) under delegator's public share
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ContributionAuthorization<E: Curve> {
+    /// Commitment to the ephemeral secret
+    pub commit: Point<E>,
+    /// Response to the challenge
+    pub proof: Scalar<E>,
+}
+
 /// ECDSA signature
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug)]
 #[serde(bound = "")]
@@ -105,6 +259,70 @@ pub struct Signature<E: Curve> {
     pub s: NonZero<Scalar<E>>,
 }
 
+/// Signature along with the hash of the signing session transcript
+///
+/// Output of [`SigningBuilder::sign_with_transcript`]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(bound = "")]
+pub struct SignatureWithTranscript<E: Curve> {
+    /// Resulting signature
+    pub signature: Signature<E>,
+    /// Digest committing to the signing session
+    ///
+    /// It commits to execution ID, signers set, public key, message to sign, nonce commitments
+    /// (ciphertexts $K_j, G_j$), public values $\Gamma_j, \delta_j, \Delta_j, \sigma_j$ sent by each
+    /// signer, and the resulting signature. Zero-knowledge proofs and other data sent over p2p
+    /// channels are specific to each pair of signers, so they're not included: all signers obtain
+    /// the same transcript hash.
+    pub transcript_hash: [u8; 32],
+    /// Proof that signers took part in the session, if [enabled](SigningBuilder::prove_participation)
+    pub participation_proof: Option<ParticipationProof>,
+}
+
+/// State of signer that can be persisted and resumed later
+///
+/// Obtained via [`SigningBuilder::sign_resumable`] once presignature is generated. Signing can be
+/// continued with [`resume_signing`]: it issues a partial signature and combines it with partial
+/// signatures of other signers. It lets signers suspend after the expensive interactive part of the
+/// protocol is done, e.g. when a mobile OS suspends the app.
+///
+/// State is bound to the execution ID, signers set, and the message to sign: it can only be resumed
+/// to sign the message it was created for. **Contains secret data**: presignature that is zeroized
+/// on drop. The state must be resumed at most once, and must not be used after the signing completed,
+/// so it intentionally doesn't implement `Clone`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SigningState<E: Curve> {
+    #[serde(with = "hex::serde")]
+    execution_id: Vec<u8>,
+    i: PartyIndex,
+    signers: Vec<PartyIndex>,
+    message: Scalar<E>,
+    public_key: NonZero<Point<E>>,
+    presignature: Presignature<E>,
+}
+
+impl<E: Curve> SigningState<E> {
+    /// Execution ID of the signing session
+    pub fn execution_id(&self) -> ExecutionId<'_> {
+        ExecutionId::new(&self.execution_id)
+    }
+    /// Indexes of signers (at keygen) participating in the session
+    pub fn signers(&self) -> &[PartyIndex] {
+        &self.signers
+    }
+    /// Message the state is bound to
+    pub fn message(&self) -> DataToSign<E> {
+        DataToSign::from_scalar(self.message)
+    }
+    /// Public key the signature will be valid for
+    ///
+    /// If derivation path was set, it's the child public key.
+    pub fn public_key(&self) -> NonZero<Point<E>> {
+        self.public_key
+    }
+}
+
 macro_rules! prefixed {
     ($name:tt) => {
         concat!("dfns.cggmp21.signing.", $name)
@@ -146,6 +364,23 @@ pub mod msg {
         Round4(MsgRound4<E>),
         /// Reliability check message (optional additional round)
         ReliabilityCheck(MsgReliabilityCheck<D>),
+        /// Participation message (optional additional round)
+        Participation(MsgParticipation),
+    }
+
+    impl<E: Curve, D: Digest> Msg<E, D> {
+        /// Name of the round the message belongs to
+        pub fn round_name(&self) -> &'static str {
+            match self {
+                Msg::Round1a(_) => "round 1a",
+                Msg::Round1b(_) => "round 1b",
+                Msg::Round2(_) => "round 2",
+                Msg::Round3(_) => "round 3",
+                Msg::Round4(_) => "round 4",
+                Msg::ReliabilityCheck(_) => "reliability check",
+                Msg::Participation(_) => "participation",
+            }
+        }
     }
 
     /// Message from round 1a
@@ -158,6 +393,15 @@ pub mod msg {
         /// $G_i$
         #[udigest(as = utils::encoding::Integer)]
         pub G: fast_paillier::Ciphertext,
+        /// Round 1a messages for the rest of presignatures, when signing a batch of messages
+        ///
+        /// Each message in the batch has empty `batch` field
+        pub batch: Vec<MsgRound1a>,
+        /// Epoch of the signer's key share
+        ///
+        /// Lets signers detect that their key shares come from different
+        /// [epochs](crate::key_share::DirtyKeyInfo::epoch)
+        pub epoch: u64,
     }
 
     /// Message from round 1b
@@ -165,13 +409,19 @@ pub mod msg {
     pub struct MsgRound1b {
         /// $\psi^0_{j,i}$
         pub psi0: (pi_enc::Commitment, pi_enc::Proof),
+        /// Round 1b messages for the rest of presignatures, when signing a batch of messages
+        ///
+        /// Each message in the batch has empty `batch` field
+        pub batch: Vec<MsgRound1b>,
     }
 
     /// Message from round 2
+    #[serde_with::serde_as]
     #[derive(Clone, Serialize, Deserialize)]
     #[serde(bound = "")]
     pub struct MsgRound2<E: Curve> {
         /// $\Gamma_i$
+        #[serde_as(as = "generic_ec::serde::Compact")]
         pub Gamma: Point<E>,
         /// $D_{j,i}$
         pub D: fast_paillier::Ciphertext,
@@ -187,32 +437,56 @@ pub mod msg {
         pub hat_psi: (pi_aff::Commitment<E>, pi_aff::Proof),
         /// $\psi'_{j,i}$
         pub psi_prime: (pi_log::Commitment<E>, pi_log::Proof),
+        /// Round 2 messages for the rest of presignatures, when signing a batch of messages
+        ///
+        /// Each message in the batch has empty `batch` field
+        pub batch: Vec<MsgRound2<E>>,
     }
 
     /// Message from round 3
+    #[serde_with::serde_as]
     #[derive(Clone, Serialize, Deserialize)]
     #[serde(bound = "")]
     pub struct MsgRound3<E: Curve> {
         /// $\delta_i$
+        #[serde_as(as = "generic_ec::serde::Compact")]
         pub delta: Scalar<E>,
         /// $\Delta_i$
+        #[serde_as(as = "generic_ec::serde::Compact")]
         pub Delta: Point<E>,
         /// $\psi''_{j,i}$
         pub psi_prime_prime: (pi_log::Commitment<E>, pi_log::Proof),
+        /// Round 3 messages for the rest of presignatures, when signing a batch of messages
+        ///
+        /// Each message in the batch has empty `batch` field
+        pub batch: Vec<MsgRound3<E>>,
     }
 
     /// Message from round 4
+    #[serde_with::serde_as]
     #[derive(Clone, Serialize, Deserialize)]
     #[serde(bound = "")]
     pub struct MsgRound4<E: Curve> {
         /// $\sigma_i$
+        #[serde_as(as = "generic_ec::serde::Compact")]
         pub sigma: Scalar<E>,
+        /// Round 4 messages for the rest of presignatures, when signing a batch of messages
+        ///
+        /// Each message in the batch has empty `batch` field
+        pub batch: Vec<MsgRound4<E>>,
     }
 
-    /// Message from auxiliary round for reliability check
+    #[doc(no_inline)]
+    pub use crate::reliable_broadcast::MsgReliabilityCheck;
+
+    /// Message from auxiliary round for [proof of participation](crate::participation)
+    #[serde_with::serde_as]
     #[derive(Clone, Serialize, Deserialize)]
-    #[serde(bound = "")]
-    pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+    pub struct MsgParticipation {
+        /// Signature of the signer over the session transcript
+        #[serde_as(as = "serde_with::Bytes")]
+        pub signature: Vec<u8>,
+    }
 }
 
 mod unambiguous {
@@ -241,12 +515,75 @@ mod unambiguous {
         pub prime_prime: bool,
     }
 
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("batch_sid"))]
+    pub struct BatchSid<'a> {
+        pub sid: ExecutionId<'a>,
+        pub index: u64,
+    }
+
     #[derive(udigest::Digestable)]
     #[udigest(tag = prefixed!("echo_round"))]
     pub struct Echo<'a> {
         pub sid: ExecutionId<'a>,
         pub ciphertexts: &'a super::MsgRound1a,
     }
+
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("transcript"))]
+    #[udigest(bound = "")]
+    pub struct Transcript<'a, E: generic_ec::Curve> {
+        pub sid: ExecutionId<'a>,
+        pub signers: &'a [u16],
+        pub public_key: generic_ec::Point<E>,
+        pub parties: Vec<PartyTranscript<'a, E>>,
+        pub signatures: Vec<SignedMessage<E>>,
+    }
+
+    #[derive(udigest::Digestable)]
+    #[udigest(bound = "")]
+    pub struct PartyTranscript<'a, E: generic_ec::Curve> {
+        pub ciphertexts: &'a super::MsgRound1a,
+        pub Gamma: Vec<generic_ec::Point<E>>,
+        pub delta: Vec<generic_ec::Scalar<E>>,
+        pub Delta: Vec<generic_ec::Point<E>>,
+        pub sigma: Vec<generic_ec::Scalar<E>>,
+    }
+
+    #[derive(udigest::Digestable)]
+    #[udigest(bound = "")]
+    pub struct SignedMessage<E: generic_ec::Curve> {
+        pub message: generic_ec::Scalar<E>,
+        pub r: generic_ec::Scalar<E>,
+        pub s: generic_ec::Scalar<E>,
+    }
+
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("delegated_contribution"))]
+    #[udigest(bound = "")]
+    pub struct DelegatedContribution<'a, E: generic_ec::Curve> {
+        pub sid: ExecutionId<'a>,
+        pub public_share: generic_ec::Point<E>,
+        pub commit: generic_ec::Point<E>,
+        pub R: generic_ec::Point<E>,
+        pub sigma: generic_ec::Scalar<E>,
+        pub message: generic_ec::Scalar<E>,
+        pub expires_at: u64,
+    }
+
+    #[derive(udigest::Digestable)]
+    #[udigest(tag = prefixed!("deterministic_session"))]
+    #[udigest(bound = "")]
+    pub struct DeterministicSession<'a, E: generic_ec::Curve> {
+        #[udigest(as_bytes)]
+        pub seed_domain: &'a [u8],
+        pub sid: ExecutionId<'a>,
+        pub i: u16,
+        pub signers: &'a [u16],
+        pub secret_share: &'a generic_ec::Scalar<E>,
+        pub additive_shift: Option<generic_ec::Scalar<E>>,
+        pub messages: Vec<generic_ec::Scalar<E>>,
+    }
 }
 
 /// Signing entry point
@@ -265,7 +602,20 @@ pub struct SigningBuilder<
     key_share: &'r KeyShare<E, L>,
     execution_id: ExecutionId<'r>,
     tracer: Option<&'r mut dyn Tracer>,
+    round_observer: Option<&'r mut dyn RoundObserver>,
+    cancellation: Option<CancellationToken>,
+    timer: Option<BoxedTimer<'r>>,
+    round_timeout: Option<Duration>,
     enforce_reliable_broadcast: bool,
+    deterministic_session: Option<&'r [u8]>,
+    nonce_commitment_checkpoint: NonceCommitmentCheckpoint<'r>,
+    aggregate_nonce_checkpoint: AggregateNonceCheckpoint<'r, E>,
+    expected_public_key: Option<NonZero<Point<E>>>,
+    require_production_level: bool,
+    participation_signer: Option<&'r dyn BroadcastSigner>,
+    arena: Option<&'r mut SigningArena>,
+    modexp: Option<&'r dyn ModExpBackend>,
+    session_permit: Option<SessionPermit>,
     _digest: std::marker::PhantomData<D>,
 
     #[cfg(feature = "hd-wallet")]
@@ -292,7 +642,20 @@ where
             key_share: secret_key_share,
             execution_id: eid,
             tracer: None,
+            round_observer: None,
+            cancellation: None,
+            timer: None,
+            round_timeout: None,
             enforce_reliable_broadcast: true,
+            deterministic_session: None,
+            nonce_commitment_checkpoint: Default::default(),
+            aggregate_nonce_checkpoint: Default::default(),
+            expected_public_key: None,
+            require_production_level: false,
+            participation_signer: None,
+            arena: None,
+            modexp: None,
+            session_permit: None,
             _digest: std::marker::PhantomData,
             #[cfg(feature = "hd-wallet")]
             additive_shift: None,
@@ -309,7 +672,20 @@ where
             parties_indexes_at_keygen: self.parties_indexes_at_keygen,
             key_share: self.key_share,
             tracer: self.tracer,
+            round_observer: self.round_observer,
+            cancellation: self.cancellation,
+            timer: self.timer,
+            round_timeout: self.round_timeout,
             enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            deterministic_session: self.deterministic_session,
+            nonce_commitment_checkpoint: self.nonce_commitment_checkpoint,
+            aggregate_nonce_checkpoint: self.aggregate_nonce_checkpoint,
+            expected_public_key: self.expected_public_key,
+            require_production_level: self.require_production_level,
+            participation_signer: self.participation_signer,
+            arena: self.arena,
+            modexp: self.modexp,
+            session_permit: self.session_permit,
             execution_id: self.execution_id,
             _digest: std::marker::PhantomData,
             #[cfg(feature = "hd-wallet")]
@@ -317,12 +693,85 @@ where
         }
     }
 
+    /// Holds the permit until the protocol is completed
+    ///
+    /// Permit is moved into the protocol future, so it's released when the future is completed or
+    /// dropped.
+    pub(crate) fn hold_session_permit(mut self, permit: SessionPermit) -> Self {
+        self.session_permit = Some(permit);
+        self
+    }
+
     /// Specifies a tracer that tracks progress of protocol execution
     pub fn set_progress_tracer(mut self, tracer: &'r mut dyn Tracer) -> Self {
         self.tracer = Some(tracer);
         self
     }
 
+    /// Specifies an observer that is notified each time a message is received
+    ///
+    /// See [`round_observer`](crate::round_observer) module for details.
+    pub fn set_round_observer(mut self, observer: &'r mut dyn RoundObserver) -> Self {
+        self.round_observer = Some(observer);
+        self
+    }
+
+    /// Sets a token that can be used to cancel the protocol
+    ///
+    /// Cancellation is checked when each round begins. See [`cancellation`](crate::cancellation)
+    /// module for details.
+    pub fn set_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sets a timer used to enforce [round timeout](Self::set_round_timeout)
+    ///
+    /// See [`timeout`](crate::timeout) module for details.
+    pub fn set_timer<T>(mut self, timer: T) -> Self
+    where
+        T: Timer + 'r,
+        T::Sleep: 'r,
+    {
+        self.timer = Some(BoxedTimer::new(timer));
+        self
+    }
+
+    /// Limits time the protocol waits for messages of each round
+    ///
+    /// If messages of a round are not received within `timeout`, the protocol aborts with an error
+    /// (see [`SigningError::is_timed_out`]). Requires a [timer](Self::set_timer) to be set, otherwise
+    /// the protocol returns an error on start.
+    pub fn set_round_timeout(mut self, timeout: Duration) -> Self {
+        self.round_timeout = Some(timeout);
+        self
+    }
+
+    /// Specifies public key that the signature is expected to be valid for
+    ///
+    /// Before the protocol starts, signer checks that the key share corresponds to `public_key` and
+    /// returns an error otherwise (see [`SigningError::is_wrong_key`]). If derivation path is set,
+    /// `public_key` is compared against the derived child public key. No messages are sent if
+    /// the check fails.
+    ///
+    /// It's a cheap safety check that prevents signing with a wrong key share.
+    pub fn expect_public_key(mut self, public_key: NonZero<Point<E>>) -> Self {
+        self.expected_public_key = Some(public_key);
+        self
+    }
+
+    /// Refuses to sign if security level of the key share is not suitable for production
+    ///
+    /// When set, signer returns an error before sending any message if
+    /// [`SecurityLevel::IS_PRODUCTION_SAFE`](crate::security_level::KeygenSecurityLevel::IS_PRODUCTION_SAFE)
+    /// is `false` (see [`SigningError::is_not_production_safe`]).
+    pub fn require_production_level(self) -> Self {
+        Self {
+            require_production_level: true,
+            ..self
+        }
+    }
+
     #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
     pub fn enforce_reliable_broadcast(self, v: bool) -> Self {
         Self {
@@ -331,6 +780,137 @@ where
         }
     }
 
+    /// Makes signers prove their participation in the session
+    ///
+    /// Once the signature is obtained, each signer signs the session transcript using `signer`
+    /// and sends the signature to other signers in an additional round. Signatures of all signers
+    /// are output by [`sign_with_transcript`](Self::sign_with_transcript) as
+    /// [`ParticipationProof`]. See [`participation`](crate::participation) module for details.
+    ///
+    /// All signers must enable it.
+    pub fn prove_participation(mut self, signer: &'r dyn BroadcastSigner) -> Self {
+        self.participation_signer = Some(signer);
+        self
+    }
+
+    /// Reuses values derived from the key share across signing sessions
+    ///
+    /// Reduces amount of heap allocations per session. See [`SigningArena`] for details.
+    pub fn set_arena(mut self, arena: &'r mut SigningArena) -> Self {
+        self.arena = Some(arena);
+        self
+    }
+
+    /// Offloads modular exponentiations to the `backend`
+    ///
+    /// Paillier encryptions and homomorphic multiplications are routed through the backend. See
+    /// [`modexp`](crate::modexp) module for details.
+    pub fn set_modexp_backend(mut self, backend: &'r dyn ModExpBackend) -> Self {
+        self.modexp = Some(backend);
+        self
+    }
+
+    /// Sets a hook that observes nonce commitments of all signers
+    ///
+    /// Once the first round is completed and all signers' commitments $K_j, G_j$ (encrypted nonce
+    /// shares) are received and their proofs are verified, the hook is called with index of each
+    /// signer $0 \le j < t$ (including the local party) and its commitment. The hook is called
+    /// before any message of the second round is sent.
+    ///
+    /// Hook only observes the commitments, it can't alter the messages. Use
+    /// [`require_nonce_commitment_ack`](Self::require_nonce_commitment_ack) to gate progress of
+    /// the protocol.
+    pub fn on_nonce_commitment(
+        mut self,
+        hook: &'r mut (dyn FnMut(PartyIndex, &MsgRound1a) + Send),
+    ) -> Self {
+        self.nonce_commitment_checkpoint.hook = Some(hook);
+        self
+    }
+
+    /// Requires an acknowledgement before proceeding after nonce commitments are received
+    ///
+    /// Once the [hook](Self::on_nonce_commitment) (if any) is called for all nonce commitments,
+    /// the protocol awaits `ack`. If it resolves into `true`, the protocol continues, otherwise
+    /// it aborts with an error. For instance, `ack` can be a receiving end of a channel through
+    /// which the coordinator sends its decision after inspecting the commitments.
+    ///
+    /// Aborting at this point doesn't leak anything: so far, the local party has only sent its
+    /// nonce shares encrypted under its own Paillier key, along with zero-knowledge proofs. Note
+    /// that with [deterministic session](Self::set_deterministic_session) the nonce shares are
+    /// derived from the execution id and other inputs, so an aborted session must be retried
+    /// with a fresh execution id, otherwise the retry reuses the same nonce shares.
+    pub fn require_nonce_commitment_ack(
+        mut self,
+        ack: impl std::future::Future<Output = bool> + Send + 'r,
+    ) -> Self {
+        self.nonce_commitment_checkpoint.ack = Some(Box::pin(ack));
+        self
+    }
+
+    /// Sets a hook that observes aggregate nonce $R$
+    ///
+    /// Once presignature is computed, the hook is called with aggregate nonce $R$ (the point
+    /// whose $x$ coordinate becomes $r$ component of the signature). The hook is called before
+    /// partial signature is sent to other signers. When signing a batch of messages, the hook is
+    /// called for each presignature in the same order as messages to sign.
+    ///
+    /// Hook is not called when [generating a presignature](Self::generate_presignature). Use
+    /// [`Presignature::R`] instead.
+    ///
+    /// Hook only observes the nonce, it can't alter the protocol. Use
+    /// [`require_aggregate_nonce_ack`](Self::require_aggregate_nonce_ack) to gate release of
+    /// partial signature.
+    pub fn on_aggregate_nonce(
+        mut self,
+        hook: &'r mut (dyn FnMut(&NonZero<Point<E>>) + Send),
+    ) -> Self {
+        self.aggregate_nonce_checkpoint.hook = Some(hook);
+        self
+    }
+
+    /// Requires an acknowledgement before releasing partial signature
+    ///
+    /// Once the [hook](Self::on_aggregate_nonce) (if any) is called for all aggregate nonces,
+    /// the protocol awaits `ack`. If it resolves into `true`, partial signature is sent to other
+    /// signers, otherwise the protocol aborts with an error and partial signature is never sent.
+    ///
+    /// Aborting at this point doesn't leak the secret share: partial signature $\sigma_i$ is the
+    /// only message that combines the nonce share with the secret share, and it's not sent.
+    /// Note that with [deterministic session](Self::set_deterministic_session) the nonce shares
+    /// are derived from the execution id and other inputs, so an aborted session must be retried
+    /// with a fresh execution id. Retrying with the same execution id reuses the nonce shares,
+    /// while other signers may change their contributions, which is not covered by security
+    /// analysis of the protocol.
+    pub fn require_aggregate_nonce_ack(
+        mut self,
+        ack: impl std::future::Future<Output = bool> + Send + 'r,
+    ) -> Self {
+        self.aggregate_nonce_checkpoint.ack = Some(Box::pin(ack));
+        self
+    }
+
+    /// Derives randomness of the local party deterministically
+    ///
+    /// When set, the `rng` passed to the protocol is ignored. Instead, all randomness of the local
+    /// party (nonce shares, Paillier encryption nonces, ZK proofs) is derived from a seed that
+    /// includes `seed_domain`, the secret key share, execution id, signers indexes, HD derivation
+    /// path, and data to sign. Running the protocol again with the same inputs reproduces the same
+    /// messages and the same signature. Different signers still contribute independent secrets,
+    /// so the nonce remains distributed.
+    ///
+    /// **This mode is intended for testing and auditing reproducibility.** Unlike RFC6979, it isn't
+    /// safe against malicious co-signers: if the protocol is executed twice with the same inputs,
+    /// and another signer changes its contribution in the second execution, the resulting two
+    /// signatures reveal the secret key. Never reuse execution id across sessions, and don't use this
+    /// mode in production unless all signers are trusted.
+    pub fn set_deterministic_session(self, seed_domain: &'r [u8]) -> Self {
+        Self {
+            deterministic_session: Some(seed_domain),
+            ..self
+        }
+    }
+
     /// Specifies HD derivation path
     ///
     /// Note: when generating a presignature, derivation path doesn't need to be known in advance. Instead
@@ -395,6 +975,70 @@ where
         Ok(self)
     }
 
+    /// Checks that security level is production-safe if it's
+    /// [required](Self::require_production_level)
+    fn check_production_level(&self) -> Result<(), SigningError> {
+        if self.require_production_level && !L::IS_PRODUCTION_SAFE {
+            return Err(InvalidArgs::NotProductionSafe.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that security level is [compatible](crate::security_level::is_compatible_with_curve)
+    /// with the curve
+    fn check_level_for_curve(&self) -> Result<(), SigningError> {
+        if !crate::security_level::is_compatible_with_curve::<L, E>() {
+            return Err(InvalidArgs::IncompatibleLevelForCurve {
+                ell: L::ELL,
+                curve_order_bits: crate::security_level::curve_order_bits::<E>(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Checks that key share corresponds to [expected public key](Self::expect_public_key), if
+    /// it's set
+    fn check_expected_public_key(&self) -> Result<(), SigningError> {
+        let Some(expected) = self.expected_public_key else {
+            return Ok(());
+        };
+        let public_key = *self.key_share.core.shared_public_key;
+        #[cfg(feature = "hd-wallet")]
+        let public_key = public_key + Point::generator() * self.additive_shift.unwrap_or_default();
+        if public_key != *expected {
+            return Err(InvalidArgs::WrongKey.into());
+        }
+        Ok(())
+    }
+
+    /// Constructs rng for [deterministic session](Self::set_deterministic_session), if it's enabled
+    fn deterministic_session_rng(
+        &self,
+        messages_to_sign: Option<&[DataToSign<E>]>,
+    ) -> Option<rand_hash::HashRng<D, unambiguous::DeterministicSession<'r, E>>> {
+        let seed_domain = self.deterministic_session?;
+        let key_share: &'r KeyShare<E, L> = self.key_share;
+        Some(rand_hash::HashRng::from_seed(
+            unambiguous::DeterministicSession {
+                seed_domain,
+                sid: self.execution_id,
+                i: self.i,
+                signers: self.parties_indexes_at_keygen,
+                secret_share: key_share.core.x.as_ref(),
+                #[cfg(feature = "hd-wallet")]
+                additive_shift: self.additive_shift,
+                #[cfg(not(feature = "hd-wallet"))]
+                additive_shift: None,
+                messages: messages_to_sign
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|m| m.to_scalar())
+                    .collect(),
+            },
+        ))
+    }
+
     /// Starts presignature generation protocol
     pub async fn generate_presignature<R, M>(
         self,
@@ -405,16 +1049,30 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = Msg<E, D>>,
     {
+        self.check_production_level()?;
+        self.check_level_for_curve()?;
+        self.check_expected_public_key()?;
+        let messages_to_sign = None;
+        let mut rng = SessionRng::new(self.deterministic_session_rng(messages_to_sign), rng);
+        let round_timeout = RoundTimeout::new(self.timer, self.round_timeout)?;
         match signing_t_out_of_n(
             self.tracer,
-            rng,
+            self.round_observer,
+            self.cancellation.as_ref(),
+            &round_timeout,
+            &mut rng,
             party,
             self.execution_id,
             self.i,
             self.key_share,
             self.parties_indexes_at_keygen,
-            None,
+            messages_to_sign,
             self.enforce_reliable_broadcast,
+            self.nonce_commitment_checkpoint,
+            self.aggregate_nonce_checkpoint,
+            self.participation_signer,
+            self.arena,
+            self.modexp,
             #[cfg(feature = "hd-wallet")]
             self.additive_shift,
             #[cfg(not(feature = "hd-wallet"))]
@@ -422,8 +1080,10 @@ where
         )
         .await?
         {
-            ProtocolOutput::Presignature(presig) => Ok(presig),
-            ProtocolOutput::Signature(_) => Err(Bug::UnexpectedProtocolOutput.into()),
+            ProtocolOutput::Presignatures(mut presigs) if presigs.len() == 1 => {
+                Ok(presigs.remove(0))
+            }
+            _ => Err(Bug::UnexpectedProtocolOutput.into()),
         }
     }
 
@@ -455,16 +1115,30 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = Msg<E, D>>,
     {
+        self.check_production_level()?;
+        self.check_level_for_curve()?;
+        self.check_expected_public_key()?;
+        let messages_to_sign = Some(core::slice::from_ref(&message_to_sign));
+        let mut rng = SessionRng::new(self.deterministic_session_rng(messages_to_sign), rng);
+        let round_timeout = RoundTimeout::new(self.timer, self.round_timeout)?;
         match signing_t_out_of_n(
             self.tracer,
-            rng,
+            self.round_observer,
+            self.cancellation.as_ref(),
+            &round_timeout,
+            &mut rng,
             party,
             self.execution_id,
             self.i,
             self.key_share,
             self.parties_indexes_at_keygen,
-            Some(message_to_sign),
+            messages_to_sign,
             self.enforce_reliable_broadcast,
+            self.nonce_commitment_checkpoint,
+            self.aggregate_nonce_checkpoint,
+            self.participation_signer,
+            self.arena,
+            self.modexp,
             #[cfg(feature = "hd-wallet")]
             self.additive_shift,
             #[cfg(not(feature = "hd-wallet"))]
@@ -472,8 +1146,8 @@ where
         )
         .await?
         {
-            ProtocolOutput::Signature(sig) => Ok(sig),
-            ProtocolOutput::Presignature(_) => Err(Bug::UnexpectedProtocolOutput.into()),
+            ProtocolOutput::Signatures { mut sigs, .. } if sigs.len() == 1 => Ok(sigs.remove(0)),
+            _ => Err(Bug::UnexpectedProtocolOutput.into()),
         }
     }
 
@@ -496,44 +1170,392 @@ where
             self.sign(rng, party, message_to_sign)
         })
     }
-}
 
-/// t-out-of-n signing
-///
-/// CGGMP paper doesn't support threshold signing out of the box. However, threshold signing
-/// can be easily implemented on top of CGGMP's [`signing_n_out_of_n`] by converting polynomial
-/// (VSS) key shares into additive (by multiplying at lagrange coefficient) and calling
-/// t-out-of-t protocol. The trick is described in more details in the spec.
-async fn signing_t_out_of_n<M, E, L, D, R>(
-    mut tracer: Option<&mut dyn Tracer>,
-    rng: &mut R,
-    party: M,
-    sid: ExecutionId<'_>,
-    i: PartyIndex,
-    key_share: &KeyShare<E, L>,
-    S: &[PartyIndex],
-    message_to_sign: Option<DataToSign<E>>,
-    enforce_reliable_broadcast: bool,
-    additive_shift: Option<Scalar<E>>,
-) -> Result<ProtocolOutput<E>, SigningError>
-where
-    M: Mpc<ProtocolMessage = Msg<E, D>>,
-    E: Curve,
-    L: SecurityLevel,
-    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
-    R: RngCore + CryptoRng,
-    NonZero<Point<E>>: AlwaysHasAffineX<E>,
-{
-    tracer.protocol_begins();
-    tracer.stage("Map t-out-of-n protocol to t-out-of-t");
+    /// Starts signing protocol, outputs signature along with the transcript hash
+    ///
+    /// Same as [`sign`](Self::sign), but additionally outputs a digest committing to the
+    /// signing session. All signers obtain the same digest. It can be logged and later used
+    /// to prove that a specific session produced a specific signature.
+    /// See [`SignatureWithTranscript::transcript_hash`] for what it commits to.
+    pub async fn sign_with_transcript<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        message_to_sign: DataToSign<E>,
+    ) -> Result<SignatureWithTranscript<E>, SigningError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        self.check_production_level()?;
+        self.check_level_for_curve()?;
+        self.check_expected_public_key()?;
+        let messages_to_sign = Some(core::slice::from_ref(&message_to_sign));
+        let mut rng = SessionRng::new(self.deterministic_session_rng(messages_to_sign), rng);
+        let round_timeout = RoundTimeout::new(self.timer, self.round_timeout)?;
+        match signing_t_out_of_n(
+            self.tracer,
+            self.round_observer,
+            self.cancellation.as_ref(),
+            &round_timeout,
+            &mut rng,
+            party,
+            self.execution_id,
+            self.i,
+            self.key_share,
+            self.parties_indexes_at_keygen,
+            messages_to_sign,
+            self.enforce_reliable_broadcast,
+            self.nonce_commitment_checkpoint,
+            self.aggregate_nonce_checkpoint,
+            self.participation_signer,
+            self.arena,
+            self.modexp,
+            #[cfg(feature = "hd-wallet")]
+            self.additive_shift,
+            #[cfg(not(feature = "hd-wallet"))]
+            None,
+        )
+        .await?
+        {
+            ProtocolOutput::Signatures {
+                mut sigs,
+                transcript_hash,
+                participation_proof,
+            } if sigs.len() == 1 => Ok(SignatureWithTranscript {
+                signature: sigs.remove(0),
+                transcript_hash,
+                participation_proof,
+            }),
+            _ => Err(Bug::UnexpectedProtocolOutput.into()),
+        }
+    }
 
-    // Validate arguments
-    let n: u16 = key_share
-        .aux
-        .parties
-        .len()
-        .try_into()
+    /// Returns a state machine that can be used to carry out the signing protocol that outputs
+    /// transcript hash
+    ///
+    /// See [`round_based::state_machine`] for details on how that can be done.
+    #[cfg(feature = "state-machine")]
+    pub fn sign_with_transcript_sync<R>(
+        self,
+        rng: &'r mut R,
+        message_to_sign: DataToSign<E>,
+    ) -> impl round_based::state_machine::StateMachine<
+        Output = Result<SignatureWithTranscript<E>, SigningError>,
+        Msg = Msg<E, D>,
+    > + 'r
+    where
+        R: RngCore + CryptoRng,
+    {
+        round_based::state_machine::wrap_protocol(move |party| {
+            self.sign_with_transcript(rng, party, message_to_sign)
+        })
+    }
+
+    /// Starts signing protocol that can be suspended and resumed later
+    ///
+    /// Carries out presignature generation, and outputs [`SigningState`] bound to the session and
+    /// `message_to_sign`. State can be persisted, and signing can be completed later via
+    /// [`resume_signing`]. Presignature generation is not resumable: if signer is suspended before
+    /// it's completed, the session needs to be restarted with a new execution ID.
+    ///
+    /// All signers must use resumable signing.
+    pub async fn sign_resumable<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        message_to_sign: DataToSign<E>,
+    ) -> Result<SigningState<E>, SigningError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        let execution_id = self.execution_id.as_bytes().to_vec();
+        let i = self.i;
+        let signers = self.parties_indexes_at_keygen.to_vec();
+        let public_key = self.key_share.core.shared_public_key;
+        #[cfg(feature = "hd-wallet")]
+        let public_key = NonZero::from_point(
+            *public_key + Point::generator() * self.additive_shift.unwrap_or_default(),
+        )
+        .ok_or(Bug::DerivedChildKeyZero)?;
+
+        let presignature = self.generate_presignature(rng, party).await?;
+        Ok(SigningState {
+            execution_id,
+            i,
+            signers,
+            message: message_to_sign.to_scalar(),
+            public_key,
+            presignature,
+        })
+    }
+
+    /// Starts signing protocol that signs a batch of messages
+    ///
+    /// Generates a presignature per message within a single protocol execution, so the expensive
+    /// message-independent rounds are run once for the whole batch. Each presignature is used to
+    /// sign exactly one message. Outputs signatures in the same order as `messages_to_sign`.
+    ///
+    /// Returns an error if `messages_to_sign` is empty. All signers must sign the same list of
+    /// messages.
+    pub async fn sign_batch<R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        messages_to_sign: &[DataToSign<E>],
+    ) -> Result<Vec<Signature<E>>, SigningError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        self.check_production_level()?;
+        self.check_level_for_curve()?;
+        self.check_expected_public_key()?;
+        let messages_to_sign = Some(messages_to_sign);
+        let mut rng = SessionRng::new(self.deterministic_session_rng(messages_to_sign), rng);
+        let round_timeout = RoundTimeout::new(self.timer, self.round_timeout)?;
+        match signing_t_out_of_n(
+            self.tracer,
+            self.round_observer,
+            self.cancellation.as_ref(),
+            &round_timeout,
+            &mut rng,
+            party,
+            self.execution_id,
+            self.i,
+            self.key_share,
+            self.parties_indexes_at_keygen,
+            messages_to_sign,
+            self.enforce_reliable_broadcast,
+            self.nonce_commitment_checkpoint,
+            self.aggregate_nonce_checkpoint,
+            self.participation_signer,
+            self.arena,
+            self.modexp,
+            #[cfg(feature = "hd-wallet")]
+            self.additive_shift,
+            #[cfg(not(feature = "hd-wallet"))]
+            None,
+        )
+        .await?
+        {
+            ProtocolOutput::Signatures { sigs, .. } => Ok(sigs),
+            ProtocolOutput::Presignatures(_) => Err(Bug::UnexpectedProtocolOutput.into()),
+        }
+    }
+
+    /// Returns a state machine that can be used to carry out the batch signing protocol
+    ///
+    /// See [`round_based::state_machine`] for details on how that can be done.
+    #[cfg(feature = "state-machine")]
+    pub fn sign_batch_sync<R>(
+        self,
+        rng: &'r mut R,
+        messages_to_sign: &'r [DataToSign<E>],
+    ) -> impl round_based::state_machine::StateMachine<
+        Output = Result<Vec<Signature<E>>, SigningError>,
+        Msg = Msg<E, D>,
+    > + 'r
+    where
+        R: RngCore + CryptoRng,
+    {
+        round_based::state_machine::wrap_protocol(move |party| {
+            self.sign_batch(rng, party, messages_to_sign)
+        })
+    }
+}
+
+/// Upper bound on size of signing protocol message encoded via [codec](crate::codec)
+///
+/// Returns size (in bytes) of the largest message that an honest signer may send when signing
+/// a batch of `batch_size` messages (use `batch_size = 1` for regular signing and presignature
+/// generation). Size of the messages doesn't depend on the number of signers. The bound can be
+/// used as `max_len` in [`codec::decode_message`](crate::codec::decode_message) to reject
+/// oversized messages.
+///
+/// Messages carry Paillier ciphertexts and ZK proofs whose size depends on Paillier moduli of the
+/// signers. The bound assumes that all moduli are generated at security level `L` (i.e. they are
+/// $8\kappa$ bits long), which is the case for aux info generated by this library. The bound
+/// is computed by encoding a message where each integer takes the largest size that honest signer
+/// can produce.
+#[cfg(feature = "codec")]
+pub fn max_message_size<E: Curve, L: SecurityLevel>(batch_size: usize) -> usize {
+    // Largest negative integer of given bit length, it takes the most space when encoded
+    let max_int = |bits: u32| -(Integer::from(Integer::u_pow_u(2, bits)) - 1u8);
+    // Ciphertexts are computed modulo N^2
+    let ciphertext = max_int(16 * L::SECURITY_BITS);
+    // Other integers are either computed modulo N, or are masked responses of ZK proofs
+    // that are bounded by N * 2^(ell' + epsilon) * q
+    let int = max_int(
+        8 * L::SECURITY_BITS + (L::ELL_PRIME + L::EPSILON) as u32 + L::q().significant_bits() + 2,
+    );
+    let point = Point::<E>::generator().to_point();
+    let scalar = -Scalar::<E>::one();
+
+    let pi_aff = || {
+        (
+            pi_aff::Commitment {
+                a: ciphertext.clone(),
+                b_x: point,
+                b_y: ciphertext.clone(),
+                e: int.clone(),
+                s: int.clone(),
+                f: int.clone(),
+                t: int.clone(),
+            },
+            pi_aff::Proof {
+                z1: int.clone(),
+                z2: int.clone(),
+                z3: int.clone(),
+                z4: int.clone(),
+                w: int.clone(),
+                w_y: int.clone(),
+            },
+        )
+    };
+    let pi_log = || {
+        (
+            pi_log::Commitment {
+                s: int.clone(),
+                a: ciphertext.clone(),
+                y: point,
+                d: int.clone(),
+            },
+            pi_log::Proof {
+                z1: int.clone(),
+                z2: int.clone(),
+                z3: int.clone(),
+            },
+        )
+    };
+    let with_batch = |msg: Msg<E, sha2::Sha256>| -> Msg<E, sha2::Sha256> {
+        let rest = batch_size.saturating_sub(1);
+        match msg {
+            Msg::Round1a(m) => Msg::Round1a(MsgRound1a {
+                batch: vec![m.clone(); rest],
+                ..m
+            }),
+            Msg::Round1b(m) => Msg::Round1b(MsgRound1b {
+                batch: vec![m.clone(); rest],
+                ..m
+            }),
+            Msg::Round2(m) => Msg::Round2(MsgRound2 {
+                batch: vec![m.clone(); rest],
+                ..m
+            }),
+            Msg::Round3(m) => Msg::Round3(MsgRound3 {
+                batch: vec![m.clone(); rest],
+                ..m
+            }),
+            Msg::Round4(m) => Msg::Round4(MsgRound4 {
+                batch: vec![m.clone(); rest],
+                ..m
+            }),
+            msg => msg,
+        }
+    };
+
+    let msgs = [
+        Msg::Round1a(MsgRound1a {
+            K: ciphertext.clone(),
+            G: ciphertext.clone(),
+            batch: vec![],
+            epoch: u64::MAX,
+        }),
+        Msg::Round1b(MsgRound1b {
+            psi0: (
+                pi_enc::Commitment {
+                    s: int.clone(),
+                    a: ciphertext.clone(),
+                    c: int.clone(),
+                },
+                pi_enc::Proof {
+                    z1: int.clone(),
+                    z2: int.clone(),
+                    z3: int.clone(),
+                },
+            ),
+            batch: vec![],
+        }),
+        Msg::Round2(MsgRound2 {
+            Gamma: point,
+            D: ciphertext.clone(),
+            F: ciphertext.clone(),
+            hat_D: ciphertext.clone(),
+            hat_F: ciphertext.clone(),
+            psi: pi_aff(),
+            hat_psi: pi_aff(),
+            psi_prime: pi_log(),
+            batch: vec![],
+        }),
+        Msg::Round3(MsgRound3 {
+            delta: scalar,
+            Delta: point,
+            psi_prime_prime: pi_log(),
+            batch: vec![],
+        }),
+        Msg::Round4(MsgRound4 {
+            sigma: scalar,
+            batch: vec![],
+        }),
+        Msg::ReliabilityCheck(MsgReliabilityCheck(Default::default())),
+        Msg::Participation(MsgParticipation { signature: vec![] }),
+    ];
+    msgs.into_iter()
+        .map(with_batch)
+        .map(|msg| crate::codec::encode_message(&msg, usize::MAX).map_or(usize::MAX, |b| b.len()))
+        .max()
+        .unwrap_or(usize::MAX)
+}
+
+/// t-out-of-n signing
+///
+/// CGGMP paper doesn't support threshold signing out of the box. However, threshold signing
+/// can be easily implemented on top of CGGMP's [`signing_n_out_of_n`] by converting polynomial
+/// (VSS) key shares into additive (by multiplying at lagrange coefficient) and calling
+/// t-out-of-t protocol. The trick is described in more details in the spec.
+async fn signing_t_out_of_n<M, E, L, D, R>(
+    mut tracer: Option<&mut dyn Tracer>,
+    round_observer: Option<&mut dyn RoundObserver>,
+    cancellation: Option<&CancellationToken>,
+    round_timeout: &RoundTimeout<'_>,
+    rng: &mut R,
+    party: M,
+    sid: ExecutionId<'_>,
+    i: PartyIndex,
+    key_share: &KeyShare<E, L>,
+    S: &[PartyIndex],
+    messages_to_sign: Option<&[DataToSign<E>]>,
+    enforce_reliable_broadcast: bool,
+    nonce_commitment_checkpoint: NonceCommitmentCheckpoint<'_>,
+    aggregate_nonce_checkpoint: AggregateNonceCheckpoint<'_, E>,
+    participation_signer: Option<&dyn BroadcastSigner>,
+    arena: Option<&mut SigningArena>,
+    modexp: Option<&dyn ModExpBackend>,
+    additive_shift: Option<Scalar<E>>,
+) -> Result<ProtocolOutput<E>, SigningError>
+where
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    R: RngCore + CryptoRng,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+{
+    tracer.protocol_begins();
+    tracer.stage("Map t-out-of-n protocol to t-out-of-t");
+
+    // Validate arguments
+    let n: u16 = key_share
+        .aux
+        .parties
+        .len()
+        .try_into()
         .map_err(|_| Bug::PartiesNumberExceedsU16)?;
+    if n > crate::MAX_PARTIES {
+        return Err(InvalidArgs::TooManyParties { n }.into());
+    }
     let t = key_share
         .core
         .vss_setup
@@ -549,6 +1571,9 @@ where
     if S.iter().any(|&S_j| S_j >= n) {
         return Err(InvalidArgs::InvalidS.into());
     }
+    if let Some((_, &S_j)) = S.iter().enumerate().find(|(j, S_j)| S[..*j].contains(S_j)) {
+        return Err(InvalidArgs::DuplicateSigner(S_j).into());
+    }
 
     // Assemble x_i and \vec X
     let (mut x_i, mut X) = if let Some(VssSetup { I, .. }) = &key_share.core.vss_setup {
@@ -590,48 +1615,139 @@ where
     );
 
     // Assemble rest of the data
-    let (p_i, q_i) = (&key_share.aux.p, &key_share.aux.q);
-    let R = utils::subset(S, &key_share.aux.parties).ok_or(Bug::Subset)?;
+    let mut local_arena = None;
+    let arena = match arena {
+        Some(arena) => arena,
+        None => local_arena.insert(SigningArena::new()),
+    };
+    let paillier = arena.prepare(key_share, S)?;
 
     // t-out-of-t signing
     signing_n_out_of_n::<_, _, L, _, _>(
         tracer,
+        round_observer,
+        cancellation,
+        round_timeout,
         rng,
         party,
         sid,
         i,
         t,
+        S,
         &x_i,
         &X,
         key_share.core.shared_public_key + Shift,
-        p_i,
-        q_i,
-        &R,
-        message_to_sign,
+        &paillier,
+        ModExp::new(modexp),
+        key_share.core.epoch,
+        messages_to_sign,
         enforce_reliable_broadcast,
+        nonce_commitment_checkpoint,
+        aggregate_nonce_checkpoint,
+        participation_signer,
     )
     .await
 }
 
+/// Resumes signing from the state obtained via [`SigningBuilder::sign_resumable`]
+///
+/// Signer issues a partial signature for the message the state is bound to, sends it to other signers,
+/// and combines partial signatures into the signature. Consumes the state.
+///
+/// `eid` must be the execution ID of the session the state was created in, otherwise an error is
+/// returned.
+pub async fn resume_signing<E, D, M>(
+    eid: ExecutionId<'_>,
+    state: SigningState<E>,
+    party: M,
+) -> Result<Signature<E>, SigningError>
+where
+    E: Curve,
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+{
+    if state.execution_id != eid.as_bytes() {
+        return Err(InvalidArgs::ExecutionIdMismatch.into());
+    }
+    let n = u16::try_from(state.signers.len()).map_err(|_| Bug::PartiesNumberExceedsU16)?;
+    let i = state.i;
+    if !(i < n) {
+        return Err(InvalidArgs::SignerIndexOutOfBounds.into());
+    }
+    let message_to_sign = state.message();
+
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
+    let round4 = rounds.add_round(RoundInput::<MsgRound4<E>>::broadcast(i, n));
+    let mut rounds = rounds.listen(incomings);
+
+    let partial_sig = state.presignature.issue_partial_signature(message_to_sign);
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round4(MsgRound4 {
+            sigma: partial_sig.sigma,
+            batch: vec![],
+        })))
+        .await
+        .map_err(IoError::send_message)?;
+
+    let partial_sigs = rounds
+        .complete(round4)
+        .await
+        .map_err(IoError::receive_message)?;
+    let faulty_parties = partial_sigs
+        .iter_indexed()
+        .filter(|(_, _, msg)| !is_batch_of_size(*msg, 1))
+        .map(|(j, msg_id, _)| (j, msg_id))
+        .collect::<Vec<_>>();
+    if !faulty_parties.is_empty() {
+        return Err(SigningAborted::MismatchedBatchSize(faulty_parties).into());
+    }
+
+    let r = NonZero::from_scalar(partial_sig.r);
+    let s = NonZero::from_scalar(
+        partial_sig.sigma + partial_sigs.iter().map(|m| m.sigma).sum::<Scalar<E>>(),
+    );
+    let sig = Option::zip(r, s)
+        .map(|(r, s)| Signature { r, s }.normalize_s())
+        .ok_or(SigningAborted::SignatureInvalid)?;
+    if sig.verify(&state.public_key, &message_to_sign).is_err() {
+        return Err(SigningAborted::SignatureInvalid.into());
+    }
+    Ok(sig)
+}
+
 /// Original CGGMP n-out-of-n signing
 ///
 /// Implementation has very little differences compared to original CGGMP protocol: we added broadcast
 /// reliability check, fixed some typos in CGGMP, etc. Differences are covered in the specs.
+///
+/// When `messages_to_sign` contains more than one message, the protocol generates a presignature per
+/// message within a single execution: each message carries data for all presignatures in the batch.
 async fn signing_n_out_of_n<M, E, L, D, R>(
     mut tracer: Option<&mut dyn Tracer>,
+    round_observer: Option<&mut dyn RoundObserver>,
+    cancellation: Option<&CancellationToken>,
+    round_timeout: &RoundTimeout<'_>,
     rng: &mut R,
     party: M,
     sid: ExecutionId<'_>,
     i: PartyIndex,
     n: u16,
+    signers: &[PartyIndex],
     x_i: &NonZero<SecretScalar<E>>,
     X: &[NonZero<Point<E>>],
     pk: Point<E>,
-    p_i: &Integer,
-    q_i: &Integer,
-    R: &[PartyAux],
-    message_to_sign: Option<DataToSign<E>>,
+    paillier: &arena::Prepared<'_>,
+    modexp: ModExp<'_>,
+    epoch: u64,
+    messages_to_sign: Option<&[DataToSign<E>]>,
     enforce_reliable_broadcast: bool,
+    nonce_commitment_checkpoint: NonceCommitmentCheckpoint<'_>,
+    aggregate_nonce_checkpoint: AggregateNonceCheckpoint<'_, E>,
+    participation_signer: Option<&dyn BroadcastSigner>,
 ) -> Result<ProtocolOutput<E>, SigningError>
 where
     M: Mpc<ProtocolMessage = Msg<E, D>>,
@@ -646,15 +1762,28 @@ where
     } = party.into_party();
     let (incomings, mut outgoings) = delivery.split();
 
+    let batch_size = messages_to_sign.map(|m| m.len()).unwrap_or(1);
+    if batch_size == 0 {
+        return Err(InvalidArgs::EmptyBatch.into());
+    }
+
     tracer.stage("Retrieve auxiliary data");
-    let R_i = &R[usize::from(i)];
-    let N_i = &R_i.N;
-    let dec_i: fast_paillier::DecryptionKey =
-        fast_paillier::DecryptionKey::from_primes(p_i.clone(), q_i.clone())
-            .map_err(|_| Bug::InvalidOwnPaillierKey)?;
+    let dec_i = paillier.dec_i;
+    let N_i = dec_i.n();
+    let aux_i = &paillier.parties[usize::from(i)].aux;
 
     tracer.stage("Precompute execution id and security params");
     let security_params = crate::utils::SecurityParams::new::<L>();
+    // Each presignature in the batch is generated under its own session id
+    let batch_sids = (1..batch_size)
+        .map(|index| {
+            let index = u64::try_from(index).map_err(|_| Bug::BatchSizeOverflow)?;
+            Ok(udigest::hash::<D>(&unambiguous::BatchSid { sid, index }))
+        })
+        .collect::<Result<Vec<_>, Bug>>()?;
+    let sids = iter::once(sid)
+        .chain(batch_sids.iter().map(|h| ExecutionId::new(h)))
+        .collect::<Vec<_>>();
 
     tracer.stage("Setup networking");
     let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
@@ -664,60 +1793,100 @@ where
     let round2 = rounds.add_round(RoundInput::<MsgRound2<E>>::p2p(i, n));
     let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::p2p(i, n));
     let round4 = rounds.add_round(RoundInput::<MsgRound4<E>>::broadcast(i, n));
-    let mut rounds = rounds.listen(incomings);
+    let round_participation = rounds.add_round(RoundInput::<MsgParticipation>::broadcast(i, n));
+    let mut rounds = rounds.listen(ObservedIncomings::new(
+        incomings,
+        round_observer,
+        n,
+        Msg::round_name,
+    ));
 
     // Round 1
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
+
+    let mut ephemeral = Vec::with_capacity(batch_size);
+    for _ in 0..batch_size {
+        tracer.stage("Generate local ephemeral secrets (k_i, y_i, p_i, v_i)");
+        let gamma_i = SecretScalar::<E>::random(rng);
+        let k_i = SecretScalar::<E>::random(rng);
+
+        let v_i = Integer::gen_invertible(N_i, rng);
+        let rho_i = Integer::gen_invertible(N_i, rng);
+
+        tracer.stage("Encrypt G_i and K_i");
+        let G_i = modexp
+            .encrypt_with_dec(dec_i, &utils::scalar_to_bignumber_ct(&gamma_i), &v_i)
+            .ok_or(Bug::PaillierEnc(BugSource::G_i))?;
+        let K_i = modexp
+            .encrypt_with_dec(dec_i, &utils::scalar_to_bignumber_ct(&k_i), &rho_i)
+            .ok_or(Bug::PaillierEnc(BugSource::K_i))?;
+        runtime.yield_now().await;
 
-    tracer.stage("Generate local ephemeral secrets (k_i, y_i, p_i, v_i)");
-    let gamma_i = SecretScalar::<E>::random(rng);
-    let k_i = SecretScalar::<E>::random(rng);
-
-    let v_i = Integer::gen_invertible(N_i, rng);
-    let rho_i = Integer::gen_invertible(N_i, rng);
-
-    tracer.stage("Encrypt G_i and K_i");
-    let G_i = dec_i
-        .encrypt_with(&utils::scalar_to_bignumber(&gamma_i), &v_i)
-        .map_err(|_| Bug::PaillierEnc(BugSource::G_i))?;
-    let K_i = dec_i
-        .encrypt_with(&utils::scalar_to_bignumber(&k_i), &rho_i)
-        .map_err(|_| Bug::PaillierEnc(BugSource::K_i))?;
-    runtime.yield_now().await;
+        ephemeral.push(EphemeralSecrets {
+            gamma_i,
+            k_i,
+            v_i,
+            rho_i,
+            G_i,
+            K_i,
+        });
+    }
 
+    let my_ciphertexts = MsgRound1a {
+        epoch,
+        ..into_batch(
+            ephemeral
+                .iter()
+                .map(|e| MsgRound1a {
+                    K: e.K_i.clone(),
+                    G: e.G_i.clone(),
+                    batch: vec![],
+                    epoch: 0,
+                })
+                .collect(),
+        )?
+    };
     tracer.send_msg();
     outgoings
-        .send(Outgoing::broadcast(Msg::Round1a(MsgRound1a {
-            K: K_i.clone(),
-            G: G_i.clone(),
-        })))
+        .send(Outgoing::broadcast(Msg::Round1a(my_ciphertexts.clone())))
         .await
         .map_err(IoError::send_message)?;
     tracer.msg_sent();
 
     for j in utils::iter_peers(i, n) {
-        tracer.stage("Prove ψ0_j");
-        let R_j = &R[usize::from(j)];
-
-        let psi0 = pi_enc::non_interactive::prove::<D>(
-            &unambiguous::ProofEnc { sid, prover: i },
-            &R_j.into(),
-            pi_enc::Data {
-                key: &dec_i,
-                ciphertext: &K_i,
-            },
-            pi_enc::PrivateData {
-                plaintext: &utils::scalar_to_bignumber(&k_i),
-                nonce: &rho_i,
-            },
-            &security_params.pi_enc,
-            &mut *rng,
-        )
-        .map_err(|e| Bug::PiEnc(BugSource::psi0, e))?;
+        let aux_j = &paillier.parties[usize::from(j)].aux;
+
+        let mut psi0 = Vec::with_capacity(batch_size);
+        for (sid, e) in sids.iter().zip(&ephemeral) {
+            tracer.stage("Prove ψ0_j");
+            let psi0_j = pi_enc::non_interactive::prove::<D>(
+                &unambiguous::ProofEnc {
+                    sid: *sid,
+                    prover: i,
+                },
+                aux_j,
+                pi_enc::Data {
+                    key: dec_i,
+                    ciphertext: &e.K_i,
+                },
+                pi_enc::PrivateData {
+                    plaintext: &utils::scalar_to_bignumber_ct(&e.k_i),
+                    nonce: &e.rho_i,
+                },
+                &security_params.pi_enc,
+                &mut *rng,
+            )
+            .map_err(|e| Bug::PiEnc(BugSource::psi0, e))?;
+            psi0.push(MsgRound1b {
+                psi0: psi0_j,
+                batch: vec![],
+            });
+        }
 
         tracer.send_msg();
         outgoings
-            .send(Outgoing::p2p(j, Msg::Round1b(MsgRound1b { psi0 })))
+            .send(Outgoing::p2p(j, Msg::Round1b(into_batch(psi0)?)))
             .await
             .map_err(IoError::send_message)?;
         tracer.msg_sent();
@@ -725,55 +1894,71 @@ where
 
     // Round 2
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
     tracer.receive_msgs();
     // Contains G_j, K_j sent by other parties
-    let ciphertexts = rounds
-        .complete(round1a)
-        .await
+    let ciphertexts = round_timeout
+        .race(rounds.complete(round1a))
+        .await?
         .map_err(IoError::receive_message)?;
-    let psi0 = rounds
-        .complete(round1b)
-        .await
+    let psi0 = round_timeout
+        .race(rounds.complete(round1b))
+        .await?
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
+    tracer.stage("Validate batch size");
+    {
+        let faulty_parties = ciphertexts
+            .iter_indexed()
+            .filter(|(_, _, msg)| !is_batch_of_size(*msg, batch_size))
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .chain(
+                psi0.iter_indexed()
+                    .filter(|(_, _, msg)| !is_batch_of_size(*msg, batch_size))
+                    .map(|(j, msg_id, _)| (j, msg_id)),
+            )
+            .collect::<Vec<_>>();
+        if !faulty_parties.is_empty() {
+            return Err(SigningAborted::MismatchedBatchSize(faulty_parties).into());
+        }
+    }
+
+    tracer.stage("Check that all signers use key shares from the same epoch");
+    {
+        // Epoch is reported by signers themselves, and the local key share may be the outdated
+        // one, so a mismatch is not attributed to any signer: epochs of all signers are reported
+        let epochs = ciphertexts
+            .iter_including_me(&my_ciphertexts)
+            .map(|msg| msg.epoch)
+            .collect::<Vec<_>>();
+        if epochs.iter().any(|e| *e != epoch) {
+            return Err(SigningAborted::EpochMismatch(epochs).into());
+        }
+    }
+
     // Reliability check (if enabled)
     if enforce_reliable_broadcast {
-        tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::hash_iter::<D>(
+        let failure = reliable_broadcast::echo_round(
             ciphertexts
-                .iter_including_me(&MsgRound1a {
-                    K: K_i.clone(),
-                    G: G_i.clone(),
-                })
+                .iter_including_me(&my_ciphertexts)
                 .map(|ciphertexts| unambiguous::Echo { sid, ciphertexts }),
-        );
-
-        tracer.send_msg();
-        outgoings
-            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
-                MsgReliabilityCheck(h_i),
-            )))
-            .await
-            .map_err(IoError::send_message)?;
-        tracer.msg_sent();
-
-        tracer.round_begins();
-
-        tracer.receive_msgs();
-        let round1a_hashes = rounds
-            .complete(round1a_sync)
-            .await
-            .map_err(IoError::receive_message)?;
-        tracer.msgs_received();
-        tracer.stage("Assert other parties hashed messages (reliability check)");
-        let parties_have_different_hashes = round1a_hashes
-            .into_iter_indexed()
-            .filter(|(_j, _msg_id, hash)| hash.0 != h_i)
-            .map(|(j, msg_id, _)| (j, msg_id))
-            .collect::<Vec<_>>();
-        if !parties_have_different_hashes.is_empty() {
+            Msg::ReliabilityCheck,
+            &mut outgoings,
+            &mut rounds,
+            round1a_sync,
+            round_timeout,
+            cancellation,
+            &mut tracer,
+        )
+        .await?;
+        if let Some(failure) = failure {
+            let parties_have_different_hashes = failure
+                .mismatched
+                .into_iter()
+                .map(|(j, msg_id, _)| (j, msg_id))
+                .collect();
             return Err(SigningAborted::Round1aNotReliable(parties_have_different_hashes).into());
         }
     }
@@ -785,20 +1970,28 @@ where
         for ((j, msg1_id, ciphertext), (_, msg2_id, proof)) in
             ciphertexts.iter_indexed().zip(psi0.iter_indexed())
         {
-            let R_j = &R[usize::from(j)];
-            if pi_enc::non_interactive::verify::<D>(
-                &unambiguous::ProofEnc { sid, prover: j },
-                &R_i.into(),
-                pi_enc::Data {
-                    key: &fast_paillier::EncryptionKey::from_n(R_j.N.clone()),
-                    ciphertext: &ciphertext.K,
-                },
-                &proof.psi0.0,
-                &security_params.pi_enc,
-                &proof.psi0.1,
-            )
-            .is_err()
-            {
+            let enc_j = &paillier.parties[usize::from(j)].enc;
+            let invalid = sids
+                .iter()
+                .zip(iter_batch(ciphertext).zip(iter_batch(proof)))
+                .any(|(sid, (ciphertext, proof))| {
+                    pi_enc::non_interactive::verify::<D>(
+                        &unambiguous::ProofEnc {
+                            sid: *sid,
+                            prover: j,
+                        },
+                        aux_i,
+                        pi_enc::Data {
+                            key: enc_j,
+                            ciphertext: &ciphertext.K,
+                        },
+                        &proof.psi0.0,
+                        &security_params.pi_enc,
+                        &proof.psi0.1,
+                    )
+                    .is_err()
+                });
+            if invalid {
                 faulty_parties.push((j, msg1_id, msg2_id))
             }
         }
@@ -809,162 +2002,187 @@ where
     }
     runtime.yield_now().await;
 
+    let NonceCommitmentCheckpoint { hook, ack } = nonce_commitment_checkpoint;
+    if let Some(hook) = hook {
+        tracer.stage("Report nonce commitments");
+        for (j, commitment) in (0u16..).zip(ciphertexts.iter_including_me(&my_ciphertexts)) {
+            hook(j, commitment)
+        }
+    }
+    if let Some(ack) = ack {
+        tracer.stage("Await nonce commitments acknowledgement");
+        if !ack.await {
+            return Err(SigningError(Reason::NonceCommitmentsRejected));
+        }
+    }
+
     // Step 2
-    let Gamma_i = Point::generator() * &gamma_i;
+    let Gamma_i = ephemeral
+        .iter()
+        .map(|e| Point::generator() * &e.gamma_i)
+        .collect::<Vec<_>>();
     let J = (Integer::ONE << L::ELL_PRIME).complete();
 
-    let mut beta_sum = Scalar::zero();
-    let mut hat_beta_sum = Scalar::zero();
-    for (j, _, ciphertext_j) in ciphertexts.iter_indexed() {
-        tracer.stage("Sample random r, hat_r, s, hat_s, beta, hat_beta");
-        let R_j = &R[usize::from(j)];
-        let N_j = &R_j.N;
-        let enc_j = fast_paillier::EncryptionKey::from_n(N_j.clone());
-
-        let r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
-        let hat_r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
-        let s_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
-        let hat_s_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
-
-        let beta_ij = Integer::from_rng_pm(&J, rng);
-        let hat_beta_ij = Integer::from_rng_pm(&J, rng);
-
-        beta_sum += beta_ij.to_scalar();
-        hat_beta_sum += hat_beta_ij.to_scalar();
-
-        tracer.stage("Encrypt D_ji");
-        // D_ji = (gamma_i * K_j) + enc_j(-beta_ij, s_ij)
-        let D_ji = {
-            let gamma_i_times_K_j = enc_j
-                .omul(&utils::scalar_to_bignumber(&gamma_i), &ciphertext_j.K)
-                .map_err(|_| Bug::PaillierOp(BugSource::gamma_i_times_K_j))?;
-            let neg_beta_ij_enc = enc_j
-                .encrypt_with(&(-&beta_ij).complete(), &s_ij)
-                .map_err(|_| Bug::PaillierEnc(BugSource::neg_beta_ij_enc))?;
-            enc_j
-                .oadd(&gamma_i_times_K_j, &neg_beta_ij_enc)
-                .map_err(|_| Bug::PaillierOp(BugSource::D_ji))?
-        };
-
-        tracer.stage("Encrypt F_ji");
-        let F_ji = dec_i
-            .encrypt_with(&(-&beta_ij).complete(), &r_ij)
-            .map_err(|_| Bug::PaillierEnc(BugSource::F_ji))?;
-
-        tracer.stage("Encrypt hat_D_ji");
-        // Dˆ_ji = (x_i * K_j) + enc_j(-hat_beta_ij, hat_s_ij)
-        let hat_D_ji = {
-            let x_i_times_K_j = enc_j
-                .omul(&utils::scalar_to_bignumber(x_i), &ciphertext_j.K)
-                .map_err(|_| Bug::PaillierOp(BugSource::x_i_times_K_j))?;
-            let neg_hat_beta_ij_enc = enc_j
-                .encrypt_with(&(-&hat_beta_ij).complete(), &hat_s_ij)
-                .map_err(|_| Bug::PaillierEnc(BugSource::hat_beta_ij_enc))?;
-            enc_j
-                .oadd(&x_i_times_K_j, &neg_hat_beta_ij_enc)
-                .map_err(|_| Bug::PaillierOp(BugSource::hat_D))?
-        };
-        runtime.yield_now().await;
-
-        tracer.stage("Encrypt hat_F_ji");
-        let hat_F_ji = dec_i
-            .encrypt_with(&(-&hat_beta_ij).complete(), &hat_r_ij)
-            .map_err(|_| Bug::PaillierEnc(BugSource::hat_F))?;
-
-        tracer.stage("Prove psi_ji");
-        let psi_ji = pi_aff::non_interactive::prove::<E, D>(
-            &unambiguous::ProofPsi {
-                sid,
-                prover: i,
-                hat: false,
-            },
-            &R_j.into(),
-            pi_aff::Data {
-                key0: &enc_j,
-                key1: &dec_i,
-                c: &ciphertext_j.K,
-                d: &D_ji,
-                y: &F_ji,
-                x: &Gamma_i,
-            },
-            pi_aff::PrivateData {
-                x: &utils::scalar_to_bignumber(&gamma_i),
-                y: &(-&beta_ij).complete(),
-                nonce: &s_ij,
-                nonce_y: &r_ij,
-            },
-            &security_params.pi_aff,
-            &mut *rng,
-        )
-        .map_err(|e| Bug::PiAffG(BugSource::psi, e))?;
-        runtime.yield_now().await;
-
-        tracer.stage("Prove psiˆ_ji");
-        let hat_psi_ji = pi_aff::non_interactive::prove::<E, D>(
-            &unambiguous::ProofPsi {
-                sid,
-                prover: i,
-                hat: true,
-            },
-            &R_j.into(),
-            pi_aff::Data {
-                key0: &enc_j,
-                key1: &dec_i,
-                c: &ciphertext_j.K,
-                d: &hat_D_ji,
-                y: &hat_F_ji,
-                x: &(Point::generator() * x_i),
-            },
-            pi_aff::PrivateData {
-                x: &utils::scalar_to_bignumber(x_i),
-                y: &(-&hat_beta_ij).complete(),
-                nonce: &hat_s_ij,
-                nonce_y: &hat_r_ij,
-            },
-            &security_params.pi_aff,
-            &mut *rng,
-        )
-        .map_err(|e| Bug::PiAffG(BugSource::hat_psi, e))?;
-
-        tracer.stage("Prove psi_prime_ji ");
-        let psi_prime_ji = pi_log::non_interactive::prove::<E, D>(
-            &unambiguous::ProofLog {
-                sid,
-                prover: i,
-                prime_prime: false,
-            },
-            &R_j.into(),
-            pi_log::Data {
-                key0: &dec_i,
-                c: &G_i,
-                x: &Gamma_i,
-                b: &Point::<E>::generator().to_point(),
-            },
-            pi_log::PrivateData {
-                x: &utils::scalar_to_bignumber(&gamma_i),
-                nonce: &v_i,
-            },
-            &security_params.pi_log,
-            &mut *rng,
-        )
-        .map_err(|e| Bug::PiLog(BugSource::psi_prime, e))?;
-        runtime.yield_now().await;
+    let mut beta_sum = vec![Scalar::<E>::zero(); batch_size];
+    let mut hat_beta_sum = vec![Scalar::<E>::zero(); batch_size];
+    for (j, _, ciphertexts_j) in ciphertexts.iter_indexed() {
+        let enc_j = &paillier.parties[usize::from(j)].enc;
+        let aux_j = &paillier.parties[usize::from(j)].aux;
+
+        let mut msgs = Vec::with_capacity(batch_size);
+        for (b, ciphertext_j) in iter_batch(ciphertexts_j).enumerate() {
+            let (sid, e) = (sids[b], &ephemeral[b]);
+
+            tracer.stage("Sample random r, hat_r, s, hat_s, beta, hat_beta");
+            let r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
+            let hat_r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
+            let s_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
+            let hat_s_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
+
+            let beta_ij = Integer::from_rng_pm(&J, rng);
+            let hat_beta_ij = Integer::from_rng_pm(&J, rng);
+
+            beta_sum[b] += beta_ij.to_scalar();
+            hat_beta_sum[b] += hat_beta_ij.to_scalar();
+
+            tracer.stage("Encrypt D_ji");
+            // D_ji = (gamma_i * K_j) + enc_j(-beta_ij, s_ij)
+            let D_ji = {
+                let gamma_i_times_K_j = modexp
+                    .omul(
+                        enc_j,
+                        &utils::scalar_to_bignumber_ct(&e.gamma_i),
+                        &ciphertext_j.K,
+                    )
+                    .ok_or(Bug::PaillierOp(BugSource::gamma_i_times_K_j))?;
+                let neg_beta_ij_enc = modexp
+                    .encrypt_with(enc_j, &(-&beta_ij).complete(), &s_ij)
+                    .ok_or(Bug::PaillierEnc(BugSource::neg_beta_ij_enc))?;
+                enc_j
+                    .oadd(&gamma_i_times_K_j, &neg_beta_ij_enc)
+                    .map_err(|_| Bug::PaillierOp(BugSource::D_ji))?
+            };
+
+            tracer.stage("Encrypt F_ji");
+            let F_ji = modexp
+                .encrypt_with_dec(dec_i, &(-&beta_ij).complete(), &r_ij)
+                .ok_or(Bug::PaillierEnc(BugSource::F_ji))?;
+
+            tracer.stage("Encrypt hat_D_ji");
+            // Dˆ_ji = (x_i * K_j) + enc_j(-hat_beta_ij, hat_s_ij)
+            let hat_D_ji = {
+                let x_i_times_K_j = modexp
+                    .omul(enc_j, &utils::scalar_to_bignumber_ct(x_i), &ciphertext_j.K)
+                    .ok_or(Bug::PaillierOp(BugSource::x_i_times_K_j))?;
+                let neg_hat_beta_ij_enc = modexp
+                    .encrypt_with(enc_j, &(-&hat_beta_ij).complete(), &hat_s_ij)
+                    .ok_or(Bug::PaillierEnc(BugSource::hat_beta_ij_enc))?;
+                enc_j
+                    .oadd(&x_i_times_K_j, &neg_hat_beta_ij_enc)
+                    .map_err(|_| Bug::PaillierOp(BugSource::hat_D))?
+            };
+            runtime.yield_now().await;
+
+            tracer.stage("Encrypt hat_F_ji");
+            let hat_F_ji = modexp
+                .encrypt_with_dec(dec_i, &(-&hat_beta_ij).complete(), &hat_r_ij)
+                .ok_or(Bug::PaillierEnc(BugSource::hat_F))?;
+
+            tracer.stage("Prove psi_ji");
+            let psi_ji = pi_aff::non_interactive::prove::<E, D>(
+                &unambiguous::ProofPsi {
+                    sid,
+                    prover: i,
+                    hat: false,
+                },
+                aux_j,
+                pi_aff::Data {
+                    key0: enc_j,
+                    key1: dec_i,
+                    c: &ciphertext_j.K,
+                    d: &D_ji,
+                    y: &F_ji,
+                    x: &Gamma_i[b],
+                },
+                pi_aff::PrivateData {
+                    x: &utils::scalar_to_bignumber_ct(&e.gamma_i),
+                    y: &(-&beta_ij).complete(),
+                    nonce: &s_ij,
+                    nonce_y: &r_ij,
+                },
+                &security_params.pi_aff,
+                &mut *rng,
+            )
+            .map_err(|e| Bug::PiAffG(BugSource::psi, e))?;
+            runtime.yield_now().await;
+
+            tracer.stage("Prove psiˆ_ji");
+            let hat_psi_ji = pi_aff::non_interactive::prove::<E, D>(
+                &unambiguous::ProofPsi {
+                    sid,
+                    prover: i,
+                    hat: true,
+                },
+                aux_j,
+                pi_aff::Data {
+                    key0: enc_j,
+                    key1: dec_i,
+                    c: &ciphertext_j.K,
+                    d: &hat_D_ji,
+                    y: &hat_F_ji,
+                    x: &(Point::generator() * x_i),
+                },
+                pi_aff::PrivateData {
+                    x: &utils::scalar_to_bignumber_ct(x_i),
+                    y: &(-&hat_beta_ij).complete(),
+                    nonce: &hat_s_ij,
+                    nonce_y: &hat_r_ij,
+                },
+                &security_params.pi_aff,
+                &mut *rng,
+            )
+            .map_err(|e| Bug::PiAffG(BugSource::hat_psi, e))?;
+
+            tracer.stage("Prove psi_prime_ji ");
+            let psi_prime_ji = pi_log::non_interactive::prove::<E, D>(
+                &unambiguous::ProofLog {
+                    sid,
+                    prover: i,
+                    prime_prime: false,
+                },
+                aux_j,
+                pi_log::Data {
+                    key0: dec_i,
+                    c: &e.G_i,
+                    x: &Gamma_i[b],
+                    b: &Point::<E>::generator().to_point(),
+                },
+                pi_log::PrivateData {
+                    x: &utils::scalar_to_bignumber_ct(&e.gamma_i),
+                    nonce: &e.v_i,
+                },
+                &security_params.pi_log,
+                &mut *rng,
+            )
+            .map_err(|e| Bug::PiLog(BugSource::psi_prime, e))?;
+            runtime.yield_now().await;
+
+            msgs.push(MsgRound2 {
+                Gamma: Gamma_i[b],
+                D: D_ji,
+                F: F_ji,
+                hat_D: hat_D_ji,
+                hat_F: hat_F_ji,
+                psi: psi_ji,
+                hat_psi: hat_psi_ji,
+                psi_prime: psi_prime_ji,
+                batch: vec![],
+            });
+        }
 
         tracer.send_msg();
         outgoings
-            .send(Outgoing::p2p(
-                j,
-                Msg::Round2(MsgRound2 {
-                    Gamma: Gamma_i,
-                    D: D_ji,
-                    F: F_ji,
-                    hat_D: hat_D_ji,
-                    hat_F: hat_F_ji,
-                    psi: psi_ji,
-                    hat_psi: hat_psi_ji,
-                    psi_prime: psi_prime_ji,
-                }),
-            ))
+            .send(Outgoing::p2p(j, Msg::Round2(into_batch(msgs)?)))
             .await
             .map_err(IoError::send_message)?;
         tracer.msg_sent();
@@ -972,97 +2190,114 @@ where
 
     // Round 3
     tracer.round_begins();
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
     // Step 1
     tracer.receive_msgs();
-    let round2_msgs = rounds
-        .complete(round2)
-        .await
+    let round2_msgs = round_timeout
+        .race(rounds.complete(round2))
+        .await?
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
+    tracer.stage("Validate batch size");
+    {
+        let faulty_parties = round2_msgs
+            .iter_indexed()
+            .filter(|(_, _, msg)| !is_batch_of_size(*msg, batch_size))
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !faulty_parties.is_empty() {
+            return Err(SigningAborted::MismatchedBatchSize(faulty_parties).into());
+        }
+    }
+
     let mut faulty_parties = vec![];
-    for ((j, msg_id, msg), (_, ciphertext_msg_id, ciphertexts)) in
+    for ((j, msg_id, msgs), (_, ciphertext_msg_id, ciphertexts)) in
         round2_msgs.iter_indexed().zip(ciphertexts.iter_indexed())
     {
         tracer.stage("Retrieve auxiliary data");
         let X_j = X[usize::from(j)];
-        let R_j = &R[usize::from(j)];
-        let enc_j = fast_paillier::EncryptionKey::from_n(R_j.N.clone());
-
-        tracer.stage("Validate psi");
-        let psi_invalid = pi_aff::non_interactive::verify::<E, D>(
-            &unambiguous::ProofPsi {
-                sid,
-                prover: j,
-                hat: false,
-            },
-            &R_i.into(),
-            pi_aff::Data {
-                key0: &dec_i,
-                key1: &enc_j,
-                c: &K_i,
-                d: &msg.D,
-                y: &msg.F,
-                x: &msg.Gamma,
-            },
-            &msg.psi.0,
-            &security_params.pi_aff,
-            &msg.psi.1,
-        )
-        .err();
-
-        tracer.stage("Validate hat_psi");
-        let hat_psi_invalid = pi_aff::non_interactive::verify::<E, D>(
-            &unambiguous::ProofPsi {
-                sid,
-                prover: j,
-                hat: true,
-            },
-            &R_i.into(),
-            pi_aff::Data {
-                key0: &dec_i,
-                key1: &enc_j,
-                c: &K_i,
-                d: &msg.hat_D,
-                y: &msg.hat_F,
-                x: &X_j,
-            },
-            &msg.hat_psi.0,
-            &security_params.pi_aff,
-            &msg.hat_psi.1,
-        )
-        .err();
-
-        tracer.stage("Validate psi_prime");
-        let psi_prime_invalid = pi_log::non_interactive::verify::<E, D>(
-            &unambiguous::ProofLog {
-                sid,
-                prover: j,
-                prime_prime: false,
-            },
-            &R_i.into(),
-            pi_log::Data {
-                key0: &enc_j,
-                c: &ciphertexts.G,
-                x: &msg.Gamma,
-                b: &Point::<E>::generator().to_point(),
-            },
-            &msg.psi_prime.0,
-            &security_params.pi_log,
-            &msg.psi_prime.1,
-        )
-        .err();
+        let enc_j = &paillier.parties[usize::from(j)].enc;
+
+        for (b, (msg, ciphertexts)) in iter_batch(msgs).zip(iter_batch(ciphertexts)).enumerate() {
+            let (sid, e) = (sids[b], &ephemeral[b]);
 
-        if psi_invalid.is_some() || hat_psi_invalid.is_some() || psi_prime_invalid.is_some() {
-            faulty_parties.push((
-                j,
-                ciphertext_msg_id,
-                msg_id,
-                (psi_invalid, hat_psi_invalid, psi_prime_invalid),
-            ))
+            tracer.stage("Validate psi");
+            let psi_invalid = pi_aff::non_interactive::verify::<E, D>(
+                &unambiguous::ProofPsi {
+                    sid,
+                    prover: j,
+                    hat: false,
+                },
+                aux_i,
+                pi_aff::Data {
+                    key0: dec_i,
+                    key1: enc_j,
+                    c: &e.K_i,
+                    d: &msg.D,
+                    y: &msg.F,
+                    x: &msg.Gamma,
+                },
+                &msg.psi.0,
+                &security_params.pi_aff,
+                &msg.psi.1,
+            )
+            .err();
+
+            tracer.stage("Validate hat_psi");
+            let hat_psi_invalid = pi_aff::non_interactive::verify::<E, D>(
+                &unambiguous::ProofPsi {
+                    sid,
+                    prover: j,
+                    hat: true,
+                },
+                aux_i,
+                pi_aff::Data {
+                    key0: dec_i,
+                    key1: enc_j,
+                    c: &e.K_i,
+                    d: &msg.hat_D,
+                    y: &msg.hat_F,
+                    x: &X_j,
+                },
+                &msg.hat_psi.0,
+                &security_params.pi_aff,
+                &msg.hat_psi.1,
+            )
+            .err();
+
+            tracer.stage("Validate psi_prime");
+            let psi_prime_invalid = pi_log::non_interactive::verify::<E, D>(
+                &unambiguous::ProofLog {
+                    sid,
+                    prover: j,
+                    prime_prime: false,
+                },
+                aux_i,
+                pi_log::Data {
+                    key0: enc_j,
+                    c: &ciphertexts.G,
+                    x: &msg.Gamma,
+                    b: &Point::<E>::generator().to_point(),
+                },
+                &msg.psi_prime.0,
+                &security_params.pi_log,
+                &msg.psi_prime.1,
+            )
+            .err();
+            runtime.yield_now().await;
+
+            if psi_invalid.is_some() || hat_psi_invalid.is_some() || psi_prime_invalid.is_some() {
+                faulty_parties.push((
+                    j,
+                    ciphertext_msg_id,
+                    msg_id,
+                    (psi_invalid, hat_psi_invalid, psi_prime_invalid),
+                ));
+                break;
+            }
         }
-        runtime.yield_now().await;
     }
 
     if !faulty_parties.is_empty() {
@@ -1071,69 +2306,81 @@ where
 
     // Step 2
     tracer.stage("Compute Gamma, Delta_i, delta_i, chi_i");
-    let Gamma = Gamma_i + round2_msgs.iter().map(|msg| msg.Gamma).sum::<Point<E>>();
-    let Delta_i = Gamma * &k_i;
-
-    let alpha_sum =
-        round2_msgs
-            .iter()
-            .map(|msg| &msg.D)
-            .try_fold(Scalar::<E>::zero(), |sum, D_ij| {
-                let alpha_ij = dec_i
-                    .decrypt(D_ij)
-                    .map_err(|_| Bug::PaillierDec(BugSource::alpha))?;
-                Ok::<_, Bug>(sum + alpha_ij.to_scalar())
-            })?;
-    let hat_alpha_sum =
-        round2_msgs
-            .iter()
-            .map(|msg| &msg.hat_D)
-            .try_fold(Scalar::zero(), |sum, hat_D_ij| {
-                let hat_alpha_ij = dec_i
-                    .decrypt(hat_D_ij)
-                    .map_err(|_| Bug::PaillierDec(BugSource::hat_alpha))?;
-                Ok::<_, Bug>(sum + hat_alpha_ij.to_scalar())
-            })?;
-
-    let delta_i = gamma_i.as_ref() * k_i.as_ref() + alpha_sum + beta_sum;
-    let chi_i = x_i * k_i.as_ref() + hat_alpha_sum + hat_beta_sum;
-    runtime.yield_now().await;
+    let mut Gamma = Vec::with_capacity(batch_size);
+    let mut Delta_i = Vec::with_capacity(batch_size);
+    let mut delta_i = Vec::with_capacity(batch_size);
+    let mut chi_i = Vec::with_capacity(batch_size);
+    for (b, e) in ephemeral.iter().enumerate() {
+        let round2_msgs_b = || round2_msgs.iter().flat_map(|msgs| iter_batch(msgs).nth(b));
+
+        let Gamma_b = Gamma_i[b] + round2_msgs_b().map(|msg| msg.Gamma).sum::<Point<E>>();
+        let Delta_i_b = Gamma_b * &e.k_i;
+
+        let alpha_sum =
+            round2_msgs_b()
+                .map(|msg| &msg.D)
+                .try_fold(Scalar::<E>::zero(), |sum, D_ij| {
+                    let alpha_ij = dec_i
+                        .decrypt(D_ij)
+                        .map_err(|_| Bug::PaillierDec(BugSource::alpha))?;
+                    Ok::<_, Bug>(sum + alpha_ij.to_scalar())
+                })?;
+        let hat_alpha_sum =
+            round2_msgs_b()
+                .map(|msg| &msg.hat_D)
+                .try_fold(Scalar::zero(), |sum, hat_D_ij| {
+                    let hat_alpha_ij = dec_i
+                        .decrypt(hat_D_ij)
+                        .map_err(|_| Bug::PaillierDec(BugSource::hat_alpha))?;
+                    Ok::<_, Bug>(sum + hat_alpha_ij.to_scalar())
+                })?;
+
+        Gamma.push(Gamma_b);
+        Delta_i.push(Delta_i_b);
+        delta_i.push(e.gamma_i.as_ref() * e.k_i.as_ref() + alpha_sum + beta_sum[b]);
+        chi_i.push(x_i * e.k_i.as_ref() + hat_alpha_sum + hat_beta_sum[b]);
+        runtime.yield_now().await;
+    }
 
     for j in utils::iter_peers(i, n) {
-        tracer.stage("Prove psi_prime_prime");
-        let R_j = &R[usize::from(j)];
-        let psi_prime_prime = pi_log::non_interactive::prove::<E, D>(
-            &unambiguous::ProofLog {
-                sid,
-                prover: i,
-                prime_prime: true,
-            },
-            &R_j.into(),
-            pi_log::Data {
-                key0: &dec_i,
-                c: &K_i,
-                x: &Delta_i,
-                b: &Gamma,
-            },
-            pi_log::PrivateData {
-                x: &utils::scalar_to_bignumber(&k_i),
-                nonce: &rho_i,
-            },
-            &security_params.pi_log,
-            &mut *rng,
-        )
-        .map_err(|e| Bug::PiLog(BugSource::psi_prime_prime, e))?;
+        let aux_j = &paillier.parties[usize::from(j)].aux;
+
+        let mut msgs = Vec::with_capacity(batch_size);
+        for (b, e) in ephemeral.iter().enumerate() {
+            tracer.stage("Prove psi_prime_prime");
+            let psi_prime_prime = pi_log::non_interactive::prove::<E, D>(
+                &unambiguous::ProofLog {
+                    sid: sids[b],
+                    prover: i,
+                    prime_prime: true,
+                },
+                aux_j,
+                pi_log::Data {
+                    key0: dec_i,
+                    c: &e.K_i,
+                    x: &Delta_i[b],
+                    b: &Gamma[b],
+                },
+                pi_log::PrivateData {
+                    x: &utils::scalar_to_bignumber_ct(&e.k_i),
+                    nonce: &e.rho_i,
+                },
+                &security_params.pi_log,
+                &mut *rng,
+            )
+            .map_err(|e| Bug::PiLog(BugSource::psi_prime_prime, e))?;
+
+            msgs.push(MsgRound3 {
+                delta: delta_i[b],
+                Delta: Delta_i[b],
+                psi_prime_prime,
+                batch: vec![],
+            });
+        }
 
         tracer.send_msg();
         outgoings
-            .send(Outgoing::p2p(
-                j,
-                Msg::Round3(MsgRound3 {
-                    delta: delta_i,
-                    Delta: Delta_i,
-                    psi_prime_prime,
-                }),
-            ))
+            .send(Outgoing::p2p(j, Msg::Round3(into_batch(msgs)?)))
             .await
             .map_err(IoError::send_message)?;
         tracer.msg_sent();
@@ -1141,44 +2388,61 @@ where
 
     // Output
     tracer.named_round_begins("Presig output");
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
 
     // Step 1
     tracer.receive_msgs();
-    let round3_msgs = rounds
-        .complete(round3)
-        .await
+    let round3_msgs = round_timeout
+        .race(rounds.complete(round3))
+        .await?
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
+    tracer.stage("Validate batch size");
+    {
+        let faulty_parties = round3_msgs
+            .iter_indexed()
+            .filter(|(_, _, msg)| !is_batch_of_size(*msg, batch_size))
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !faulty_parties.is_empty() {
+            return Err(SigningAborted::MismatchedBatchSize(faulty_parties).into());
+        }
+    }
+
     tracer.stage("Validate psi_prime_prime");
     let mut faulty_parties = vec![];
-    for ((j, msg_id, msg_j), (_, ciphertext_id, ciphertext_j)) in
+    for ((j, msg_id, msgs_j), (_, ciphertext_id, ciphertexts_j)) in
         round3_msgs.iter_indexed().zip(ciphertexts.iter_indexed())
     {
-        let R_j = &R[usize::from(j)];
-        let enc_j = fast_paillier::EncryptionKey::from_n(R_j.N.clone());
-
-        let data = pi_log::Data {
-            key0: &enc_j,
-            c: &ciphertext_j.K,
-            x: &msg_j.Delta,
-            b: &Gamma,
-        };
-
-        if pi_log::non_interactive::verify::<E, D>(
-            &unambiguous::ProofLog {
-                sid,
-                prover: j,
-                prime_prime: true,
-            },
-            &R_i.into(),
-            data,
-            &msg_j.psi_prime_prime.0,
-            &security_params.pi_log,
-            &msg_j.psi_prime_prime.1,
-        )
-        .is_err()
-        {
+        let enc_j = &paillier.parties[usize::from(j)].enc;
+
+        let invalid = iter_batch(msgs_j)
+            .zip(iter_batch(ciphertexts_j))
+            .enumerate()
+            .any(|(b, (msg_j, ciphertext_j))| {
+                let data = pi_log::Data {
+                    key0: enc_j,
+                    c: &ciphertext_j.K,
+                    x: &msg_j.Delta,
+                    b: &Gamma[b],
+                };
+
+                pi_log::non_interactive::verify::<E, D>(
+                    &unambiguous::ProofLog {
+                        sid: sids[b],
+                        prover: j,
+                        prime_prime: true,
+                    },
+                    aux_i,
+                    data,
+                    &msg_j.psi_prime_prime.0,
+                    &security_params.pi_log,
+                    &msg_j.psi_prime_prime.1,
+                )
+                .is_err()
+            });
+        if invalid {
             faulty_parties.push((j, ciphertext_id, msg_id))
         }
     }
@@ -1189,77 +2453,303 @@ where
     }
 
     // Step 2
-    tracer.stage("Calculate presignature");
-    let delta = delta_i + round3_msgs.iter().map(|m| m.delta).sum::<Scalar<E>>();
-    let Delta = Delta_i + round3_msgs.iter().map(|m| m.Delta).sum::<Point<E>>();
-
-    if Point::generator() * delta != Delta {
-        // Following the protocol, party should broadcast additional proofs
-        // to convince others it didn't cheat. However, since identifiable
-        // abort is not implemented yet, this part of the protocol is missing
-        return Err(SigningAborted::MismatchedDelta.into());
-    }
-
-    let R = Gamma * delta.invert().ok_or(Bug::ZeroDelta)?;
-    let R = NonZero::from_point(R).ok_or(Bug::ZeroR)?;
-    let presig = Presignature {
-        R,
-        k: k_i,
-        chi: SecretScalar::new(&mut chi_i.clone()),
+    let mut presigs = Vec::with_capacity(batch_size);
+    for (b, e) in ephemeral.into_iter().enumerate() {
+        tracer.stage("Calculate presignature");
+        let round3_msgs_b = || round3_msgs.iter().flat_map(|msgs| iter_batch(msgs).nth(b));
+        let delta = delta_i[b] + round3_msgs_b().map(|m| m.delta).sum::<Scalar<E>>();
+        let Delta = Delta_i[b] + round3_msgs_b().map(|m| m.Delta).sum::<Point<E>>();
+
+        if Point::generator() * delta != Delta {
+            // Following the protocol, party should broadcast additional proofs
+            // to convince others it didn't cheat. However, since identifiable
+            // abort is not implemented yet, this part of the protocol is missing
+            return Err(SigningAborted::MismatchedDelta.into());
+        }
+
+        let R = Gamma[b] * delta.invert().ok_or(Bug::ZeroDelta)?;
+        let R = NonZero::from_point(R).ok_or(Bug::ZeroR)?;
+        presigs.push(Presignature {
+            R,
+            k: e.k_i,
+            chi: SecretScalar::new(&mut chi_i[b].clone()),
+        });
+    }
+
+    // If message is not specified, protocol terminates here and outputs partial
+    // signature
+    let Some(messages_to_sign) = messages_to_sign else {
+        tracer.protocol_ends();
+        return Ok(ProtocolOutput::Presignatures(presigs));
     };
 
-    // If message is not specified, protocol terminates here and outputs partial
-    // signature
-    let Some(message_to_sign) = message_to_sign else {
-        tracer.protocol_ends();
-        return Ok(ProtocolOutput::Presignature(presig));
+    // Signing
+    tracer.named_round_begins("Partial signing");
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
+
+    // Round 1
+    // Each presignature is consumed by exactly one message
+    if messages_to_sign.len() != presigs.len() {
+        return Err(Bug::MismatchedAmountOfPresignatures.into());
+    }
+
+    let AggregateNonceCheckpoint { hook, ack } = aggregate_nonce_checkpoint;
+    if let Some(hook) = hook {
+        tracer.stage("Report aggregate nonce");
+        for presig in &presigs {
+            hook(&presig.R)
+        }
+    }
+    if let Some(ack) = ack {
+        tracer.stage("Await aggregate nonce acknowledgement");
+        if !ack.await {
+            return Err(SigningError(Reason::AggregateNonceRejected));
+        }
+    }
+
+    let partial_sigs_i = presigs
+        .into_iter()
+        .zip(messages_to_sign)
+        .map(|(presig, message_to_sign)| presig.issue_partial_signature(*message_to_sign))
+        .collect::<Vec<_>>();
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round4(into_batch(
+            partial_sigs_i
+                .iter()
+                .map(|partial_sig| MsgRound4 {
+                    sigma: partial_sig.sigma,
+                    batch: vec![],
+                })
+                .collect(),
+        )?)))
+        .await
+        .map_err(IoError::send_message)?;
+    tracer.msg_sent();
+
+    // Output
+    tracer.named_round_begins("Signature reconstruction");
+    cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
+
+    tracer.receive_msgs();
+    let partial_sigs = round_timeout
+        .race(rounds.complete(round4))
+        .await?
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    tracer.stage("Validate batch size");
+    {
+        let faulty_parties = partial_sigs
+            .iter_indexed()
+            .filter(|(_, _, msg)| !is_batch_of_size(*msg, batch_size))
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !faulty_parties.is_empty() {
+            return Err(SigningAborted::MismatchedBatchSize(faulty_parties).into());
+        }
+    }
+
+    let mut sigs = Vec::with_capacity(batch_size);
+    for (b, (partial_sig, message_to_sign)) in
+        partial_sigs_i.iter().zip(messages_to_sign).enumerate()
+    {
+        let sig = {
+            let r = NonZero::from_scalar(partial_sig.r);
+            let s = NonZero::from_scalar(
+                partial_sig.sigma
+                    + partial_sigs
+                        .iter()
+                        .flat_map(|msgs| iter_batch(msgs).nth(b))
+                        .map(|m| m.sigma)
+                        .sum::<Scalar<E>>(),
+            );
+            Option::zip(r, s).map(|(r, s)| Signature { r, s }.normalize_s())
+        };
+        let sig_invalid = match &sig {
+            Some(sig) => sig.verify(&pk, message_to_sign).is_err(),
+            None => true,
+        };
+        if sig_invalid {
+            // Following the protocol, party should broadcast additional proofs
+            // to convince others it didn't cheat. However, since identifiable
+            // abort is not implemented yet, this part of the protocol is missing
+            return Err(SigningAborted::SignatureInvalid.into());
+        }
+        sigs.push(sig.ok_or(SigningAborted::SignatureInvalid)?);
+    }
+
+    tracer.stage("Hash transcript");
+    let mut parties = ciphertexts
+        .iter()
+        .zip(round2_msgs.iter())
+        .zip(round3_msgs.iter())
+        .zip(partial_sigs.iter())
+        .map(|(((ciphertexts, round2), round3), round4)| {
+            let round3 = || iter_batch(round3);
+            unambiguous::PartyTranscript {
+                ciphertexts,
+                Gamma: iter_batch(round2).map(|m| m.Gamma).collect(),
+                delta: round3().map(|m| m.delta).collect(),
+                Delta: round3().map(|m| m.Delta).collect(),
+                sigma: iter_batch(round4).map(|m| m.sigma).collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    parties.insert(
+        usize::from(i),
+        unambiguous::PartyTranscript {
+            ciphertexts: &my_ciphertexts,
+            Gamma: Gamma_i,
+            delta: delta_i,
+            Delta: Delta_i,
+            sigma: partial_sigs_i.iter().map(|s| s.sigma).collect(),
+        },
+    );
+    let transcript_hash = udigest::hash::<D>(&unambiguous::Transcript {
+        sid,
+        signers,
+        public_key: pk,
+        parties,
+        signatures: sigs
+            .iter()
+            .zip(messages_to_sign)
+            .map(|(sig, message)| unambiguous::SignedMessage {
+                message: message.to_scalar(),
+                r: *sig.r,
+                s: *sig.s,
+            })
+            .collect(),
+    })
+    .into();
+
+    // Proof of participation (if enabled)
+    let participation_proof = if let Some(participation_signer) = participation_signer {
+        tracer.stage("Sign transcript (proof of participation)");
+        let my_signature = participation_signer.sign(&crate::participation::signed_data::<D>(
+            &transcript_hash,
+            signers,
+            signers[usize::from(i)],
+        ));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Participation(MsgParticipation {
+                signature: my_signature.clone(),
+            })))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+        cancellation.map_or(Ok(()), CancellationToken::ensure_not_cancelled)?;
+
+        tracer.receive_msgs();
+        let participation = round_timeout
+            .race(rounds.complete(round_participation))
+            .await?
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Verify signatures over transcript (proof of participation)");
+        let faulty_parties = participation
+            .iter_indexed()
+            .filter(|(j, _, msg)| {
+                let S_j = signers[usize::from(*j)];
+                let data = crate::participation::signed_data::<D>(&transcript_hash, signers, S_j);
+                !participation_signer.verify(S_j, &data, &msg.signature)
+            })
+            .map(|(j, msg_id, _)| (j, msg_id))
+            .collect::<Vec<_>>();
+        if !faulty_parties.is_empty() {
+            return Err(SigningAborted::InvalidParticipationSignature(faulty_parties).into());
+        }
+
+        let my_msg = MsgParticipation {
+            signature: my_signature,
+        };
+        Some(ParticipationProof {
+            transcript_hash,
+            signatures: participation
+                .iter_including_me(&my_msg)
+                .map(|msg| msg.signature.clone())
+                .collect(),
+        })
+    } else {
+        None
     };
 
-    // Signing
-    tracer.named_round_begins("Partial signing");
+    tracer.protocol_ends();
+    Ok(ProtocolOutput::Signatures {
+        sigs,
+        transcript_hash,
+        participation_proof,
+    })
+}
 
-    // Round 1
-    let partial_sig = presig.issue_partial_signature(message_to_sign);
+/// Ephemeral secrets of the local party generated for a single presignature
+struct EphemeralSecrets<E: Curve> {
+    gamma_i: SecretScalar<E>,
+    k_i: SecretScalar<E>,
+    v_i: Integer,
+    rho_i: Integer,
+    G_i: fast_paillier::Ciphertext,
+    K_i: fast_paillier::Ciphertext,
+}
 
-    tracer.send_msg();
-    outgoings
-        .send(Outgoing::broadcast(Msg::Round4(MsgRound4 {
-            sigma: partial_sig.sigma,
-        })))
-        .await
-        .map_err(IoError::send_message)?;
-    tracer.msg_sent();
+/// Message that can carry data for a batch of presignatures
+///
+/// The message itself corresponds to the first presignature in the batch, and
+/// [`batch`](Self::batch) contains messages for the rest of them.
+trait Batched: Sized {
+    fn batch(&self) -> &[Self];
+    fn batch_mut(&mut self) -> &mut Vec<Self>;
+}
 
-    // Output
-    tracer.named_round_begins("Signature reconstruction");
+macro_rules! impl_batched {
+    ($($msg:ident$(<$E:ident>)?),+$(,)?) => {$(
+        impl$(<$E: Curve>)? Batched for $msg$(<$E>)? {
+            fn batch(&self) -> &[Self] {
+                &self.batch
+            }
+            fn batch_mut(&mut self) -> &mut Vec<Self> {
+                &mut self.batch
+            }
+        }
+    )+};
+}
 
-    tracer.receive_msgs();
-    let partial_sigs = rounds
-        .complete(round4)
-        .await
-        .map_err(IoError::receive_message)?;
-    tracer.msgs_received();
-    let sig = {
-        let r = NonZero::from_scalar(partial_sig.r);
-        let s = NonZero::from_scalar(
-            partial_sig.sigma + partial_sigs.iter().map(|m| m.sigma).sum::<Scalar<E>>(),
-        );
-        Option::zip(r, s).map(|(r, s)| Signature { r, s }.normalize_s())
-    };
-    let sig_invalid = match &sig {
-        Some(sig) => sig.verify(&pk, &message_to_sign).is_err(),
-        None => true,
-    };
-    if sig_invalid {
-        // Following the protocol, party should broadcast additional proofs
-        // to convince others it didn't cheat. However, since identifiable
-        // abort is not implemented yet, this part of the protocol is missing
-        return Err(SigningAborted::SignatureInvalid.into());
+impl_batched!(
+    MsgRound1a,
+    MsgRound1b,
+    MsgRound2<E>,
+    MsgRound3<E>,
+    MsgRound4<E>
+);
+
+/// Packs messages for each presignature in the batch into a single message
+fn into_batch<T: Batched>(mut msgs: Vec<T>) -> Result<T, Bug> {
+    if msgs.is_empty() {
+        return Err(Bug::EmptyBatch);
     }
-    let sig = sig.ok_or(SigningAborted::SignatureInvalid)?;
+    let rest = msgs.split_off(1);
+    let mut msg = msgs.pop().ok_or(Bug::EmptyBatch)?;
+    *msg.batch_mut() = rest;
+    Ok(msg)
+}
 
-    tracer.protocol_ends();
-    Ok(ProtocolOutput::Signature(sig))
+/// Iterates over messages for each presignature in the batch
+fn iter_batch<T: Batched>(msg: &T) -> impl Iterator<Item = &T> {
+    iter::once(msg).chain(msg.batch())
+}
+
+/// Checks that message carries data for exactly `batch_size` presignatures
+///
+/// Messages in the batch must have empty `batch` field: [`iter_batch`] doesn't visit nested
+/// messages, yet they would be hashed into the echo round and the transcript.
+fn is_batch_of_size<T: Batched>(msg: &T, batch_size: usize) -> bool {
+    msg.batch().len() + 1 == batch_size && msg.batch().iter().all(|m| m.batch().is_empty())
 }
 
 impl<E> Presignature<E>
@@ -1277,6 +2767,165 @@ where
         let sigma_i = self.k.as_ref() * m + r * self.chi.as_ref();
         PartialSignature { r, sigma: sigma_i }
     }
+
+    /// Returns public commitments to the presignature
+    ///
+    /// Commitment needs to be sent to a coordinator before the message to sign is known, so
+    /// the coordinator can later verify [signature share](Self::issue_signature_share) of the
+    /// signer. See [`PresignatureCommitment`].
+    pub fn commitment(&self) -> PresignatureCommitment<E> {
+        PresignatureCommitment {
+            R: self.R,
+            K: self.R * &self.k,
+            S: self.R * &self.chi,
+        }
+    }
+
+    /// Issues signature share for given message
+    ///
+    /// Same as [`issue_partial_signature`](Self::issue_partial_signature), but the output is
+    /// meant to be verified by a coordinator holding the [commitment](Self::commitment), which is
+    /// useful when signature is assembled by a coordinator. Signature shares can be combined via
+    /// [`Signature::from_shares`].
+    ///
+    /// **Never reuse presignatures!** If you use the same presignatures to sign two different
+    /// messages, it leaks the private key!
+    pub fn issue_signature_share(self, message_to_sign: DataToSign<E>) -> SignatureShare<E> {
+        let R = self.R;
+        let PartialSignature { sigma, .. } = self.issue_partial_signature(message_to_sign);
+        SignatureShare { R, sigma }
+    }
+
+    /// Issues signature share that an aggregator can use on behalf of the signer
+    ///
+    /// Share is issued for `message_to_sign` and is valid until `expires_at` within signing session
+    /// identified by `eid`. Contribution is signed with secret share of `key_share`, which must be
+    /// the key share the presignature was generated with. See [`DelegatedContribution`].
+    ///
+    /// **Never reuse presignatures!** If you use the same presignatures to sign two different
+    /// messages, it leaks the private key!
+    pub fn delegate_contribution<L: SecurityLevel, D: Digest>(
+        self,
+        rng: &mut (impl RngCore + CryptoRng),
+        key_share: &KeyShare<E, L>,
+        message_to_sign: DataToSign<E>,
+        eid: ExecutionId,
+        expires_at: std::time::SystemTime,
+    ) -> DelegatedContribution<E> {
+        let share = self.issue_signature_share(message_to_sign);
+        let message = message_to_sign.to_scalar();
+        let expires_at = unix_seconds(expires_at);
+
+        let (ephemeral_secret, commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+        let public_share = key_share.core.public_shares[usize::from(key_share.core.i)];
+        let challenge = contribution_challenge::<E, D>(
+            &public_share,
+            &commit.0,
+            &share,
+            &message,
+            eid,
+            expires_at,
+        );
+        let proof = schnorr_pok::prove(&ephemeral_secret, &challenge, &key_share.core.x);
+
+        DelegatedContribution {
+            share,
+            message,
+            execution_id: eid.as_bytes().to_vec(),
+            expires_at,
+            authorization: ContributionAuthorization {
+                commit: commit.0,
+                proof: proof.0,
+            },
+        }
+    }
+}
+
+impl<E: Curve> DelegatedContribution<E> {
+    /// Redeems the contribution into a signature share
+    ///
+    /// `public_share` is public share $X_i$ of the delegator. Returns an error if the contribution
+    /// wasn't signed by the delegator, if it was issued for another message or signing session,
+    /// or if it's expired according to the system clock.
+    pub fn redeem<D: Digest>(
+        self,
+        public_share: &Point<E>,
+        message_to_sign: &DataToSign<E>,
+        eid: ExecutionId,
+    ) -> Result<SignatureShare<E>, RedeemContributionError> {
+        self.redeem_at::<D>(
+            public_share,
+            message_to_sign,
+            eid,
+            std::time::SystemTime::now(),
+        )
+    }
+
+    /// Redeems the contribution into a signature share, checking expiration against time `now`
+    ///
+    /// Same as [`redeem`](Self::redeem), but lets the caller provide the current time.
+    pub fn redeem_at<D: Digest>(
+        self,
+        public_share: &Point<E>,
+        message_to_sign: &DataToSign<E>,
+        eid: ExecutionId,
+        now: std::time::SystemTime,
+    ) -> Result<SignatureShare<E>, RedeemContributionError> {
+        let challenge = contribution_challenge::<E, D>(
+            public_share,
+            &self.authorization.commit,
+            &self.share,
+            &self.message,
+            ExecutionId::new(&self.execution_id),
+            self.expires_at,
+        );
+        schnorr_pok::Proof(self.authorization.proof)
+            .verify(
+                &schnorr_pok::Commit(self.authorization.commit),
+                &challenge,
+                public_share,
+            )
+            .map_err(|_| RedeemContributionError::InvalidAuthorization)?;
+
+        if self.message != message_to_sign.to_scalar() {
+            return Err(RedeemContributionError::WrongMessage);
+        }
+        if self.execution_id != eid.as_bytes() {
+            return Err(RedeemContributionError::WrongExecutionId);
+        }
+        if unix_seconds(now) >= self.expires_at {
+            return Err(RedeemContributionError::Expired);
+        }
+        Ok(self.share)
+    }
+}
+
+/// Challenge of the schnorr signature that authorizes [delegated contribution](DelegatedContribution)
+fn contribution_challenge<E: Curve, D: Digest>(
+    public_share: &Point<E>,
+    commit: &Point<E>,
+    share: &SignatureShare<E>,
+    message: &Scalar<E>,
+    eid: ExecutionId,
+    expires_at: u64,
+) -> schnorr_pok::Challenge<E> {
+    let nonce = Scalar::from_hash::<D>(&unambiguous::DelegatedContribution {
+        sid: eid,
+        public_share: *public_share,
+        commit: *commit,
+        R: *share.R,
+        sigma: share.sigma,
+        message: *message,
+        expires_at,
+    });
+    schnorr_pok::Challenge { nonce }
+}
+
+/// Converts time into seconds since Unix epoch, time before the epoch is mapped to `0`
+fn unix_seconds(time: std::time::SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 impl<E: Curve> Presignature<E> {
@@ -1376,6 +3025,29 @@ impl<E: Curve> PartialSignature<E> {
             Some(Signature { r, s }.normalize_s())
         }
     }
+
+    /// Combines partial signatures without knowing the message they were issued for
+    ///
+    /// Combining partial signatures never involves the message: it only needs $r$ and the
+    /// partial $\sigma_i$ values, so a coordinator routing partial signatures doesn't need to learn
+    /// the message. Unlike [`combine`](Self::combine), it checks that each partial signature
+    /// was issued for the presignature with expected `r` (i.e. $x$ coordinate of presignature
+    /// $R$ reduced modulo curve order).
+    ///
+    /// Returns `None` if input is malformed or any partial signature carries a different $r$.
+    ///
+    /// Signature can't be verified without the message, so the public key isn't taken: resulting
+    /// signature must be verified by someone who knows the message (e.g. the signers) before it's
+    /// used.
+    pub fn combine_blind(
+        partial_signatures: &[PartialSignature<E>],
+        r: NonZero<Scalar<E>>,
+    ) -> Option<Signature<E>> {
+        if partial_signatures.iter().any(|partial| partial.r != *r) {
+            return None;
+        }
+        Self::combine(partial_signatures)
+    }
 }
 
 impl<E: Curve> Signature<E>
@@ -1397,6 +3069,140 @@ where
             Err(InvalidSignature)
         }
     }
+
+    /// Combines threshold amount of signature shares into regular signature
+    ///
+    /// `commitments[i]` is the [commitment](PresignatureCommitment) that the coordinator received
+    /// from the issuer of `shares[i]` after the presignature was generated. Commitments must be
+    /// consistent with the public key: $\sum_i K_i = G$ and $\sum_i S_i = \text{public\_key}$.
+    /// Each share is verified against the commitment held for its issuer:
+    /// $\sigma_i \cdot R = m \cdot K_i + r \cdot S_i$. Resulting signature is verified before
+    /// it's returned.
+    ///
+    /// Returns error if any of the shares is invalid. Indexes of invalid shares in `shares` can be
+    /// obtained via [`CombineSharesError::blame`]. Commitments and shares are expected to be issued
+    /// for the same presignature as the first commitment, shares issued for different presignature
+    /// are blamed.
+    ///
+    /// If presignature was derived via HD derivation, `public_key` must be the child public key.
+    pub fn from_shares(
+        public_key: &Point<E>,
+        commitments: &[PresignatureCommitment<E>],
+        shares: &[SignatureShare<E>],
+        message: &DataToSign<E>,
+    ) -> Result<Self, CombineSharesError> {
+        let R = commitments.first().ok_or(CombineSharesReason::NoShares)?.R;
+        if commitments.len() != shares.len() {
+            return Err(CombineSharesReason::MismatchedCommitments.into());
+        }
+        if commitments.iter().any(|commitment| commitment.R != R)
+            || commitments.iter().map(|c| c.K).sum::<Point<E>>() != Point::generator().to_point()
+            || commitments.iter().map(|c| c.S).sum::<Point<E>>() != *public_key
+        {
+            return Err(CombineSharesReason::InconsistentCommitments.into());
+        }
+
+        let blame = (0..)
+            .zip(shares)
+            .filter(|(_, share)| share.R != R)
+            .map(|(j, _)| j)
+            .collect::<Vec<_>>();
+        if !blame.is_empty() {
+            return Err(CombineSharesReason::MismatchedPresignature(blame).into());
+        }
+
+        let r = R.x().to_scalar();
+        let m = message.to_scalar();
+        let blame = (0..)
+            .zip(shares.iter().zip(commitments))
+            .filter(|(_, (share, commitment))| {
+                R * share.sigma != commitment.K * m + commitment.S * r
+            })
+            .map(|(j, _)| j)
+            .collect::<Vec<_>>();
+        if !blame.is_empty() {
+            return Err(CombineSharesReason::InvalidShares(blame).into());
+        }
+
+        let r = NonZero::from_scalar(r).ok_or(CombineSharesReason::SignatureInvalid)?;
+        let s = NonZero::from_scalar(shares.iter().map(|share| share.sigma).sum())
+            .ok_or(CombineSharesReason::SignatureInvalid)?;
+        let signature = Signature { r, s }.normalize_s();
+        signature
+            .verify(public_key, message)
+            .map_err(|_| CombineSharesReason::SignatureInvalid)?;
+        Ok(signature)
+    }
+}
+
+impl<E: Curve> Signature<E>
+where
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    Point<E>: HasAffineXAndParity<E>,
+{
+    /// Recovers public key from the signature and the message
+    ///
+    /// `recovery_id` identifies nonce point $R$ the signature was produced with: bit 0 is parity of
+    /// $R_y$ (set if odd), bit 1 is set if $R_x = r + q$ where $q$ is curve order, i.e. if $R_x$
+    /// was reduced when $r$ was computed. Recovery ID of the signature can be obtained via
+    /// [`Signature::recovery_id`].
+    ///
+    /// Returns error if `recovery_id > 3` or if there's no public key corresponding to given
+    /// recovery ID.
+    pub fn recover_public_key(
+        &self,
+        recovery_id: u8,
+        message: &DataToSign<E>,
+    ) -> Result<NonZero<Point<E>>, PublicKeyRecoveryError> {
+        if recovery_id > 3 {
+            return Err(PublicKeyRecoveryError);
+        }
+
+        let mut x = self.r.to_be_bytes().as_bytes().to_vec();
+        if recovery_id & 2 != 0 {
+            // R_x = r + q = r + (q - 1) + 1
+            let q_minus_one = -Scalar::<E>::one();
+            let mut carry = 1u16;
+            for (x_i, q_i) in x
+                .iter_mut()
+                .rev()
+                .zip(q_minus_one.to_be_bytes().as_bytes().iter().rev())
+            {
+                let sum = u16::from(*x_i) + u16::from(*q_i) + carry;
+                *x_i = sum as u8;
+                carry = sum >> 8;
+            }
+            if carry != 0 {
+                return Err(PublicKeyRecoveryError);
+            }
+        }
+        let x = Coordinate::<E>::from_be_bytes(&x).map_err(|_| PublicKeyRecoveryError)?;
+        let y_parity = if recovery_id & 1 != 0 {
+            Parity::Odd
+        } else {
+            Parity::Even
+        };
+        let R = Point::<E>::from_x_and_parity(&x, y_parity).ok_or(PublicKeyRecoveryError)?;
+        if R.x().map(|x| x.to_scalar()) != Some(*self.r) {
+            return Err(PublicKeyRecoveryError);
+        }
+
+        let public_key = (R * self.s - Point::generator() * message.to_scalar()) * self.r.invert();
+        NonZero::from_point(public_key).ok_or(PublicKeyRecoveryError)
+    }
+
+    /// Finds recovery ID of the signature
+    ///
+    /// Returns recovery ID such that [`Signature::recover_public_key`] outputs `public_key`, or
+    /// `None` if signature is not valid for given public key and message.
+    ///
+    /// Note that [normalizing](Signature::normalize_s) the signature changes its recovery ID.
+    pub fn recovery_id(&self, public_key: &Point<E>, message: &DataToSign<E>) -> Option<u8> {
+        (0..4).find(|&recovery_id| {
+            self.recover_public_key(recovery_id, message)
+                .is_ok_and(|recovered| *recovered == *public_key)
+        })
+    }
 }
 
 impl<E: Curve> Signature<E> {
@@ -1459,11 +3265,131 @@ impl<E: Curve> Signature<E> {
     pub fn serialized_len() -> usize {
         2 * Scalar::<E>::serialized_len()
     }
+
+    /// Encodes signature in ASN.1 DER format
+    ///
+    /// Signature is encoded as `SEQUENCE { r INTEGER, s INTEGER }`, as specified in
+    /// [RFC 3279](https://www.rfc-editor.org/rfc/rfc3279#section-2.2.3). This encoding is
+    /// expected, for instance, by OpenSSL. Use [`write_to_slice`](Self::write_to_slice) for
+    /// compact fixed-size encoding.
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(Self::serialized_len() + 6);
+        utils::der::write_unsigned_integer(&mut body, &self.r.to_be_bytes());
+        utils::der::write_unsigned_integer(&mut body, &self.s.to_be_bytes());
+
+        let mut out = Vec::with_capacity(body.len() + 4);
+        out.push(utils::der::SEQUENCE);
+        utils::der::write_length(&mut out, body.len());
+        out.extend_from_slice(&body);
+        out
+    }
+
+    /// Decodes signature from ASN.1 DER format
+    ///
+    /// Inverse of [`to_der`](Self::to_der). Returns `None` if input is not a valid DER
+    /// encoding (including non-minimal encodings and trailing bytes), or if any of signature
+    /// parts is zero or out of range.
+    pub fn from_der(inp: &[u8]) -> Option<Self> {
+        let (body, rest) = utils::der::read_tlv(utils::der::SEQUENCE, inp)?;
+        if !rest.is_empty() {
+            return None;
+        }
+        let (r, body) = utils::der::read_unsigned_integer(body)?;
+        let (s, rest) = utils::der::read_unsigned_integer(body)?;
+        if !rest.is_empty() {
+            return None;
+        }
+        let r = Scalar::from_be_bytes(r).ok()?.try_into().ok()?;
+        let s = Scalar::from_be_bytes(s).ok()?.try_into().ok()?;
+        Some(Self::from_raw_parts(r, s))
+    }
+}
+
+/// Randomness of the local party
+///
+/// Either derived [deterministically](SigningBuilder::set_deterministic_session), or provided by
+/// the caller. Unlike `&mut dyn CryptoRngCore`, it's `Send` whenever both sources are, so the
+/// protocol future remains `Send`.
+enum SessionRng<'a, S, R> {
+    Deterministic(S),
+    Provided(&'a mut R),
+}
+
+impl<'a, S, R> SessionRng<'a, S, R> {
+    fn new(session_rng: Option<S>, rng: &'a mut R) -> Self {
+        match session_rng {
+            Some(session_rng) => Self::Deterministic(session_rng),
+            None => Self::Provided(rng),
+        }
+    }
+}
+
+impl<S: RngCore, R: RngCore> RngCore for SessionRng<'_, S, R> {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Deterministic(rng) => rng.next_u32(),
+            Self::Provided(rng) => rng.next_u32(),
+        }
+    }
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Deterministic(rng) => rng.next_u64(),
+            Self::Provided(rng) => rng.next_u64(),
+        }
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Deterministic(rng) => rng.fill_bytes(dest),
+            Self::Provided(rng) => rng.fill_bytes(dest),
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match self {
+            Self::Deterministic(rng) => rng.try_fill_bytes(dest),
+            Self::Provided(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+impl<S: CryptoRng, R: CryptoRng> CryptoRng for SessionRng<'_, S, R> {}
+
+/// Hook and acknowledgement set via [`SigningBuilder::on_nonce_commitment`] and
+/// [`SigningBuilder::require_nonce_commitment_ack`]
+#[derive(Default)]
+struct NonceCommitmentCheckpoint<'r> {
+    hook: Option<NonceCommitmentHook<'r>>,
+    ack: Option<NonceCommitmentAck<'r>>,
+}
+
+type NonceCommitmentHook<'r> = &'r mut (dyn FnMut(PartyIndex, &MsgRound1a) + Send);
+type NonceCommitmentAck<'r> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = bool> + Send + 'r>>;
+
+/// Hook and acknowledgement set via [`SigningBuilder::on_aggregate_nonce`] and
+/// [`SigningBuilder::require_aggregate_nonce_ack`]
+struct AggregateNonceCheckpoint<'r, E: Curve> {
+    hook: Option<AggregateNonceHook<'r, E>>,
+    ack: Option<NonceCommitmentAck<'r>>,
+}
+
+impl<E: Curve> Default for AggregateNonceCheckpoint<'_, E> {
+    fn default() -> Self {
+        Self {
+            hook: None,
+            ack: None,
+        }
+    }
 }
 
+type AggregateNonceHook<'r, E> = &'r mut (dyn FnMut(&NonZero<Point<E>>) + Send);
+
 enum ProtocolOutput<E: Curve> {
-    Presignature(Presignature<E>),
-    Signature(Signature<E>),
+    Presignatures(Vec<Presignature<E>>),
+    Signatures {
+        sigs: Vec<Signature<E>>,
+        transcript_hash: [u8; 32],
+        participation_proof: Option<ParticipationProof>,
+    },
 }
 
 /// Error indicating that signing protocol failed
@@ -1478,6 +3404,128 @@ crate::errors::impl_from! {
         err: SigningAborted => SigningError(Reason::Aborted(err)),
         err: IoError => SigningError(Reason::IoError(err)),
         err: Bug => SigningError(Reason::Bug(err)),
+        err: Cancelled => SigningError(Reason::Cancelled(err)),
+        err: TimedOut => SigningError(Reason::TimedOut(err)),
+        err: TimerNotSet => SigningError(Reason::TimerNotSet(err)),
+    }
+}
+
+impl<SendErr, RecvErr> From<reliable_broadcast::EchoRoundError<SendErr, RecvErr>> for SigningError
+where
+    SendErr: std::error::Error + Send + Sync + 'static,
+    RecvErr: std::error::Error + Send + Sync + 'static,
+{
+    fn from(err: reliable_broadcast::EchoRoundError<SendErr, RecvErr>) -> Self {
+        use reliable_broadcast::EchoRoundError;
+        match err {
+            EchoRoundError::Send(err) => IoError::send_message(err).into(),
+            EchoRoundError::Receive(err) => IoError::receive_message(err).into(),
+            EchoRoundError::Cancelled(err) => err.into(),
+            EchoRoundError::TimedOut(err) => err.into(),
+        }
+    }
+}
+
+impl SigningError {
+    /// Indicates whether the protocol was [cancelled](crate::cancellation)
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self.0, Reason::Cancelled(_))
+    }
+
+    /// Indicates whether the protocol was aborted due to [round timeout](SigningBuilder::set_round_timeout)
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self.0, Reason::TimedOut(_))
+    }
+
+    /// Indicates whether the protocol refused to run as [round timeout](SigningBuilder::set_round_timeout)
+    /// was set without a [timer](SigningBuilder::set_timer)
+    pub fn is_timer_not_set(&self) -> bool {
+        matches!(self.0, Reason::TimerNotSet(_))
+    }
+
+    /// Indicates whether signing was aborted because nonce commitments were not
+    /// [acknowledged](SigningBuilder::require_nonce_commitment_ack)
+    pub fn is_nonce_commitments_rejected(&self) -> bool {
+        matches!(self.0, Reason::NonceCommitmentsRejected)
+    }
+
+    /// Indicates whether signing was aborted because aggregate nonce was not
+    /// [acknowledged](SigningBuilder::require_aggregate_nonce_ack)
+    pub fn is_aggregate_nonce_rejected(&self) -> bool {
+        matches!(self.0, Reason::AggregateNonceRejected)
+    }
+
+    /// Indicates whether the key share doesn't correspond to the
+    /// [expected public key](SigningBuilder::expect_public_key)
+    pub fn is_wrong_key(&self) -> bool {
+        matches!(self.0, Reason::InvalidArgs(InvalidArgs::WrongKey))
+    }
+
+    /// Indicates whether signer refused to sign with a key share which security level is not
+    /// [production-safe](SigningBuilder::require_production_level)
+    pub fn is_not_production_safe(&self) -> bool {
+        matches!(self.0, Reason::InvalidArgs(InvalidArgs::NotProductionSafe))
+    }
+
+    /// Indicates whether [signing state](SigningState) was refused to be [resumed](resume_signing)
+    /// as it was obtained in a session with a different execution ID
+    pub fn is_execution_id_mismatch(&self) -> bool {
+        matches!(
+            self.0,
+            Reason::InvalidArgs(InvalidArgs::ExecutionIdMismatch)
+        )
+    }
+
+    /// Indicates whether the protocol refused to run as security level is not
+    /// [compatible](crate::security_level::is_compatible_with_curve) with the curve
+    pub fn is_incompatible_level_for_curve(&self) -> bool {
+        matches!(
+            self.0,
+            Reason::InvalidArgs(InvalidArgs::IncompatibleLevelForCurve { .. })
+        )
+    }
+
+    /// Indicates whether signers use key shares from different [epochs](crate::key_share::DirtyKeyInfo::epoch)
+    ///
+    /// It happens when some signers use key shares obtained before the key refresh and others use
+    /// the refreshed ones.
+    pub fn is_epoch_mismatch(&self) -> bool {
+        matches!(self.0, Reason::Aborted(SigningAborted::EpochMismatch(_)))
+    }
+
+    /// Returns epochs reported by signers, if they use key shares from different
+    /// [epochs](crate::key_share::DirtyKeyInfo::epoch)
+    ///
+    /// `epochs[j]` is the epoch reported by j-th signer, including the local one. Epochs are
+    /// self-reported, so they don't identify which signer is at fault: the signers whose epoch is
+    /// lower than others' are likely to use key shares obtained before the key refresh.
+    pub fn epoch_mismatch(&self) -> Option<&[u64]> {
+        match &self.0 {
+            Reason::Aborted(SigningAborted::EpochMismatch(epochs)) => Some(epochs),
+            _ => None,
+        }
+    }
+
+    /// Returns index of the signer that appears more than once in the list of signers
+    pub fn duplicate_signer(&self) -> Option<PartyIndex> {
+        match self.0 {
+            Reason::InvalidArgs(InvalidArgs::DuplicateSigner(j)) => Some(j),
+            _ => None,
+        }
+    }
+
+    /// Returns indexes of signers that sent data for a different amount of presignatures than
+    /// expected, or sent messages with malformed batch
+    pub fn mismatched_batch_size(&self) -> Option<Vec<PartyIndex>> {
+        match &self.0 {
+            Reason::Aborted(SigningAborted::MismatchedBatchSize(parties)) => {
+                let mut parties = parties.iter().map(|(j, _)| *j).collect::<Vec<_>>();
+                parties.sort_unstable();
+                parties.dedup();
+                Some(parties)
+            }
+            _ => None,
+        }
     }
 }
 
@@ -1505,6 +3553,16 @@ enum Reason {
     ),
     #[error("i/o error")]
     IoError(#[source] IoError),
+    #[error("nonce commitments were not acknowledged")]
+    NonceCommitmentsRejected,
+    #[error("aggregate nonce was not acknowledged")]
+    AggregateNonceRejected,
+    #[error("protocol was cancelled")]
+    Cancelled(#[source] Cancelled),
+    #[error("round timed out")]
+    TimedOut(#[source] TimedOut),
+    #[error("timer is not set")]
+    TimerNotSet(#[source] TimerNotSet),
     /// Bug occurred
     #[error("bug occurred")]
     Bug(Bug),
@@ -1535,10 +3593,16 @@ enum SigningAborted {
     InvalidPsiPrimePrime(Vec<(PartyIndex, MsgId, MsgId)>),
     #[error("Delta != G * delta")]
     MismatchedDelta,
+    #[error("party sent data for a different amount of presignatures than expected")]
+    MismatchedBatchSize(Vec<(PartyIndex, MsgId)>),
     #[error("resulting signature is not valid")]
     SignatureInvalid,
     #[error("other parties received different broadcast messages at round1a")]
     Round1aNotReliable(Vec<(PartyIndex, MsgId)>),
+    #[error("signers use key shares from different epochs: {0:?}")]
+    EpochMismatch(Vec<u64>),
+    #[error("signature over transcript is invalid (proof of participation)")]
+    InvalidParticipationSignature(Vec<(PartyIndex, MsgId)>),
 }
 
 #[derive(Debug, Error)]
@@ -1549,6 +3613,20 @@ enum InvalidArgs {
     SignerIndexOutOfBounds,
     #[error("party index in S is out of bounds (must be < n)")]
     InvalidS,
+    #[error("party {0} appears in S more than once")]
+    DuplicateSigner(PartyIndex),
+    #[error("list of messages to sign is empty")]
+    EmptyBatch,
+    #[error("key share doesn't correspond to expected public key")]
+    WrongKey,
+    #[error("security level is not suitable for production")]
+    NotProductionSafe,
+    #[error("security level (ell = {ell}) is not compatible with curve order of {curve_order_bits} bits")]
+    IncompatibleLevelForCurve { ell: usize, curve_order_bits: usize },
+    #[error("amount of parties {n} exceeds the limit {}", crate::MAX_PARTIES)]
+    TooManyParties { n: u16 },
+    #[error("signing state was obtained in a session with different execution id")]
+    ExecutionIdMismatch,
 }
 
 #[derive(Debug, Error)]
@@ -1575,6 +3653,12 @@ enum Bug {
     ZeroR,
     #[error("unexpected protocol output")]
     UnexpectedProtocolOutput,
+    #[error("batch of messages is empty")]
+    EmptyBatch,
+    #[error("batch size overflows u64")]
+    BatchSizeOverflow,
+    #[error("amount of messages to sign doesn't match amount of presignatures")]
+    MismatchedAmountOfPresignatures,
     #[error("derive lagrange coef")]
     LagrangeCoef,
     #[error("subset function returned error")]
@@ -1612,6 +3696,71 @@ enum BugSource {
 #[error("signature is not valid")]
 pub struct InvalidSignature;
 
+/// Error indicating that public key couldn't be recovered from the signature
+#[derive(Debug, Error)]
+#[error("couldn't recover public key from the signature")]
+pub struct PublicKeyRecoveryError;
+
+/// Error indicating that [delegated contribution](DelegatedContribution) couldn't be redeemed
+#[derive(Debug, Error)]
+pub enum RedeemContributionError {
+    /// Contribution is not signed by the delegator, or was altered after it was signed
+    #[error("contribution is not authorized by the delegator")]
+    InvalidAuthorization,
+    /// Contribution was issued for another message
+    #[error("contribution was issued for another message")]
+    WrongMessage,
+    /// Contribution was issued for another signing session
+    #[error("contribution was issued for another execution id")]
+    WrongExecutionId,
+    /// Contribution is expired
+    #[error("contribution is expired")]
+    Expired,
+}
+
+/// Error indicating that signature shares couldn't be combined into a signature
+#[derive(Debug, Error)]
+#[error("couldn't combine signature shares")]
+pub struct CombineSharesError(#[source] CombineSharesReason);
+
+impl CombineSharesError {
+    /// Indexes of shares (in the list passed to [`Signature::from_shares`]) that were found invalid
+    ///
+    /// Returns empty list if the error can't be attributed to specific shares.
+    pub fn blame(&self) -> &[usize] {
+        match &self.0 {
+            CombineSharesReason::MismatchedPresignature(blame)
+            | CombineSharesReason::InvalidShares(blame) => blame,
+            CombineSharesReason::NoShares
+            | CombineSharesReason::MismatchedCommitments
+            | CombineSharesReason::InconsistentCommitments
+            | CombineSharesReason::SignatureInvalid => &[],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum CombineSharesReason {
+    #[error("list of signature shares is empty")]
+    NoShares,
+    #[error("amount of commitments doesn't match amount of shares")]
+    MismatchedCommitments,
+    #[error("shares {0:?} were issued for a different presignature")]
+    MismatchedPresignature(Vec<usize>),
+    #[error("shares {0:?} are invalid")]
+    InvalidShares(Vec<usize>),
+    #[error("commitments are inconsistent with the public key")]
+    InconsistentCommitments,
+    #[error("resulting signature is not valid")]
+    SignatureInvalid,
+}
+
+impl From<CombineSharesReason> for CombineSharesError {
+    fn from(err: CombineSharesReason) -> Self {
+        Self(err)
+    }
+}
+
 #[cfg(test)]
 mod test {
     fn read_write_signature<E: generic_ec::Curve>() {
@@ -1627,6 +3776,62 @@ mod test {
         }
     }
 
+    /// Hash that fits into bit length of curve order is never truncated
+    fn digest_truncating_is_reducing_for_short_hash<E: generic_ec::Curve>() {
+        let data = b"data to sign";
+        assert_eq!(
+            super::DataToSign::<E>::digest_truncating::<sha2::Sha256>(data).to_scalar(),
+            super::DataToSign::<E>::digest_reducing::<sha2::Sha256>(data).to_scalar(),
+        );
+    }
+
+    #[test]
+    fn digest_truncating_short_hash() {
+        digest_truncating_is_reducing_for_short_hash::<crate::supported_curves::Secp256k1>();
+        digest_truncating_is_reducing_for_short_hash::<crate::supported_curves::Secp256r1>();
+    }
+
+    /// Test vectors from [RFC 6979, A.2.5](https://www.rfc-editor.org/rfc/rfc6979#appendix-A.2.5):
+    /// ECDSA, 256 bits (prime field), with SHA-512
+    #[test]
+    fn digest_truncating_rfc6979_p256_sha512() {
+        type E = crate::supported_curves::Secp256r1;
+
+        let public_key = generic_ec::Point::<E>::from_bytes(
+            hex::decode(
+                "04\
+                 60FED4BA255A9D31C961EB74C6356D68C049B8923B61FA6CE669622E60F29FB6\
+                 7903FE1008B8BC99A41AE9E95628BC64F2F1B20C2D7E9F5177A3C294D4462299",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let vectors: [(&[u8], &str); 2] = [
+            (
+                b"sample",
+                "8496A60B5E9B47C825488827E0495B0E3FA109EC4568FD3F8D1097678EB97F00\
+                 2362AB1ADBE2B8ADF9CB9EDAB740EA6049C028114F2460F96554F61FAE3302FE",
+            ),
+            (
+                b"test",
+                "461D93F31B6540894788FD206C07CFA0CC35F46FA3C91816FFF1040AD1581A04\
+                 39AF9F15DE0DB8D97E72719C74820D304CE5226E32DEDAE67519E840D1194E55",
+            ),
+        ];
+
+        for (message, signature) in vectors {
+            let signature =
+                super::Signature::<E>::read_from_slice(&hex::decode(signature).unwrap()).unwrap();
+
+            let truncating = super::DataToSign::digest_truncating::<sha2::Sha512>(message);
+            signature.verify(&public_key, &truncating).unwrap();
+
+            // Reducing the hash modulo curve order yields a different message representative
+            let reducing = super::DataToSign::digest_reducing::<sha2::Sha512>(message);
+            assert!(signature.verify(&public_key, &reducing).is_err());
+        }
+    }
+
     #[test]
     fn read_write_signature_secp256k1() {
         read_write_signature::<crate::supported_curves::Secp256k1>()
@@ -0,0 +1,106 @@
+use paillier_zk::{fast_paillier, paillier_encryption_in_range as π_enc};
+use round_based::PartyIndex;
+
+use crate::key_share::{KeyShare, PartyAux};
+use crate::security_level::SecurityLevel;
+
+use super::Bug;
+
+/// Values derived from the key share that are reused across signing sessions
+///
+/// Each signing session needs Paillier encryption key and ZK auxiliary data (ring-Pedersen
+/// parameters) of every signer, as well as own Paillier decryption key. Without an arena, they're
+/// derived from the key share every time they're used, which involves allocating and copying big
+/// integers. Arena derives them once, at the first session, and lends them to subsequent sessions,
+/// which reduces amount of heap allocations per signing session. Set it via
+/// [`SigningBuilder::set_arena`](super::SigningBuilder::set_arena).
+///
+/// Arena can be used with any key share: cached values are compared against the key share at
+/// the beginning of each session, and derived again if they don't match. Still, to benefit from
+/// the arena, it should be used with the same key share across sessions.
+///
+/// **Contains secret data**: own Paillier decryption key. Arena must be kept as secret as the key
+/// share itself.
+#[derive(Default)]
+pub struct SigningArena {
+    /// Own Paillier decryption key
+    dec: Option<fast_paillier::DecryptionKey>,
+    /// Cached data of each party, indexed by party index at keygen
+    parties: Vec<Option<PartyCache>>,
+}
+
+/// Data of a single party derived from its [`PartyAux`]
+pub(super) struct PartyCache {
+    pub enc: fast_paillier::EncryptionKey,
+    pub aux: π_enc::Aux,
+}
+
+/// Paillier keys and ZK auxiliary data of the signers, prepared for a signing session
+pub(super) struct Prepared<'a> {
+    /// Own Paillier decryption key
+    pub dec_i: &'a fast_paillier::DecryptionKey,
+    /// Data of each signer, indexed by signer index `0 <= j < t`
+    pub parties: Vec<&'a PartyCache>,
+}
+
+impl SigningArena {
+    /// Constructs an empty arena
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Derives (or takes from cache) values needed to sign with `key_share` by signers `S`
+    ///
+    /// `S` are indexes of the signers at keygen, they must be validated by the caller.
+    pub(super) fn prepare<E, L>(
+        &mut self,
+        key_share: &KeyShare<E, L>,
+        S: &[PartyIndex],
+    ) -> Result<Prepared<'_>, Bug>
+    where
+        E: generic_ec::Curve,
+        L: SecurityLevel,
+    {
+        let (p, q) = (&key_share.aux.p, &key_share.aux.q);
+        if !matches!(&self.dec, Some(dec) if dec.p() == p && dec.q() == q) {
+            self.dec = Some(
+                fast_paillier::DecryptionKey::from_primes(p.clone(), q.clone())
+                    .map_err(|_| Bug::InvalidOwnPaillierKey)?,
+            );
+        }
+
+        let parties = &key_share.aux.parties;
+        self.parties.resize_with(parties.len(), || None);
+        for &S_j in S {
+            let aux_j = &parties[usize::from(S_j)];
+            let cache_j = &mut self.parties[usize::from(S_j)];
+            if !matches!(cache_j, Some(cache) if cache.matches(aux_j)) {
+                *cache_j = Some(PartyCache {
+                    enc: fast_paillier::EncryptionKey::from_n(aux_j.N.clone()),
+                    aux: aux_j.into(),
+                });
+            }
+        }
+
+        Ok(Prepared {
+            dec_i: self.dec.as_ref().ok_or(Bug::InvalidOwnPaillierKey)?,
+            parties: S
+                .iter()
+                .map(|&S_j| self.parties[usize::from(S_j)].as_ref())
+                .collect::<Option<Vec<_>>>()
+                .ok_or(Bug::Subset)?,
+        })
+    }
+}
+
+impl PartyCache {
+    /// Checks that cache was derived from `aux`
+    fn matches(&self, aux: &PartyAux) -> bool {
+        self.enc.n() == &aux.N
+            && self.aux.rsa_modulo == aux.N
+            && self.aux.s == aux.s
+            && self.aux.t == aux.t
+            && self.aux.multiexp.is_some() == aux.multiexp.is_some()
+            && self.aux.crt.is_some() == aux.crt.is_some()
+    }
+}
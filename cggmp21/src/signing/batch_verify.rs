@@ -0,0 +1,80 @@
+use generic_ec::{coords::AlwaysHasAffineX, Curve, NonZero, Point};
+
+use super::{DataToSign, Signature};
+
+/// Verifies a stream of independent signatures
+///
+/// Signatures are [pushed](Self::push) one by one, each under its own public key and message, and
+/// the outcome is obtained via [`finalize`](Self::finalize), which reports positions (in the
+/// order of pushing) of all the signatures that didn't verify. Only those positions are kept in
+/// memory, so arbitrarily large batches can be verified without buffering them.
+///
+/// Note that batch is not verified via randomized linear combination. Such check needs nonce
+/// point $R$ of each signature, but ECDSA signature carries only its $x$ coordinate (reduced
+/// modulo curve order). $R$ could be decompressed from $r$, however parity of $R_y$ is unknown,
+/// so each point is only determined up to a sign, and the combination can't be formed. Each
+/// signature is therefore verified when it's pushed.
+pub struct BatchVerifier<E: Curve> {
+    count: usize,
+    failed: Vec<usize>,
+    _curve: core::marker::PhantomData<E>,
+}
+
+impl<E: Curve> BatchVerifier<E>
+where
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+{
+    /// Constructs an empty batch
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            failed: Vec::new(),
+            _curve: core::marker::PhantomData,
+        }
+    }
+
+    /// Adds signature to the batch
+    ///
+    /// Signature is checked against `public_key` and `message` as in [`Signature::verify`].
+    pub fn push(
+        &mut self,
+        public_key: &Point<E>,
+        signature: &Signature<E>,
+        message: &DataToSign<E>,
+    ) {
+        if signature.verify(public_key, message).is_err() {
+            self.failed.push(self.count);
+        }
+        self.count += 1;
+    }
+
+    /// Amount of signatures pushed so far
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Indicates whether no signatures were pushed
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Completes the verification
+    ///
+    /// Returns `Ok(())` if all signatures are valid, or indexes of invalid signatures otherwise.
+    pub fn finalize(self) -> Result<(), Vec<usize>> {
+        if self.failed.is_empty() {
+            Ok(())
+        } else {
+            Err(self.failed)
+        }
+    }
+}
+
+impl<E: Curve> Default for BatchVerifier<E>
+where
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
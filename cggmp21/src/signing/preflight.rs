@@ -0,0 +1,114 @@
+use generic_ec::Curve;
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::key_share::{DirtyAuxInfo, KeyShare};
+use crate::security_level::SecurityLevel;
+
+/// Checks that key shares of the signers can be used to sign together
+///
+/// Signing fails in the middle of the protocol (after expensive rounds are carried out) if signers
+/// use key shares that don't fit together. This function catches the most common mistakes in
+/// setup beforehand. It checks that key shares of all `signers`:
+/// * Are shares of the same key, i.e. have the same shared public key
+/// * Have the same set of parties: the same public shares and VSS setup
+/// * Come from the same [epoch](crate::key_share::DirtyKeyInfo::epoch) and carry the same public
+///   aux info of all parties (i.e. were produced by the same aux info generation or key refresh)
+/// * Belong to distinct parties
+///
+/// Only public data of the key shares is inspected: secret shares and Paillier secret keys are
+/// ignored.
+///
+/// Key shares are compared against the first one. Returns error naming the first signer whose
+/// key share is inconsistent with it.
+pub fn preflight<E: Curve, L: SecurityLevel>(
+    signers: &[KeyShare<E, L>],
+) -> Result<(), PreflightError> {
+    let Some((first, rest)) = signers.split_first() else {
+        return Ok(());
+    };
+    for (k, share) in (1..).zip(rest) {
+        let err = |reason| PreflightError {
+            party: share.core.i,
+            reason,
+        };
+        if signers[..k].iter().any(|s| s.core.i == share.core.i) {
+            return Err(err(Reason::DuplicateSigner));
+        }
+        if share.core.shared_public_key != first.core.shared_public_key {
+            return Err(err(Reason::PublicKey));
+        }
+        if !share.core.key_info.same_key(&first.core.key_info) {
+            return Err(err(Reason::PartySet));
+        }
+        if share.core.epoch != first.core.epoch {
+            return Err(err(Reason::Epoch {
+                expected: first.core.epoch,
+                actual: share.core.epoch,
+            }));
+        }
+        if let Some(j) = mismatched_aux(&share.aux, &first.aux) {
+            return Err(err(Reason::AuxInfo { j }));
+        }
+    }
+    Ok(())
+}
+
+/// Returns index of the first party whose public aux data differs in `a` and `b`
+fn mismatched_aux<L: SecurityLevel>(a: &DirtyAuxInfo<L>, b: &DirtyAuxInfo<L>) -> Option<u16> {
+    let n = a.parties.len().max(b.parties.len());
+    (0u16..).take(n).find(|&j| {
+        match (a.parties.get(usize::from(j)), b.parties.get(usize::from(j))) {
+            (Some(a), Some(b)) => a.N != b.N || a.s != b.s || a.t != b.t,
+            _ => true,
+        }
+    })
+}
+
+/// Error indicating that key shares of the signers are inconsistent
+///
+/// Returned by [`preflight`]
+#[derive(Debug, Error)]
+#[error("key share of signer {party} is inconsistent with other signers")]
+pub struct PreflightError {
+    party: PartyIndex,
+    #[source]
+    reason: Reason,
+}
+
+impl PreflightError {
+    /// Index of the signer (at keygen) whose key share is inconsistent
+    pub fn party(&self) -> PartyIndex {
+        self.party
+    }
+
+    /// Indicates whether the signer appears more than once
+    pub fn is_duplicate_signer(&self) -> bool {
+        matches!(self.reason, Reason::DuplicateSigner)
+    }
+
+    /// Indicates whether key share of the signer is a share of another key
+    pub fn is_wrong_key(&self) -> bool {
+        matches!(self.reason, Reason::PublicKey | Reason::PartySet)
+    }
+
+    /// Indicates whether key share of the signer carries different aux info, or comes from
+    /// another epoch
+    pub fn is_aux_info_mismatch(&self) -> bool {
+        matches!(self.reason, Reason::Epoch { .. } | Reason::AuxInfo { .. })
+    }
+}
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("signer appears more than once")]
+    DuplicateSigner,
+    #[error("shared public key doesn't match")]
+    PublicKey,
+    #[error("public shares or vss setup don't match")]
+    PartySet,
+    #[error("key share is from another epoch: expected {expected}, actual {actual}")]
+    Epoch { expected: u64, actual: u64 },
+    #[error("public aux data of party {j} doesn't match")]
+    AuxInfo { j: u16 },
+}
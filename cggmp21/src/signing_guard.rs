@@ -0,0 +1,155 @@
+//! Limits amount of concurrent signing sessions per key share
+//!
+//! High-throughput signers may run many signing sessions with the same key share at once. Reusing
+//! the share across too many simultaneous sessions amplifies side-channel risk, so it may be
+//! desired to cap it. [`SigningGuard`] wraps a [`KeyShare`], tracks sessions that are in flight,
+//! and refuses to start a new one once the configured limit is reached.
+//!
+//! Each session started via [`SigningGuard::signing`] holds a [`SessionPermit`]. The permit is
+//! moved into the protocol future, so the session is considered in flight until the future is
+//! completed (either successfully or not) or dropped. Key share can't be borrowed from the guard,
+//! so sessions can only be started through it.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn example<E, M>(
+//! #     eid: cggmp21::ExecutionId<'_>,
+//! #     i: u16,
+//! #     key_share: cggmp21::KeyShare<E>,
+//! #     party: M,
+//! #     message: cggmp21::DataToSign<E>,
+//! # ) -> Result<(), Box<dyn std::error::Error>>
+//! # where
+//! #     E: generic_ec::Curve,
+//! #     generic_ec::Point<E>: generic_ec::coords::HasAffineX<E>,
+//! #     M: round_based::Mpc<ProtocolMessage = cggmp21::signing::msg::Msg<E, sha2::Sha256>>,
+//! # {
+//! # let mut rng = rand::rngs::OsRng;
+//! use cggmp21::signing_guard::SigningGuard;
+//!
+//! let guard = SigningGuard::new(key_share, 16);
+//!
+//! let signers = [0, 2];
+//! let signature = guard
+//!     .signing(eid, i, &signers)?
+//!     .sign(&mut rng, party, message)
+//!     .await?;
+//! # let _ = signature;
+//! # Ok(()) }
+//! ```
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use generic_ec::{coords::HasAffineX, Curve, Point};
+use round_based::PartyIndex;
+use thiserror::Error;
+
+use crate::{
+    key_share::KeyShare, security_level::SecurityLevel, signing::SigningBuilder, ExecutionId,
+};
+
+/// Key share that limits amount of concurrent signing sessions
+///
+/// See [module-level](self) docs.
+pub struct SigningGuard<E: Curve, L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    key_share: KeyShare<E, L>,
+    max_sessions: usize,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl<E: Curve, L: SecurityLevel> SigningGuard<E, L> {
+    /// Wraps a key share
+    ///
+    /// At most `max_sessions` signing sessions may be in flight at the same time.
+    pub fn new(key_share: KeyShare<E, L>, max_sessions: usize) -> Self {
+        Self {
+            key_share,
+            max_sessions,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Starts a signing session
+    ///
+    /// Same as [`crate::signing`], but returns an error if there are already `max_sessions`
+    /// sessions in flight. Returned builder holds a [`SessionPermit`]: the session is counted as
+    /// in flight until the builder, or the protocol future it's turned into, is dropped.
+    pub fn signing<'r>(
+        &'r self,
+        eid: ExecutionId<'r>,
+        i: PartyIndex,
+        parties_indexes_at_keygen: &'r [PartyIndex],
+    ) -> Result<SigningBuilder<'r, E, L>, TooManyConcurrentSessions>
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let permit = self.acquire()?;
+        Ok(
+            crate::signing(eid, i, parties_indexes_at_keygen, &self.key_share)
+                .hold_session_permit(permit),
+        )
+    }
+
+    /// Reserves a slot for a signing session
+    ///
+    /// Can be used when signing session is started in some other way than
+    /// [`SigningGuard::signing`], e.g. when presignature is generated on the side. The session
+    /// is counted as in flight until returned permit is dropped.
+    pub fn acquire(&self) -> Result<SessionPermit, TooManyConcurrentSessions> {
+        self.in_flight
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.max_sessions).then_some(n + 1)
+            })
+            .map_err(|_| TooManyConcurrentSessions {
+                max_sessions: self.max_sessions,
+            })?;
+        Ok(SessionPermit {
+            in_flight: self.in_flight.clone(),
+        })
+    }
+
+    /// Returns amount of signing sessions that are currently in flight
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Returns maximum amount of concurrent signing sessions
+    pub fn max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+
+    /// Unwraps the key share
+    pub fn into_key_share(self) -> KeyShare<E, L> {
+        self.key_share
+    }
+}
+
+/// Signing session slot reserved in the [`SigningGuard`]
+///
+/// The slot is released when permit is dropped.
+#[must_use = "session slot is released as soon as permit is dropped"]
+pub struct SessionPermit {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for SessionPermit {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Error indicating that [`SigningGuard`] refused to start a signing session as there are too many
+/// sessions in flight
+#[derive(Debug, Error)]
+#[error("too many concurrent signing sessions (max: {max_sessions})")]
+pub struct TooManyConcurrentSessions {
+    max_sessions: usize,
+}
+
+impl TooManyConcurrentSessions {
+    /// Maximum amount of concurrent signing sessions that was exceeded
+    pub fn max_sessions(&self) -> usize {
+        self.max_sessions
+    }
+}
@@ -6,6 +6,13 @@
 //! unexpected consequences: for instance, [default security level](crate::security_level::SecurityLevel128)
 //! might not be compatible with another curve, which might result into unexpected runtime error or
 //! reduced security of the protocol.
+//!
+//! ## Ristretto255
+//! Ristretto255 is not supported. Signing protocol of this crate produces ECDSA signatures that
+//! require $x$ coordinate of the nonce point, and Ristretto group elements don't have one (the group
+//! is a quotient of Curve25519 points). `generic-ec` doesn't provide Ristretto255 either, so it can't
+//! be plugged into keygen. Protocols that need a key over Ristretto255 along with Schnorr signatures
+//! need a threshold Schnorr implementation, see [crate-level](crate) docs.
 
 #[cfg(feature = "curve-secp256k1")]
 pub use generic_ec::curves::Secp256k1;
@@ -0,0 +1,279 @@
+//! Local simulation of the protocols for testing
+//!
+//! Helpers run all parties of a protocol within a single thread on top of [`round_based::sim`],
+//! so a full flow (key generation, aux info generation, signing) can be tested without setting up
+//! any networking. Every party gets its own rng forked from the one provided by the caller.
+//!
+//! Simulation is intended for tests only: a single process holds all key shares, so there's no
+//! point in using it in production.
+//!
+//! [`adversary`] module provides malicious parties that can be used to test blame handling.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn main() -> Result<(), cggmp21::testing::SimulationError> {
+//! use cggmp21::{security_level::SecurityLevel128, supported_curves::Secp256k1};
+//! # let mut rng = rand::rngs::OsRng;
+//!
+//! // 2-out-of-3 key. Note that it takes a while as it generates Paillier primes for every party
+//! let shares = cggmp21::testing::simulate_keygen::<Secp256k1, SecurityLevel128, _>(&mut rng, Some(2), 3)?;
+//!
+//! let data_to_sign = cggmp21::DataToSign::digest::<sha2::Sha256>(b"data to be signed");
+//! let signature = cggmp21::testing::simulate_signing(&mut rng, &shares[..2], data_to_sign)?;
+//! # Ok(()) }
+//! ```
+
+pub mod adversary;
+
+use generic_ec::{coords::HasAffineX, Curve, Point};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_core::{CryptoRng, RngCore};
+use thiserror::Error;
+
+use crate::{
+    key_share::InvalidKeyShare, security_level::SecurityLevel, DataToSign, ExecutionId,
+    KeyRefreshError, KeyShare, KeygenError, PregeneratedPrimes, Signature, SigningError,
+};
+
+/// Simulates key generation followed by aux info generation
+///
+/// Outputs `n` key shares of a fresh key. If `t` is `Some(_)`, the key is `t`-out-of-`n`,
+/// otherwise it's non-threshold. Takes a while as it generates Paillier primes for every party.
+pub fn simulate_keygen<E, L, R>(
+    rng: &mut R,
+    t: Option<u16>,
+    n: u16,
+) -> Result<Vec<KeyShare<E, L>>, SimulationError>
+where
+    E: Curve,
+    L: SecurityLevel,
+    R: RngCore + CryptoRng,
+{
+    let eid = random_eid(rng);
+    let eid = ExecutionId::new(&eid);
+    let party_rngs = fork_rngs(rng, n)?;
+    let core_shares = match t {
+        Some(t) => round_based::sim::run_with_setup(party_rngs, |i, party, mut rng| async move {
+            crate::keygen::<E>(eid, i, n)
+                .set_threshold(t)
+                .set_security_level::<L>()
+                .start(&mut rng, party)
+                .await
+        }),
+        None => round_based::sim::run_with_setup(party_rngs, |i, party, mut rng| async move {
+            crate::keygen::<E>(eid, i, n)
+                .set_security_level::<L>()
+                .start(&mut rng, party)
+                .await
+        }),
+    }
+    .map_err(|err| Reason::Simulation {
+        protocol: "keygen",
+        err,
+    })?;
+    let core_shares = collect_outputs(core_shares.into_vec(), |party, err| Reason::Keygen {
+        party,
+        err,
+    })?;
+
+    let aux_infos = simulate_aux_info_gen::<L, _>(rng, n)?;
+
+    core_shares
+        .into_iter()
+        .zip(aux_infos)
+        .map(|(core, aux)| {
+            KeyShare::from_parts((core, aux))
+                .map_err(|err| Reason::InvalidKeyShare(err.into_error()).into())
+        })
+        .collect()
+}
+
+/// Simulates aux info generation
+///
+/// Outputs aux info for each of `n` parties. Takes a while as it generates Paillier primes for
+/// every party.
+pub fn simulate_aux_info_gen<L, R>(
+    rng: &mut R,
+    n: u16,
+) -> Result<Vec<crate::key_share::AuxInfo<L>>, SimulationError>
+where
+    L: SecurityLevel,
+    R: RngCore + CryptoRng,
+{
+    let eid = random_eid(rng);
+    let eid = ExecutionId::new(&eid);
+    let party_rngs = fork_rngs(rng, n)?;
+    let aux_infos = round_based::sim::run_with_setup(party_rngs, |i, party, mut rng| async move {
+        let primes = PregeneratedPrimes::<L>::generate(&mut rng);
+        crate::aux_info_gen(eid, i, n, primes)
+            .start(&mut rng, party)
+            .await
+    })
+    .map_err(|err| Reason::Simulation {
+        protocol: "aux info generation",
+        err,
+    })?;
+    collect_outputs(aux_infos.into_vec(), |party, err| Reason::AuxGen {
+        party,
+        err,
+    })
+}
+
+/// Simulates signing
+///
+/// Every key share in `shares` takes part in signing, so there must be exactly as many shares as
+/// [`min_signers`](crate::IncompleteKeyShare::min_signers). Outputs a signature which is verified to be
+/// valid.
+pub fn simulate_signing<E, L, R>(
+    rng: &mut R,
+    shares: &[KeyShare<E, L>],
+    data_to_sign: DataToSign<E>,
+) -> Result<Signature<E>, SimulationError>
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+    L: SecurityLevel,
+    R: RngCore + CryptoRng,
+{
+    let first_share = shares.first().ok_or(Reason::NoSigners)?;
+    let n = u16::try_from(shares.len()).map_err(|_| Reason::NoSigners)?;
+    let parties_indexes_at_keygen = shares.iter().map(|s| s.core.i).collect::<Vec<_>>();
+    let parties_indexes_at_keygen = &parties_indexes_at_keygen;
+
+    let eid = random_eid(rng);
+    let eid = ExecutionId::new(&eid);
+    let party_rngs = fork_rngs(rng, n)?;
+    let signatures = round_based::sim::run_with_setup(
+        party_rngs.into_iter().zip(shares),
+        |i, party, (mut rng, share)| async move {
+            crate::signing(eid, i, parties_indexes_at_keygen, share)
+                .sign(&mut rng, party, data_to_sign)
+                .await
+        },
+    )
+    .map_err(|err| Reason::Simulation {
+        protocol: "signing",
+        err,
+    })?;
+    let signatures = collect_outputs(signatures.into_vec(), |party, err| Reason::Signing {
+        party,
+        err,
+    })?;
+
+    let signature = signatures[0];
+    if signatures.iter().any(|s| *s != signature) {
+        return Err(Reason::OutputsMismatch.into());
+    }
+    signature
+        .verify(&first_share.core.shared_public_key, &data_to_sign)
+        .map_err(|_| Reason::InvalidSignature)?;
+    Ok(signature)
+}
+
+fn random_eid(rng: &mut impl RngCore) -> [u8; 32] {
+    let mut eid = [0u8; 32];
+    rng.fill_bytes(&mut eid);
+    eid
+}
+
+fn fork_rngs(rng: &mut impl RngCore, n: u16) -> Result<Vec<StdRng>, SimulationError> {
+    (0..n)
+        .map(|_| StdRng::from_rng(&mut *rng).map_err(|_| Reason::ForkRng.into()))
+        .collect()
+}
+
+/// Unwraps output of each party, returns error of the first party that failed
+fn collect_outputs<T, E>(
+    outputs: Vec<Result<T, E>>,
+    err: impl Fn(u16, E) -> Reason,
+) -> Result<Vec<T>, SimulationError> {
+    (0u16..)
+        .zip(outputs)
+        .map(|(party, output)| output.map_err(|e| err(party, e).into()))
+        .collect()
+}
+
+/// Error indicating that simulation failed
+#[derive(Debug, Error)]
+#[error("simulation failed")]
+pub struct SimulationError(#[source] Reason);
+
+impl SimulationError {
+    /// Returns index of the party whose protocol execution failed, if simulation failed due to it
+    pub fn failed_party(&self) -> Option<u16> {
+        match &self.0 {
+            Reason::Keygen { party, .. }
+            | Reason::AuxGen { party, .. }
+            | Reason::Signing { party, .. } => Some(*party),
+            _ => None,
+        }
+    }
+
+    /// Returns keygen error, if simulation failed due to it
+    pub fn keygen_error(&self) -> Option<&KeygenError> {
+        match &self.0 {
+            Reason::Keygen { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns aux info generation error, if simulation failed due to it
+    pub fn aux_gen_error(&self) -> Option<&KeyRefreshError> {
+        match &self.0 {
+            Reason::AuxGen { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns signing error, if simulation failed due to it
+    pub fn signing_error(&self) -> Option<&SigningError> {
+        match &self.0 {
+            Reason::Signing { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("{protocol} simulation failed")]
+    Simulation {
+        protocol: &'static str,
+        #[source]
+        err: round_based::sim::SimError,
+    },
+    #[error("keygen failed at party {party}")]
+    Keygen {
+        party: u16,
+        #[source]
+        err: KeygenError,
+    },
+    #[error("aux info generation failed at party {party}")]
+    AuxGen {
+        party: u16,
+        #[source]
+        err: KeyRefreshError,
+    },
+    #[error("signing failed at party {party}")]
+    Signing {
+        party: u16,
+        #[source]
+        err: SigningError,
+    },
+    #[error("resulting key share is invalid")]
+    InvalidKeyShare(#[source] InvalidKeyShare),
+    #[error("list of signers is empty or too large")]
+    NoSigners,
+    #[error("parties output different signatures")]
+    OutputsMismatch,
+    #[error("resulting signature is invalid")]
+    InvalidSignature,
+    #[error("couldn't fork rng")]
+    ForkRng,
+}
+
+impl From<Reason> for SimulationError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
@@ -0,0 +1,260 @@
+//! Malicious parties for testing blame handling
+//!
+//! Wrappers take a party of [keygen](crate::keygen()) represented as a [state machine](StateMachine)
+//! and deterministically corrupt one of its messages, so that tests can make sure honest parties
+//! abort and blame the right party. Wrapped parties output `Option<_>`: malicious party outputs
+//! `None` as it halts right after sending corrupted messages. Honest parties need to be wrapped
+//! via [`honest`] to run within the same simulation.
+//!
+//! Keygen can be turned into a state machine either via `into_state_machine` (requires
+//! `state-machine` feature), or by wrapping the keygen future with
+//! [`round_based::state_machine::wrap_protocol`].
+//!
+//! ## Example
+//! ```rust,no_run
+//! use cggmp21::supported_curves::Secp256k1;
+//! use cggmp21::testing::adversary;
+//! # let mut rng = rand::rngs::OsRng;
+//! # let eid = cggmp21::ExecutionId::new(b"execution id, unique per protocol execution");
+//!
+//! let n = 3;
+//! let mut party_rngs = (0..n).map(|_| rng.clone()).collect::<Vec<_>>();
+//! let mut simulation = round_based::sim::Simulation::with_capacity(n);
+//! for (i, party_rng) in (0..).zip(&mut party_rngs) {
+//!     let party = round_based::state_machine::wrap_protocol(|party| {
+//!         cggmp21::keygen::<Secp256k1>(eid, i, n).start(party_rng, party)
+//!     });
+//!     if i == 0 {
+//!         simulation.add_party(adversary::corrupt_round3_proof(party));
+//!     } else {
+//!         simulation.add_party(adversary::honest(party));
+//!     }
+//! }
+//! let outputs = simulation.run().unwrap().into_vec();
+//! for output in &outputs[1..] {
+//!     let err = output.as_ref().unwrap().as_ref().err().unwrap();
+//!     assert_eq!(err.invalid_schnorr_proof(), Some(vec![0]));
+//! }
+//! ```
+
+use generic_ec::Curve;
+use round_based::state_machine::{ProceedResult, StateMachine};
+use round_based::{Incoming, Outgoing, PartyIndex};
+
+use crate::keygen::msg::{non_threshold, threshold};
+use crate::security_level::SecurityLevel;
+
+/// Keygen message that can be corrupted
+///
+/// Implemented for both [threshold](crate::keygen::ThresholdMsg) and
+/// [non-threshold](crate::keygen::NonThresholdMsg) keygen messages.
+pub trait KeygenMsg {
+    /// Modifies commitment if it's round 1 message
+    ///
+    /// Returns `true` if the message was modified
+    fn corrupt_round1_commitment(&mut self) -> bool;
+    /// Modifies decommitment if it's round 2 (broadcast) message, so it doesn't match the
+    /// commitment anymore
+    ///
+    /// Returns `true` if the message was modified
+    fn corrupt_round2_decommitment(&mut self) -> bool;
+    /// Modifies schnorr proof if it's round 3 message
+    ///
+    /// Returns `true` if the message was modified
+    fn corrupt_round3_proof(&mut self) -> bool;
+}
+
+impl<E: Curve, L: SecurityLevel, D: digest::Digest> KeygenMsg for non_threshold::Msg<E, L, D> {
+    fn corrupt_round1_commitment(&mut self) -> bool {
+        match self {
+            Self::Round1(msg) => flip_first_bit(&mut msg.commitment),
+            _ => false,
+        }
+    }
+    fn corrupt_round2_decommitment(&mut self) -> bool {
+        match self {
+            Self::Round2(msg) => flip_first_bit(msg.decommit.as_mut()),
+            _ => false,
+        }
+    }
+    fn corrupt_round3_proof(&mut self) -> bool {
+        match self {
+            Self::Round3(msg) => {
+                msg.sch_proof.0 += generic_ec::Scalar::one();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<E: Curve, L: SecurityLevel, D: digest::Digest> KeygenMsg for threshold::Msg<E, L, D> {
+    fn corrupt_round1_commitment(&mut self) -> bool {
+        match self {
+            Self::Round1(msg) => flip_first_bit(&mut msg.commitment),
+            _ => false,
+        }
+    }
+    fn corrupt_round2_decommitment(&mut self) -> bool {
+        match self {
+            Self::Round2Broad(msg) => flip_first_bit(msg.decommit.as_mut()),
+            _ => false,
+        }
+    }
+    fn corrupt_round3_proof(&mut self) -> bool {
+        match self {
+            Self::Round3(msg) => {
+                msg.sch_proof.0 += generic_ec::Scalar::one();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Wraps honest party
+///
+/// Output of the party is wrapped into `Some(_)`, so it can be run within the same simulation
+/// as malicious parties.
+pub fn honest<SM: StateMachine>(
+    party: SM,
+) -> impl StateMachine<Output = Option<SM::Output>, Msg = SM::Msg> {
+    Adversary {
+        party,
+        tamper_sent: |_: &mut Outgoing<SM::Msg>| false,
+        tamper_received: |_: &mut Incoming<SM::Msg>| {},
+        corrupted: false,
+    }
+}
+
+/// Party sends invalid schnorr proof in round 3
+///
+/// Honest parties are expected to abort, [blaming](crate::KeygenError::invalid_schnorr_proof)
+/// the party. Malicious party halts after the corrupted message is sent and outputs `None`.
+pub fn corrupt_round3_proof<SM>(
+    party: SM,
+) -> impl StateMachine<Output = Option<SM::Output>, Msg = SM::Msg>
+where
+    SM: StateMachine,
+    SM::Msg: KeygenMsg,
+{
+    tamper_sent(party, |msg| msg.msg.corrupt_round3_proof())
+}
+
+/// Party sends decommitment in round 2 that doesn't match its commitment from round 1
+///
+/// Honest parties are expected to abort, [blaming](crate::KeygenError::invalid_decommitment)
+/// the party. Malicious party halts after the corrupted message is sent and outputs `None`.
+pub fn corrupt_round2_decommitment<SM>(
+    party: SM,
+) -> impl StateMachine<Output = Option<SM::Output>, Msg = SM::Msg>
+where
+    SM: StateMachine,
+    SM::Msg: KeygenMsg,
+{
+    tamper_sent(party, |msg| msg.msg.corrupt_round2_decommitment())
+}
+
+/// Party `equivocator` sends to `victim` a different round 1 message than to everyone else
+///
+/// Broadcast messages are delivered to all parties identically by [`round_based::sim`], so
+/// equivocation is simulated on the receiving side: the wrapper is applied to the `victim` party
+/// and modifies round 1 commitment it receives from `equivocator`. Output of the victim is always
+/// `Some(_)`.
+///
+/// If [reliable broadcast](crate::keygen::GenericKeygenBuilder::enforce_reliable_broadcast) is
+/// enforced, all parties are expected to abort with a
+/// [reliability check failure](crate::KeygenError::reliability_check_failure). Otherwise, only the
+/// victim aborts, [blaming](crate::KeygenError::invalid_decommitment) the equivocator, and other
+/// parties never complete as they wait for messages from the victim.
+pub fn equivocate_round1<SM>(
+    victim: SM,
+    equivocator: PartyIndex,
+) -> impl StateMachine<Output = Option<SM::Output>, Msg = SM::Msg>
+where
+    SM: StateMachine,
+    SM::Msg: KeygenMsg,
+{
+    Adversary {
+        party: victim,
+        tamper_sent: |_: &mut Outgoing<SM::Msg>| false,
+        tamper_received: move |msg: &mut Incoming<SM::Msg>| {
+            if msg.sender == equivocator {
+                msg.msg.corrupt_round1_commitment();
+            }
+        },
+        corrupted: false,
+    }
+}
+
+/// Party modifies messages it sends
+///
+/// Building block for malicious parties not covered by the functions in this module. `tamper` is
+/// called on every message sent by the party, and returns `true` if it corrupted the message.
+/// Once a message was corrupted, the party halts as soon as it completes sending messages of the
+/// current round, and outputs `None`.
+pub fn tamper_sent<SM, F>(
+    party: SM,
+    tamper: F,
+) -> impl StateMachine<Output = Option<SM::Output>, Msg = SM::Msg>
+where
+    SM: StateMachine,
+    F: FnMut(&mut Outgoing<SM::Msg>) -> bool,
+{
+    Adversary {
+        party,
+        tamper_sent: tamper,
+        tamper_received: |_: &mut Incoming<SM::Msg>| {},
+        corrupted: false,
+    }
+}
+
+/// State machine wrapper that modifies sent and received messages
+struct Adversary<SM, S, R> {
+    party: SM,
+    tamper_sent: S,
+    tamper_received: R,
+    /// Indicates that the party sent a corrupted message
+    corrupted: bool,
+}
+
+impl<SM, S, R> StateMachine for Adversary<SM, S, R>
+where
+    SM: StateMachine,
+    S: FnMut(&mut Outgoing<SM::Msg>) -> bool,
+    R: FnMut(&mut Incoming<SM::Msg>),
+{
+    type Output = Option<SM::Output>;
+    type Msg = SM::Msg;
+
+    fn proceed(&mut self) -> ProceedResult<Self::Output, Self::Msg> {
+        match self.party.proceed() {
+            ProceedResult::SendMsg(mut msg) => {
+                self.corrupted |= (self.tamper_sent)(&mut msg);
+                ProceedResult::SendMsg(msg)
+            }
+            // Honest parties are expected to abort after receiving corrupted message, so we
+            // won't receive anything from them
+            ProceedResult::NeedsOneMoreMessage if self.corrupted => ProceedResult::Output(None),
+            ProceedResult::NeedsOneMoreMessage => ProceedResult::NeedsOneMoreMessage,
+            ProceedResult::Output(output) => ProceedResult::Output(Some(output)),
+            ProceedResult::Yielded => ProceedResult::Yielded,
+            ProceedResult::Error(err) => ProceedResult::Error(err),
+        }
+    }
+
+    fn received_msg(&mut self, mut msg: Incoming<Self::Msg>) -> Result<(), Incoming<Self::Msg>> {
+        (self.tamper_received)(&mut msg);
+        self.party.received_msg(msg)
+    }
+}
+
+fn flip_first_bit(bytes: &mut [u8]) -> bool {
+    match bytes.first_mut() {
+        Some(byte) => {
+            *byte ^= 1;
+            true
+        }
+        None => false,
+    }
+}
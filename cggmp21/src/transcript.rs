@@ -0,0 +1,157 @@
+//! Transcripts of broadcast messages
+//!
+//! [`Transcript`] is a log of broadcast messages that a party observed during protocol execution.
+//! When the protocol fails across several parties (e.g. the reliability check fails), transcripts
+//! can be collected from each party and compared via [`diff`] to find the round and the party at
+//! which they diverged: with a reliable broadcast channel, all parties must observe exactly the
+//! same broadcast messages.
+//!
+//! P2P messages are not recorded: they're addressed to a specific party, so they naturally differ
+//! between transcripts of different parties.
+//!
+//! Transcript is serializable, so it can be persisted and shipped elsewhere for analysis.
+//!
+//! Requires `codec` feature.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # fn main() -> Result<(), cggmp21::codec::CodecError> {
+//! # type Msg = cggmp21::keygen::ThresholdMsg<cggmp21::supported_curves::Secp256k1, cggmp21::security_level::SecurityLevel128, sha2::Sha256>;
+//! # let incomings: Vec<round_based::Incoming<Msg>> = vec![];
+//! # let transcript_of_another_party = cggmp21::transcript::Transcript::new();
+//! use cggmp21::transcript::{self, Transcript};
+//!
+//! let mut transcript = Transcript::new();
+//! for incoming in &incomings {
+//!     transcript.record_incoming(incoming)?;
+//! }
+//!
+//! for divergence in transcript::diff(&transcript, &transcript_of_another_party) {
+//!     println!(
+//!         "round {}: messages from party {} differ",
+//!         divergence.round, divergence.sender,
+//!     );
+//! }
+//! # Ok(()) }
+//! ```
+
+use std::collections::BTreeMap;
+
+use round_based::{Incoming, MessageType, PartyIndex, ProtocolMessage};
+use serde::{Deserialize, Serialize};
+
+use crate::codec::CodecError;
+
+/// Log of broadcast messages observed by the party
+///
+/// See [module level documentation](self) for more details.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript {
+    entries: Vec<Entry>,
+}
+
+/// Message recorded in the transcript
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Round the message belongs to
+    pub round: u16,
+    /// Index of party who sent the message
+    pub sender: PartyIndex,
+    /// Encoded message
+    #[serde(with = "hex::serde")]
+    pub msg: Vec<u8>,
+}
+
+impl Transcript {
+    /// Constructs an empty transcript
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a broadcast message encoded as bytes
+    pub fn record(&mut self, round: u16, sender: PartyIndex, msg: Vec<u8>) {
+        self.entries.push(Entry { round, sender, msg })
+    }
+
+    /// Records a received message
+    ///
+    /// Message is encoded using [codec](crate::codec). P2P messages are ignored.
+    pub fn record_incoming<M>(&mut self, incoming: &Incoming<M>) -> Result<(), CodecError>
+    where
+        M: ProtocolMessage + Serialize,
+    {
+        if incoming.msg_type != MessageType::Broadcast {
+            return Ok(());
+        }
+        let msg = crate::codec::encode_message(&incoming.msg, usize::MAX)?;
+        self.record(incoming.msg.round(), incoming.sender, msg);
+        Ok(())
+    }
+
+    /// Returns recorded messages in order they were recorded
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Indexes messages by round and sender
+    ///
+    /// If the same sender has several messages in the same round, only the first one is kept:
+    /// protocols ignore duplicated messages.
+    fn indexed(&self) -> BTreeMap<(u16, PartyIndex), &[u8]> {
+        let mut index = BTreeMap::new();
+        for entry in &self.entries {
+            index
+                .entry((entry.round, entry.sender))
+                .or_insert(entry.msg.as_slice());
+        }
+        index
+    }
+}
+
+/// Place where two transcripts diverge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Round at which transcripts diverge
+    pub round: u16,
+    /// Index of party whose messages differ
+    pub sender: PartyIndex,
+    /// Message recorded in the first transcript, or `None` if it wasn't recorded
+    pub a: Option<Vec<u8>>,
+    /// Message recorded in the second transcript, or `None` if it wasn't recorded
+    pub b: Option<Vec<u8>>,
+}
+
+/// Compares two transcripts
+///
+/// Returns all the places where transcripts diverge, i.e. where the same party sent different
+/// messages in the same round, or where a message is present in one transcript but not in
+/// another. Divergences are ordered by round and then by sender, so the first one is where
+/// transcripts diverged first. Empty list means that transcripts match.
+///
+/// Note that the local party doesn't receive its own broadcast messages, so it's expected that
+/// transcript of party `i` is missing messages sent by `i`, unless they were recorded explicitly.
+pub fn diff(a: &Transcript, b: &Transcript) -> Vec<Divergence> {
+    let a = a.indexed();
+    let mut b = b.indexed();
+
+    let mut divergences = vec![];
+    for ((round, sender), msg_a) in a {
+        let msg_b = b.remove(&(round, sender));
+        if msg_b != Some(msg_a) {
+            divergences.push(Divergence {
+                round,
+                sender,
+                a: Some(msg_a.to_vec()),
+                b: msg_b.map(|msg| msg.to_vec()),
+            })
+        }
+    }
+    divergences.extend(b.into_iter().map(|((round, sender), msg_b)| Divergence {
+        round,
+        sender,
+        a: None,
+        b: Some(msg_b.to_vec()),
+    }));
+    divergences.sort_by_key(|d| (d.round, d.sender));
+    divergences
+}
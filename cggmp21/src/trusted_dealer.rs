@@ -182,9 +182,11 @@ impl<E: Curve, L: SecurityLevel> TrustedDealerBuilder<E, L> {
         let key_shares = core_key_shares
             .into_iter()
             .zip(aux_data)
-            .map(|(core, aux)| KeyShare::from_parts((core, aux)))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|err| Reason::InvalidKeyShare(err.into_error()))?;
+            .map(|(core, aux)| {
+                KeyShare::from_parts((core, aux))
+                    .map_err(|err| Reason::InvalidKeyShare(err.into_error()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(key_shares)
     }
@@ -265,6 +267,7 @@ pub fn generate_aux_data_with_primes<L: SecurityLevel, R: RngCore + CryptoRng>(
                 p,
                 q,
                 parties: public_aux_data,
+                party_set_commitment: None,
                 security_level: PhantomData,
             }
             .validate()
@@ -1,5 +1,6 @@
 use generic_ec::{Curve, Scalar};
 use paillier_zk::rug::{self, Integer};
+use paillier_zk::IntegerExt;
 use paillier_zk::{
     group_element_vs_paillier_encryption_in_range as pi_log,
     paillier_affine_operation_in_range as pi_aff, paillier_encryption_in_range as pi_enc,
@@ -11,11 +12,54 @@ use crate::security_level::SecurityLevel;
 
 pub use paillier_zk::fast_paillier::utils::external_rand;
 
-/// Converts `&Scalar<E>` into Integer
+/// Converts a scalar into an integer
+///
+/// Scalar is interpreted as its canonical representative in range $[0, q)$, where $q$ is the
+/// curve order: its big-endian bytes are read as unsigned integer. That's how scalars are
+/// embedded into Paillier plaintexts by the protocols.
+///
+/// Conversion is not constant-time, use [`scalar_to_bignumber_ct`] for secret scalars.
 pub fn scalar_to_bignumber<E: Curve>(scalar: impl AsRef<Scalar<E>>) -> Integer {
     Integer::from_digits(&scalar.as_ref().to_be_bytes(), rug::integer::Order::Msf)
 }
 
+/// Converts a secret scalar into an integer
+///
+/// Outputs the same integer as [`scalar_to_bignumber`], but the integer is built from a fixed
+/// amount of 64-bit limbs which are assembled from scalar bytes without branching on their values.
+/// Protocols use it to convert secret scalars (secret shares, nonces, etc.).
+///
+/// ## Which conversions are constant-time
+/// * Scalar serialization into bytes is constant-time for all supported curves
+/// * Assembling limbs in this function is constant-time
+/// * GMP strips leading zero limbs when it builds the integer, so the size of the integer reveals
+///   whether the most significant limbs of the scalar are zero. For a uniformly random secret,
+///   it happens with negligible probability.
+/// * [`scalar_to_bignumber`] and [`bignumber_to_scalar`] are not constant-time. Arithmetic on
+///   the resulting integer is generally not constant-time either, as it's done by GMP.
+pub fn scalar_to_bignumber_ct<E: Curve>(scalar: impl AsRef<Scalar<E>>) -> Integer {
+    let bytes = scalar.as_ref().to_be_bytes();
+    let bytes = bytes.as_bytes();
+    let mut limbs = vec![0u64; bytes.len().div_ceil(8)];
+    for (k, byte) in bytes.iter().rev().enumerate() {
+        limbs[k / 8] |= u64::from(*byte) << (8 * (k % 8));
+    }
+    Integer::from_digits(&limbs, rug::integer::Order::Lsf)
+}
+
+/// Converts an integer into a scalar
+///
+/// Absolute value of the integer is reduced modulo the curve order $q$; if the integer is negative,
+/// the result is negated, i.e. it computes $x \bmod q$ for any integer $x$. That's how decrypted
+/// Paillier plaintexts are mapped back to scalars by the protocols.
+///
+/// Inverse of [`scalar_to_bignumber`]: `bignumber_to_scalar(&scalar_to_bignumber(s)) == s`.
+///
+/// Conversion is not constant-time.
+pub fn bignumber_to_scalar<E: Curve>(x: &Integer) -> Scalar<E> {
+    x.to_scalar()
+}
+
 pub struct SecurityParams {
     pub pi_aff: pi_aff::SecurityParams,
     pub pi_log: pi_log::SecurityParams,
@@ -45,6 +89,11 @@ impl SecurityParams {
     }
 }
 
+/// XORs `b` into `a`, returns `a`
+///
+/// Byte `a[k]` is XORed with `b[k]`. If `b` is shorter than `a`, the trailing bytes of `a` are kept
+/// unchanged; if `b` is longer, its trailing bytes are ignored. Protocols use it to combine
+/// contributions of the parties, e.g. `rid` and chain code.
 pub fn xor_array<A, B>(mut a: A, b: B) -> A
 where
     A: AsMut<[u8]>,
@@ -230,6 +279,91 @@ pub mod encoding {
     }
 }
 
+/// Minimal subset of ASN.1 DER needed to encode ECDSA signatures
+pub mod der {
+    pub const INTEGER: u8 = 0x02;
+    pub const SEQUENCE: u8 = 0x30;
+
+    /// Appends encoded length to `out`
+    pub fn write_length(out: &mut Vec<u8>, len: usize) {
+        if len < 0x80 {
+            out.push(len as u8);
+        } else {
+            let bytes = len.to_be_bytes();
+            let skip = bytes.iter().take_while(|b| **b == 0).count();
+            out.push(0x80 | (bytes.len() - skip) as u8);
+            out.extend_from_slice(&bytes[skip..]);
+        }
+    }
+
+    /// Appends non-negative integer given in big-endian to `out`
+    ///
+    /// Integer is encoded in minimal amount of bytes: leading zeroes are stripped, and a
+    /// single zero byte is prepended if highest bit is set, so the integer is not interpreted
+    /// as negative.
+    pub fn write_unsigned_integer(out: &mut Vec<u8>, be_bytes: &[u8]) {
+        let skip = be_bytes.iter().take_while(|b| **b == 0).count();
+        let bytes = &be_bytes[skip..];
+        let pad = bytes.first().map(|b| b & 0x80 != 0).unwrap_or(true);
+
+        out.push(INTEGER);
+        write_length(out, bytes.len() + usize::from(pad));
+        if pad {
+            out.push(0);
+        }
+        out.extend_from_slice(bytes);
+    }
+
+    /// Reads a value with expected `tag`
+    ///
+    /// Returns the value and remaining bytes. Returns `None` if tag mismatched or
+    /// length is not encoded in minimal form.
+    pub fn read_tlv(tag: u8, inp: &[u8]) -> Option<(&[u8], &[u8])> {
+        let (&actual_tag, inp) = inp.split_first()?;
+        if actual_tag != tag {
+            return None;
+        }
+        let (&len_byte, inp) = inp.split_first()?;
+        let (len, inp) = if len_byte < 0x80 {
+            (usize::from(len_byte), inp)
+        } else {
+            let len_size = usize::from(len_byte & 0x7f);
+            if len_size == 0 || len_size > core::mem::size_of::<usize>() || inp.len() < len_size {
+                return None;
+            }
+            let (len_bytes, inp) = inp.split_at(len_size);
+            if len_bytes[0] == 0 {
+                return None;
+            }
+            let len = len_bytes
+                .iter()
+                .fold(0usize, |acc, b| (acc << 8) | usize::from(*b));
+            if len < 0x80 {
+                return None;
+            }
+            (len, inp)
+        };
+        if inp.len() < len {
+            return None;
+        }
+        Some(inp.split_at(len))
+    }
+
+    /// Reads non-negative integer, returns its big-endian bytes without leading zeroes
+    ///
+    /// Returns `None` if the integer is negative or not encoded in minimal form.
+    pub fn read_unsigned_integer(inp: &[u8]) -> Option<(&[u8], &[u8])> {
+        let (bytes, rest) = read_tlv(INTEGER, inp)?;
+        match bytes {
+            [] => None,
+            [b, ..] if b & 0x80 != 0 => None,
+            [0, b, ..] if b & 0x80 == 0 => None,
+            [0, bytes @ ..] => Some((bytes, rest)),
+            bytes => Some((bytes, rest)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use paillier_zk::rug::Complete;
@@ -261,4 +395,95 @@ mod test {
             assert!(root.square_ref().complete() > x);
         }
     }
+
+    #[test]
+    fn bignumber_conversions() {
+        use super::{
+            bignumber_to_scalar, scalar_to_bignumber, scalar_to_bignumber_ct, xor_array, Integer,
+        };
+        type E = generic_ec::curves::Secp256k1;
+        type Scalar = generic_ec::Scalar<E>;
+
+        let mut rng = rand_dev::DevRng::new();
+        for _ in 0..10 {
+            let s = Scalar::random(&mut rng);
+            let x = scalar_to_bignumber(s);
+            assert!(x >= 0);
+            assert_eq!(x.to_digits::<u8>(paillier_zk::rug::integer::Order::Msf), {
+                let bytes = s.to_be_bytes();
+                let skip = bytes.iter().take_while(|b| **b == 0).count();
+                bytes[skip..].to_vec()
+            });
+            assert_eq!(scalar_to_bignumber_ct(s), x);
+            assert_eq!(bignumber_to_scalar::<E>(&x), s);
+            assert_eq!(bignumber_to_scalar::<E>(&-x), -s);
+        }
+
+        for s in [Scalar::zero(), Scalar::one(), -Scalar::one()] {
+            assert_eq!(scalar_to_bignumber_ct(s), scalar_to_bignumber(s));
+        }
+
+        let q = scalar_to_bignumber(-Scalar::one()) + 1u8;
+        assert_eq!(bignumber_to_scalar::<E>(&q), Scalar::zero());
+        assert_eq!(
+            bignumber_to_scalar::<E>(&(q.clone() * 3u8 + 5u8)),
+            Scalar::from(5)
+        );
+        assert_eq!(bignumber_to_scalar::<E>(&Integer::from(-1)), -Scalar::one());
+
+        assert_eq!(
+            xor_array([0b1100u8, 0xff, 1], [0b1010u8, 0x0f]),
+            [0b0110, 0xf0, 1]
+        );
+    }
+
+    #[test]
+    fn der_integer_encoding() {
+        use super::der;
+
+        let cases: &[(&[u8], &[u8])] = &[
+            (&[0x00, 0x00, 0x01], &[0x02, 0x01, 0x01]),
+            (&[0x7f], &[0x02, 0x01, 0x7f]),
+            // high bit set, needs zero padding
+            (&[0x80], &[0x02, 0x02, 0x00, 0x80]),
+            (&[0x00, 0xff, 0x01], &[0x02, 0x03, 0x00, 0xff, 0x01]),
+            (&[0x00, 0x00], &[0x02, 0x01, 0x00]),
+        ];
+        for (int, expected) in cases {
+            let mut encoded = vec![];
+            der::write_unsigned_integer(&mut encoded, int);
+            assert_eq!(&encoded, expected);
+
+            let (decoded, rest) = der::read_unsigned_integer(&encoded).unwrap();
+            assert!(rest.is_empty());
+            let skip = int.iter().take_while(|b| **b == 0).count();
+            assert_eq!(decoded, &int[skip..]);
+        }
+
+        // negative integer
+        assert!(der::read_unsigned_integer(&[0x02, 0x01, 0x80]).is_none());
+        // non-minimal encoding
+        assert!(der::read_unsigned_integer(&[0x02, 0x02, 0x00, 0x01]).is_none());
+        // non-minimal length
+        assert!(der::read_unsigned_integer(&[0x02, 0x81, 0x01, 0x01]).is_none());
+        // truncated
+        assert!(der::read_unsigned_integer(&[0x02, 0x02, 0x01]).is_none());
+    }
+
+    #[test]
+    fn der_long_length() {
+        use super::der;
+
+        let mut out = vec![];
+        der::write_length(&mut out, 0x7f);
+        der::write_length(&mut out, 0x80);
+        der::write_length(&mut out, 0x1234);
+        assert_eq!(out, [0x7f, 0x81, 0x80, 0x82, 0x12, 0x34]);
+
+        let mut encoded = vec![der::SEQUENCE, 0x81, 0x80];
+        encoded.extend_from_slice(&[0xaa; 0x80]);
+        let (value, rest) = der::read_tlv(der::SEQUENCE, &encoded).unwrap();
+        assert_eq!(value, [0xaa; 0x80]);
+        assert!(rest.is_empty());
+    }
 }
@@ -10,6 +10,8 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use thiserror::Error;
 
+use crate::modexp::ModExp;
+
 struct Challenge<const M: usize> {
     es: [bool; M],
 }
@@ -87,12 +89,45 @@ pub fn prove<const M: usize, D: Digest>(
     data: Data,
     phi: &Integer,
     lambda: &Integer,
+    modexp: ModExp,
 ) -> Result<Proof<M>, ZkError> {
+    let commitment = commit::<M>(rng, data, phi, modexp)?;
+    Ok(prove_with_commitment::<M, D>(
+        shared_state,
+        data,
+        phi,
+        lambda,
+        commitment,
+    ))
+}
+
+/// Commitment of the prover, can be computed ahead of time with [`commit`]
+///
+/// Contains secret data. Must not be used for more than one proof.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct Commitment<const M: usize> {
+    #[serde_as(as = "[_; M]")]
+    private: [Integer; M],
+    #[serde_as(as = "[_; M]")]
+    public: [Integer; M],
+}
+
+/// Samples random commitment. It's the most expensive part of proving, which doesn't
+/// depend on `shared_state` and `lambda`.
+///
+/// - `phi` - $φ(N) = (p-1)(q-1)$
+pub fn commit<const M: usize>(
+    rng: &mut impl rand_core::RngCore,
+    data: Data,
+    phi: &Integer,
+    modexp: ModExp,
+) -> Result<Commitment<M>, ZkError> {
     let private_commitment =
         [(); M].map(|()| phi.random_below_ref(&mut utils::external_rand(rng)).into());
     let commitment = private_commitment
         .clone()
-        .map(|a| data.t.pow_mod_ref(&a, data.N).map(|r| r.into()));
+        .map(|a| modexp.pow_mod(data.t, &a, data.N));
     // TODO: since array::try_map is not stable yet, we have to be hacky here
     let commitment = if commitment.iter().any(Option::is_none) {
         return Err(Reason::PowMod.into());
@@ -101,7 +136,26 @@ pub fn prove<const M: usize, D: Digest>(
         #[allow(clippy::unwrap_used)]
         commitment.map(Option::unwrap)
     };
+    Ok(Commitment {
+        private: private_commitment,
+        public: commitment,
+    })
+}
 
+/// Computes the proof using commitment obtained from [`commit`]
+///
+/// `data` and `phi` must be the same as were used in [`commit`]
+pub fn prove_with_commitment<const M: usize, D: Digest>(
+    shared_state: &impl udigest::Digestable,
+    data: Data,
+    phi: &Integer,
+    lambda: &Integer,
+    commitment: Commitment<M>,
+) -> Proof<M> {
+    let Commitment {
+        private: private_commitment,
+        public: commitment,
+    } = commitment;
     let challenge: Challenge<M> = derive_challenge::<M, D>(shared_state, data, &commitment);
 
     let mut zs = private_commitment;
@@ -111,7 +165,7 @@ pub fn prove<const M: usize, D: Digest>(
             z_ref.modulo_mut(phi);
         }
     }
-    Ok(Proof { commitment, zs })
+    Proof { commitment, zs }
 }
 
 /// Verify the proof. Derives determenistic challenge based on `shared_state`
@@ -120,10 +174,11 @@ pub fn verify<const M: usize, D: Digest>(
     shared_state: &impl udigest::Digestable,
     data: Data,
     proof: &Proof<M>,
+    modexp: ModExp,
 ) -> Result<(), InvalidProof> {
     let challenge: Challenge<M> = derive_challenge::<M, D>(shared_state, data, &proof.commitment);
     for ((z, a), e) in proof.zs.iter().zip(&proof.commitment).zip(&challenge.es) {
-        let lhs: Integer = data.t.pow_mod_ref(z, data.N).ok_or(InvalidProof)?.into();
+        let lhs = modexp.pow_mod(data.t, z, data.N).ok_or(InvalidProof)?;
         if *e {
             let rhs = (data.s * a).complete().modulo(data.N);
             if lhs != rhs {
@@ -185,9 +240,17 @@ mod test {
             t: &t,
         };
 
-        let proof: super::Proof<16> =
-            super::prove::<16, D>(&shared_state, &mut rng, data, &phi, &lambda).unwrap();
-        super::verify::<16, D>(&shared_state, data, &proof).expect("proof should pass");
+        let proof: super::Proof<16> = super::prove::<16, D>(
+            &shared_state,
+            &mut rng,
+            data,
+            &phi,
+            &lambda,
+            Default::default(),
+        )
+        .unwrap();
+        super::verify::<16, D>(&shared_state, data, &proof, Default::default())
+            .expect("proof should pass");
     }
 
     #[test]
@@ -214,9 +277,16 @@ mod test {
             t: &t,
         };
 
-        let proof: super::Proof<16> =
-            super::prove::<16, D>(&shared_state, &mut rng, data, &phi, &lambda).unwrap();
-        if super::verify::<16, D>(&shared_state, data, &proof).is_ok() {
+        let proof: super::Proof<16> = super::prove::<16, D>(
+            &shared_state,
+            &mut rng,
+            data,
+            &phi,
+            &lambda,
+            Default::default(),
+        )
+        .unwrap();
+        if super::verify::<16, D>(&shared_state, data, &proof, Default::default()).is_ok() {
             panic!("proof should fail");
         }
     }
@@ -0,0 +1,25 @@
+//! Checks that decoding protocol messages received from the network never panics
+
+#![no_main]
+
+use cggmp21::{
+    codec::FramedMessage, security_level::SecurityLevel128, supported_curves::Secp256k1,
+};
+use libfuzzer_sys::fuzz_target;
+
+type E = Secp256k1;
+type D = sha2::Sha256;
+type L = SecurityLevel128;
+
+const MAX_LEN: usize = 1 << 20;
+
+fuzz_target!(|bytes: &[u8]| {
+    let _ = cggmp21::keygen::NonThresholdMsg::<E, L, D>::try_decode(bytes, MAX_LEN);
+    let _ = cggmp21::keygen::ThresholdMsg::<E, L, D>::try_decode(bytes, MAX_LEN);
+    let _ = cggmp21::key_refresh::AuxOnlyMsg::<D, L>::try_decode(bytes, MAX_LEN);
+    let _ = cggmp21::key_refresh::NonThresholdMsg::<E, D, L>::try_decode(bytes, MAX_LEN);
+    let _ = cggmp21::signing::msg::Msg::<E, D>::try_decode(bytes, MAX_LEN);
+    let _ = cggmp21::enroll::Msg::<E, D>::try_decode(bytes, MAX_LEN);
+    let _ = cggmp21::reshare::Msg::<E, D>::try_decode(bytes, MAX_LEN);
+    let _ = cggmp21::deframe::<cggmp21::signing::msg::Msg<E, D>>(bytes, MAX_LEN);
+});
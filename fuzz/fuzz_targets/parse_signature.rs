@@ -0,0 +1,60 @@
+//! Checks that parsing signatures and constructing data to sign from untrusted bytes never panics,
+//! and that only canonical encodings are accepted
+
+#![no_main]
+
+use cggmp21::{
+    generic_ec::{
+        coords::{AlwaysHasAffineX, HasAffineXAndParity},
+        Curve, NonZero, Point, Scalar,
+    },
+    signing::{DataToSign, Signature},
+    supported_curves::{Secp256k1, Secp256r1, Stark},
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    parse::<Secp256k1>(bytes);
+    parse::<Secp256r1>(bytes);
+    parse::<Stark>(bytes);
+});
+
+fn parse<E: Curve>(bytes: &[u8])
+where
+    NonZero<Point<E>>: AlwaysHasAffineX<E>,
+    Point<E>: HasAffineXAndParity<E>,
+{
+    let messages = [
+        DataToSign::<E>::digest::<sha2::Sha256>(bytes),
+        DataToSign::<E>::digest_reducing::<sha2::Sha512>(bytes),
+        DataToSign::<E>::digest_truncating::<sha2::Sha256>(bytes),
+        DataToSign::<E>::digest_truncating::<sha2::Sha512>(bytes),
+        DataToSign::<E>::from_scalar(Scalar::from_be_bytes_mod_order(bytes)),
+    ];
+
+    let mut signatures = vec![];
+    if let Some(signature) = Signature::<E>::read_from_slice(bytes) {
+        // Only canonical encoding is accepted
+        let mut encoded = vec![0u8; Signature::<E>::serialized_len()];
+        signature.write_to_slice(&mut encoded);
+        assert_eq!(encoded, bytes);
+        signatures.push(signature);
+    }
+    if let Some(signature) = Signature::<E>::from_der(bytes) {
+        // Only canonical (strict DER) encoding is accepted
+        assert_eq!(signature.to_der(), bytes);
+        signatures.push(signature);
+    }
+
+    for signature in signatures {
+        let signature = signature.normalize_s();
+        for message in &messages {
+            for recovery_id in 0..=4 {
+                if let Ok(public_key) = signature.recover_public_key(recovery_id, message) {
+                    assert!(signature.verify(&public_key, message).is_ok());
+                }
+            }
+            let _ = signature.verify(&Point::generator().to_point(), message);
+        }
+    }
+}
@@ -9,6 +9,13 @@
 /// as list of integers. Hex encoding is preferred for such formats as it's more
 /// compact and readable.
 ///
+/// # Cross-format compatibility
+/// Deserialization accepts any of the encodings regardless of the format: hex string,
+/// raw bytes, or sequence of bytes. That way, byte arrays that were transcoded from binary
+/// format into human-readable one without knowing the schema (and therefore became a list
+/// of integers) can still be deserialized. Note that binary formats are not required to be
+/// self-describing, so for them we still hint the deserializer to expect bytes.
+///
 /// # Private API
 /// `HexOrBin` is shared between several crates in the project, however we do not
 /// publicly expose it. Although it works perfectly fine in our case, it may not
@@ -52,7 +59,7 @@ where
             out: T,
             _ph: core::marker::PhantomData<T>,
         }
-        impl<T> serde::de::Visitor<'_> for Visitor<T>
+        impl<'de, T> serde::de::Visitor<'de> for Visitor<T>
         where
             T: AsMut<[u8]>,
         {
@@ -70,12 +77,6 @@ where
             where
                 E: serde::de::Error,
             {
-                if self.expect_hex {
-                    return Err(E::invalid_value(
-                        serde::de::Unexpected::Bytes(v),
-                        &"expected hex-encoded bytes",
-                    ));
-                }
                 let out_len = self.out.as_mut().len();
                 if out_len != v.len() {
                     return Err(E::invalid_length(v.len(), &ExpectedLen(out_len)));
@@ -88,21 +89,34 @@ where
             where
                 E: serde::de::Error,
             {
-                if !self.expect_hex {
-                    return Err(E::invalid_value(
-                        serde::de::Unexpected::Str(v),
-                        &"expected raw bytes",
-                    ));
-                }
-
                 hex::decode_to_slice(v, self.out.as_mut()).map_err(E::custom)?;
+                Ok(self.out)
+            }
 
+            fn visit_seq<A>(mut self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let out = self.out.as_mut();
+                let out_len = out.len();
+                for (i, byte) in out.iter_mut().enumerate() {
+                    *byte = seq.next_element()?.ok_or_else(|| {
+                        <A::Error as serde::de::Error>::invalid_length(i, &ExpectedLen(out_len))
+                    })?;
+                }
+                if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+                    return Err(<A::Error as serde::de::Error>::invalid_length(
+                        out_len + 1,
+                        &ExpectedLen(out_len),
+                    ));
+                }
                 Ok(self.out)
             }
         }
 
         if deserializer.is_human_readable() {
-            deserializer.deserialize_str(Visitor {
+            // Human-readable formats are self-describing, so we can accept any encoding
+            deserializer.deserialize_any(Visitor {
                 expect_hex: true,
                 out: Default::default(),
                 _ph: Default::default(),
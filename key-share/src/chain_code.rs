@@ -0,0 +1,104 @@
+//! Chain code of HD-capable key
+
+use core::fmt;
+
+use generic_ec::{Curve, Point};
+use hd_wallet::{ChainCode, ExtendedPublicKey, HdWallet, NonHardenedIndex};
+
+/// Chain code of the key
+///
+/// Chain code is combined from contributions of all signers at keygen. Together with shared public
+/// key, it forms [extended public key](ExtendedPublicKey) of the wallet.
+///
+/// Wraps [`ChainCode`], serialized and hashed the same way as raw bytes. [`Display`](fmt::Display)
+/// and [`Debug`](fmt::Debug) print it in hex.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct CombinedChainCode(ChainCode);
+
+impl CombinedChainCode {
+    /// Wraps raw chain code
+    pub fn new(chain_code: ChainCode) -> Self {
+        Self(chain_code)
+    }
+
+    /// Combines contributions of the signers into chain code
+    ///
+    /// Resulting chain code is XOR of all contributions.
+    pub fn combine(contributions: impl IntoIterator<Item = ChainCode>) -> Self {
+        let mut chain_code = ChainCode::default();
+        for contribution in contributions {
+            chain_code
+                .iter_mut()
+                .zip(contribution)
+                .for_each(|(a_i, b_i)| *a_i ^= b_i);
+        }
+        Self(chain_code)
+    }
+
+    /// Returns raw bytes of the chain code
+    pub fn as_bytes(&self) -> &ChainCode {
+        &self.0
+    }
+
+    /// Returns raw chain code
+    pub fn into_inner(self) -> ChainCode {
+        self.0
+    }
+
+    /// Constructs extended public key from `public_key` and this chain code
+    pub fn extended_public_key<E: Curve>(&self, public_key: Point<E>) -> ExtendedPublicKey<E> {
+        ExtendedPublicKey {
+            public_key,
+            chain_code: self.0,
+        }
+    }
+
+    /// Derives chain code of the child key at non-hardened `child_index`
+    ///
+    /// `public_key` is the public key that this chain code is associated with. Derivation is done
+    /// using [`HdWallet`] algorithm `Hd`.
+    pub fn derive_child<E: Curve, Hd: HdWallet<E>>(
+        &self,
+        public_key: Point<E>,
+        child_index: NonHardenedIndex,
+    ) -> Self {
+        let child = Hd::derive_child_public_key(&self.extended_public_key(public_key), child_index);
+        Self(child.chain_code)
+    }
+}
+
+impl From<ChainCode> for CombinedChainCode {
+    fn from(chain_code: ChainCode) -> Self {
+        Self(chain_code)
+    }
+}
+
+impl From<CombinedChainCode> for ChainCode {
+    fn from(chain_code: CombinedChainCode) -> Self {
+        chain_code.0
+    }
+}
+
+impl AsRef<[u8]> for CombinedChainCode {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for CombinedChainCode {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl fmt::Display for CombinedChainCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl fmt::Debug for CombinedChainCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CombinedChainCode({self})")
+    }
+}
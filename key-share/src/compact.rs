@@ -0,0 +1,196 @@
+//! Compact representation of the key share
+//!
+//! Core key share stores public shares of all $n$ signers, which dominates its size for large $n$.
+//! For polynomial key shares, public shares are redundant: they all lie on the polynomial
+//! of degree $t-1$, so it's sufficient to store the [commitment](crate::KeyInfo::commitment_polynomial)
+//! to that polynomial ($t$ points) and evaluate it on demand.
+//!
+//! [`CompactCoreKeyShare`] can be obtained via [`CoreKeyShare::compact`], and converted back via
+//! [`CompactCoreKeyShare::expand`]. Additive (non-threshold) key shares don't have a commitment
+//! polynomial, so their compact form keeps all the public shares as is.
+
+use alloc::vec::Vec;
+
+use generic_ec::{serde::CurveName, Curve, NonZero, Point, SecretScalar};
+
+use crate::{
+    CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, InvalidCoreShare, InvalidShareReason, Validate,
+    VssSetup,
+};
+
+#[cfg(feature = "serde")]
+use serde_with::As;
+
+/// Compact core key share
+///
+/// Holds the same data as [`CoreKeyShare`], but public shares of polynomial key shares are
+/// replaced with the commitment polynomial. See [module-level](self) docs.
+///
+/// Compact key share is not validated. Use [`expand`](Self::expand) to obtain a [`CoreKeyShare`]
+/// that can be used in the protocols.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct CompactCoreKeyShare<E: Curve> {
+    curve: CurveName<E>,
+    i: u16,
+    #[cfg_attr(feature = "serde", serde(with = "As::<generic_ec::serde::Compact>"))]
+    shared_public_key: NonZero<Point<E>>,
+    public_shares: PublicShares<E>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    vss_setup: Option<VssSetup<E>>,
+    #[cfg(feature = "hd-wallet")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default),
+        serde(skip_serializing_if = "Option::is_none"),
+        serde(with = "As::<Option<crate::utils::HexOrBin>>")
+    )]
+    chain_code: Option<crate::CombinedChainCode>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "crate::utils::is_zero")
+    )]
+    epoch: u64,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none"),
+        serde(with = "As::<Option<serde_with::Bytes>>")
+    )]
+    party_set_commitment: Option<Vec<u8>>,
+    #[cfg_attr(feature = "serde", serde(with = "As::<generic_ec::serde::Compact>"))]
+    x: NonZero<SecretScalar<E>>,
+}
+
+/// Public shares in either full or compact form
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = "", rename_all = "snake_case"))]
+enum PublicShares<E: Curve> {
+    /// Public shares of all signers
+    Full(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "As::<Vec<generic_ec::serde::Compact>>")
+        )]
+        Vec<NonZero<Point<E>>>,
+    ),
+    /// Coefficients of the commitment polynomial
+    Commitment(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "As::<Vec<generic_ec::serde::Compact>>")
+        )]
+        Vec<Point<E>>,
+    ),
+}
+
+impl<E: Curve> CoreKeyShare<E> {
+    /// Converts key share into [compact form](CompactCoreKeyShare)
+    ///
+    /// For polynomial key shares, public shares are replaced with the commitment polynomial.
+    /// Additive key shares keep the public shares as is.
+    pub fn compact(&self) -> CompactCoreKeyShare<E> {
+        let public_shares = match self.commitment_polynomial() {
+            Some(commitment) => PublicShares::Commitment(commitment),
+            None => PublicShares::Full(self.public_shares.clone()),
+        };
+        CompactCoreKeyShare {
+            curve: self.curve,
+            i: self.i,
+            shared_public_key: self.shared_public_key,
+            public_shares,
+            vss_setup: self.vss_setup.clone(),
+            #[cfg(feature = "hd-wallet")]
+            chain_code: self.chain_code,
+            epoch: self.epoch,
+            party_set_commitment: self.party_set_commitment.clone(),
+            x: self.x.clone(),
+        }
+    }
+}
+
+impl<E: Curve> CompactCoreKeyShare<E> {
+    /// Index of local party
+    pub fn i(&self) -> u16 {
+        self.i
+    }
+
+    /// Returns amount of key co-holders
+    ///
+    /// Returns `None` if it overflows `u16`, which never happens for compact key share obtained
+    /// from a valid key share.
+    pub fn n(&self) -> Option<u16> {
+        let n = match (&self.public_shares, &self.vss_setup) {
+            (PublicShares::Full(public_shares), _) => public_shares.len(),
+            (PublicShares::Commitment(_), Some(vss_setup)) => vss_setup.I.len(),
+            (PublicShares::Commitment(_), None) => return None,
+        };
+        n.try_into().ok()
+    }
+
+    /// Returns public key shared by signers
+    pub fn shared_public_key(&self) -> NonZero<Point<E>> {
+        self.shared_public_key
+    }
+
+    /// Indicates whether public shares are replaced with commitment polynomial
+    pub fn is_compressed(&self) -> bool {
+        matches!(self.public_shares, PublicShares::Commitment(_))
+    }
+
+    /// Returns public share of $\jth$ signer
+    ///
+    /// If the key share is compressed, public share is recomputed from the commitment polynomial
+    /// on every call. Returns `None` if `j` is out of bounds, or if the public share is zero
+    /// (which is never the case for compact key share obtained from a valid key share).
+    pub fn public_share(&self, j: u16) -> Option<NonZero<Point<E>>> {
+        match (&self.public_shares, &self.vss_setup) {
+            (PublicShares::Full(public_shares), _) => public_shares.get(usize::from(j)).copied(),
+            (PublicShares::Commitment(commitment), Some(vss_setup)) => {
+                let I_j = vss_setup.I.get(usize::from(j))?;
+                let X_j = commitment
+                    .iter()
+                    .rev()
+                    .fold(Point::zero(), |acc, A_k| acc * I_j + A_k);
+                NonZero::from_point(X_j)
+            }
+            (PublicShares::Commitment(_), None) => None,
+        }
+    }
+
+    /// Converts compact key share back into [`CoreKeyShare`]
+    ///
+    /// Recomputes public shares of all signers and validates the resulting key share.
+    pub fn expand(&self) -> Result<CoreKeyShare<E>, InvalidCoreShare> {
+        let public_shares = match &self.public_shares {
+            PublicShares::Full(public_shares) => public_shares.clone(),
+            PublicShares::Commitment(_) => {
+                let n = self.n().ok_or(InvalidShareReason::NOverflowsU16)?;
+                (0..n)
+                    .map(|j| self.public_share(j))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(InvalidShareReason::ZeroPublicShare)?
+            }
+        };
+        DirtyCoreKeyShare {
+            i: self.i,
+            key_info: DirtyKeyInfo {
+                curve: self.curve,
+                shared_public_key: self.shared_public_key,
+                public_shares,
+                vss_setup: self.vss_setup.clone(),
+                #[cfg(feature = "hd-wallet")]
+                chain_code: self.chain_code,
+                epoch: self.epoch,
+                party_set_commitment: self.party_set_commitment.clone(),
+            },
+            x: self.x.clone(),
+        }
+        .validate()
+        .map_err(|err| err.into_error())
+    }
+}
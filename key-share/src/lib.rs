@@ -28,13 +28,22 @@ use core::ops;
 use generic_ec::{serde::CurveName, Curve, NonZero, Point, Scalar, SecretScalar};
 use generic_ec_zkp::polynomial::lagrange_coefficient;
 
+#[cfg(feature = "hd-wallet")]
+mod chain_code;
+pub mod compact;
 #[cfg(feature = "serde")]
 mod serde_fix;
 #[cfg(feature = "spof")]
 pub mod trusted_dealer;
+#[cfg(feature = "tweak")]
+pub mod tweak;
 mod utils;
 mod valid;
+pub mod weighted;
 
+#[cfg(feature = "hd-wallet")]
+pub use self::chain_code::CombinedChainCode;
+pub use self::compact::CompactCoreKeyShare;
 pub use self::valid::{Valid, Validate, ValidateError, ValidateFromParts};
 
 /// Core key share
@@ -67,6 +76,15 @@ pub type CoreKeyShare<E> = Valid<DirtyCoreKeyShare<E>>;
 /// you need to obtain dirty key info via [`Valid::into_inner`], modify the key info, and validate it
 /// again to obtain [`KeyInfo`].
 pub type KeyInfo<E> = Valid<DirtyKeyInfo<E>>;
+/// Public-only key share
+///
+/// Contains everything about the key that [`CoreKeyShare`] has except the secret share: shared public
+/// key, public shares of all signers, VSS setup and chain code. It's sufficient to derive child public
+/// keys and verify signatures, but cannot be used to sign. Unlike [`CoreKeyShare`], it is safe to be
+/// handed out to untrusted parties, e.g. to monitoring infrastructure.
+///
+/// Can be obtained via [`CoreKeyShare::to_public`].
+pub type PublicKeyShare<E> = KeyInfo<E>;
 
 #[cfg(feature = "serde")]
 use serde_with::As;
@@ -93,6 +111,7 @@ use serde_with::As;
 /// # HD wallets support
 /// If `hd-wallets` feature is enabled, key share provides basic support of deterministic key derivation:
 /// * [`chain_code`](DirtyKeyInfo::chain_code) field is added. If it's `Some(_)`, then the key is HD-capable.
+///   Chain code is represented by [`CombinedChainCode`] type.
 ///   `(shared_public_key, chain_code)` is extended public key of the wallet (can be retrieved via
 ///   [extended_public_key](DirtyCoreKeyShare::extended_public_key) method).
 ///   * Setting `chain_code` to `None` disables HD wallets support for the key
@@ -142,6 +161,8 @@ impl<E: Curve> serde::Serialize for DirtyCoreKeyShare<E> {
                     vss_setup,
                     #[cfg(feature = "hd-wallet")]
                     chain_code,
+                    epoch,
+                    party_set_commitment,
                 },
             x,
         } = &self;
@@ -154,6 +175,8 @@ impl<E: Curve> serde::Serialize for DirtyCoreKeyShare<E> {
             x,
             #[cfg(feature = "hd-wallet")]
             chain_code,
+            epoch,
+            party_set_commitment,
         }
         .serialize(serializer)
     }
@@ -175,6 +198,8 @@ impl<'de, E: Curve> serde::Deserialize<'de> for DirtyCoreKeyShare<E> {
             x,
             #[cfg(feature = "hd-wallet")]
             chain_code,
+            epoch,
+            party_set_commitment,
         } = serde::Deserialize::deserialize(deserializer)?;
         Ok(Self {
             i,
@@ -185,6 +210,8 @@ impl<'de, E: Curve> serde::Deserialize<'de> for DirtyCoreKeyShare<E> {
                 vss_setup,
                 #[cfg(feature = "hd-wallet")]
                 chain_code,
+                epoch,
+                party_set_commitment,
             },
             x,
         })
@@ -232,7 +259,28 @@ pub struct DirtyKeyInfo<E: Curve> {
         serde(with = "As::<Option<utils::HexOrBin>>")
     )]
     #[cfg_attr(feature = "udigest", udigest(as = Option<udigest::Bytes>))]
-    pub chain_code: Option<hd_wallet::ChainCode>,
+    pub chain_code: Option<CombinedChainCode>,
+    /// Epoch of the key
+    ///
+    /// Freshly generated key has epoch `0`, each key refresh increments it. Key shares from
+    /// different epochs can't be used to sign together.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "utils::is_zero")
+    )]
+    pub epoch: u64,
+    /// Commitment to network identities of the parties that generated the key, if they were set
+    ///
+    /// Recorded by keygen when parties were bound to their network identities and each party
+    /// checked identities of others. Aux info generation records the same commitment, which makes
+    /// it possible to check that key info and aux info were produced by the same set of parties.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none"),
+        serde(with = "As::<Option<serde_with::Bytes>>")
+    )]
+    #[cfg_attr(feature = "udigest", udigest(as = Option<udigest::Bytes>))]
+    pub party_set_commitment: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -253,23 +301,63 @@ pub struct VssSetup<E: Curve> {
         serde(with = "As::<Vec<generic_ec::serde::PreferCompact>>")
     )]
     pub I: Vec<NonZero<Scalar<E>>>,
+    /// Weights of the parties, present if key was generated using weighted threshold scheme
+    ///
+    /// In weighted scheme, a party with weight $w_j$ holds $w_j$ sub-shares, each sub-share
+    /// being a regular key share with its own index in [`I`](Self::I). Sub-shares of the
+    /// parties are laid out consecutively: the first $w_0$ sub-shares belong to party 0, the
+    /// next $w_1$ sub-shares belong to party 1, and so on. [`min_signers`](Self::min_signers)
+    /// then specifies a weight threshold: any set of parties whose weights sum up to at least
+    /// `min_signers` can sign.
+    ///
+    /// Note that size of key share grows linearly with the total weight $\sum_j w_j$: each
+    /// sub-share has its own public share, and a party needs to keep $w_i$ secret sub-shares.
+    /// Keep the weights as small as possible.
+    ///
+    /// See [`weighted`] module for the helpers.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub weights: Option<Vec<u16>>,
 }
 
 impl<E: Curve> Validate for DirtyCoreKeyShare<E> {
     type Error = InvalidCoreShare;
 
     fn is_valid(&self) -> Result<(), Self::Error> {
-        let party_public_share = self
-            .public_shares
-            .get(usize::from(self.i))
-            .ok_or(InvalidShareReason::PartyIndexOutOfBounds)?;
-        if *party_public_share != Point::generator() * &self.x {
-            return Err(InvalidShareReason::PartySecretShareDoesntMatchPublicShare.into());
-        }
+        first_problem(self.problems())
+    }
+}
 
-        self.key_info.is_valid()?;
+impl<E: Curve> DirtyCoreKeyShare<E> {
+    /// Validates the key share, reporting all problems found
+    ///
+    /// Unlike [`validate`](Validate::validate) which stops at the first problem, this method carries
+    /// out all the consistency checks and returns every problem it found, which is handy for debugging
+    /// a share that's corrupted in several ways. Checks that depend on a failed check (e.g.
+    /// interpolation of public shares requires correct threshold) are skipped.
+    pub fn validate_all(&self) -> Result<CoreKeyShare<E>, Vec<InvalidCoreShare>> {
+        let problems = self.problems();
+        if !problems.is_empty() {
+            return Err(problems.into_iter().map(InvalidCoreShare).collect());
+        }
+        self.clone()
+            .validate()
+            .map_err(|err| alloc::vec![err.into_error()])
+    }
 
-        Ok(())
+    fn problems(&self) -> Vec<InvalidShareReason> {
+        let mut problems = Vec::new();
+        match self.public_shares.get(usize::from(self.i)) {
+            None => problems.push(InvalidShareReason::PartyIndexOutOfBounds),
+            Some(party_public_share) if *party_public_share != Point::generator() * &self.x => {
+                problems.push(InvalidShareReason::PartySecretShareDoesntMatchPublicShare)
+            }
+            Some(_) => {}
+        }
+        problems.extend(self.key_info.problems());
+        problems
     }
 }
 
@@ -299,12 +387,34 @@ impl<E: Curve> Validate for DirtyKeyInfo<E> {
     type Error = InvalidCoreShare;
 
     fn is_valid(&self) -> Result<(), Self::Error> {
+        first_problem(self.problems())
+    }
+}
+
+impl<E: Curve> DirtyKeyInfo<E> {
+    fn problems(&self) -> Vec<InvalidShareReason> {
+        let mut problems = Vec::new();
         match &self.vss_setup {
-            Some(vss_setup) => {
-                validate_vss_key_info(self.shared_public_key, &self.public_shares, vss_setup)
-            }
-            None => validate_non_vss_key_info(self.shared_public_key, &self.public_shares),
+            Some(vss_setup) => validate_vss_key_info(
+                self.shared_public_key,
+                &self.public_shares,
+                vss_setup,
+                &mut problems,
+            ),
+            None => validate_non_vss_key_info(
+                self.shared_public_key,
+                &self.public_shares,
+                &mut problems,
+            ),
         }
+        problems
+    }
+}
+
+fn first_problem(problems: Vec<InvalidShareReason>) -> Result<(), InvalidCoreShare> {
+    match problems.into_iter().next() {
+        Some(problem) => Err(problem.into()),
+        None => Ok(()),
     }
 }
 
@@ -313,24 +423,43 @@ fn validate_vss_key_info<E: Curve>(
     shared_public_key: NonZero<Point<E>>,
     public_shares: &[NonZero<Point<E>>],
     vss_setup: &VssSetup<E>,
-) -> Result<(), InvalidCoreShare> {
-    let n: u16 = public_shares
-        .len()
-        .try_into()
-        .map_err(|_| InvalidShareReason::NOverflowsU16)?;
+    problems: &mut Vec<InvalidShareReason>,
+) {
+    let Ok(n) = u16::try_from(public_shares.len()) else {
+        problems.push(InvalidShareReason::NOverflowsU16);
+        return;
+    };
     if n < 2 {
-        return Err(InvalidShareReason::TooFewParties.into());
+        problems.push(InvalidShareReason::TooFewParties);
     }
 
     let t = vss_setup.min_signers;
+    let mut can_interpolate = n >= 2;
     if !(2 <= t) {
-        return Err(InvalidShareReason::ThresholdTooSmall.into());
+        problems.push(InvalidShareReason::ThresholdTooSmall);
+        can_interpolate = false;
     }
     if !(t <= n) {
-        return Err(InvalidShareReason::ThresholdTooLarge.into());
+        problems.push(InvalidShareReason::ThresholdTooLarge);
+        can_interpolate = false;
     }
     if vss_setup.I.len() != usize::from(n) {
-        return Err(InvalidShareReason::ILen.into());
+        problems.push(InvalidShareReason::ILen);
+        can_interpolate = false;
+    }
+    if let Some(weights) = &vss_setup.weights {
+        if weights.len() < 2 {
+            problems.push(InvalidShareReason::TooFewParties);
+        }
+        if weights.contains(&0) {
+            problems.push(InvalidShareReason::ZeroWeight);
+        }
+        if weights.iter().map(|w| usize::from(*w)).sum::<usize>() != usize::from(n) {
+            problems.push(InvalidShareReason::WeightsSum);
+        }
+    }
+    if !can_interpolate {
+        return;
     }
 
     // Now we need to check that public key shares indeed form a public key.
@@ -347,39 +476,41 @@ fn validate_vss_key_info<E: Curve>(
             .map(|j| lagrange_coefficient(x, j, indexes))
             .collect::<Option<Vec<_>>>()
             .ok_or(InvalidShareReason::INotPairwiseDistinct)?;
-        Ok::<_, InvalidCoreShare>(Scalar::multiscalar_mul(
+        Ok::<_, InvalidShareReason>(Scalar::multiscalar_mul(
             lagrange_coefficients.into_iter().zip(first_t_shares),
         ))
     };
-    let reconstructed_pk = interpolation(Scalar::zero())?;
-    if reconstructed_pk != shared_public_key {
-        return Err(InvalidShareReason::SharesDontMatchPublicKey.into());
-    }
-
-    for (&j, public_share_j) in vss_setup.I.iter().zip(public_shares).skip(t.into()) {
-        if interpolation(j.into())? != *public_share_j {
-            return Err(InvalidShareReason::SharesDontMatchPublicKey.into());
+    let result = (|| {
+        if interpolation(Scalar::zero())? != shared_public_key {
+            return Err(InvalidShareReason::SharesDontMatchPublicKey);
+        }
+        for (&j, public_share_j) in vss_setup.I.iter().zip(public_shares).skip(t.into()) {
+            if interpolation(j.into())? != *public_share_j {
+                return Err(InvalidShareReason::SharesDontMatchPublicKey);
+            }
         }
+        Ok(())
+    })();
+    if let Err(problem) = result {
+        problems.push(problem)
     }
-
-    Ok(())
 }
 
 fn validate_non_vss_key_info<E: Curve>(
     shared_public_key: NonZero<Point<E>>,
     public_shares: &[NonZero<Point<E>>],
-) -> Result<(), InvalidCoreShare> {
-    let n: u16 = public_shares
-        .len()
-        .try_into()
-        .map_err(|_| InvalidShareReason::NOverflowsU16)?;
-    if n < 2 {
-        return Err(InvalidShareReason::TooFewParties.into());
+    problems: &mut Vec<InvalidShareReason>,
+) {
+    if u16::try_from(public_shares.len()).is_err() {
+        problems.push(InvalidShareReason::NOverflowsU16);
+        return;
+    }
+    if public_shares.len() < 2 {
+        problems.push(InvalidShareReason::TooFewParties);
     }
     if shared_public_key != public_shares.iter().sum::<Point<E>>() {
-        return Err(InvalidShareReason::SharesDontMatchPublicKey.into());
+        problems.push(InvalidShareReason::SharesDontMatchPublicKey);
     }
-    Ok(())
 }
 
 impl<E: Curve> DirtyKeyInfo<E> {
@@ -404,6 +535,27 @@ impl<E: Curve> DirtyKeyInfo<E> {
             None
         }
     }
+
+    /// Checks whether `self` and `other` describe the same key
+    ///
+    /// Compares shared public key, public shares of all signers, VSS setup, and chain code (if
+    /// HD wallets support is enabled).
+    pub fn same_key(&self, other: &DirtyKeyInfo<E>) -> bool {
+        let same_chain_code = {
+            #[cfg(feature = "hd-wallet")]
+            {
+                self.chain_code == other.chain_code
+            }
+            #[cfg(not(feature = "hd-wallet"))]
+            {
+                true
+            }
+        };
+        self.shared_public_key == other.shared_public_key
+            && self.public_shares == other.public_shares
+            && self.vss_setup == other.vss_setup
+            && same_chain_code
+    }
 }
 
 #[cfg(feature = "hd-wallet")]
@@ -415,10 +567,10 @@ impl<E: Curve> DirtyKeyInfo<E> {
 
     /// Returns extended public key, if HD support was enabled
     pub fn extended_public_key(&self) -> Option<hd_wallet::ExtendedPublicKey<E>> {
-        Some(hd_wallet::ExtendedPublicKey {
-            public_key: self.shared_public_key.into_inner(),
-            chain_code: self.chain_code?,
-        })
+        Some(
+            self.chain_code?
+                .extended_public_key(self.shared_public_key.into_inner()),
+        )
     }
 
     /// Derives child public key, if it's HD key, using [`HdWallet`](hd_wallet::HdWallet) algorithm
@@ -493,6 +645,299 @@ impl<E: Curve> CoreKeyShare<E> {
     pub fn shared_public_key(&self) -> NonZero<Point<E>> {
         self.shared_public_key
     }
+
+    /// Checks whether `self` and `other` are shares of the same key
+    ///
+    /// Unlike equality, it ignores party-specific data (index `i` and secret share `x`), so shares
+    /// of different parties of the same key are considered to be of the same key. Use it, for
+    /// instance, to make sure that shares loaded from different backups belong to the same key
+    /// before assembling a quorum.
+    ///
+    /// See [`DirtyKeyInfo::same_key`] for the list of compared data.
+    pub fn same_key(&self, other: &CoreKeyShare<E>) -> bool {
+        self.key_info.same_key(&other.key_info)
+    }
+
+    /// Returns Feldman commitment to the polynomial that secret key is shared with
+    ///
+    /// Returns `None` for non-polynomial (additive) key shares. See [`KeyInfo::commitment_polynomial`]
+    /// for details.
+    pub fn commitment_polynomial(&self) -> Option<Vec<Point<E>>> {
+        let key_info: &KeyInfo<E> = self.as_ref();
+        key_info.commitment_polynomial()
+    }
+
+    /// Returns [public-only](PublicKeyShare) key share
+    ///
+    /// Public key share contains the same [key info](DirtyKeyInfo) as `self`, but not the secret
+    /// share `x`. It doesn't need to be validated again.
+    pub fn to_public(&self) -> PublicKeyShare<E> {
+        let key_info: &KeyInfo<E> = self.as_ref();
+        key_info.clone()
+    }
+
+    /// Returns additive secret share of the local party for the set of `signers`
+    ///
+    /// It's the share $x'_i = \lambda_i \cdot x_i$ that the party uses in signing, such that additive
+    /// shares of all signers sum up to the secret key. Public counterpart can be obtained via
+    /// [`public_additive_share_for`](Self::public_additive_share_for). See
+    /// [`KeyInfo::public_additive_share_for`] for requirements on `signers`.
+    ///
+    /// For a pair of signers, see [`two_party_additive_share`](Self::two_party_additive_share).
+    ///
+    /// <div class="warning">
+    ///
+    /// **Returned value is as sensitive as the secret share itself.** It must not leave the party.
+    ///
+    /// </div>
+    pub fn additive_share_for(
+        &self,
+        signers: &[u16],
+    ) -> Result<SecretScalar<E>, AdditiveShareError> {
+        let key_info: &KeyInfo<E> = self.as_ref();
+        let lambda_i = key_info.lagrange_coefficient_for(self.i, signers)?;
+        let mut x_i = lambda_i * &self.x;
+        Ok(SecretScalar::new(&mut x_i))
+    }
+
+    /// Returns public additive share of `j`-th party for the set of `signers`
+    ///
+    /// See [`KeyInfo::public_additive_share_for`].
+    pub fn public_additive_share_for(
+        &self,
+        j: u16,
+        signers: &[u16],
+    ) -> Result<Point<E>, AdditiveShareError> {
+        let key_info: &KeyInfo<E> = self.as_ref();
+        key_info.public_additive_share_for(j, signers)
+    }
+
+    /// Returns additive sharing of the key between local party and the `counterparty`
+    ///
+    /// Specialized 2-party signing protocols take additive shares of the key as input: two secret
+    /// scalars that sum up to the secret key. For threshold keys with `min_signers <= 2`, any pair
+    /// of signers can obtain such sharing locally, without running a separate keygen: each party
+    /// multiplies its secret share at the Lagrange coefficient for the pair (see
+    /// [`additive_share_for`](Self::additive_share_for)). For additive (non-threshold) keys, it's
+    /// only possible when $n = 2$.
+    ///
+    /// Both parties must call this function with each other's index. Returned value contains
+    /// secret additive share of the local party and public additive shares of both parties,
+    /// which sum up to the shared public key.
+    ///
+    /// ## Example
+    /// ```rust,no_run
+    /// # use generic_ec::{Curve, Point};
+    /// # use key_share::CoreKeyShare;
+    /// # fn load_key_share<E: Curve>(i: u16) -> CoreKeyShare<E> { unimplemented!() }
+    /// # fn example<E: Curve>() -> Result<(), key_share::AdditiveShareError> {
+    /// // Signers 0 and 3 of 2-out-of-n key
+    /// let share_0 = load_key_share::<E>(0).two_party_additive_share(3)?;
+    /// let share_3 = load_key_share::<E>(3).two_party_additive_share(0)?;
+    ///
+    /// // Parties agree on public additive shares without any interaction
+    /// assert_eq!(share_0.counterparty_public_share, share_3.public_share);
+    /// assert_eq!(share_0.public_share, share_3.counterparty_public_share);
+    /// // ... and their secret shares sum up to the secret key
+    /// assert_eq!(
+    ///     share_0.public_share + share_0.counterparty_public_share,
+    ///     *load_key_share::<E>(0).shared_public_key,
+    /// );
+    /// assert_eq!(Point::generator() * &share_0.x, share_0.public_share);
+    ///
+    /// // `share_0.x` and `share_3.x` can now be passed to 2-party signing protocol
+    /// # Ok(()) }
+    /// ```
+    pub fn two_party_additive_share(
+        &self,
+        counterparty: u16,
+    ) -> Result<TwoPartyAdditiveShare<E>, AdditiveShareError> {
+        let signers = [self.i, counterparty];
+        Ok(TwoPartyAdditiveShare {
+            x: self.additive_share_for(&signers)?,
+            public_share: self.public_additive_share_for(self.i, &signers)?,
+            counterparty_public_share: self.public_additive_share_for(counterparty, &signers)?,
+        })
+    }
+
+    /// Re-validates public shares against rogue-key attack
+    ///
+    /// Proofs of knowledge in keygen prevent a party from choosing its public share as a function
+    /// of public shares of other parties. This is a cheap additional check that can be run on
+    /// any loaded key share. It checks that:
+    /// * Public shares are consistent with the shared public key: they sum up to the public key for
+    ///   additive shares, or lie on the same polynomial (as specified by [`VssSetup`]) for
+    ///   polynomial shares
+    /// * Public shares are pairwise distinct
+    /// * None of public shares equals to the shared public key, i.e. a single party doesn't
+    ///   control the key while contributions of other parties cancel out
+    pub fn verify_no_rogue_key(&self) -> Result<(), RogueKeyError> {
+        self.key_info
+            .is_valid()
+            .map_err(RogueKeyReason::Inconsistent)?;
+
+        for (j, X_j) in (0u16..).zip(&self.public_shares) {
+            if *X_j == self.shared_public_key {
+                return Err(RogueKeyReason::ShareEqualsPublicKey(j).into());
+            }
+            if let Some(k) = (0u16..)
+                .zip(&self.public_shares)
+                .take(usize::from(j))
+                .find_map(|(k, X_k)| (X_k == X_j).then_some(k))
+            {
+                return Err(RogueKeyReason::DuplicatePublicShares(k, j).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: Curve> KeyInfo<E> {
+    /// Returns amount of key co-holders
+    pub fn n(&self) -> u16 {
+        #[allow(clippy::expect_used)]
+        self.public_shares
+            .len()
+            .try_into()
+            .expect("valid key info is guaranteed to have amount of signers fitting into u16")
+    }
+
+    /// Returns threshold
+    ///
+    /// Threshold is an amount of signers required to cooperate in order to sign a message
+    /// and/or generate presignature
+    pub fn min_signers(&self) -> u16 {
+        self.vss_setup
+            .as_ref()
+            .map(|s| s.min_signers)
+            .unwrap_or_else(|| self.n())
+    }
+
+    /// Returns public additive share of `j`-th party for the set of `signers`
+    ///
+    /// Before signing, polynomial key shares are converted into additive shares of the signers by
+    /// multiplying at Lagrange coefficient: $X'_j = \lambda_j \cdot X_j$, such that public additive
+    /// shares of all signers sum up to the shared public key. `signers` are indexes of the signers
+    /// (in any order), there must be at least [`min_signers`](Self::min_signers) of them, and
+    /// they must include `j`.
+    ///
+    /// For non-polynomial (additive) keys, `signers` must include all the parties, and public share
+    /// is returned as is. Weighted keys are treated as regular polynomial keys, i.e. `signers` are
+    /// indexes of sub-shares.
+    pub fn public_additive_share_for(
+        &self,
+        j: u16,
+        signers: &[u16],
+    ) -> Result<Point<E>, AdditiveShareError> {
+        let lambda_j = self.lagrange_coefficient_for(j, signers)?;
+        Ok(lambda_j * self.public_shares[usize::from(j)])
+    }
+
+    /// Returns Lagrange coefficient of `j`-th party for the set of `signers`
+    fn lagrange_coefficient_for(
+        &self,
+        j: u16,
+        signers: &[u16],
+    ) -> Result<Scalar<E>, AdditiveShareError> {
+        if let Some(&s) = signers.iter().find(|&&s| s >= self.n()) {
+            return Err(AdditiveShareReason::SignerOutOfBounds(s).into());
+        }
+        if let Some((_, &s)) = signers
+            .iter()
+            .enumerate()
+            .find(|(k, s)| signers[..*k].contains(s))
+        {
+            return Err(AdditiveShareReason::DuplicateSigner(s).into());
+        }
+        let pos = signers
+            .iter()
+            .position(|&s| s == j)
+            .ok_or(AdditiveShareReason::NotASigner(j))?;
+        if signers.len() < usize::from(self.min_signers()) {
+            return Err(AdditiveShareReason::NotEnoughSigners {
+                signers: signers.len(),
+                min_signers: self.min_signers(),
+            }
+            .into());
+        }
+
+        match &self.vss_setup {
+            Some(vss_setup) => {
+                let I = signers
+                    .iter()
+                    .map(|&s| vss_setup.I[usize::from(s)])
+                    .collect::<Vec<_>>();
+                let lambda_j = generic_ec_zkp::polynomial::lagrange_coefficient_at_zero(pos, &I)
+                    .ok_or(AdditiveShareReason::Interpolation)?;
+                Ok(*lambda_j)
+            }
+            None => Ok(Scalar::one()),
+        }
+    }
+
+    /// Returns Feldman commitment to the polynomial that secret key is shared with
+    ///
+    /// Returns coefficients $[A_0, \dots, A_{t-1}]$ such that public share of $\ith$ signer
+    /// is $X_i = \sum_k A_k \cdot I_i^k$ (where $I_i$ is [share preimage](DirtyKeyInfo::share_preimage)),
+    /// and $A_0$ is the shared public key. It lets an external party verify that all public shares
+    /// lie on the same polynomial of degree $t-1$, without access to any secret.
+    ///
+    /// Commitment is interpolated from the first $t$ public shares. Returns `None` if the key is
+    /// not polynomial (i.e. [`vss_setup`](DirtyKeyInfo::vss_setup) is `None`).
+    pub fn commitment_polynomial(&self) -> Option<Vec<Point<E>>> {
+        let vss_setup = self.vss_setup.as_ref()?;
+        let t = usize::from(vss_setup.min_signers);
+        let indexes = &vss_setup.I[..t];
+        let public_shares = &self.public_shares[..t];
+
+        // Coefficients of $P(x) = \prod_j (x - I_j)$, from the lowest to the highest
+        let mut product = alloc::vec![Scalar::<E>::one()];
+        for I_j in indexes {
+            product.insert(0, Scalar::zero());
+            for k in 0..product.len() - 1 {
+                product[k] = product[k] - product[k + 1] * I_j;
+            }
+        }
+
+        // Coefficients of Lagrange polynomials $L_j(x) = P(x) / ((x - I_j) \cdot P'(I_j))$
+        let lagrange_polynomials = indexes
+            .iter()
+            .map(|I_j| {
+                let mut numerator = alloc::vec![Scalar::<E>::zero(); t];
+                let mut carry = Scalar::zero();
+                for k in (0..t).rev() {
+                    carry = product[k + 1] + carry * I_j;
+                    numerator[k] = carry;
+                }
+                let denominator = numerator
+                    .iter()
+                    .rev()
+                    .fold(Scalar::zero(), |acc, coef| acc * I_j + coef);
+                #[allow(clippy::expect_used)]
+                let denominator_inv = denominator
+                    .invert()
+                    .expect("indexes are guaranteed to be pairwise distinct in valid key info");
+                numerator
+                    .into_iter()
+                    .map(|coef| coef * denominator_inv)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        Some(
+            (0..t)
+                .map(|k| {
+                    Scalar::multiscalar_mul(
+                        lagrange_polynomials
+                            .iter()
+                            .map(|l_j| l_j[k])
+                            .zip(public_shares),
+                    )
+                })
+                .collect(),
+        )
+    }
 }
 
 impl<E: Curve> ops::Deref for DirtyCoreKeyShare<E> {
@@ -542,6 +987,12 @@ enum InvalidShareReason {
     ILen,
     #[displaydoc("indexes of shares in I are not pairwise distinct")]
     INotPairwiseDistinct,
+    #[displaydoc("weight of a party is zero")]
+    ZeroWeight,
+    #[displaydoc("sum of weights doesn't match amount of sub-shares: weights.sum() != n")]
+    WeightsSum,
+    #[displaydoc("public share evaluated from commitment polynomial is zero")]
+    ZeroPublicShare,
 }
 
 impl From<InvalidShareReason> for InvalidCoreShare {
@@ -550,6 +1001,69 @@ impl From<InvalidShareReason> for InvalidCoreShare {
     }
 }
 
+/// Error indicating that key share failed [rogue key check](CoreKeyShare::verify_no_rogue_key)
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("key share failed rogue key check")]
+pub struct RogueKeyError(#[cfg_attr(feature = "std", source)] RogueKeyReason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum RogueKeyReason {
+    #[displaydoc("public shares are inconsistent with shared public key")]
+    Inconsistent(#[cfg_attr(feature = "std", source)] InvalidCoreShare),
+    #[displaydoc("public shares of parties {0} and {1} are equal")]
+    DuplicatePublicShares(u16, u16),
+    #[displaydoc("public share of party {0} equals shared public key")]
+    ShareEqualsPublicKey(u16),
+}
+
+impl From<RogueKeyReason> for RogueKeyError {
+    fn from(err: RogueKeyReason) -> Self {
+        Self(err)
+    }
+}
+
+/// Additive sharing of the key between two signers
+///
+/// Returned by [`CoreKeyShare::two_party_additive_share`]. Secret additive shares of both
+/// parties sum up to the secret key, and public shares sum up to the shared public key.
+pub struct TwoPartyAdditiveShare<E: Curve> {
+    /// Secret additive share of the local party
+    pub x: SecretScalar<E>,
+    /// Public additive share of the local party, $X' = x \cdot G$
+    pub public_share: Point<E>,
+    /// Public additive share of the counterparty
+    pub counterparty_public_share: Point<E>,
+}
+
+/// Error indicating that additive share can't be computed for the given set of signers
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("can't compute additive share")]
+pub struct AdditiveShareError(#[cfg_attr(feature = "std", source)] AdditiveShareReason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum AdditiveShareReason {
+    #[displaydoc("signer {0} is out of bounds")]
+    SignerOutOfBounds(u16),
+    #[displaydoc("signer {0} is listed more than once")]
+    DuplicateSigner(u16),
+    #[displaydoc("party {0} is not in the list of signers")]
+    NotASigner(u16),
+    #[displaydoc("{signers} signers are not enough, at least {min_signers} are required")]
+    NotEnoughSigners { signers: usize, min_signers: u16 },
+    #[displaydoc("interpolation failed")]
+    Interpolation,
+}
+
+impl From<AdditiveShareReason> for AdditiveShareError {
+    fn from(err: AdditiveShareReason) -> Self {
+        Self(err)
+    }
+}
+
 /// Error related to HD key derivation
 #[derive(Debug, displaydoc::Display)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
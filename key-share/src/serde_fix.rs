@@ -59,7 +59,14 @@ core_key_share! {
     #[cfg(feature = "hd-wallet")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[serde(with = "As::<Option<crate::utils::HexOrBin>>")]
-    pub chain_code: Option<hd_wallet::ChainCode>,
+    pub chain_code: Option<crate::CombinedChainCode>,
+
+    #[serde(default, skip_serializing_if = "crate::utils::is_zero")]
+    pub epoch: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(with = "As::<Option<serde_with::Bytes>>")]
+    pub party_set_commitment: Option<Vec<u8>>,
 
     #[serde(with = "As::<generic_ec::serde::Compact>")]
     pub x: NonZero<SecretScalar<E>>,
@@ -151,13 +151,14 @@ impl<E: Curve> TrustedDealerBuilder<E> {
         let vss_setup = self.t.map(|t| VssSetup {
             min_signers: t,
             I: key_shares_indexes,
+            weights: None,
         });
 
         #[cfg(feature = "hd-wallet")]
         let chain_code = if self.enable_hd {
             let mut code = hd_wallet::ChainCode::default();
             rng.fill_bytes(&mut code);
-            Some(code)
+            Some(crate::CombinedChainCode::new(code))
         } else {
             None
         };
@@ -174,6 +175,8 @@ impl<E: Curve> TrustedDealerBuilder<E> {
                         vss_setup: vss_setup.clone(),
                         #[cfg(feature = "hd-wallet")]
                         chain_code,
+                        epoch: 0,
+                        party_set_commitment: None,
                     },
                     x: x_i,
                 })
@@ -0,0 +1,101 @@
+//! Independent keys derived from a single key via additive tweaks
+//!
+//! One key generation may back many independent-looking keys. Given a domain (arbitrary
+//! byte string, e.g. account identifier), all signers deterministically derive a tweak
+//! $t = H(\pk, \text{domain})$ and shift the key by it: tweaked public key is $\pk + t \cdot G$.
+//! Each signer converts its key share locally, without any interaction, and tweaked key shares
+//! can be used in signing as any other key share.
+//!
+//! Unlike [HD derivation](crate::DirtyKeyInfo::derive_child_public_key), tweaks are not organized
+//! in a tree, and any byte string can be used as a domain. Note that the tweak is public: anyone
+//! who knows the original public key and the domain can link the tweaked key to the original one.
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+
+use crate::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, KeyInfo, Validate};
+
+impl<E: Curve> KeyInfo<E> {
+    /// Returns tweak corresponding to the `domain`
+    ///
+    /// See [module-level](crate::tweak) docs.
+    pub fn tweak_scalar<D: Digest>(&self, domain: &[u8]) -> Scalar<E> {
+        Scalar::from_hash::<D>(&Tweak {
+            shared_public_key: &self.shared_public_key,
+            domain,
+        })
+    }
+
+    /// Returns public key info of the key tweaked by the `domain`
+    ///
+    /// Returns `None` if tweaked key is degenerate, which happens with negligible probability.
+    /// See [module-level](crate::tweak) docs.
+    pub fn tweak<D: Digest>(&self, domain: &[u8]) -> Option<KeyInfo<E>> {
+        let t = self.tweak_scalar::<D>(domain);
+        tweak_key_info(self, t)?.validate().ok()
+    }
+}
+
+impl<E: Curve> CoreKeyShare<E> {
+    /// Returns key share of the key tweaked by the `domain`
+    ///
+    /// Tweaked key share is a share of public key $\pk + t \cdot G$, where $t$ is
+    /// [tweak](KeyInfo::tweak_scalar) derived from the `domain`. All signers must use the same
+    /// `domain` and digest `D`.
+    ///
+    /// Returns `None` if tweaked key is degenerate, which happens with negligible probability.
+    /// See [module-level](crate::tweak) docs.
+    pub fn tweak<D: Digest>(&self, domain: &[u8]) -> Option<CoreKeyShare<E>> {
+        let key_info: &KeyInfo<E> = self.as_ref();
+        let t = key_info.tweak_scalar::<D>(domain);
+
+        // Polynomial key shares: secret polynomial is shifted by `t`, so every share is shifted
+        // by `t`. Additive key shares: only the first share is shifted.
+        let x = if self.vss_setup.is_some() || self.i == 0 {
+            let mut x = t + &self.x;
+            NonZero::from_secret_scalar(SecretScalar::new(&mut x))?
+        } else {
+            self.x.clone()
+        };
+
+        DirtyCoreKeyShare {
+            i: self.i,
+            key_info: tweak_key_info(key_info, t)?,
+            x,
+        }
+        .validate()
+        .ok()
+    }
+}
+
+/// Shifts public key and public shares by $t \cdot G$
+fn tweak_key_info<E: Curve>(key_info: &DirtyKeyInfo<E>, t: Scalar<E>) -> Option<DirtyKeyInfo<E>> {
+    let T = Point::generator() * t;
+    let shared_public_key = NonZero::from_point(key_info.shared_public_key + T)?;
+    let public_shares = if key_info.vss_setup.is_some() {
+        key_info
+            .public_shares
+            .iter()
+            .map(|X_j| NonZero::from_point(*X_j + T))
+            .collect::<Option<_>>()?
+    } else {
+        let mut public_shares = key_info.public_shares.clone();
+        let X_0 = public_shares.first_mut()?;
+        *X_0 = NonZero::from_point(*X_0 + T)?;
+        public_shares
+    };
+    Some(DirtyKeyInfo {
+        shared_public_key,
+        public_shares,
+        ..key_info.clone()
+    })
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.key_share.tweak")]
+#[udigest(bound = "")]
+struct Tweak<'a, E: Curve> {
+    shared_public_key: &'a NonZero<Point<E>>,
+    #[udigest(as_bytes)]
+    domain: &'a [u8],
+}
@@ -10,6 +10,14 @@ pub use hex_or_bin::HexOrBin;
 #[cfg(all(feature = "serde", not(feature = "hd-wallet")))]
 use hex as _;
 
+/// Checks whether the number is zero, used to skip serializing default epoch
+///
+/// Takes `impl Borrow<u64>` so it can be used with both owned and borrowed fields
+#[cfg(feature = "serde")]
+pub fn is_zero(n: &impl core::borrow::Borrow<u64>) -> bool {
+    *n.borrow() == 0
+}
+
 #[cfg(feature = "udigest")]
 pub mod encoding {
     pub struct CurveName;
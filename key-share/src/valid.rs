@@ -122,6 +122,20 @@ where
         }
     }
 
+    /// Modifies the value and validates it again
+    ///
+    /// Takes `f` that modifies the value in place. If modified value is valid, returns it wrapped
+    /// into [`Valid<T>`](Valid), otherwise returns `Err(err)` containing the error and the modified
+    /// value.
+    pub fn modify(
+        self,
+        f: impl FnOnce(&mut T),
+    ) -> Result<Self, ValidateError<T, <T as Validate>::Error>> {
+        let mut value = self.0;
+        f(&mut value);
+        Self::validate(value)
+    }
+
     /// Constructs `&Valid<T>` from `&T`, assumes that `T` has been validated
     ///
     /// Performs a debug assertion that `T` is validated
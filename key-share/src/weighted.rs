@@ -0,0 +1,212 @@
+//! Weighted threshold key shares
+//!
+//! In weighted threshold scheme, each party $j$ is assigned a weight $w_j$, and any set of parties
+//! whose weights sum up to at least threshold $W$ can sign. It's implemented on top of regular
+//! polynomial key shares: party $j$ holds $w_j$ sub-shares of $W$-out-of-$\sum_j w_j$ key. Each
+//! sub-share is a regular [`CoreKeyShare`], and [`VssSetup::weights`](crate::VssSetup::weights)
+//! specifies which sub-shares belong to which party.
+//!
+//! Note that size of the key share and cost of the key generation grow linearly with the total
+//! weight $\sum_j w_j$. Keep the weights as small as possible, e.g. divide them by their greatest
+//! common divisor.
+//!
+//! Signing protocols typically operate on a single key share per party. Use [`signing_share`]
+//! to convert the sub-shares of the party into an additive key share of a specific set of
+//! signers.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::polynomial::lagrange_coefficient_at_zero;
+
+use crate::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, InvalidCoreShare, Validate};
+
+/// Returns indexes of sub-shares that belong to the party `j`
+///
+/// Returns `None` if `j` is out of bounds or indexes overflow `u16`
+pub fn sub_shares(weights: &[u16], j: u16) -> Option<Range<u16>> {
+    let weight = *weights.get(usize::from(j))?;
+    let start = weights[..usize::from(j)]
+        .iter()
+        .try_fold(0u16, |acc, w| acc.checked_add(*w))?;
+    let end = start.checked_add(weight)?;
+    Some(start..end)
+}
+
+/// Returns index of the party that owns the sub-share `k`
+///
+/// Returns `None` if `k` is out of bounds
+pub fn owner_of(weights: &[u16], k: u16) -> Option<u16> {
+    let mut end = 0u32;
+    for (j, w) in (0u16..).zip(weights) {
+        end += u32::from(*w);
+        if u32::from(k) < end {
+            return Some(j);
+        }
+    }
+    None
+}
+
+/// Returns total weight of the parties
+///
+/// Returns `None` if any of `parties` is out of bounds
+pub fn total_weight(weights: &[u16], parties: &[u16]) -> Option<u32> {
+    parties
+        .iter()
+        .map(|j| weights.get(usize::from(*j)).map(|w| u32::from(*w)))
+        .sum()
+}
+
+impl<E: Curve> DirtyKeyInfo<E> {
+    /// Returns weights of the parties, if the key was generated using weighted threshold scheme
+    pub fn weights(&self) -> Option<&[u16]> {
+        self.vss_setup.as_ref()?.weights.as_deref()
+    }
+}
+
+/// Converts sub-shares of the party into additive key share of the signers
+///
+/// Takes all sub-shares of the local party and a set of `signers`, where `signers[j]` is index of
+/// j-th signer. The signers must include the local party, and their total weight must be at
+/// least the [threshold](crate::VssSetup::min_signers).
+///
+/// Resulting key share is an additive $|S|$-out-of-$|S|$ key share of the same public key, where
+/// $|S|$ is amount of signers, and index of the local party is its position in `signers`. As with
+/// any additive key share, at least two signers are required.
+pub fn signing_share<E: Curve>(
+    sub_shares: &[impl AsRef<CoreKeyShare<E>>],
+    signers: &[u16],
+) -> Result<CoreKeyShare<E>, WeightedError> {
+    let first = sub_shares.first().ok_or(Reason::NoKeyShares)?.as_ref();
+    let vss_setup = first.vss_setup.as_ref().ok_or(Reason::NotWeighted)?;
+    let weights = vss_setup.weights.as_deref().ok_or(Reason::NotWeighted)?;
+
+    if sub_shares[1..].iter().any(|s| {
+        let s = s.as_ref();
+        s.shared_public_key != first.shared_public_key
+            || s.public_shares != first.public_shares
+            || s.vss_setup != first.vss_setup
+    }) {
+        return Err(Reason::DifferentKeyShares.into());
+    }
+
+    let owner = owner_of(weights, first.i).ok_or(Reason::UnknownOwner)?;
+    let owned = self::sub_shares(weights, owner).ok_or(Reason::UnknownOwner)?;
+    let mut x_owned = Vec::with_capacity(owned.len());
+    for k in owned.clone() {
+        let x_k = sub_shares
+            .iter()
+            .find(|s| s.as_ref().i == k)
+            .ok_or(Reason::MissingSubShare { k })?;
+        x_owned.push(&x_k.as_ref().x);
+    }
+
+    for (j, s) in signers.iter().enumerate() {
+        if signers[..j].contains(s) {
+            return Err(Reason::DuplicatedSigner { j: *s }.into());
+        }
+    }
+    let i = signers
+        .iter()
+        .position(|s| *s == owner)
+        .ok_or(Reason::NotASigner)?;
+    let weight = total_weight(weights, signers).ok_or(Reason::SignerOutOfBounds)?;
+    if weight < u32::from(vss_setup.min_signers) {
+        return Err(Reason::NotEnoughWeight {
+            weight,
+            threshold: vss_setup.min_signers,
+        }
+        .into());
+    }
+
+    let ranges = signers
+        .iter()
+        .map(|s| self::sub_shares(weights, *s).ok_or(Reason::SignerOutOfBounds))
+        .collect::<Result<Vec<_>, _>>()?;
+    let I = ranges
+        .iter()
+        .flat_map(|r| r.clone())
+        .map(|k| vss_setup.I[usize::from(k)])
+        .collect::<Vec<_>>();
+    let lambda = (0..I.len())
+        .map(|pos| lagrange_coefficient_at_zero(pos, &I))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Reason::Interpolation)?;
+
+    let mut pos = 0;
+    let mut public_shares = Vec::with_capacity(signers.len());
+    let mut x = Scalar::zero();
+    for (j, range) in ranges.iter().enumerate() {
+        let lambda_j = &lambda[pos..pos + range.len()];
+        let X_j = lambda_j
+            .iter()
+            .zip(&first.public_shares[usize::from(range.start)..usize::from(range.end)])
+            .map(|(lambda_k, X_k)| *lambda_k * X_k)
+            .sum::<Point<E>>();
+        public_shares.push(NonZero::from_point(X_j).ok_or(Reason::ZeroShare)?);
+        if j == i {
+            x = lambda_j
+                .iter()
+                .zip(&x_owned)
+                .fold(Scalar::zero(), |acc, (lambda_k, x_k)| {
+                    acc + *lambda_k * *x_k
+                });
+        }
+        pos += range.len();
+    }
+    let x = NonZero::from_secret_scalar(SecretScalar::new(&mut x)).ok_or(Reason::ZeroShare)?;
+
+    DirtyCoreKeyShare {
+        i: i.try_into().map_err(|_| Reason::SignerOutOfBounds)?,
+        key_info: DirtyKeyInfo {
+            vss_setup: None,
+            public_shares,
+            ..first.key_info.clone()
+        },
+        x,
+    }
+    .validate()
+    .map_err(|err| Reason::InvalidKeyShare(err.into_error()).into())
+}
+
+/// Error indicating that weighted key share couldn't be converted
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("weighted key share error")]
+pub struct WeightedError(#[cfg_attr(feature = "std", source)] Reason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Reason {
+    #[displaydoc("no key shares provided")]
+    NoKeyShares,
+    #[displaydoc("key share is not weighted")]
+    NotWeighted,
+    #[displaydoc("provided sub-shares doesn't share the same key")]
+    DifferentKeyShares,
+    #[displaydoc("couldn't determine owner of the sub-share")]
+    UnknownOwner,
+    #[displaydoc("sub-share {k} is missing")]
+    MissingSubShare { k: u16 },
+    #[displaydoc("signer {j} is listed more than once")]
+    DuplicatedSigner { j: u16 },
+    #[displaydoc("local party is not in the list of signers")]
+    NotASigner,
+    #[displaydoc("signer index is out of bounds")]
+    SignerOutOfBounds,
+    #[displaydoc("total weight of signers {weight} is less than threshold {threshold}")]
+    NotEnoughWeight { weight: u32, threshold: u16 },
+    #[displaydoc("interpolation failed (seems like a bug)")]
+    Interpolation,
+    #[displaydoc("resulting share is zero - probability of that is negligible")]
+    ZeroShare,
+    #[displaydoc("resulting key share is not valid")]
+    InvalidKeyShare(#[cfg_attr(feature = "std", source)] InvalidCoreShare),
+}
+
+impl From<Reason> for WeightedError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
@@ -21,6 +21,13 @@ struct Args {
     bench_signing: bool,
     optimize_multiexp: bool,
     custom_sec_level: bool,
+    per_round_latency: Option<LatencyArgs>,
+}
+
+struct LatencyArgs {
+    delay: std::time::Duration,
+    jitter: std::time::Duration,
+    runs: usize,
 }
 
 fn args() -> Args {
@@ -39,6 +46,32 @@ fn args() -> Args {
     let bench_signing = bpaf::long("no-bench-signing").switch().map(|b| !b);
     let optimize_multiexp = bpaf::long("optimize-multiexp").switch();
     let custom_sec_level = bpaf::long("custom-sec-level").switch();
+    let per_round_latency = {
+        let enabled = bpaf::long("per-round-latency")
+            .help("Measure distribution of signing rounds completion time under simulated network delay")
+            .switch();
+        let delay = bpaf::long("delay-ms")
+            .help("Network delay of every message, in milliseconds")
+            .argument::<u64>("MS")
+            .fallback(50)
+            .map(std::time::Duration::from_millis);
+        let jitter = bpaf::long("jitter-ms")
+            .help("Max random delay added on top of network delay, in milliseconds")
+            .argument::<u64>("MS")
+            .fallback(20)
+            .map(std::time::Duration::from_millis);
+        let runs = bpaf::long("latency-runs")
+            .help("Amount of signing executions to collect latency statistics from")
+            .argument::<usize>("RUNS")
+            .fallback(20);
+        bpaf::construct!(enabled, delay, jitter, runs).map(|(enabled, delay, jitter, runs)| {
+            enabled.then_some(LatencyArgs {
+                delay,
+                jitter,
+                runs,
+            })
+        })
+    };
 
     bpaf::construct!(Args {
         n,
@@ -49,6 +82,7 @@ fn args() -> Args {
         bench_signing,
         optimize_multiexp,
         custom_sec_level,
+        per_round_latency,
     })
     .to_options()
     .run()
@@ -65,6 +99,8 @@ fn main() {
 fn do_becnhmarks<L: SecurityLevel>(args: Args) {
     let mut rng = DevRng::new();
 
+    let bench_signing = args.bench_signing || args.per_round_latency.is_some();
+
     for n in args.n {
         println!("n = {n}");
         println!();
@@ -82,7 +118,7 @@ fn do_becnhmarks<L: SecurityLevel>(args: Args) {
         }
 
         let non_threshold_key_shares: Option<Vec<cggmp21::IncompleteKeyShare<E>>> =
-            if args.bench_non_threshold_keygen || args.bench_signing {
+            if args.bench_non_threshold_keygen || bench_signing {
                 let eid: [u8; 32] = rng.gen();
                 let eid = ExecutionId::new(&eid);
 
@@ -155,7 +191,7 @@ fn do_becnhmarks<L: SecurityLevel>(args: Args) {
             };
 
         let mut aux_data: Option<Vec<cggmp21::key_share::AuxInfo<L>>> =
-            if args.bench_aux_data_gen || args.bench_signing {
+            if args.bench_aux_data_gen || bench_signing {
                 let eid: [u8; 32] = rng.gen();
                 let eid = ExecutionId::new(&eid);
 
@@ -221,7 +257,7 @@ fn do_becnhmarks<L: SecurityLevel>(args: Args) {
             println!();
         }
 
-        if args.bench_signing {
+        if bench_signing {
             // Note that we don't parametrize signing performance tests by `t` as it doesn't make much sense
             // since performance of t-out-of-n protocol should be roughly the same as t-out-of-t
             let shares = non_threshold_key_shares
@@ -230,6 +266,7 @@ fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                 .zip(aux_data.expect("aux data is not generated"))
                 .map(|(key_share, aux_data)| {
                     cggmp21::key_share::KeyShare::from_parts((key_share, aux_data))
+                        .map_err(|err| err.into_error())
                 })
                 .collect::<Result<Vec<_>, _>>()
                 .expect("couldn't complete a share");
@@ -260,9 +297,273 @@ fn do_becnhmarks<L: SecurityLevel>(args: Args) {
             .expect_ok()
             .into_vec();
 
-            println!("Signing protocol");
-            println!("{}", perf_reports[0].clone().display_io(false));
-            println!();
+            if args.bench_signing {
+                println!("Signing protocol");
+                println!("{}", perf_reports[0].clone().display_io(false));
+                println!();
+            }
+
+            if let Some(latency_args) = &args.per_round_latency {
+                measure_per_round_latency(&mut rng, &shares, latency_args)
+                    .expect("per-round latency measurement failed");
+            }
+        }
+    }
+}
+
+/// Runs signing over network with simulated delay and reports percentiles of time each round
+/// took to complete
+///
+/// Parties are executed in parallel, each in its own thread. Round completion time includes
+/// computation, sending, and waiting for messages of other parties.
+fn measure_per_round_latency<L: SecurityLevel>(
+    rng: &mut DevRng,
+    shares: &[cggmp21::KeyShare<E, L>],
+    args: &LatencyArgs,
+) -> anyhow::Result<()> {
+    let n = u16::try_from(shares.len()).context("too many parties")?;
+    let signers_indexes_at_keygen = &(0..n).collect::<Vec<_>>();
+    let message_to_sign = DataToSign::digest::<Sha256>(b"Dfns rules!");
+
+    let mut rounds: Vec<(Option<&'static str>, Vec<std::time::Duration>)> = vec![];
+    for _ in 0..args.runs {
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let network = delayed_network::connect(n, args.delay, args.jitter, rng);
+
+        let reports = std::thread::scope(|s| {
+            let parties = shares
+                .iter()
+                .zip(network)
+                .zip(0..)
+                .map(|((share, delivery), i)| {
+                    let mut party_rng = rng.fork();
+                    s.spawn(move || {
+                        futures::executor::block_on(async move {
+                            let mut profiler = PerfProfiler::new();
+                            let party = round_based::MpcParty::connected(delivery);
+                            let _signature =
+                                cggmp21::signing(eid, i, signers_indexes_at_keygen, share)
+                                    .set_progress_tracer(&mut profiler)
+                                    .sign(&mut party_rng, party, message_to_sign)
+                                    .await
+                                    .context("signing failed")?;
+                            profiler.get_report().context("get perf report")
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+            parties
+                .into_iter()
+                .map(|party| party.join().expect("party panicked"))
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?;
+
+        for report in reports {
+            if rounds.is_empty() {
+                rounds = report
+                    .rounds
+                    .iter()
+                    .map(|round| (round.round_name, vec![]))
+                    .collect();
+            }
+            for ((_, durations), round) in rounds.iter_mut().zip(&report.rounds) {
+                durations.push(round.computation + round.sending + round.receiving);
+            }
+        }
+    }
+
+    println!(
+        "Signing per-round latency (delay = {:?}, jitter = {:?}, runs = {})",
+        args.delay, args.jitter, args.runs
+    );
+    for (k, (name, mut durations)) in rounds.into_iter().enumerate() {
+        durations.sort_unstable();
+        let percentile = |p: usize| durations[(durations.len() * p / 100).min(durations.len() - 1)];
+        println!(
+            "Round {} ({}): p50 = {:?}, p95 = {:?}, p99 = {:?}",
+            k + 1,
+            name.unwrap_or("unnamed"),
+            percentile(50),
+            percentile(95),
+            percentile(99),
+        );
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Simulated network that delivers every message after a delay
+///
+/// Synchronous simulation from `round_based::sim` drives parties as state machines, so they can't
+/// await on timers. Instead, this network schedules messages on a separate thread, and parties are
+/// meant to be executed in their own threads.
+mod delayed_network {
+    use std::collections::BTreeMap;
+    use std::convert::Infallible;
+    use std::pin::Pin;
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    };
+    use std::task::{Context, Poll};
+    use std::time::{Duration, Instant};
+
+    use futures::{channel::mpsc as async_mpsc, Sink, StreamExt};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use round_based::{Incoming, MessageDestination, MessageType, Outgoing, PartyIndex};
+
+    /// Incoming messages of a party
+    pub type Incomings<M> = futures::stream::Map<
+        async_mpsc::UnboundedReceiver<Incoming<M>>,
+        fn(Incoming<M>) -> Result<Incoming<M>, Infallible>,
+    >;
+
+    struct Scheduled<M> {
+        deliver_at: Instant,
+        recipient: PartyIndex,
+        msg: Incoming<M>,
+    }
+
+    /// Connects `n` parties
+    ///
+    /// Every message is delivered after `delay` plus a random extra delay up to `jitter`
+    pub fn connect<M: Clone + Send + 'static>(
+        n: u16,
+        delay: Duration,
+        jitter: Duration,
+        rng: &mut impl rand::RngCore,
+    ) -> Vec<(Incomings<M>, Outgoings<M>)> {
+        let (inboxes, incomings): (Vec<_>, Vec<_>) =
+            (0..n).map(|_| async_mpsc::unbounded()).unzip();
+        let (scheduler, scheduled) = mpsc::channel();
+        std::thread::spawn(move || deliver(scheduled, inboxes));
+
+        let next_id = Arc::new(AtomicU64::new(0));
+        incomings
+            .into_iter()
+            .zip(0..)
+            .map(|(incoming, i)| {
+                let incoming: Incomings<M> = incoming.map(Ok);
+                let outgoing = Outgoings {
+                    i,
+                    n,
+                    delay,
+                    jitter,
+                    rng: StdRng::from_seed(rng.gen()),
+                    next_id: next_id.clone(),
+                    scheduler: scheduler.clone(),
+                };
+                (incoming, outgoing)
+            })
+            .collect()
+    }
+
+    /// Forwards scheduled messages to recipients once they're due
+    ///
+    /// Exits when all parties dropped their outgoing channels and all messages are delivered
+    fn deliver<M>(
+        scheduled: mpsc::Receiver<Scheduled<M>>,
+        inboxes: Vec<async_mpsc::UnboundedSender<Incoming<M>>>,
+    ) {
+        let mut queue = BTreeMap::<(Instant, u64), Scheduled<M>>::new();
+        let mut seq = 0u64;
+        loop {
+            let next = match queue.first_key_value() {
+                Some(((deliver_at, _), _)) => {
+                    scheduled.recv_timeout(deliver_at.saturating_duration_since(Instant::now()))
+                }
+                None => scheduled
+                    .recv()
+                    .map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+            match next {
+                Ok(msg) => {
+                    queue.insert((msg.deliver_at, seq), msg);
+                    seq += 1;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) if queue.is_empty() => return,
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    if let Some(((deliver_at, _), _)) = queue.first_key_value() {
+                        std::thread::sleep(deliver_at.saturating_duration_since(Instant::now()))
+                    }
+                }
+            }
+
+            let now = Instant::now();
+            while let Some(entry) = queue.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                let msg = entry.remove();
+                // Recipient might have already completed the protocol
+                let _ = inboxes[usize::from(msg.recipient)].unbounded_send(msg.msg);
+            }
+        }
+    }
+
+    /// Outgoing messages of a party
+    pub struct Outgoings<M> {
+        i: PartyIndex,
+        n: u16,
+        delay: Duration,
+        jitter: Duration,
+        rng: StdRng,
+        next_id: Arc<AtomicU64>,
+        scheduler: mpsc::Sender<Scheduled<M>>,
+    }
+
+    impl<M: Clone> Sink<Outgoing<M>> for Outgoings<M> {
+        type Error = Infallible;
+
+        fn poll_ready(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, msg: Outgoing<M>) -> Result<(), Self::Error> {
+            let this = self.get_mut();
+            let (recipients, msg_type) = match msg.recipient {
+                MessageDestination::AllParties => (
+                    (0..this.n).filter(|j| *j != this.i).collect::<Vec<_>>(),
+                    MessageType::Broadcast,
+                ),
+                MessageDestination::OneParty(j) => (vec![j], MessageType::P2P),
+            };
+            let incoming = Incoming {
+                id: this.next_id.fetch_add(1, Ordering::Relaxed),
+                sender: this.i,
+                msg_type,
+                msg: msg.msg,
+            };
+            for recipient in recipients {
+                let jitter = this.rng.gen_range(Duration::ZERO..=this.jitter);
+                // Delivery thread can't exit while we hold the sender
+                let _ = this.scheduler.send(Scheduled {
+                    deliver_at: Instant::now() + this.delay + jitter,
+                    recipient,
+                    msg: incoming.clone(),
+                });
+            }
+            Ok(())
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
         }
     }
 }
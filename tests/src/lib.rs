@@ -174,6 +174,28 @@ pub fn random_derivation_path(rng: &mut impl rand::RngCore) -> Vec<u32> {
         .collect::<Vec<_>>()
 }
 
+/// Timer that fires immediately: the round times out unless its messages were already received
+#[derive(Clone, Copy)]
+pub struct ImmediateTimer;
+
+impl cggmp21::timeout::Timer for ImmediateTimer {
+    type Sleep = std::future::Ready<()>;
+    fn sleep(&self, _duration: std::time::Duration) -> Self::Sleep {
+        std::future::ready(())
+    }
+}
+
+/// Timer that never fires
+#[derive(Clone, Copy)]
+pub struct NeverTimer;
+
+impl cggmp21::timeout::Timer for NeverTimer {
+    type Sleep = std::future::Pending<()>;
+    fn sleep(&self, _duration: std::time::Duration) -> Self::Sleep {
+        std::future::pending()
+    }
+}
+
 /// Parameters per each curve that are needed in tests
 pub trait CurveParams: Curve {
     /// Which HD derivation algorithm to use with that curve
@@ -0,0 +1,129 @@
+//! Counts heap allocations made by signing
+//!
+//! Lives in its own test binary: it replaces global allocator, and makes GMP allocate via Rust
+//! allocator so that allocations of big integers are counted too. The latter must happen before
+//! any big integer is allocated.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ffi::c_void;
+
+use rand::Rng;
+use rand_dev::DevRng;
+use sha2::Sha256;
+
+use cggmp21::signing::{DataToSign, SigningArena};
+use cggmp21::supported_curves::Secp256k1;
+use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+
+/// Global allocator that counts allocations made by each thread
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_allocation() {
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+/// Amount of allocations made by the current thread so far
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc(layout)
+    }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc_zeroed(layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// GMP passes size of allocation on realloc and free, so layout can be restored
+fn gmp_layout(size: usize) -> Layout {
+    Layout::from_size_align(size.max(1), 16).expect("invalid layout")
+}
+
+extern "C" fn gmp_alloc(size: usize) -> *mut c_void {
+    unsafe { std::alloc::alloc(gmp_layout(size)).cast() }
+}
+
+unsafe extern "C" fn gmp_realloc(
+    ptr: *mut c_void,
+    old_size: usize,
+    new_size: usize,
+) -> *mut c_void {
+    std::alloc::realloc(ptr.cast(), gmp_layout(old_size), new_size.max(1)).cast()
+}
+
+unsafe extern "C" fn gmp_free(ptr: *mut c_void, size: usize) {
+    std::alloc::dealloc(ptr.cast(), gmp_layout(size))
+}
+
+#[test]
+fn signing_arena_reduces_allocations() {
+    // Safety: no big integer is allocated yet, so none of them is freed with another allocator
+    unsafe {
+        gmp_mpfr_sys::gmp::set_memory_functions(Some(gmp_alloc), Some(gmp_realloc), Some(gmp_free))
+    };
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 3, false)
+        .expect("retrieve cached shares");
+    let participants = &[0, 1, 2];
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let mut arenas = std::iter::repeat_with(SigningArena::new)
+        .take(participants.len())
+        .collect::<Vec<_>>();
+
+    // Runs signing, returns amount of allocations made during the session
+    let mut sign = |arenas: Option<&mut [SigningArena]>| {
+        let eid: [u8; 32] = rng.gen();
+        let arenas: Vec<Option<&mut SigningArena>> = match arenas {
+            Some(arenas) => arenas.iter_mut().map(Some).collect(),
+            None => participants.iter().map(|_| None).collect(),
+        };
+
+        let before = allocations();
+        round_based::sim::run_with_setup(shares.iter().zip(arenas), |i, party, (share, arena)| {
+            let mut party_rng = rng.fork();
+            async move {
+                let signing = cggmp21::signing(ExecutionId::new(&eid), i, participants, share);
+                let signing = match arena {
+                    Some(arena) => signing.set_arena(arena),
+                    None => signing,
+                };
+                signing.sign(&mut party_rng, party, message_to_sign).await
+            }
+        })
+        .unwrap()
+        .expect_ok();
+        allocations() - before
+    };
+
+    let without_arena = sign(None);
+    // First session populates the arena
+    sign(Some(arenas.as_mut_slice()));
+    let with_arena = sign(Some(arenas.as_mut_slice()));
+
+    println!(
+        "Allocations per signing session: {without_arena} without arena, {with_arena} with arena"
+    );
+    assert!(with_arena < without_arena);
+}
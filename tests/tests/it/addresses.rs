@@ -0,0 +1,29 @@
+use cggmp21::{
+    addresses::{self, BitcoinNetwork},
+    supported_curves::Secp256k1,
+};
+use generic_ec::{NonZero, Point};
+
+#[test]
+fn addresses_of_generator() {
+    // Public key corresponding to secret key `1`
+    let public_key = NonZero::from_point(Point::<Secp256k1>::generator().to_point()).unwrap();
+
+    assert_eq!(
+        hex::encode(addresses::hash160(&public_key)),
+        "751e76e8199196d454941c45d1b3a323f1433bd6"
+    );
+    // Test vector from BIP-173
+    assert_eq!(
+        addresses::bitcoin_p2wpkh(&public_key, BitcoinNetwork::Mainnet),
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    );
+    assert_eq!(
+        addresses::bitcoin_p2wpkh(&public_key, BitcoinNetwork::Testnet),
+        "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+    );
+    assert_eq!(
+        addresses::ethereum(&public_key),
+        "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+    );
+}
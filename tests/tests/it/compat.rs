@@ -0,0 +1,48 @@
+use cggmp21::{compat::gg20, key_share::IncompleteKeyShare, security_level::SecurityLevel128};
+use generic_ec::{Curve, Point, Scalar};
+
+cggmp21_tests::test_suite! {
+    test: gg20_public_keys_lie_on_committed_polynomial,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+        t3n5: (Some(3), 5),
+    }
+}
+fn gg20_public_keys_lie_on_committed_polynomial<E: Curve>(t: Option<u16>, n: u16) {
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let core: &IncompleteKeyShare<E> = shares[0].as_ref();
+    let public_keys = gg20::public_keys(core).unwrap();
+
+    let t = t.unwrap_or(n);
+    assert_eq!(public_keys.t, t - 1);
+    assert_eq!(public_keys.n, n);
+    assert_eq!(public_keys.vss_scheme.parameters.threshold, t - 1);
+    assert_eq!(public_keys.vss_scheme.parameters.share_count, n);
+    assert_eq!(public_keys.y_sum_s.0, *core.shared_public_key);
+    assert_eq!(public_keys.pk_vec.len(), usize::from(n));
+
+    let commitments = &public_keys.vss_scheme.commitments;
+    assert_eq!(commitments.len(), usize::from(t));
+    assert_eq!(commitments[0].0, *core.shared_public_key);
+    for (j, pk_j) in (1..).zip(&public_keys.pk_vec) {
+        let x = Scalar::<E>::from(j);
+        let evaluated = commitments
+            .iter()
+            .rev()
+            .fold(Point::zero(), |acc, c| acc * x + c.0);
+        assert_eq!(evaluated, pk_j.0);
+    }
+
+    let json = serde_json::to_value(&public_keys).unwrap();
+    assert_eq!(json["y_sum_s"]["curve"], E::CURVE_NAME);
+    assert_eq!(
+        json["y_sum_s"]["point"],
+        hex::encode(core.shared_public_key.to_bytes(true))
+    );
+    let restored: gg20::PublicKeys<E> = serde_json::from_value(json).unwrap();
+    assert_eq!(restored, public_keys);
+}
@@ -0,0 +1,124 @@
+use generic_ec::{Curve, Point};
+use rand::{seq::SliceRandom, Rng};
+use rand_dev::DevRng;
+
+use cggmp21::{
+    key_share::{reconstruct_secret_key, IncompleteKeyShare},
+    security_level::SecurityLevel128,
+    ExecutionId,
+};
+
+cggmp21_tests::test_suite! {
+    test: enroll_party_works,
+    generics: all_curves,
+    suites: {
+        t2n2: (2, 2, false),
+        t2n3: (2, 3, false),
+        t3n4: (3, 4, false),
+        t2n3_reliable: (2, 3, true),
+    }
+}
+fn enroll_party_works<E: Curve>(t: u16, old_n: u16, reliable_broadcast: bool) {
+    let mut rng = DevRng::new();
+
+    let old_shares: Vec<IncompleteKeyShare<E>> =
+        cggmp21::trusted_dealer::builder::<E, SecurityLevel128>(old_n)
+            .set_threshold(Some(t))
+            .generate_core_shares(&mut rng)
+            .unwrap();
+    let key_info: &cggmp21::key_share::KeyInfo<E> = old_shares[0].as_ref();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let new_n = old_n + 1;
+
+    let new_shares = round_based::sim::run(new_n, |i, party| {
+        let mut party_rng = rng.fork();
+        let builder = match old_shares.get(usize::from(i)) {
+            Some(share) => cggmp21::key_share::enroll_party(eid, share, old_n, new_n),
+            None => cggmp21::enroll::EnrollBuilder::new_party(eid, key_info, old_n, new_n),
+        };
+        async move {
+            builder
+                .enforce_reliable_broadcast(reliable_broadcast)
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    for (i, share) in (0u16..).zip(&new_shares) {
+        assert_eq!(share.i, i);
+        assert_eq!(share.n(), new_n);
+        assert_eq!(share.min_signers(), t);
+        assert_eq!(share.shared_public_key, old_shares[0].shared_public_key);
+        assert_eq!(share.public_shares, new_shares[0].public_shares);
+    }
+
+    // Any `t` parties, including the newcomer, can reconstruct the key
+    let mut t_shares = new_shares[..usize::from(old_n)]
+        .choose_multiple(&mut rng, usize::from(t) - 1)
+        .cloned()
+        .collect::<Vec<_>>();
+    t_shares.push(new_shares[usize::from(old_n)].clone());
+    let sk = reconstruct_secret_key(&t_shares).unwrap();
+    assert_eq!(Point::generator() * sk, old_shares[0].shared_public_key);
+}
+
+cggmp21_tests::test_suite! {
+    test: enroll_party_blames_inconsistent_holder,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn enroll_party_blames_inconsistent_holder<E: Curve>() {
+    let mut rng = DevRng::new();
+    let (t, old_n) = (2, 3);
+    let new_n = old_n + 1;
+
+    let old_shares: Vec<IncompleteKeyShare<E>> =
+        cggmp21::trusted_dealer::builder::<E, SecurityLevel128>(old_n)
+            .set_threshold(Some(t))
+            .generate_core_shares(&mut rng)
+            .unwrap();
+    // Party 1 uses a share of another key, so it deals a wrong value
+    let other_shares: Vec<IncompleteKeyShare<E>> =
+        cggmp21::trusted_dealer::builder::<E, SecurityLevel128>(old_n)
+            .set_threshold(Some(t))
+            .generate_core_shares(&mut rng)
+            .unwrap();
+    let faulty = 1u16;
+    let key_info: &cggmp21::key_share::KeyInfo<E> = old_shares[0].as_ref();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let results = round_based::sim::run(new_n, |i, party| {
+        let mut party_rng = rng.fork();
+        let builder = if i == faulty {
+            cggmp21::key_share::enroll_party(eid, &other_shares[usize::from(i)], old_n, new_n)
+        } else if i < old_n {
+            cggmp21::key_share::enroll_party(eid, &old_shares[usize::from(i)], old_n, new_n)
+        } else {
+            cggmp21::enroll::EnrollBuilder::new_party(eid, key_info, old_n, new_n)
+        };
+        async move { builder.start(&mut party_rng, party).await.map(|_| ()) }
+    })
+    .unwrap()
+    .into_vec();
+
+    for (i, result) in (0u16..).zip(results) {
+        if i == faulty {
+            continue;
+        }
+        let err = result.unwrap_err();
+        assert_eq!(
+            err.invalid_commitment(),
+            Some(vec![faulty]),
+            "party {i}: {err:?}"
+        );
+    }
+}
@@ -4,7 +4,7 @@ use rand::Rng;
 use sha2::Sha256;
 
 use cggmp21::{
-    key_share::{DirtyKeyShare, Validate},
+    key_share::{AnyKeyShare, DirtyKeyShare, KeyShare, Validate},
     security_level::SecurityLevel128,
     ExecutionId,
 };
@@ -57,10 +57,16 @@ where
     for (i, key_share) in key_shares.iter().enumerate() {
         let i = i as u16;
         assert_eq!(i, key_share.core.i);
+        assert_eq!(i, key_share.party_index());
         assert_eq!(
             key_share.core.shared_public_key,
             key_shares[0].core.shared_public_key
         );
+        assert_eq!(
+            key_share.shared_public_key(),
+            key_share.core.shared_public_key
+        );
+        assert_eq!(key_share.public_shares(), key_share.core.public_shares);
         assert_eq!(
             key_share.core.public_shares,
             key_shares[0].core.public_shares
@@ -85,6 +91,9 @@ where
     for key_share in &key_shares {
         assert_eq!(key_share.chain_code, shares[0].chain_code);
     }
+    for key_share in &key_shares {
+        assert_eq!(key_share.core.epoch, shares[0].core.epoch + 1);
+    }
 
     // attempt to sign with new shares and verify the signature
 
@@ -108,18 +117,47 @@ where
 
     sig.verify(&key_shares[0].core.shared_public_key, &message_to_sign)
         .expect("signature is not valid");
+
+    // signing with a mix of old and refreshed shares is refused
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let mixed_shares = std::iter::once(&shares[0])
+        .chain(&key_shares[1..])
+        .collect::<Vec<_>>();
+    let results = round_based::sim::run_with_setup(mixed_shares, |_i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, share.core.i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .into_vec();
+    for (i, result) in results.into_iter().enumerate() {
+        let err = result.expect_err("signing with mixed epochs succeeded");
+        assert!(err.is_epoch_mismatch(), "party {i}: {err:?}");
+        // Every signer, including the one holding outdated share, sees the same epochs
+        let epochs = err.epoch_mismatch().unwrap();
+        assert_eq!(epochs.len(), usize::from(n));
+        assert_eq!(epochs[0], shares[0].core.epoch);
+        assert!(epochs[1..].iter().all(|e| *e == shares[0].core.epoch + 1));
+    }
 }
 
 cggmp21_tests::test_suite! {
     test: aux_gen_works,
     generics: all_curves,
     suites: {
-        t2n3: (2, 3, false),
-        t3n5: (3, 5, false),
-        t3n5_reliable: (3, 5, true),
+        t2n3: (2, 3, false, false),
+        t3n5: (3, 5, false, false),
+        t3n5_reliable: (3, 5, true, false),
+        t2n3_precomputed: (2, 3, false, true),
     }
 }
-fn aux_gen_works<E: generic_ec::Curve>(t: u16, n: u16, reliable_broadcast: bool)
+fn aux_gen_works<E: generic_ec::Curve>(t: u16, n: u16, reliable_broadcast: bool, precompute: bool)
 where
     Point<E>: generic_ec::coords::HasAffineX<E>,
 {
@@ -138,11 +176,17 @@ where
     let aux_infos = round_based::sim::run(n, |i, party| {
         let mut party_rng = rng.fork();
         let pregenerated_data = primes.next().expect("Can't fetch primes");
+        let precomputed = precompute.then(|| {
+            cggmp21::key_refresh::precompute_aux_info(&mut party_rng, &pregenerated_data).unwrap()
+        });
         async move {
-            cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
-                .enforce_reliable_broadcast(reliable_broadcast)
-                .start(&mut party_rng, party)
-                .await
+            let aux_gen = cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
+                .enforce_reliable_broadcast(reliable_broadcast);
+            let aux_gen = match precomputed {
+                Some(precomputed) => aux_gen.set_precomputed(precomputed),
+                None => aux_gen,
+            };
+            aux_gen.start(&mut party_rng, party).await
         }
     })
     .unwrap()
@@ -158,6 +202,7 @@ where
             DirtyKeyShare {
                 core: share.into_inner().core,
                 aux: aux.into_inner(),
+                metadata: Default::default(),
             }
             .validate()
             .unwrap()
@@ -194,3 +239,125 @@ where
     sig.verify(&key_shares[0].core.shared_public_key, &message_to_sign)
         .expect("signature is not valid");
 }
+
+#[test]
+fn aux_gen_refuses_precompute_for_other_primes() {
+    let mut rng = rand_dev::DevRng::new();
+    let n = 2;
+    let primes = cggmp21_tests::CACHED_PRIMES
+        .iter::<SecurityLevel128>()
+        .take(2 * usize::from(n))
+        .collect::<Vec<_>>();
+    let (primes, other_primes) = primes.split_at(n.into());
+
+    // Each party precomputes data from primes that differ from the ones given to the protocol
+    let setups = primes
+        .iter()
+        .zip(other_primes)
+        .map(|(primes, other_primes)| {
+            let precomputed =
+                cggmp21::key_refresh::precompute_aux_info(&mut rng, other_primes).unwrap();
+            (primes.clone(), precomputed)
+        })
+        .collect::<Vec<_>>();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let results = round_based::sim::run_with_setup(setups, |i, party, (primes, precomputed)| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::aux_info_gen(eid, i, n, primes)
+                .set_precomputed(precomputed)
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .into_vec();
+    for result in results {
+        let Err(err) = result else {
+            panic!("protocol unexpectedly succeeded")
+        };
+        assert!(err.is_precomputed_mismatch(), "{err:?}");
+    }
+}
+
+#[test]
+fn key_share_checks_that_keygen_and_aux_gen_had_same_parties() {
+    type E = cggmp21::supported_curves::Secp256k1;
+    const IDENTITIES: &[&[u8]] = &[b"party 0", b"party 1"];
+    const OTHER_IDENTITIES: &[&[u8]] = &[b"party 0", b"impostor"];
+
+    let mut rng = rand_dev::DevRng::new();
+    let n = 2;
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let cores = round_based::sim::run(n, |i, party| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_party_identity(IDENTITIES[usize::from(i)])
+                .set_peer_identities(IDENTITIES)
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+    assert!(cores[0].party_set_commitment.is_some());
+
+    let mut run_aux_gen = |identities: Option<&'static [&'static [u8]]>| {
+        let mut primes = cggmp21_tests::CACHED_PRIMES.iter::<SecurityLevel128>();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            let primes = primes.next().expect("Can't fetch primes");
+            async move {
+                let aux_gen = cggmp21::aux_info_gen(eid, i, n, primes);
+                let aux_gen = match identities {
+                    Some(identities) => aux_gen
+                        .set_party_identity(identities[usize::from(i)])
+                        .set_peer_identities(identities),
+                    None => aux_gen,
+                };
+                aux_gen.start(&mut party_rng, party).await
+            }
+        })
+        .unwrap()
+        .expect_ok()
+        .into_vec()
+    };
+
+    // Aux info generated by the same parties can be combined with key info
+    for (core, aux) in cores.iter().zip(run_aux_gen(Some(IDENTITIES))) {
+        assert_eq!(aux.party_set_commitment, core.party_set_commitment);
+        KeyShare::from_parts((core.clone(), aux)).unwrap();
+    }
+    // Aux info that doesn't record identities can be combined as well
+    for (core, aux) in cores.iter().zip(run_aux_gen(None)) {
+        assert!(aux.party_set_commitment.is_none());
+        KeyShare::from_parts((core.clone(), aux)).unwrap();
+    }
+    // Aux info generated by another set of parties is rejected
+    for (core, aux) in cores.iter().zip(run_aux_gen(Some(OTHER_IDENTITIES))) {
+        let Err(err) = KeyShare::from_parts((core.clone(), aux)) else {
+            panic!("key share was assembled from parts generated by different parties")
+        };
+        let err = err.into_error();
+        assert!(err.is_party_set_mismatch(), "{err:?}");
+    }
+}
+
+#[test]
+fn aux_gen_duration_estimate_grows_with_amount_of_parties() {
+    let estimate = cggmp21::key_refresh::estimate_aux_info_gen_duration::<SecurityLevel128>;
+
+    let small = estimate(3);
+    let large = estimate(10);
+    assert!(!small.is_zero());
+    assert!(small < large, "{small:?} >= {large:?}");
+}
@@ -1,9 +1,11 @@
-use std::iter;
+use std::{cell::RefCell, iter};
 
-use generic_ec::{Curve, Point};
-use rand::{seq::SliceRandom, Rng};
+use generic_ec::{Curve, Point, Scalar};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use rand_dev::DevRng;
+use round_based::state_machine::{ProceedResult, StateMachine};
 
+use cggmp21::keygen::equivocation::{self, BroadcastSigner, BroadcastVerifier};
 use cggmp21::{key_share::reconstruct_secret_key, ExecutionId};
 
 cggmp21_tests::test_suite! {
@@ -140,6 +142,70 @@ fn threshold_keygen_sync_works<E: Curve>(t: u16, n: u16, hd_wallet: bool) {
     validate_keygen_output(&mut rng, &key_shares, hd_wallet);
 }
 
+cggmp21_tests::test_suite! {
+    test: key_material_comes_from_secret_rng,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn key_material_comes_from_secret_rng<E: Curve>(t: Option<u16>, n: u16) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let secret_seeds: Vec<[u8; 32]> = iter::repeat_with(|| rng.gen()).take(n.into()).collect();
+
+    // Runs keygen where secret rng of each party is seeded with `secret_seeds`, and
+    // everything else is sampled from `rng`
+    let run_keygen = |rng: &mut DevRng| {
+        match t {
+            Some(t) => round_based::sim::run(n, |i, party| {
+                let mut party_rng = rng.fork();
+                let mut secret_rng = StdRng::from_seed(secret_seeds[usize::from(i)]);
+                async move {
+                    let keygen = cggmp21::keygen::<E>(eid, i, n)
+                        .set_threshold(t)
+                        .set_secret_rng(&mut secret_rng);
+                    #[cfg(feature = "hd-wallet")]
+                    let keygen = keygen.hd_wallet(true);
+                    keygen.start(&mut party_rng, party).await
+                }
+            }),
+            None => round_based::sim::run(n, |i, party| {
+                let mut party_rng = rng.fork();
+                let mut secret_rng = StdRng::from_seed(secret_seeds[usize::from(i)]);
+                async move {
+                    let keygen = cggmp21::keygen::<E>(eid, i, n).set_secret_rng(&mut secret_rng);
+                    #[cfg(feature = "hd-wallet")]
+                    let keygen = keygen.hd_wallet(true);
+                    keygen.start(&mut party_rng, party).await
+                }
+            }),
+        }
+        .unwrap()
+        .expect_ok()
+        .into_vec()
+    };
+
+    // Ephemeral randomness differs, but key material must be the same
+    let key_shares1 = run_keygen(&mut rng.fork());
+    let key_shares2 = run_keygen(&mut rng.fork());
+
+    validate_keygen_output(&mut rng, &key_shares1, cfg!(feature = "hd-wallet"));
+    for (share1, share2) in key_shares1.iter().zip(&key_shares2) {
+        assert_eq!(
+            AsRef::<Scalar<E>>::as_ref(&share1.x),
+            AsRef::<Scalar<E>>::as_ref(&share2.x)
+        );
+        assert_eq!(share1.public_shares, share2.public_shares);
+        assert_eq!(share1.shared_public_key, share2.shared_public_key);
+        #[cfg(feature = "hd-wallet")]
+        assert_eq!(share1.chain_code, share2.chain_code);
+    }
+}
+
 fn validate_keygen_output<E: generic_ec::Curve>(
     rng: &mut impl rand::RngCore,
     key_shares: &[cggmp21::IncompleteKeyShare<E>],
@@ -180,3 +246,1956 @@ fn validate_keygen_output<E: generic_ec::Curve>(
     let sk = reconstruct_secret_key(&t_shares).unwrap();
     assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
 }
+
+cggmp21_tests::test_suite! {
+    test: weighted_keygen_works,
+    generics: all_curves,
+    suites: {
+        t3w111: (3, &[1, 1, 1]),
+        t4w321: (4, &[3, 2, 1]),
+        t5w1213: (5, &[1, 2, 1, 3]),
+    }
+}
+fn weighted_keygen_works<E: Curve>(t: u16, weights: &[u16]) {
+    let mut rng = DevRng::new();
+    let n: u16 = weights.len().try_into().unwrap();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let key_shares = round_based::sim::run(n, |i, party| {
+        let mut party_rng = rng.fork();
+
+        async move {
+            let keygen = cggmp21::keygen::<E>(eid, i, n).set_weights(t, weights);
+
+            #[cfg(feature = "hd-wallet")]
+            let keygen = keygen.hd_wallet(false);
+
+            keygen.start(&mut party_rng, party).await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    for (j, sub_shares) in key_shares.iter().enumerate() {
+        assert_eq!(sub_shares.len(), usize::from(weights[j]));
+    }
+    let all_sub_shares = key_shares.into_iter().flatten().collect::<Vec<_>>();
+    validate_keygen_output(&mut rng, &all_sub_shares, false);
+    assert_eq!(all_sub_shares[0].weights(), Some(weights));
+
+    use cggmp21::key_share::AnyKeyShare;
+    let total_weight: u16 = weights.iter().sum();
+    assert_eq!(all_sub_shares[0].parties_count(), n);
+    assert_eq!(all_sub_shares[0].n(), total_weight);
+    assert_eq!(all_sub_shares[0].threshold(), Some(t));
+}
+
+cggmp21_tests::test_suite! {
+    test: weighted_keygen_rejects_invalid_weights,
+    generics: all_curves,
+    suites: {
+        t7w321: (7, &[3, 2, 1]),
+        t2w101: (2, &[1, 0, 1]),
+        t2w11: (2, &[1, 1]),
+    }
+}
+fn weighted_keygen_rejects_invalid_weights<E: Curve>(t: u16, weights: &[u16]) {
+    let mut rng = DevRng::new();
+    let n = 3;
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // Protocol must return an error before communicating with other parties
+    let party = round_based::MpcParty::connected((
+        futures::stream::pending::<Result<round_based::Incoming<_>, std::convert::Infallible>>(),
+        futures::sink::drain(),
+    ));
+    let result = futures::executor::block_on(
+        cggmp21::keygen::<E>(eid, 0, n)
+            .set_weights(t, weights)
+            .start(&mut rng, party),
+    );
+    assert!(matches!(result, Err(err) if err.is_invalid_weights()));
+}
+
+cggmp21_tests::test_suite! {
+    test: schnorr_contribution_verifies,
+    generics: all_curves,
+    suites: {
+        n3: (3),
+    }
+}
+fn schnorr_contribution_verifies<E: Curve>(n: u16) {
+    use cggmp21::{keygen::msg::non_threshold::Msg, security_level::SecurityLevel128};
+    type KeygenMsg<E> = Msg<E, SecurityLevel128, sha2::Sha256>;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let mut party_rng = iter::repeat_with(|| rng.fork())
+        .take(n.into())
+        .collect::<Vec<_>>();
+    let transcript = iter::repeat_with(|| RefCell::new(vec![]))
+        .take(n.into())
+        .collect::<Vec<_>>();
+
+    let mut simulation = round_based::sim::Simulation::with_capacity(n);
+    for ((i, party_rng), sent) in (0..).zip(&mut party_rng).zip(&transcript) {
+        let keygen = cggmp21::keygen::<E>(eid, i, n);
+
+        #[cfg(feature = "hd-wallet")]
+        let keygen = keygen.hd_wallet(false);
+
+        simulation.add_party(RecordSent {
+            party: keygen.into_state_machine(party_rng),
+            sent,
+        })
+    }
+    simulation.run().unwrap().expect_ok();
+
+    let round2 = transcript
+        .iter()
+        .map(|sent| {
+            sent.borrow()
+                .iter()
+                .find_map(|msg: &KeygenMsg<E>| match msg {
+                    Msg::Round2(msg) => Some(msg.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let round3 = transcript
+        .iter()
+        .map(|sent| {
+            sent.borrow()
+                .iter()
+                .find_map(|msg: &KeygenMsg<E>| match msg {
+                    Msg::Round3(msg) => Some(msg.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let mut rid = round2[0].rid.clone();
+    for msg in &round2[1..] {
+        rid.as_mut()
+            .iter_mut()
+            .zip(msg.rid.as_ref())
+            .for_each(|(r, r_j)| *r ^= r_j);
+    }
+
+    for (j, (decommit, sch_proof)) in (0u16..).zip(round2.iter().zip(&round3)) {
+        assert!(cggmp21::keygen::verify_schnorr_contribution::<
+            E,
+            SecurityLevel128,
+            sha2::Sha256,
+        >(eid, j, rid.as_ref(), decommit, sch_proof));
+
+        // Proof must not verify on behalf of another party
+        let other = (j + 1) % n;
+        assert!(!cggmp21::keygen::verify_schnorr_contribution::<
+            E,
+            SecurityLevel128,
+            sha2::Sha256,
+        >(eid, other, rid.as_ref(), decommit, sch_proof));
+        // ... nor with someone else's decommitment
+        assert!(!cggmp21::keygen::verify_schnorr_contribution::<
+            E,
+            SecurityLevel128,
+            sha2::Sha256,
+        >(
+            eid,
+            j,
+            rid.as_ref(),
+            &round2[usize::from(other)],
+            sch_proof
+        ));
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_reports_reliability_check_failure,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_reports_reliability_check_failure<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::keygen::msg::{non_threshold, threshold};
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let mut party_rng = iter::repeat_with(|| rng.fork())
+        .take(n.into())
+        .collect::<Vec<_>>();
+
+    // Party 0 receives the same commitment from all other parties: the first one it received
+    let errors = match t {
+        Some(t) => {
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut party_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .enforce_reliable_broadcast(true)
+                    .into_state_machine(party_rng);
+                let mut first_round1 = None;
+                simulation.add_party(TamperReceived {
+                    party,
+                    tamper: move |msg: &mut threshold::Msg<_, _, _>| {
+                        if let (0, threshold::Msg::Round1(_)) = (i, &msg) {
+                            *msg = first_round1.get_or_insert_with(|| msg.clone()).clone();
+                        }
+                    },
+                });
+            }
+            simulation.run()
+        }
+        None => {
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut party_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .enforce_reliable_broadcast(true)
+                    .into_state_machine(party_rng);
+                let mut first_round1 = None;
+                simulation.add_party(TamperReceived {
+                    party,
+                    tamper: move |msg: &mut non_threshold::Msg<_, _, _>| {
+                        if let (0, non_threshold::Msg::Round1(_)) = (i, &msg) {
+                            *msg = first_round1.get_or_insert_with(|| msg.clone()).clone();
+                        }
+                    },
+                });
+            }
+            simulation.run()
+        }
+    }
+    .unwrap()
+    .into_vec()
+    .into_iter()
+    .map(|r| r.err().expect("keygen must fail"))
+    .collect::<Vec<_>>();
+
+    for (i, err) in (0u16..).zip(&errors) {
+        let failure = err
+            .reliability_check_failure()
+            .unwrap_or_else(|| panic!("party {i}: {err:?}"));
+        if i == 0 {
+            // Everyone disagrees with party 0 and agrees among themselves
+            assert!(failure.agreed.is_empty());
+            assert_eq!(failure.mismatched.len(), usize::from(n - 1));
+            assert!(!failure.is_likely_equivocation());
+        } else {
+            // Only party 0 disagrees
+            assert_eq!(failure.agreed.len(), usize::from(n - 2));
+            assert_eq!(failure.mismatched.len(), 1);
+            assert_eq!(failure.mismatched[0].0, 0);
+            assert_ne!(failure.mismatched[0].2, failure.local_hash);
+            assert!(failure.is_likely_equivocation());
+        }
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_negotiates_protocol_version,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_negotiates_protocol_version<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::keygen::msg::{non_threshold, threshold};
+    use cggmp21::keygen::version::{MsgVersion, ProtocolVersion};
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // Parties running the same version complete keygen
+    match t {
+        Some(t) => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .negotiate_version()
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        }),
+        None => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .negotiate_version()
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        }),
+    }
+    .unwrap()
+    .expect_ok();
+
+    // Last party runs a different version: it announces it to others, and it sees others
+    // running a version different from its own
+    let other_version = ProtocolVersion {
+        format_epoch: ProtocolVersion::CURRENT.format_epoch + 1,
+        ..ProtocolVersion::CURRENT
+    };
+    let outdated = n - 1;
+    let mut party_rng = iter::repeat_with(|| rng.fork())
+        .take(n.into())
+        .collect::<Vec<_>>();
+    let errors = match t {
+        Some(t) => {
+            let tamper = move |msg: &mut threshold::Msg<_, _, _>| {
+                if let threshold::Msg::Version(MsgVersion(version)) = msg {
+                    *version = other_version;
+                }
+            };
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut party_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .negotiate_version()
+                    .into_state_machine(party_rng);
+                if i == outdated {
+                    simulation.add_party(TamperReceived {
+                        party: TamperSent { party, tamper },
+                        tamper,
+                    });
+                } else {
+                    simulation.add_party(TamperReceived {
+                        party: TamperSent {
+                            party,
+                            tamper: |_: &mut _| {},
+                        },
+                        tamper: |_: &mut _| {},
+                    });
+                }
+            }
+            simulation.run()
+        }
+        None => {
+            let tamper = move |msg: &mut non_threshold::Msg<_, _, _>| {
+                if let non_threshold::Msg::Version(MsgVersion(version)) = msg {
+                    *version = other_version;
+                }
+            };
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut party_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .negotiate_version()
+                    .into_state_machine(party_rng);
+                if i == outdated {
+                    simulation.add_party(TamperReceived {
+                        party: TamperSent { party, tamper },
+                        tamper,
+                    });
+                } else {
+                    simulation.add_party(TamperReceived {
+                        party: TamperSent {
+                            party,
+                            tamper: |_: &mut _| {},
+                        },
+                        tamper: |_: &mut _| {},
+                    });
+                }
+            }
+            simulation.run()
+        }
+    }
+    .unwrap()
+    .into_vec()
+    .into_iter()
+    .map(|r| r.err().expect("keygen must fail"))
+    .collect::<Vec<_>>();
+
+    for (i, err) in (0u16..).zip(&errors) {
+        let parties = err
+            .version_mismatch()
+            .unwrap_or_else(|| panic!("party {i}: {err:?}"));
+        if i == outdated {
+            assert_eq!(parties, (0..outdated).collect::<Vec<_>>());
+        } else {
+            assert_eq!(parties, [outdated]);
+        }
+    }
+}
+
+/// State machine wrapper that modifies messages received by the party
+struct TamperReceived<SM, F> {
+    party: SM,
+    tamper: F,
+}
+
+impl<SM, F> StateMachine for TamperReceived<SM, F>
+where
+    SM: StateMachine,
+    F: FnMut(&mut SM::Msg),
+{
+    type Output = SM::Output;
+    type Msg = SM::Msg;
+
+    fn proceed(&mut self) -> ProceedResult<Self::Output, Self::Msg> {
+        self.party.proceed()
+    }
+
+    fn received_msg(
+        &mut self,
+        mut msg: round_based::Incoming<Self::Msg>,
+    ) -> Result<(), round_based::Incoming<Self::Msg>> {
+        (self.tamper)(&mut msg.msg);
+        self.party.received_msg(msg)
+    }
+}
+
+/// State machine wrapper that modifies messages sent by the party
+struct TamperSent<SM, F> {
+    party: SM,
+    tamper: F,
+}
+
+impl<SM, F> StateMachine for TamperSent<SM, F>
+where
+    SM: StateMachine,
+    F: FnMut(&mut SM::Msg),
+{
+    type Output = SM::Output;
+    type Msg = SM::Msg;
+
+    fn proceed(&mut self) -> ProceedResult<Self::Output, Self::Msg> {
+        let mut result = self.party.proceed();
+        if let ProceedResult::SendMsg(outgoing) = &mut result {
+            (self.tamper)(&mut outgoing.msg);
+        }
+        result
+    }
+
+    fn received_msg(
+        &mut self,
+        msg: round_based::Incoming<Self::Msg>,
+    ) -> Result<(), round_based::Incoming<Self::Msg>> {
+        self.party.received_msg(msg)
+    }
+}
+
+/// State machine wrapper that records all messages sent by the party
+struct RecordSent<'r, SM: StateMachine> {
+    party: SM,
+    sent: &'r RefCell<Vec<SM::Msg>>,
+}
+
+impl<SM> StateMachine for RecordSent<'_, SM>
+where
+    SM: StateMachine,
+    SM::Msg: Clone,
+{
+    type Output = SM::Output;
+    type Msg = SM::Msg;
+
+    fn proceed(&mut self) -> ProceedResult<Self::Output, Self::Msg> {
+        let result = self.party.proceed();
+        if let ProceedResult::SendMsg(outgoing) = &result {
+            self.sent.borrow_mut().push(outgoing.msg.clone());
+        }
+        result
+    }
+
+    fn received_msg(
+        &mut self,
+        msg: round_based::Incoming<Self::Msg>,
+    ) -> Result<(), round_based::Incoming<Self::Msg>> {
+        self.party.received_msg(msg)
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_detects_execution_id_mismatch,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_detects_execution_id_mismatch<E: Curve>(t: Option<u16>, n: u16) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let other_eid: [u8; 32] = rng.gen();
+    // Last party is misconfigured and uses different execution id
+    let eid_of = |i: u16| {
+        if i + 1 == n {
+            ExecutionId::new(&other_eid)
+        } else {
+            ExecutionId::new(&eid)
+        }
+    };
+
+    let errors = match t {
+        Some(t) => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid_of(i), i, n)
+                    .set_threshold(t)
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+        None => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid_of(i), i, n)
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+    }
+    .unwrap()
+    .into_vec()
+    .into_iter()
+    .map(|r| r.unwrap_err())
+    .collect::<Vec<_>>();
+
+    let misconfigured = n - 1;
+    for (i, err) in (0u16..).zip(&errors) {
+        let expected_parties = if i == misconfigured {
+            (0..misconfigured).collect::<Vec<_>>()
+        } else {
+            vec![misconfigured]
+        };
+        assert_eq!(
+            err.execution_id_mismatch(),
+            Some(expected_parties.as_slice()),
+            "party {i}: {err:?}"
+        );
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_without_proof_of_knowledge_works,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_without_proof_of_knowledge_works<E: Curve>(t: Option<u16>, n: u16) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let key_shares = match t {
+        Some(t) => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_prove_knowledge(false)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        }),
+        None => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_prove_knowledge(false)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        }),
+    }
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    validate_keygen_output(&mut rng, &key_shares, cfg!(feature = "hd-wallet"));
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_detects_prove_knowledge_mismatch,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_detects_prove_knowledge_mismatch<E: Curve>(t: Option<u16>, n: u16) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    // Last party is misconfigured and doesn't prove knowledge of its secret share
+    let prove_knowledge = |i: u16| i + 1 != n;
+
+    let errors = match t {
+        Some(t) => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_prove_knowledge(prove_knowledge(i))
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+        None => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_prove_knowledge(prove_knowledge(i))
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+    }
+    .unwrap()
+    .into_vec()
+    .into_iter()
+    .map(|r| r.unwrap_err())
+    .collect::<Vec<_>>();
+
+    for (i, err) in (0u16..).zip(&errors) {
+        assert!(err.invalid_decommitment().is_some(), "party {i}: {err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_with_custom_commitment_scheme,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_with_custom_commitment_scheme<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::keygen::commitment::{Commitment, HashCommitment};
+    use sha2::Digest;
+
+    /// Hash commitment salted with random opening
+    struct SaltedHash;
+    impl Commitment for SaltedHash {
+        fn commit(
+            &self,
+            rng: &mut dyn rand_core::CryptoRngCore,
+            data: &[u8],
+        ) -> (Vec<u8>, Vec<u8>) {
+            let salt: [u8; 32] = rng.gen();
+            let commitment = sha2::Sha256::new().chain_update(salt).chain_update(data);
+            (commitment.finalize().to_vec(), salt.to_vec())
+        }
+        fn verify(&self, commitment: &[u8], data: &[u8], opening: &[u8]) -> bool {
+            let expected = sha2::Sha256::new()
+                .chain_update(opening)
+                .chain_update(data)
+                .finalize();
+            opening.len() == 32 && expected.as_slice() == commitment
+        }
+    }
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let salted = SaltedHash;
+    let hash = HashCommitment::<sha2::Sha256>::new();
+    // All parties use custom scheme, or the last party is misconfigured and uses default one
+    for misconfigured in [false, true] {
+        let scheme = |i: u16| -> &dyn Commitment {
+            if misconfigured && i + 1 == n {
+                &hash
+            } else {
+                &salted
+            }
+        };
+        let results = match t {
+            Some(t) => round_based::sim::run(n, |i, party| {
+                let mut party_rng = rng.fork();
+                async move {
+                    cggmp21::keygen::<E>(eid, i, n)
+                        .set_threshold(t)
+                        .set_commitment_scheme(scheme(i))
+                        .start(&mut party_rng, party)
+                        .await
+                }
+            }),
+            None => round_based::sim::run(n, |i, party| {
+                let mut party_rng = rng.fork();
+                async move {
+                    cggmp21::keygen::<E>(eid, i, n)
+                        .set_commitment_scheme(scheme(i))
+                        .start(&mut party_rng, party)
+                        .await
+                }
+            }),
+        }
+        .unwrap()
+        .into_vec();
+
+        if misconfigured {
+            for (i, result) in (0u16..).zip(results) {
+                let Err(err) = result else {
+                    panic!("party {i}: keygen succeeded")
+                };
+                assert!(err.invalid_decommitment().is_some(), "party {i}: {err:?}");
+            }
+        } else {
+            let key_shares = results.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+            validate_keygen_output(&mut rng, &key_shares, cfg!(feature = "hd-wallet"));
+        }
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_can_be_cancelled,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_can_be_cancelled<E: Curve>(t: Option<u16>, n: u16) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let token = cggmp21::cancellation::CancellationToken::new();
+    token.cancel();
+
+    let errors = match t {
+        Some(t) => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            let token = token.clone();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_cancellation(token)
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+        None => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            let token = token.clone();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_cancellation(token)
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+    }
+    .unwrap()
+    .into_vec();
+
+    for (i, result) in (0u16..).zip(errors) {
+        let err = result.unwrap_err();
+        assert!(err.is_cancelled(), "party {i}: {err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_round_observer_reports_received_messages,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_round_observer_reports_received_messages<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::round_observer::RoundProgress;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let reports = match t {
+        Some(t) => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                let mut reports = vec![];
+                let mut observer = |progress: RoundProgress| reports.push(progress);
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_round_observer(&mut observer)
+                    .start(&mut party_rng, party)
+                    .await?;
+                Ok::<_, cggmp21::keygen::KeygenError>(reports)
+            }
+        }),
+        None => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                let mut reports = vec![];
+                let mut observer = |progress: RoundProgress| reports.push(progress);
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_round_observer(&mut observer)
+                    .start(&mut party_rng, party)
+                    .await?;
+                Ok::<_, cggmp21::keygen::KeygenError>(reports)
+            }
+        }),
+    }
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    let rounds: &[&str] = match t {
+        Some(_) => &[
+            "round 1",
+            "reliability check",
+            "round 2a",
+            "round 2b",
+            "round 3",
+        ],
+        None => &["round 1", "reliability check", "round 2", "round 3"],
+    };
+    for (i, reports) in (0u16..).zip(reports) {
+        for round in rounds {
+            let received = reports
+                .iter()
+                .filter(|r| r.round == *round)
+                .map(|r| r.received)
+                .collect::<Vec<_>>();
+            assert_eq!(received, (1..n).collect::<Vec<_>>(), "{round}");
+        }
+        assert_eq!(reports.len(), rounds.len() * usize::from(n - 1));
+
+        for report in reports {
+            assert_ne!(report.from, i);
+            assert_eq!(report.expected, n - 1);
+        }
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_round_timeout,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_round_timeout<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21_tests::{ImmediateTimer, NeverTimer};
+    use std::time::Duration;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let timeout = Duration::from_secs(10);
+
+    // Timer that never fires doesn't affect the protocol
+    let key_shares = match t {
+        Some(t) => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .set_timer(NeverTimer)
+                    .set_round_timeout(timeout)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        }),
+        None => round_based::sim::run(n, |i, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_timer(NeverTimer)
+                    .set_round_timeout(timeout)
+                    .start(&mut party_rng, party)
+                    .await
+            }
+        }),
+    }
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+    validate_keygen_output(&mut rng, &key_shares, cfg!(feature = "hd-wallet"));
+
+    // Only one party is online, so it times out waiting for messages of the first round.
+    // Timeout without a timer is rejected.
+    for timer in [Some(ImmediateTimer), None] {
+        let timer_set = timer.is_some();
+        let result = match t {
+            Some(t) => round_based::sim::run(1, |_, party| {
+                let mut party_rng = rng.fork();
+                let keygen = cggmp21::keygen::<E>(eid, 0, n)
+                    .set_threshold(t)
+                    .set_round_timeout(timeout);
+                let keygen = match timer {
+                    Some(timer) => keygen.set_timer(timer),
+                    None => keygen,
+                };
+                async move { keygen.start(&mut party_rng, party).await.map(|_| ()) }
+            }),
+            None => round_based::sim::run(1, |_, party| {
+                let mut party_rng = rng.fork();
+                let keygen = cggmp21::keygen::<E>(eid, 0, n).set_round_timeout(timeout);
+                let keygen = match timer {
+                    Some(timer) => keygen.set_timer(timer),
+                    None => keygen,
+                };
+                async move { keygen.start(&mut party_rng, party).await.map(|_| ()) }
+            }),
+        }
+        .unwrap()
+        .into_vec()
+        .remove(0);
+        let err = result.unwrap_err();
+        assert_eq!(err.is_timed_out(), timer_set, "{err:?}");
+        assert_eq!(err.is_timer_not_set(), !timer_set, "{err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_rejects_too_many_parties,
+    generics: all_curves,
+    suites: {
+        n: (None),
+        t: (Some(2)),
+    }
+}
+fn keygen_rejects_too_many_parties<E: Curve>(t: Option<u16>) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let n = cggmp21::MAX_PARTIES + 1;
+
+    // Keygen must fail before it sends or receives anything, so we run only one party
+    let result = match t {
+        Some(t) => round_based::sim::run(1, |_, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, 0, n)
+                    .set_threshold(t)
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+        None => round_based::sim::run(1, |_, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, 0, n)
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+    }
+    .unwrap()
+    .into_vec()
+    .remove(0);
+
+    let err = result.unwrap_err();
+    assert!(err.is_too_many_parties(), "{err:?}");
+}
+
+cggmp21_tests::test_suite! {
+    test: weighted_keygen_rejects_too_large_total_weight,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn weighted_keygen_rejects_too_large_total_weight<E: Curve>() {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // Protocol must return an error before communicating with other parties
+    let party = round_based::MpcParty::connected((
+        futures::stream::pending::<Result<round_based::Incoming<_>, std::convert::Infallible>>(),
+        futures::sink::drain(),
+    ));
+    let result = futures::executor::block_on(
+        cggmp21::keygen::<E>(eid, 0, 2)
+            .set_weights(2, &[cggmp21::MAX_PARTIES, 1])
+            .start(&mut rng, party),
+    );
+    assert!(matches!(result, Err(err) if err.is_too_many_parties()));
+}
+
+#[derive(Clone)]
+struct DevelopmentOnly;
+cggmp21::security_level::define_security_level!(DevelopmentOnly{
+    security_bits = 384,
+    epsilon = 230,
+    ell = 256,
+    ell_prime = 848,
+    m = 128,
+    q = (cggmp21::rug::Integer::ONE << 128_u32).into(),
+    production_safe = false,
+});
+
+cggmp21_tests::test_suite! {
+    test: keygen_refuses_development_security_level,
+    generics: all_curves,
+    suites: {
+        n: (None),
+        t: (Some(2)),
+    }
+}
+fn keygen_refuses_development_security_level<E: Curve>(t: Option<u16>) {
+    use cggmp21::security_level::{KeygenSecurityLevel, SecurityLevel128};
+    const _: () = assert!(SecurityLevel128::IS_PRODUCTION_SAFE);
+    const _: () = assert!(!DevelopmentOnly::IS_PRODUCTION_SAFE);
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let n = 3;
+
+    // Keygen must fail before it sends or receives anything, so we run only one party
+    let result = match t {
+        Some(t) => round_based::sim::run(1, |_, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, 0, n)
+                    .set_threshold(t)
+                    .set_security_level::<DevelopmentOnly>()
+                    .require_production_level()
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+        None => round_based::sim::run(1, |_, party| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::keygen::<E>(eid, 0, n)
+                    .set_security_level::<DevelopmentOnly>()
+                    .require_production_level()
+                    .start(&mut party_rng, party)
+                    .await
+                    .map(|_| ())
+            }
+        }),
+    }
+    .unwrap()
+    .into_vec()
+    .remove(0);
+
+    assert!(result.unwrap_err().is_not_production_safe());
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_checks_party_identities,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_checks_party_identities<E: Curve>(t: Option<u16>, n: u16) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let identities = (0..n)
+        .map(|j| format!("party {j} certificate fingerprint").into_bytes())
+        .collect::<Vec<_>>();
+    let identities = identities
+        .iter()
+        .map(|id| id.as_slice())
+        .collect::<Vec<_>>();
+    let identities = &identities;
+
+    // Party which is not the one others expect: it has another identity and it doesn't know
+    // identities of anyone else
+    let unknown_identity: &[u8] = b"unknown certificate fingerprint";
+    let unknown_identities = vec![unknown_identity; usize::from(n)];
+    let unknown_identities = &unknown_identities;
+
+    // Runs keygen where `misconfigured` party (if any) has identity that other parties don't expect
+    let run_keygen = |rng: &mut DevRng, misconfigured: Option<u16>| {
+        let identities_of = move |i: u16| -> (&[u8], &[&[u8]]) {
+            if Some(i) == misconfigured {
+                (unknown_identity, unknown_identities)
+            } else {
+                (identities[usize::from(i)], identities)
+            }
+        };
+        match t {
+            Some(t) => round_based::sim::run(n, |i, party| {
+                let mut party_rng = rng.fork();
+                let (identity, peer_identities) = identities_of(i);
+                async move {
+                    cggmp21::keygen::<E>(eid, i, n)
+                        .set_threshold(t)
+                        .set_party_identity(identity)
+                        .set_peer_identities(peer_identities)
+                        .start(&mut party_rng, party)
+                        .await
+                }
+            }),
+            None => round_based::sim::run(n, |i, party| {
+                let mut party_rng = rng.fork();
+                let (identity, peer_identities) = identities_of(i);
+                async move {
+                    cggmp21::keygen::<E>(eid, i, n)
+                        .set_party_identity(identity)
+                        .set_peer_identities(peer_identities)
+                        .start(&mut party_rng, party)
+                        .await
+                }
+            }),
+        }
+        .unwrap()
+        .into_vec()
+    };
+
+    let key_shares = run_keygen(&mut rng.fork(), None)
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    validate_keygen_output(&mut rng, &key_shares, cfg!(feature = "hd-wallet"));
+
+    // Every party detects that misconfigured party is not the one they expect, and misconfigured
+    // party doesn't recognize anyone else
+    let misconfigured = n - 1;
+    let results = run_keygen(&mut rng.fork(), Some(misconfigured));
+    for (i, result) in (0u16..).zip(results) {
+        let Err(err) = result else {
+            panic!("party {i}: keygen must fail")
+        };
+        let expected_parties = if i == misconfigured {
+            (0..misconfigured).collect::<Vec<_>>()
+        } else {
+            vec![misconfigured]
+        };
+        assert_eq!(
+            err.identity_mismatch(),
+            Some(expected_parties.as_slice()),
+            "party {i}: {err:?}"
+        );
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_detects_equivocation,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_detects_equivocation<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::keygen::msg::{non_threshold, threshold};
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let signers = (0..n).map(TestSigner).collect::<Vec<_>>();
+    let mut honest_rng = iter::repeat_with(|| rng.fork())
+        .take(n.into())
+        .collect::<Vec<_>>();
+    let mut malicious_rng = iter::repeat_with(|| rng.fork())
+        .take(n.into())
+        .collect::<Vec<_>>();
+
+    let (key_shares, results) = match t {
+        Some(t) => {
+            // Honest execution: record round 1 message of party 0
+            let sent = RefCell::new(vec![]);
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut honest_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .detect_equivocation(&signers[usize::from(i)])
+                    .into_state_machine(party_rng);
+                if i == 0 {
+                    simulation.add_party(RecordSent { party, sent: &sent });
+                } else {
+                    simulation.add_party(party);
+                }
+            }
+            let key_shares = simulation.run().unwrap().into_vec();
+            let round1 = sent
+                .into_inner()
+                .into_iter()
+                .find_map(|msg| match msg {
+                    threshold::Msg::Round1(msg) => Some(msg),
+                    _ => None,
+                })
+                .unwrap();
+
+            // Party 0 sends to party 1 a message it signed in the previous execution, which
+            // differs from the one it sends to everyone else
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut malicious_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(t)
+                    .detect_equivocation(&signers[usize::from(i)])
+                    .into_state_machine(party_rng);
+                let round1 = round1.clone();
+                simulation.add_party(TamperReceivedFrom {
+                    party,
+                    tamper: move |sender, msg: &mut threshold::Msg<_, _, _>| {
+                        if let (1, 0, threshold::Msg::Round1(_)) = (i, sender, &msg) {
+                            *msg = threshold::Msg::Round1(round1.clone());
+                        }
+                    },
+                });
+            }
+            (key_shares, simulation.run())
+        }
+        None => {
+            // Honest execution: record round 1 message of party 0
+            let sent = RefCell::new(vec![]);
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut honest_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .detect_equivocation(&signers[usize::from(i)])
+                    .into_state_machine(party_rng);
+                if i == 0 {
+                    simulation.add_party(RecordSent { party, sent: &sent });
+                } else {
+                    simulation.add_party(party);
+                }
+            }
+            let key_shares = simulation.run().unwrap().into_vec();
+            let round1 = sent
+                .into_inner()
+                .into_iter()
+                .find_map(|msg| match msg {
+                    non_threshold::Msg::Round1(msg) => Some(msg),
+                    _ => None,
+                })
+                .unwrap();
+
+            // Party 0 sends to party 1 a message it signed in the previous execution, which
+            // differs from the one it sends to everyone else
+            let mut simulation = round_based::sim::Simulation::with_capacity(n);
+            for (i, party_rng) in (0..).zip(&mut malicious_rng) {
+                let party = cggmp21::keygen::<E>(eid, i, n)
+                    .detect_equivocation(&signers[usize::from(i)])
+                    .into_state_machine(party_rng);
+                let round1 = round1.clone();
+                simulation.add_party(TamperReceivedFrom {
+                    party,
+                    tamper: move |sender, msg: &mut non_threshold::Msg<_, _, _>| {
+                        if let (1, 0, non_threshold::Msg::Round1(_)) = (i, sender, &msg) {
+                            *msg = non_threshold::Msg::Round1(round1.clone());
+                        }
+                    },
+                });
+            }
+            (key_shares, simulation.run())
+        }
+    };
+
+    let key_shares = key_shares
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    validate_keygen_output(&mut rng, &key_shares, cfg!(feature = "hd-wallet"));
+
+    // Everyone blames party 0 and holds two messages signed by it
+    for (i, result) in (0u16..).zip(results.unwrap().into_vec()) {
+        let err = result.err().expect("keygen must fail");
+        let (party, (msg1, msg2)) = err
+            .equivocation()
+            .unwrap_or_else(|| panic!("party {i}: {err:?}"));
+        assert_eq!(party, 0, "party {i}");
+        assert_ne!(msg1.commitment, msg2.commitment);
+
+        // Evidence can be verified by anyone who knows public keys of the parties
+        let evidence = (msg1.clone(), msg2.clone());
+        assert!(equivocation::verify_evidence::<sha2::Sha256>(
+            &TestSigner(0),
+            0,
+            &evidence
+        ));
+        assert!(!equivocation::verify_evidence::<sha2::Sha256>(
+            &TestSigner(0),
+            1,
+            &evidence
+        ));
+        let mut tampered = evidence.clone();
+        tampered.1.eid[0] ^= 1;
+        assert!(!equivocation::verify_evidence::<sha2::Sha256>(
+            &TestSigner(0),
+            0,
+            &tampered
+        ));
+        assert!(!equivocation::verify_evidence::<sha2::Sha256>(
+            &TestSigner(0),
+            0,
+            &(msg1.clone(), msg1.clone())
+        ));
+    }
+}
+
+/// Signer for tests: signature is a hash of signer index and data
+struct TestSigner(u16);
+
+impl BroadcastSigner for TestSigner {
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        use sha2::Digest;
+        sha2::Sha256::new()
+            .chain_update(self.0.to_be_bytes())
+            .chain_update(data)
+            .finalize()
+            .to_vec()
+    }
+}
+
+impl BroadcastVerifier for TestSigner {
+    fn verify(&self, j: u16, data: &[u8], signature: &[u8]) -> bool {
+        TestSigner(j).sign(data) == signature
+    }
+}
+
+/// State machine wrapper that modifies messages received by the party depending on the sender
+struct TamperReceivedFrom<SM, F> {
+    party: SM,
+    tamper: F,
+}
+
+impl<SM, F> StateMachine for TamperReceivedFrom<SM, F>
+where
+    SM: StateMachine,
+    F: FnMut(u16, &mut SM::Msg),
+{
+    type Output = SM::Output;
+    type Msg = SM::Msg;
+
+    fn proceed(&mut self) -> ProceedResult<Self::Output, Self::Msg> {
+        self.party.proceed()
+    }
+
+    fn received_msg(
+        &mut self,
+        mut msg: round_based::Incoming<Self::Msg>,
+    ) -> Result<(), round_based::Incoming<Self::Msg>> {
+        (self.tamper)(msg.sender, &mut msg.msg);
+        self.party.received_msg(msg)
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: perf_report_exports_machine_readable_formats,
+    generics: all_curves,
+    suites: {
+        n3: (3),
+    }
+}
+fn perf_report_exports_machine_readable_formats<E: Curve>(n: u16) {
+    use cggmp21::progress::PerfProfiler;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let reports = round_based::sim::run(n, |i, party| {
+        let mut party_rng = rng.fork();
+        let mut profiler = PerfProfiler::new();
+        async move {
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_progress_tracer(&mut profiler)
+                .start(&mut party_rng, party)
+                .await
+                .unwrap();
+            profiler.get_report().unwrap()
+        }
+    })
+    .unwrap()
+    .into_vec();
+    let report = &reports[0];
+
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["version"], 1);
+    let rounds = json["rounds"].as_array().unwrap();
+    assert_eq!(rounds.len(), report.rounds.len());
+    for (i, (round, expected)) in rounds.iter().zip(&report.rounds).enumerate() {
+        assert_eq!(round["round"], i + 1);
+        assert_eq!(
+            round["computation_ns"].as_u64().unwrap(),
+            u64::try_from(expected.computation.as_nanos()).unwrap()
+        );
+        let stages = round["stages"].as_array().unwrap();
+        let stage_names = stages
+            .iter()
+            .map(|s| s["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        let expected_names = expected.stages.iter().map(|s| s.name).collect::<Vec<_>>();
+        assert_eq!(stage_names, expected_names);
+    }
+
+    let csv = report.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("round,round_name,kind,name,duration_ns"));
+    let expected_rows = 1
+        + report.setup_stages.len()
+        + report
+            .rounds
+            .iter()
+            .map(|r| 3 + r.stages.len())
+            .sum::<usize>();
+    assert_eq!(lines.clone().count(), expected_rows);
+    for line in lines {
+        // Stage names may contain commas, in which case they're quoted
+        let (_, duration) = line.rsplit_once(',').unwrap();
+        duration.parse::<u128>().unwrap();
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_with_fixed_rid,
+    generics: all_curves,
+    suites: {
+        n3: (3),
+    }
+}
+fn keygen_with_fixed_rid<E: Curve>(n: u16) {
+    use cggmp21::{keygen::msg::non_threshold::Msg, security_level::SecurityLevel128};
+    type KeygenMsg<E> = Msg<E, SecurityLevel128, sha2::Sha256>;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // `rid` is 384 bits long for `SecurityLevel128`
+    let mut fixed_rid = [0u8; 48];
+    rand::RngCore::fill_bytes(&mut rng, &mut fixed_rid);
+
+    let mut party_rng = iter::repeat_with(|| rng.fork())
+        .take(n.into())
+        .collect::<Vec<_>>();
+    let transcript = iter::repeat_with(|| RefCell::new(vec![]))
+        .take(n.into())
+        .collect::<Vec<_>>();
+
+    let mut simulation = round_based::sim::Simulation::with_capacity(n);
+    for ((i, party_rng), sent) in (0..).zip(&mut party_rng).zip(&transcript) {
+        let keygen = cggmp21::keygen::<E>(eid, i, n).set_fixed_rid_for_testing(&fixed_rid);
+        simulation.add_party(RecordSent {
+            party: keygen.into_state_machine(party_rng),
+            sent,
+        })
+    }
+    simulation.run().unwrap().expect_ok();
+
+    let decommitments = transcript
+        .iter()
+        .map(|sent| {
+            sent.borrow()
+                .iter()
+                .find_map(|msg: &KeygenMsg<E>| match msg {
+                    Msg::Round2(msg) => Some(msg.clone()),
+                    _ => None,
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(decommitments[0].rid.as_ref(), fixed_rid);
+    for decommitment in &decommitments[1..] {
+        assert!(decommitment.rid.as_ref().iter().all(|b| *b == 0));
+    }
+
+    // Length of rid must match the security level
+    let result = round_based::sim::run(n, |i, party| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_fixed_rid_for_testing(&fixed_rid[1..])
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap();
+    for (i, result) in (0u16..).zip(result.into_vec()) {
+        let Err(err) = result else {
+            panic!("party {i}: keygen must fail")
+        };
+        assert!(err.is_invalid_fixed_rid(), "party {i}: {err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_blames_malicious_party,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_blames_malicious_party<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::testing::adversary;
+
+    #[derive(Clone, Copy, Debug)]
+    enum Attack {
+        Round1Equivocation,
+        Round2Decommitment,
+        Round3Proof,
+    }
+
+    /// Party 1 is malicious
+    fn add_party<'a, SM>(
+        simulation: &mut round_based::sim::Simulation<'a, Option<SM::Output>, SM::Msg>,
+        i: u16,
+        attack: Attack,
+        party: SM,
+    ) where
+        SM: StateMachine + 'a,
+        SM::Msg: adversary::KeygenMsg + Clone + 'static,
+    {
+        match (i, attack) {
+            (0, Attack::Round1Equivocation) => {
+                simulation.add_party(adversary::equivocate_round1(party, 1))
+            }
+            (1, Attack::Round2Decommitment) => {
+                simulation.add_party(adversary::corrupt_round2_decommitment(party))
+            }
+            (1, Attack::Round3Proof) => {
+                simulation.add_party(adversary::corrupt_round3_proof(party))
+            }
+            _ => simulation.add_party(adversary::honest(party)),
+        }
+    }
+
+    let mut rng = DevRng::new();
+
+    for attack in [
+        Attack::Round1Equivocation,
+        Attack::Round2Decommitment,
+        Attack::Round3Proof,
+    ] {
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut party_rng = iter::repeat_with(|| rng.fork())
+            .take(n.into())
+            .collect::<Vec<_>>();
+
+        let reliable_broadcast = matches!(attack, Attack::Round1Equivocation);
+        let outputs = match t {
+            Some(t) => {
+                let mut simulation = round_based::sim::Simulation::with_capacity(n);
+                for (i, party_rng) in (0..).zip(&mut party_rng) {
+                    let party = cggmp21::keygen::<E>(eid, i, n)
+                        .set_threshold(t)
+                        .enforce_reliable_broadcast(reliable_broadcast)
+                        .into_state_machine(party_rng);
+                    add_party(&mut simulation, i, attack, party);
+                }
+                simulation.run()
+            }
+            None => {
+                let mut simulation = round_based::sim::Simulation::with_capacity(n);
+                for (i, party_rng) in (0..).zip(&mut party_rng) {
+                    let party = cggmp21::keygen::<E>(eid, i, n)
+                        .enforce_reliable_broadcast(reliable_broadcast)
+                        .into_state_machine(party_rng);
+                    add_party(&mut simulation, i, attack, party);
+                }
+                simulation.run()
+            }
+        }
+        .unwrap()
+        .into_vec();
+
+        for (i, output) in (0u16..).zip(outputs) {
+            let Some(result) = output else {
+                assert_eq!(i, 1, "{attack:?}: only malicious party halts");
+                continue;
+            };
+            let err = result
+                .err()
+                .unwrap_or_else(|| panic!("{attack:?}: party {i} must fail"));
+            match attack {
+                Attack::Round1Equivocation => {
+                    let failure = err
+                        .reliability_check_failure()
+                        .unwrap_or_else(|| panic!("{attack:?}: party {i}: {err:?}"));
+                    assert!(!failure.mismatched.is_empty());
+                }
+                Attack::Round2Decommitment => {
+                    assert_eq!(err.invalid_decommitment(), Some(vec![1]), "party {i}");
+                }
+                Attack::Round3Proof => {
+                    assert_eq!(err.invalid_schnorr_proof(), Some(vec![1]), "party {i}");
+                }
+            }
+        }
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: nested_group_contributes_to_keygen,
+    generics: all_curves,
+    suites: {
+        n3_group2: (3, 2),
+        n4_group3: (4, 3),
+    }
+}
+fn nested_group_contributes_to_keygen<E: Curve>(n: u16, group_size: usize) {
+    use cggmp21::keygen::contribution;
+    use cggmp21::keygen::msg::non_threshold::{Msg, MsgRound1, MsgRound2, MsgRound3};
+    use cggmp21::security_level::{KeygenSecurityLevel, SecurityLevel128};
+    use cggmp21::testing::adversary;
+    use generic_ec::{NonZero, SecretScalar};
+    use generic_ec_zkp::schnorr_pok;
+    use rand::RngCore;
+    use round_based::rounds_router::{simple_store::RoundInput, RoundsRouter};
+    use round_based::{Delivery, Mpc, MpcParty, Outgoing, SinkExt};
+
+    type L = SecurityLevel128;
+    type D = sha2::Sha256;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // Last party is a group of members holding additive shares of its secret
+    let group = n - 1;
+    let member_secrets = iter::repeat_with(|| SecretScalar::<E>::random(&mut rng))
+        .take(group_size)
+        .collect::<Vec<_>>();
+    let group_public_share = NonZero::from_point(
+        member_secrets
+            .iter()
+            .map(|x_k| Point::generator() * x_k)
+            .sum::<Point<E>>(),
+    )
+    .unwrap();
+    let mut group_rng = rng.fork();
+    let group_secrets = &member_secrets;
+
+    let mut party_rng = iter::repeat_with(|| rng.fork())
+        .take(group.into())
+        .collect::<Vec<_>>();
+
+    let mut simulation = round_based::sim::Simulation::with_capacity(n);
+    for (i, party_rng) in (0..).zip(&mut party_rng) {
+        let keygen = cggmp21::keygen::<E>(eid, i, n).enforce_reliable_broadcast(false);
+
+        #[cfg(feature = "hd-wallet")]
+        let keygen = keygen.hd_wallet(false);
+
+        simulation.add_party(adversary::honest(keygen.into_state_machine(party_rng)));
+    }
+    simulation.add_async_party(|party| async move {
+        let MpcParty { delivery, .. } = party.into_party();
+        let (incomings, mut outgoings) = delivery.split();
+
+        let mut rounds = RoundsRouter::<Msg<E, L, D>>::builder();
+        let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(group, n));
+        let round2 = rounds.add_round(RoundInput::<MsgRound2<E, L>>::broadcast(group, n));
+        let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::broadcast(group, n));
+        let mut rounds = rounds.listen(incomings);
+
+        // Each member commits to its ephemeral schnorr secret
+        let (sch_secrets, sch_commits): (Vec<_>, Vec<_>) = iter::repeat_with(|| {
+            schnorr_pok::prover_commits_ephemeral_secret::<E, _>(&mut group_rng)
+        })
+        .take(group_size)
+        .unzip();
+
+        let mut rid = <L as KeygenSecurityLevel>::Rid::default();
+        group_rng.fill_bytes(rid.as_mut());
+        let mut decommit = <L as KeygenSecurityLevel>::Rid::default();
+        group_rng.fill_bytes(decommit.as_mut());
+        let mut my_decommitment = MsgRound2 {
+            rid,
+            X: group_public_share,
+            sch_commit: contribution::combine_schnorr_commits(sch_commits),
+            #[cfg(feature = "hd-wallet")]
+            chain_code: None,
+            identity: None,
+            decommit,
+            opening: vec![],
+        };
+        let my_commitment = contribution::commit::<E, L, D>(
+            &mut group_rng,
+            eid,
+            group,
+            true,
+            None,
+            &mut my_decommitment,
+        );
+
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round1(my_commitment)))
+            .await
+            .unwrap();
+        let commitments = rounds.complete(round1).await.unwrap();
+
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round2(my_decommitment.clone())))
+            .await
+            .unwrap();
+        let decommitments = rounds.complete(round2).await.unwrap();
+        for (j, _, decommitment) in decommitments.iter_indexed() {
+            let commitment = commitments
+                .iter_indexed()
+                .find_map(|(k, _, commitment)| (k == j).then_some(commitment))
+                .unwrap();
+            assert!(contribution::verify_decommitment::<E, L, D>(
+                eid,
+                j,
+                true,
+                None,
+                commitment,
+                decommitment
+            ));
+        }
+
+        // Each member answers the challenge using its own share
+        let rid = contribution::combine_rid(decommitments.iter_including_me(&my_decommitment));
+        let challenge = contribution::schnorr_challenge::<E, D>(eid, group, rid.as_ref());
+        let partial_proofs = sch_secrets
+            .iter()
+            .zip(group_secrets)
+            .map(|(sch_secret, x_k)| schnorr_pok::prove(sch_secret, &challenge, x_k));
+        let my_sch_proof = contribution::combine_schnorr_proofs(partial_proofs);
+        assert!(cggmp21::keygen::verify_schnorr_contribution::<E, L, D>(
+            eid,
+            group,
+            rid.as_ref(),
+            &my_decommitment,
+            &my_sch_proof
+        ));
+
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round3(my_sch_proof)))
+            .await
+            .unwrap();
+        let _sch_proofs = rounds.complete(round3).await.unwrap();
+
+        None
+    });
+
+    let key_shares = simulation
+        .run()
+        .unwrap()
+        .into_vec()
+        .into_iter()
+        .take(group.into())
+        .map(|output| output.unwrap().unwrap())
+        .collect::<Vec<_>>();
+
+    for key_share in &key_shares {
+        assert_eq!(
+            key_share.public_shares[usize::from(group)],
+            group_public_share
+        );
+        assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+    }
+
+    // Secret key is never assembled, but it's a sum of all shares including shares of the group
+    let sk = key_shares
+        .iter()
+        .map(|key_share| *AsRef::<Scalar<E>>::as_ref(&key_share.x))
+        .chain(
+            member_secrets
+                .iter()
+                .map(|x_k| *AsRef::<Scalar<E>>::as_ref(x_k)),
+        )
+        .sum::<Scalar<E>>();
+    assert_eq!(Point::generator() * sk, *key_shares[0].shared_public_key);
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_reports_zero_public_key,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn keygen_reports_zero_public_key<E: Curve>() {
+    use cggmp21::keygen::contribution;
+    use cggmp21::keygen::msg::non_threshold::{Msg, MsgRound1, MsgRound2};
+    use cggmp21::security_level::{KeygenSecurityLevel, SecurityLevel128};
+    use cggmp21::testing::adversary;
+    use generic_ec_zkp::schnorr_pok;
+    use rand::RngCore;
+    use round_based::rounds_router::{simple_store::RoundInput, RoundsRouter};
+    use round_based::{Delivery, Mpc, MpcParty, Outgoing, SinkExt};
+
+    type L = SecurityLevel128;
+    type D = sha2::Sha256;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let keygen = |i| {
+        let keygen = cggmp21::keygen::<E>(eid, i, 2)
+            .enforce_reliable_broadcast(false)
+            .set_prove_knowledge(false);
+        #[cfg(feature = "hd-wallet")]
+        let keygen = keygen.hd_wallet(false);
+        keygen
+    };
+
+    // Honest party samples the same secret share given the same randomness, so we learn its public
+    // share from the first run
+    let honest_rng = rng.fork();
+    let mut party_rng = [honest_rng.clone(), rng.fork()];
+    let mut simulation = round_based::sim::Simulation::with_capacity(2);
+    for (i, party_rng) in (0..).zip(&mut party_rng) {
+        simulation.add_party(keygen(i).into_state_machine(party_rng));
+    }
+    let key_shares = simulation
+        .run()
+        .unwrap()
+        .into_vec()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let honest_public_share = key_shares[0].public_shares[0];
+
+    // In the second run, other party contributes the opposite public share
+    let mut honest_rng = honest_rng;
+    let mut malicious_rng = rng.fork();
+    let mut simulation = round_based::sim::Simulation::with_capacity(2);
+    simulation.add_party(adversary::honest(
+        keygen(0).into_state_machine(&mut honest_rng),
+    ));
+    simulation.add_async_party(|party| async move {
+        let MpcParty { delivery, .. } = party.into_party();
+        let (incomings, mut outgoings) = delivery.split();
+
+        let mut rounds = RoundsRouter::<Msg<E, L, D>>::builder();
+        let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(1, 2));
+        let round2 = rounds.add_round(RoundInput::<MsgRound2<E, L>>::broadcast(1, 2));
+        let mut rounds = rounds.listen(incomings);
+
+        let (_, sch_commit) =
+            schnorr_pok::prover_commits_ephemeral_secret::<E, _>(&mut malicious_rng);
+        let mut rid = <L as KeygenSecurityLevel>::Rid::default();
+        malicious_rng.fill_bytes(rid.as_mut());
+        let mut decommit = <L as KeygenSecurityLevel>::Rid::default();
+        malicious_rng.fill_bytes(decommit.as_mut());
+        let mut my_decommitment = MsgRound2 {
+            rid,
+            X: -honest_public_share,
+            sch_commit,
+            #[cfg(feature = "hd-wallet")]
+            chain_code: None,
+            identity: None,
+            decommit,
+            opening: vec![],
+        };
+        let my_commitment = contribution::commit::<E, L, D>(
+            &mut malicious_rng,
+            eid,
+            1,
+            false,
+            None,
+            &mut my_decommitment,
+        );
+
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round1(my_commitment)))
+            .await
+            .unwrap();
+        let _commitments = rounds.complete(round1).await.unwrap();
+        outgoings
+            .send(Outgoing::broadcast(Msg::Round2(my_decommitment)))
+            .await
+            .unwrap();
+        let decommitments = rounds.complete(round2).await.unwrap();
+        let honest_decommitment = decommitments.iter().next().unwrap();
+        assert_eq!(honest_decommitment.X, honest_public_share);
+
+        None
+    });
+
+    let err = match simulation.run().unwrap().into_vec().remove(0) {
+        Some(Err(err)) => err,
+        _ => panic!("keygen must fail"),
+    };
+    assert!(err.is_zero_public_key(), "{err:?}");
+    assert!(err.zero_public_shares().is_none());
+}
+
+cggmp21_tests::test_suite! {
+    test: keygen_is_send,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn keygen_is_send<E: Curve>() {
+    use cggmp21::keygen::commitment::HashCommitment;
+    use cggmp21::progress::PerfProfiler;
+    use cggmp21_tests::NeverTimer;
+    use futures::{sink::Drain, stream::Pending};
+    use round_based::{Incoming, MpcParty, Outgoing};
+    use std::{convert::Infallible, time::Duration};
+
+    fn assert_send<T: Send>(_: &T) {}
+    /// Party that is never connected to anyone
+    fn party<M>() -> MpcParty<M, (Pending<Result<Incoming<M>, Infallible>>, Drain<Outgoing<M>>)> {
+        MpcParty::connected((futures::stream::pending(), futures::sink::drain()))
+    }
+
+    let mut rng = DevRng::new();
+    let mut secret_rng = rng.fork();
+    let mut tracer = PerfProfiler::new();
+    let commitment_scheme = HashCommitment::<sha2::Sha256>::new();
+    let eid = ExecutionId::new(b"keygen is send");
+
+    // Builder and protocol future are `Send` with all the options set, so keygen can be spawned
+    // on multi-threaded runtime
+    let keygen = cggmp21::keygen::<E>(eid, 0, 3)
+        .set_progress_tracer(&mut tracer)
+        .set_secret_rng(&mut secret_rng)
+        .detect_equivocation(&TestSigner(0))
+        .set_commitment_scheme(&commitment_scheme)
+        .set_timer(NeverTimer)
+        .set_round_timeout(Duration::from_secs(10));
+    assert_send(&keygen);
+    let keygen = keygen.start(&mut rng, party());
+    assert_send(&keygen);
+    drop(keygen);
+
+    let keygen = cggmp21::keygen::<E>(eid, 0, 3)
+        .set_threshold(2)
+        .set_timer(NeverTimer)
+        .set_round_timeout(Duration::from_secs(10));
+    assert_send(&keygen);
+    let keygen = keygen.start(&mut rng, party());
+    assert_send(&keygen);
+}
@@ -1,7 +1,15 @@
+mod addresses;
+mod compat;
+mod enroll;
 mod key_refresh;
 mod keygen;
 mod old_shares;
 mod pipeline;
+mod prime_cache;
+mod reshare;
+mod serialization;
 mod signing;
 mod stark_prehashed;
+mod test_vectors;
+mod testing;
 mod trusted_dealer;
@@ -29,6 +29,54 @@ where
     run_signing(&shares, hd_enabled, &mut rng);
 }
 
+cggmp21_tests::test_suite! {
+    test: keygen_with_aux_works,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn keygen_with_aux_works<E>(t: Option<u16>, n: u16)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: generic_ec::coords::HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let shares: Vec<KeyShare<E>> = round_based::sim::run(n, |i, party| {
+        let mut party_rng = rng.fork();
+        let pregenerated_data = primes.next().expect("Can't fetch primes");
+        async move {
+            let protocol = cggmp21::keygen_with_aux(eid, i, n, pregenerated_data);
+            let protocol = match t {
+                Some(t) => protocol.set_threshold(t),
+                None => protocol,
+            };
+            protocol.start(&mut party_rng, party).await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    for (i, share) in (0..).zip(&shares) {
+        assert_eq!(share.core.i, i);
+        assert_eq!(
+            share.core.shared_public_key,
+            shares[0].core.shared_public_key
+        );
+        assert_eq!(share.core.public_shares, shares[0].core.public_shares);
+        assert_eq!(share.min_signers(), t.unwrap_or(n));
+    }
+
+    run_signing(&shares, false, &mut rng);
+}
+
 fn run_keygen<E>(t: u16, n: u16, hd_enabled: bool, rng: &mut DevRng) -> Vec<IncompleteKeyShare<E>>
 where
     E: Curve,
@@ -88,6 +136,52 @@ where
         .collect()
 }
 
+cggmp21_tests::test_suite! {
+    test: key_share_builder_checks_parts,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn key_share_builder_checks_parts<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::key_share::{BuildKeyShareError, DirtyKeyShare, Validate};
+    use cggmp21::security_level::SecurityLevel128;
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let core = || shares[0].core.clone().validate().unwrap();
+    let aux = |i: usize| shares[i].aux.clone().validate().unwrap();
+
+    let key_share = DirtyKeyShare::builder()
+        .aux(aux(0))
+        .core(core())
+        .build()
+        .unwrap();
+    assert_eq!(
+        serde_json::to_value(&key_share).unwrap(),
+        serde_json::to_value(&shares[0]).unwrap()
+    );
+
+    assert!(matches!(
+        DirtyKeyShare::<E, SecurityLevel128>::builder()
+            .aux(aux(0))
+            .build(),
+        Err(BuildKeyShareError::MissingCore)
+    ));
+    assert!(matches!(
+        DirtyKeyShare::<E, SecurityLevel128>::builder()
+            .core(core())
+            .build(),
+        Err(BuildKeyShareError::MissingAux)
+    ));
+    // Aux info of another party doesn't match the core share
+    assert!(matches!(
+        DirtyKeyShare::builder().core(core()).aux(aux(1)).build(),
+        Err(BuildKeyShareError::Inconsistent(_))
+    ));
+}
+
 fn run_signing<E>(shares: &[KeyShare<E>], random_derivation_path: bool, rng: &mut DevRng)
 where
     E: Curve + cggmp21_tests::CurveParams,
@@ -0,0 +1,219 @@
+use cggmp21::{
+    prime_cache::{CachedPrimes, PrimeCache, PrimeStore},
+    security_level::SecurityLevel128,
+    PregeneratedPrimes,
+};
+use cggmp21_tests::CACHED_PRIMES;
+
+#[test]
+fn primes_are_handed_out_once() {
+    let primes = CACHED_PRIMES
+        .iter::<SecurityLevel128>()
+        .take(3)
+        .collect::<Vec<_>>();
+
+    let mut cache = PrimeCache::new(Vec::<CachedPrimes>::new());
+    for p in &primes {
+        cache.put(p.clone()).unwrap();
+    }
+    assert_eq!(cache.count::<SecurityLevel128>().unwrap(), primes.len());
+
+    let mut popped = vec![];
+    while let Some(p) = cache.pop::<SecurityLevel128>().unwrap() {
+        popped.push(p.split());
+    }
+    assert_eq!(cache.count::<SecurityLevel128>().unwrap(), 0);
+
+    let expected = primes.into_iter().map(|p| p.split()).collect::<Vec<_>>();
+    assert_eq!(popped, expected);
+}
+
+#[test]
+fn cached_primes_survive_serialization() {
+    let primes = CACHED_PRIMES.iter::<SecurityLevel128>().next().unwrap();
+
+    let mut cache = PrimeCache::new(Vec::<CachedPrimes>::new());
+    cache.put(primes.clone()).unwrap();
+
+    let serialized = serde_json::to_string(&cache.into_store()).unwrap();
+    let store: Vec<CachedPrimes> = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(store[0].security_bits(), 384);
+
+    let mut cache = PrimeCache::new(store);
+    let popped = cache.pop::<SecurityLevel128>().unwrap().unwrap();
+    assert_eq!(popped.split(), primes.split());
+    assert!(cache.pop::<SecurityLevel128>().unwrap().is_none());
+}
+
+#[test]
+fn primes_of_wrong_security_level_are_refused() {
+    #[derive(Clone)]
+    struct OtherLevel;
+    cggmp21::security_level::define_security_level!(OtherLevel {
+        security_bits = 256,
+        epsilon = 230,
+        ell = 256,
+        ell_prime = 848,
+        m = 128,
+        q = (cggmp21::rug::Integer::ONE << 128_u32).into(),
+    });
+
+    /// Store that ignores requested security level
+    struct BrokenStore(Vec<CachedPrimes>);
+    impl PrimeStore for BrokenStore {
+        type Error = std::convert::Infallible;
+        fn put(&mut self, primes: CachedPrimes) -> Result<(), Self::Error> {
+            self.0.push(primes);
+            Ok(())
+        }
+        fn take(&mut self, _security_bits: u32) -> Result<Option<CachedPrimes>, Self::Error> {
+            Ok(self.0.pop())
+        }
+        fn count(&self, _security_bits: u32) -> Result<usize, Self::Error> {
+            Ok(self.0.len())
+        }
+    }
+
+    let primes: PregeneratedPrimes<SecurityLevel128> =
+        CACHED_PRIMES.iter::<SecurityLevel128>().next().unwrap();
+
+    // Well-behaved store doesn't return primes of different security level
+    let mut cache = PrimeCache::new(Vec::<CachedPrimes>::new());
+    cache.put(primes.clone()).unwrap();
+    assert!(cache.pop::<OtherLevel>().unwrap().is_none());
+    assert_eq!(cache.count::<SecurityLevel128>().unwrap(), 1);
+
+    // If store misbehaves, cache refuses to hand out the primes
+    let mut cache = PrimeCache::new(BrokenStore(vec![]));
+    cache.put(primes).unwrap();
+    assert!(cache.pop::<OtherLevel>().is_err());
+}
+
+/// Security level with small primes, so that they're found quickly
+#[derive(Clone, Debug)]
+struct SmallPrimes;
+cggmp21::define_security_level!(SmallPrimes {
+    security_bits = 64,
+    epsilon = 128,
+    ell = 256,
+    ell_prime = 256,
+    m = 128,
+    q = (cggmp21::rug::Integer::ONE.clone() << 128) - 1,
+    production_safe = false,
+});
+
+#[test]
+fn primes_generation_respects_budget() {
+    use cggmp21::{key_refresh::BudgetExhausted, rug::integer::IsPrime};
+
+    let mut rng = rand_dev::DevRng::new();
+
+    // Nothing can be found without budget
+    let exhausted =
+        PregeneratedPrimes::<SmallPrimes>::generate_with_budget(&mut rng, 0).unwrap_err();
+    assert_eq!(exhausted.found_primes(), 0);
+
+    // Generation is resumed until primes are found, testing one candidate at a time
+    let mut state: Result<PregeneratedPrimes<SmallPrimes>, BudgetExhausted<SmallPrimes>> =
+        Err(exhausted);
+    let mut calls = 0;
+    let primes = loop {
+        match state {
+            Ok(primes) => break primes,
+            Err(exhausted) => state = exhausted.resume(&mut rng, 1),
+        }
+        calls += 1;
+    };
+    assert!(calls > 2);
+
+    let (p, q) = primes.split();
+    for prime in [p, q] {
+        assert_eq!(prime.significant_bits(), 4 * 64);
+        assert_ne!(prime.is_probably_prime(25), IsPrime::No);
+        let half = (prime - 1u8) / 2u8;
+        assert_ne!(half.is_probably_prime(25), IsPrime::No);
+    }
+}
+
+#[test]
+fn primes_generation_with_mr_rounds() {
+    use cggmp21::{
+        key_refresh::{DEFAULT_MR_ROUNDS, MIN_MR_ROUNDS},
+        rug::integer::IsPrime,
+    };
+    const _: () = assert!(MIN_MR_ROUNDS <= DEFAULT_MR_ROUNDS);
+
+    let mut rng = rand_dev::DevRng::new();
+
+    assert!(PregeneratedPrimes::<SmallPrimes>::generate_with_mr_rounds(
+        &mut rng,
+        MIN_MR_ROUNDS - 1
+    )
+    .is_none());
+
+    let (p, q) = PregeneratedPrimes::<SmallPrimes>::generate_with_mr_rounds(&mut rng, 40)
+        .unwrap()
+        .split();
+    for prime in [p, q] {
+        assert_eq!(prime.significant_bits(), 4 * 64);
+        assert_ne!(prime.is_probably_prime(25), IsPrime::No);
+        let half = (prime - 1u8) / 2u8;
+        assert_ne!(half.is_probably_prime(25), IsPrime::No);
+    }
+}
+
+#[test]
+fn primes_generation_retries_with_backoff() {
+    use std::time::Duration;
+
+    use cggmp21::{
+        key_refresh::{PrimeGenError, PrimeGenRetry},
+        progress::{Event, Tracer},
+    };
+
+    /// Records names of stages
+    #[derive(Default)]
+    struct Stages(Vec<&'static str>);
+    impl Tracer for Stages {
+        fn trace_event(&mut self, event: Event) {
+            if let Event::Stage { name } = event {
+                self.0.push(name)
+            }
+        }
+    }
+
+    let mut rng = rand_dev::DevRng::new();
+    let retry = PrimeGenRetry {
+        max_attempts: 3,
+        candidates_per_attempt: 0,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(2),
+    };
+
+    // Attempts without budget can't find anything, generation gives up instead of hanging
+    let mut stages = Stages::default();
+    let err = PregeneratedPrimes::<SmallPrimes>::generate_with_retries(
+        &mut rng,
+        retry,
+        Some(&mut stages),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        PrimeGenError::ExhaustedAttempts { attempts: 3 }
+    ));
+    // Three attempts with two backoffs between them
+    assert_eq!(stages.0.len(), 5);
+
+    let retry = PrimeGenRetry {
+        max_attempts: u32::MAX,
+        candidates_per_attempt: 100,
+        ..retry
+    };
+    let (p, q) = PregeneratedPrimes::<SmallPrimes>::generate_with_retries(&mut rng, retry, None)
+        .unwrap()
+        .split();
+    for prime in [p, q] {
+        assert_eq!(prime.significant_bits(), 4 * 64);
+    }
+}
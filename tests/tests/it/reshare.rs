@@ -0,0 +1,153 @@
+use std::iter;
+
+use generic_ec::{Curve, Point, Scalar};
+use rand::{seq::SliceRandom, Rng};
+use rand_dev::DevRng;
+
+use cggmp21::{key_share::reconstruct_secret_key, ExecutionId};
+
+cggmp21_tests::test_suite! {
+    test: reshare_to_threshold_works,
+    generics: all_curves,
+    suites: {
+        t2n3_to_n3: (3, 2, 3, false),
+        t2n2_to_n4: (2, 2, 4, false),
+        t3n3_to_n5: (3, 3, 5, false),
+        t2n3_to_n4_reliable: (3, 2, 4, true),
+    }
+}
+fn reshare_to_threshold_works<E: Curve>(
+    old_n: u16,
+    new_t: u16,
+    new_n: u16,
+    reliable_broadcast: bool,
+) {
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let old_shares = round_based::sim::run(old_n, |i, party| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, old_n)
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+    let key_info: &cggmp21::key_share::KeyInfo<E> = old_shares[0].as_ref();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let new_shares = round_based::sim::run(new_n, |i, party| {
+        let mut party_rng = rng.fork();
+        let builder = match old_shares.get(usize::from(i)) {
+            Some(share) => cggmp21::key_share::reshare_to_threshold(eid, i, share, new_t, new_n),
+            None => cggmp21::reshare::ReshareBuilder::new_party(eid, i, key_info, new_t, new_n),
+        };
+        async move {
+            builder
+                .enforce_reliable_broadcast(reliable_broadcast)
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    for (i, share) in (0u16..).zip(&new_shares) {
+        assert_eq!(share.i, i);
+        assert_eq!(share.n(), new_n);
+        assert_eq!(share.min_signers(), new_t);
+        assert_eq!(share.shared_public_key, old_shares[0].shared_public_key);
+        assert_eq!(share.public_shares, new_shares[0].public_shares);
+    }
+
+    let t_shares = new_shares
+        .choose_multiple(&mut rng, new_t.into())
+        .cloned()
+        .collect::<Vec<_>>();
+    let sk = reconstruct_secret_key(&t_shares).unwrap();
+    assert_eq!(Point::generator() * sk, old_shares[0].shared_public_key);
+}
+
+cggmp21_tests::test_suite! {
+    test: reshare_blames_dealer_of_invalid_share,
+    generics: all_curves,
+    suites: {
+        t2n3_to_n3: (3, 2, 3),
+        t2n2_to_n4: (2, 2, 4),
+    }
+}
+fn reshare_blames_dealer_of_invalid_share<E: Curve>(old_n: u16, new_t: u16, new_n: u16) {
+    use cggmp21::reshare::Msg;
+    use cggmp21::testing::adversary;
+
+    let mut rng = DevRng::new();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let old_shares = round_based::sim::run(old_n, |i, party| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, old_n)
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+    let key_info: &cggmp21::key_share::KeyInfo<E> = old_shares[0].as_ref();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let mut party_rng = iter::repeat_with(|| rng.fork())
+        .take(new_n.into())
+        .collect::<Vec<_>>();
+
+    // Party 1 holds a share of the old key, and deals shares that don't match its commitment
+    let mut simulation = round_based::sim::Simulation::with_capacity(new_n);
+    for (i, party_rng) in (0..).zip(&mut party_rng) {
+        let builder = match old_shares.get(usize::from(i)) {
+            Some(share) => cggmp21::key_share::reshare_to_threshold(eid, i, share, new_t, new_n),
+            None => cggmp21::reshare::ReshareBuilder::new_party(eid, i, key_info, new_t, new_n),
+        };
+        let party = builder
+            .enforce_reliable_broadcast(false)
+            .into_state_machine(party_rng);
+        if i == 1 {
+            simulation.add_party(adversary::tamper_sent(party, |msg| match &mut msg.msg {
+                Msg::Round1Uni(msg) => match &mut msg.sigma {
+                    Some(sigma) => {
+                        *sigma += Scalar::one();
+                        true
+                    }
+                    None => false,
+                },
+                _ => false,
+            }));
+        } else {
+            simulation.add_party(adversary::honest(party));
+        }
+    }
+    let outputs = simulation.run().unwrap().into_vec();
+
+    for (i, output) in (0u16..).zip(outputs) {
+        let Some(result) = output else {
+            assert_eq!(i, 1, "only malicious party halts");
+            continue;
+        };
+        let err = result
+            .err()
+            .unwrap_or_else(|| panic!("party {i} must fail"));
+        assert_eq!(err.invalid_secret_share(), Some(vec![1]), "party {i}");
+    }
+}
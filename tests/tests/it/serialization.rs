@@ -0,0 +1,726 @@
+use generic_ec::{coords::HasAffineX, Curve, NonZero, Point, Scalar};
+use rand::{Rng, RngCore};
+use rand_dev::DevRng;
+use round_based::state_machine::{ProceedResult, StateMachine};
+
+use cggmp21::{
+    keygen::msg::non_threshold::MsgRound2,
+    security_level::{KeygenSecurityLevel, SecurityLevel128},
+    ExecutionId, Signature,
+};
+
+cggmp21_tests::test_suite! {
+    test: keygen_msg_round_trip,
+    generics: all_curves,
+    suites: {
+        with_chain_code: (true),
+        without_chain_code: (false),
+    }
+}
+fn keygen_msg_round_trip<E: Curve>(with_chain_code: bool) {
+    let mut rng = DevRng::new();
+    let msg = random_msg_round2::<E>(&mut rng, with_chain_code);
+    let expected = serde_json::to_value(&msg).unwrap();
+
+    // json -> json
+    let json = serde_json::to_vec(&msg).unwrap();
+    let decoded: MsgRound2<E, SecurityLevel128> = serde_json::from_slice(&json).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+
+    // cbor -> cbor
+    let mut cbor = vec![];
+    ciborium::into_writer(&msg, &mut cbor).unwrap();
+    let decoded: MsgRound2<E, SecurityLevel128> = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+
+    // json -> cbor
+    let decoded: MsgRound2<E, SecurityLevel128> = serde_json::from_value(expected.clone()).unwrap();
+    let mut cbor = vec![];
+    ciborium::into_writer(&decoded, &mut cbor).unwrap();
+    let decoded: MsgRound2<E, SecurityLevel128> = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+
+    // cbor -> json
+    let json = serde_json::to_vec(&decoded).unwrap();
+    let decoded: MsgRound2<E, SecurityLevel128> = serde_json::from_slice(&json).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+
+    // json where byte arrays are encoded as list of integers, as produced when raw bytes
+    // are transcoded into json without knowing the schema
+    let mut transcoded = expected.clone();
+    for field in ["rid", "chain_code"] {
+        if let Some(value) = transcoded.get_mut(field).filter(|v| !v.is_null()) {
+            let bytes = hex::decode(value.as_str().unwrap()).unwrap();
+            *value = serde_json::to_value(bytes).unwrap();
+        }
+    }
+    let decoded: MsgRound2<E, SecurityLevel128> = serde_json::from_value(transcoded).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+}
+
+cggmp21_tests::test_suite! {
+    test: codec_enforces_size_limit,
+    generics: all_curves,
+    suites: {
+        with_chain_code: (true),
+        without_chain_code: (false),
+    }
+}
+fn codec_enforces_size_limit<E: Curve>(with_chain_code: bool) {
+    use cggmp21::codec::{decode_message, encode_message, CodecError};
+
+    let mut rng = DevRng::new();
+    let msg = random_msg_round2::<E>(&mut rng, with_chain_code);
+    let expected = serde_json::to_value(&msg).unwrap();
+
+    let bytes = encode_message(&msg, usize::MAX).unwrap();
+    let len = bytes.len();
+    let decoded: MsgRound2<E, SecurityLevel128> = decode_message(&bytes, len).unwrap();
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+
+    assert!(matches!(
+        encode_message(&msg, len - 1),
+        Err(CodecError::TooLarge { len: l, max_len }) if l == len && max_len == len - 1
+    ));
+    assert!(matches!(
+        decode_message::<MsgRound2<E, SecurityLevel128>>(&bytes, len - 1),
+        Err(CodecError::TooLarge { .. })
+    ));
+
+    let mut with_trailing_bytes = bytes.clone();
+    with_trailing_bytes.extend_from_slice(&[0; 3]);
+    assert!(matches!(
+        decode_message::<MsgRound2<E, SecurityLevel128>>(&with_trailing_bytes, usize::MAX),
+        Err(CodecError::TrailingBytes(3))
+    ));
+
+    assert!(matches!(
+        decode_message::<MsgRound2<E, SecurityLevel128>>(&bytes[..len - 1], usize::MAX),
+        Err(CodecError::Decode(_))
+    ));
+}
+
+cggmp21_tests::test_suite! {
+    test: decoding_malformed_msg_doesnt_panic,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn decoding_malformed_msg_doesnt_panic<E: Curve>() {
+    use cggmp21::codec::{encode_message, FramedMessage};
+    use cggmp21::keygen::NonThresholdMsg;
+    use cggmp21::signing::msg::Msg as SigningMsg;
+
+    type KeygenMsg<E> = NonThresholdMsg<E, SecurityLevel128, sha2::Sha256>;
+
+    let mut rng = DevRng::new();
+    let msg = KeygenMsg::<E>::Round2(random_msg_round2::<E>(&mut rng, true));
+    let bytes = encode_message(&msg, usize::MAX).unwrap();
+    assert!(KeygenMsg::<E>::try_decode(&bytes, usize::MAX).is_ok());
+
+    // Truncated message
+    for len in 0..bytes.len() {
+        assert!(KeygenMsg::<E>::try_decode(&bytes[..len], usize::MAX).is_err());
+    }
+    // Corrupted bytes, e.g. invalid encoding of points or wrong length of `rid`
+    for i in 0..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[i] ^= 1 << rng.gen_range(0..8);
+        let _ = KeygenMsg::<E>::try_decode(&corrupted, usize::MAX);
+        let _ = SigningMsg::<E, sha2::Sha256>::try_decode(&corrupted, usize::MAX);
+    }
+    // Random bytes
+    for _ in 0..100 {
+        let mut random = vec![0u8; rng.gen_range(0..bytes.len())];
+        rng.fill_bytes(&mut random);
+        let _ = KeygenMsg::<E>::try_decode(&random, usize::MAX);
+        let _ = SigningMsg::<E, sha2::Sha256>::try_decode(&random, usize::MAX);
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: key_share_migrates_from_versioned_bytes,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+        n3: (None, 3),
+    }
+}
+fn key_share_migrates_from_versioned_bytes<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::key_share::{self, MigrationError};
+
+    let mut rng = DevRng::new();
+    let key_shares = cggmp21::trusted_dealer::builder::<E, SecurityLevel128>(n)
+        .set_threshold(t)
+        .generate_core_shares(&mut rng)
+        .unwrap();
+
+    for key_share in &key_shares {
+        let bytes = key_share::to_bytes(key_share).unwrap();
+        assert_eq!(bytes[4], key_share::FORMAT_VERSION);
+
+        let migrated = key_share::migrate::<E>(&bytes).unwrap();
+        assert_eq!(
+            serde_json::to_value(&migrated).unwrap(),
+            serde_json::to_value(key_share).unwrap()
+        );
+
+        let mut unknown_version = bytes.clone();
+        unknown_version[4] = key_share::FORMAT_VERSION + 1;
+        assert!(matches!(
+            key_share::migrate::<E>(&unknown_version),
+            Err(MigrationError::UnknownVersion(v)) if v == key_share::FORMAT_VERSION + 1
+        ));
+
+        assert!(matches!(
+            key_share::migrate::<E>(&bytes[5..]),
+            Err(MigrationError::MissingHeader)
+        ));
+        assert!(matches!(
+            key_share::migrate::<E>(&bytes[..bytes.len() - 1]),
+            Err(MigrationError::Decode(_))
+        ));
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: key_share_backup_round_trip,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn key_share_backup_round_trip<E: Curve>(t: Option<u16>, n: u16) {
+    use cggmp21::backup::{self, BackupError, KdfParams};
+
+    let mut rng = DevRng::new();
+    let key_share = &cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .unwrap()[0];
+    // Weak parameters to keep the test fast
+    let kdf_params = KdfParams::new(10, 8, 1).unwrap();
+
+    let bytes =
+        backup::export_encrypted_with_params(&mut rng, key_share, b"passphrase", kdf_params)
+            .unwrap();
+    let restored: cggmp21::KeyShare<E> = backup::import_encrypted(&bytes, b"passphrase").unwrap();
+    assert_eq!(
+        serde_json::to_value(&restored).unwrap(),
+        serde_json::to_value(key_share).unwrap()
+    );
+
+    // Encryption is randomized
+    let other_bytes =
+        backup::export_encrypted_with_params(&mut rng, key_share, b"passphrase", kdf_params)
+            .unwrap();
+    assert_ne!(bytes, other_bytes);
+
+    assert!(matches!(
+        backup::import_encrypted::<E, SecurityLevel128>(&bytes, b"another passphrase"),
+        Err(BackupError::WrongPassphrase)
+    ));
+
+    let mut tampered = bytes.clone();
+    let last = tampered.len() - 40;
+    tampered[last] ^= 1;
+    assert!(matches!(
+        backup::import_encrypted::<E, SecurityLevel128>(&tampered, b"passphrase"),
+        Err(BackupError::WrongPassphrase)
+    ));
+
+    let mut unknown_version = bytes.clone();
+    unknown_version[4] += 1;
+    assert!(matches!(
+        backup::import_encrypted::<E, SecurityLevel128>(&unknown_version, b"passphrase"),
+        Err(BackupError::UnknownVersion(_))
+    ));
+
+    let mut huge_kdf_params = bytes.clone();
+    huge_kdf_params[5] = 40;
+    assert!(matches!(
+        backup::import_encrypted::<E, SecurityLevel128>(&huge_kdf_params, b"passphrase"),
+        Err(BackupError::InvalidKdfParams)
+    ));
+
+    assert!(matches!(
+        backup::import_encrypted::<E, SecurityLevel128>(&bytes[..40], b"passphrase"),
+        Err(BackupError::InvalidHeader)
+    ));
+    // Key derivation takes at most 256MiB of memory
+    assert_eq!(KdfParams::new(17, 8, 1), Some(KdfParams::RECOMMENDED));
+    assert!(KdfParams::new(18, 8, 4).is_some());
+    assert!(KdfParams::new(19, 8, 1).is_none());
+    assert!(KdfParams::new(17, 32, 1).is_none());
+    assert!(KdfParams::new(17, 8, 5).is_none());
+    assert!(KdfParams::new(63, 8, 1).is_none());
+}
+
+cggmp21_tests::test_suite! {
+    test: framed_msg_round_trip,
+    generics: all_curves,
+    suites: {
+        with_chain_code: (true),
+        without_chain_code: (false),
+    }
+}
+fn framed_msg_round_trip<E: Curve>(with_chain_code: bool) {
+    use cggmp21::codec::{CodecError, FrameHeader, ProtocolKind};
+    use cggmp21::keygen::{NonThresholdMsg, ThresholdMsg};
+
+    let mut rng = DevRng::new();
+    let msg = NonThresholdMsg::<E, SecurityLevel128, sha2::Sha256>::Round2(random_msg_round2::<E>(
+        &mut rng,
+        with_chain_code,
+    ));
+    let expected = serde_json::to_value(&msg).unwrap();
+
+    let eid = cggmp21::ExecutionId::new(b"framed execution");
+    let other_eid = cggmp21::ExecutionId::new(b"another execution");
+
+    let frame = cggmp21::frame(&msg, eid, usize::MAX).unwrap();
+
+    let header = FrameHeader::parse(&frame).unwrap();
+    assert_eq!(header.protocol_kind, ProtocolKind::NonThresholdKeygen);
+    assert_eq!(header.round, round_based::ProtocolMessage::round(&msg));
+    assert_eq!(header, FrameHeader::new(&msg, eid));
+    assert_ne!(header, FrameHeader::new(&msg, other_eid));
+    assert_eq!(header.execution_id_digest, eid.digest::<sha2::Sha256>());
+    assert_ne!(
+        eid.digest::<sha2::Sha256>(),
+        other_eid.digest::<sha2::Sha256>()
+    );
+
+    let (decoded_header, decoded): (_, NonThresholdMsg<E, SecurityLevel128, sha2::Sha256>) =
+        cggmp21::deframe(&frame, usize::MAX).unwrap();
+    assert_eq!(decoded_header, header);
+    assert_eq!(serde_json::to_value(&decoded).unwrap(), expected);
+
+    assert!(matches!(
+        cggmp21::deframe::<ThresholdMsg<E, SecurityLevel128, sha2::Sha256>>(&frame, usize::MAX),
+        Err(CodecError::HeaderMismatch)
+    ));
+    assert!(matches!(
+        FrameHeader::parse(&frame[..FrameHeader::SIZE - 1]),
+        Err(CodecError::InvalidHeader)
+    ));
+    assert!(matches!(
+        cggmp21::frame(&msg, eid, frame.len() - 1),
+        Err(CodecError::TooLarge { .. })
+    ));
+}
+
+/// State machine wrapper that passes every message received by the party through bincode
+///
+/// Bincode is not self-describing, so it catches encodings that only round-trip in formats like
+/// json or cbor (e.g. fields that are skipped when serializing)
+struct BincodeRoundTrip<SM> {
+    party: SM,
+}
+
+impl<SM> StateMachine for BincodeRoundTrip<SM>
+where
+    SM: StateMachine,
+    SM::Msg: serde::Serialize + serde::de::DeserializeOwned,
+{
+    type Output = SM::Output;
+    type Msg = SM::Msg;
+
+    fn proceed(&mut self) -> ProceedResult<Self::Output, Self::Msg> {
+        self.party.proceed()
+    }
+
+    fn received_msg(
+        &mut self,
+        mut msg: round_based::Incoming<Self::Msg>,
+    ) -> Result<(), round_based::Incoming<Self::Msg>> {
+        let bytes = bincode::serialize(&msg.msg).unwrap();
+        let decoded: SM::Msg = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(bincode::serialize(&decoded).unwrap(), bytes);
+        msg.msg = decoded;
+        self.party.received_msg(msg)
+    }
+}
+
+/// Runs the protocol, passing every message through bincode, and returns outputs of all parties
+fn run_through_bincode<SM, O, Err>(parties: impl IntoIterator<Item = SM>) -> Vec<O>
+where
+    SM: StateMachine<Output = Result<O, Err>>,
+    SM::Msg: Clone + serde::Serialize + serde::de::DeserializeOwned + 'static,
+    Err: std::fmt::Debug,
+{
+    let mut simulation = round_based::sim::Simulation::empty();
+    for party in parties {
+        simulation.add_party(BincodeRoundTrip { party });
+    }
+    simulation
+        .run()
+        .unwrap()
+        .into_vec()
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+cggmp21_tests::test_suite! {
+    test: protocol_msgs_round_trip_bincode,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn protocol_msgs_round_trip_bincode<E: Curve>()
+where
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+    let n = 3;
+    let identities = [b"party 0", b"party 1", b"party 2"];
+    let mut party_rng = std::iter::repeat_with(|| rng.fork())
+        .take(n.into())
+        .collect::<Vec<_>>();
+
+    // Non-threshold keygen
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    run_through_bincode((0..).zip(&mut party_rng).map(|(i, party_rng)| {
+        cggmp21::keygen::<E>(eid, i, n)
+            .set_party_identity(identities[usize::from(i)])
+            .into_state_machine(party_rng)
+    }));
+
+    // Threshold keygen
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    run_through_bincode((0..).zip(&mut party_rng).map(|(i, party_rng)| {
+        cggmp21::keygen::<E>(eid, i, n)
+            .set_threshold(2)
+            .set_party_identity(identities[usize::from(i)])
+            .into_state_machine(party_rng)
+    }));
+
+    // Weighted keygen, parties with weight above one receive several sub-shares
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    run_through_bincode((0..).zip(&mut party_rng).map(|(i, party_rng)| {
+        cggmp21::keygen::<E>(eid, i, n)
+            .set_weights(3, &[1, 2, 2])
+            .into_state_machine(party_rng)
+    }));
+
+    // Aux info generation
+    let mut primes = cggmp21_tests::CACHED_PRIMES.iter::<SecurityLevel128>();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    run_through_bincode((0..).zip(&mut party_rng).map(|(i, party_rng)| {
+        cggmp21::aux_info_gen(eid, i, n, primes.next().unwrap())
+            .set_party_identity(identities[usize::from(i)])
+            .into_state_machine(party_rng)
+    }));
+
+    // Key refresh
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(None, n, false)
+        .unwrap();
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    run_through_bincode(shares.iter().zip(&mut party_rng).map(|(share, party_rng)| {
+        cggmp21::key_refresh(eid, share, primes.next().unwrap()).into_state_machine(party_rng)
+    }));
+
+    // Signing a batch of messages
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(Some(2), n, false)
+        .unwrap();
+    let signers = [0, 2];
+    let messages = [
+        cggmp21::DataToSign::digest::<sha2::Sha256>(b"first"),
+        cggmp21::DataToSign::digest::<sha2::Sha256>(b"second"),
+    ];
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    run_through_bincode(
+        (0..)
+            .zip(&mut party_rng)
+            .zip(&signers)
+            .map(|((i, party_rng), j)| {
+                cggmp21::signing(eid, i, &signers, &shares[usize::from(*j)])
+                    .sign_batch_sync(party_rng, &messages)
+            }),
+    );
+}
+
+fn random_msg_round2<E: Curve>(
+    rng: &mut impl RngCore,
+    #[allow(unused_variables)] with_chain_code: bool,
+) -> MsgRound2<E, SecurityLevel128> {
+    let mut rid = <SecurityLevel128 as KeygenSecurityLevel>::Rid::default();
+    rng.fill_bytes(rid.as_mut());
+    let mut decommit = <SecurityLevel128 as KeygenSecurityLevel>::Rid::default();
+    rng.fill_bytes(decommit.as_mut());
+
+    MsgRound2 {
+        rid,
+        X: Point::<E>::generator() * NonZero::<Scalar<E>>::random(rng),
+        sch_commit: generic_ec_zkp::schnorr_pok::Commit(
+            Point::generator() * Scalar::<E>::random(rng),
+        ),
+        #[cfg(feature = "hd-wallet")]
+        chain_code: with_chain_code.then(|| rand::Rng::gen(rng)),
+        identity: None,
+        decommit,
+        opening: Vec::new(),
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: signature_der_round_trip,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn signature_der_round_trip<E: Curve>() {
+    let mut rng = DevRng::new();
+
+    let one = NonZero::<Scalar<E>>::one();
+    let mut signatures = vec![
+        // Small values are encoded in a single byte
+        Signature::from_raw_parts(one, one),
+        // Highest bit is set, requires zero padding
+        Signature::from_raw_parts(-one, -one),
+    ];
+    signatures.extend(
+        std::iter::repeat_with(|| {
+            Signature::from_raw_parts(
+                NonZero::<Scalar<E>>::random(&mut rng),
+                NonZero::<Scalar<E>>::random(&mut rng),
+            )
+        })
+        .take(20),
+    );
+
+    for signature in signatures {
+        // compact -> der -> compact
+        let mut compact = vec![0u8; Signature::<E>::serialized_len()];
+        signature.write_to_slice(&mut compact);
+        let der = Signature::<E>::read_from_slice(&compact).unwrap().to_der();
+        let decoded = Signature::<E>::from_der(&der).unwrap();
+        let mut compact2 = vec![0u8; Signature::<E>::serialized_len()];
+        decoded.write_to_slice(&mut compact2);
+        assert_eq!(compact, compact2);
+        assert_eq!(decoded, signature);
+
+        // Trailing bytes are rejected
+        let mut der_with_garbage = der.clone();
+        der_with_garbage.push(0);
+        assert!(Signature::<E>::from_der(&der_with_garbage).is_none());
+        // Truncated input is rejected
+        assert!(Signature::<E>::from_der(&der[..der.len() - 1]).is_none());
+    }
+
+    // Known encoding: r = s = 1
+    assert_eq!(
+        Signature::from_raw_parts(one, one).to_der(),
+        [0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01]
+    );
+    // Zero is not a valid signature part
+    assert!(Signature::<E>::from_der(&[0x30, 0x06, 0x02, 0x01, 0x00, 0x02, 0x01, 0x01]).is_none());
+}
+
+cggmp21_tests::test_suite! {
+    test: signature_parsing_rejects_invalid_parts,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn signature_parsing_rejects_invalid_parts<E: Curve>() {
+    let scalar_len = Scalar::<E>::serialized_len();
+    let one = Scalar::<E>::one().to_be_bytes().as_bytes().to_vec();
+    let zero = vec![0u8; scalar_len];
+    // Curve order `q`, which is not a reduced scalar
+    let mut order = (-Scalar::<E>::one()).to_be_bytes().as_bytes().to_vec();
+    for byte in order.iter_mut().rev() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            break;
+        }
+    }
+    let max = vec![0xffu8; scalar_len];
+
+    // Compact encoding
+    assert!(Signature::<E>::read_from_slice(&[one.clone(), one.clone()].concat()).is_some());
+    for invalid in [&zero, &order, &max] {
+        assert!(
+            Signature::<E>::read_from_slice(&[invalid.clone(), one.clone()].concat()).is_none()
+        );
+        assert!(
+            Signature::<E>::read_from_slice(&[one.clone(), invalid.clone()].concat()).is_none()
+        );
+    }
+    assert!(
+        Signature::<E>::read_from_slice(&[one.clone(), one.clone(), vec![0]].concat()).is_none()
+    );
+    assert!(Signature::<E>::read_from_slice(&[&one[..], &one[1..]].concat()).is_none());
+    assert!(Signature::<E>::read_from_slice(&[]).is_none());
+
+    // DER encoding
+    let der = |r: &[u8], s: &[u8]| {
+        let integer = |x: &[u8]| {
+            let x = &x[x.iter().take_while(|b| **b == 0).count()..];
+            let pad = x.is_empty() || x[0] & 0x80 != 0;
+            let mut out = vec![0x02, (x.len() + usize::from(pad)) as u8];
+            if pad {
+                out.push(0);
+            }
+            out.extend_from_slice(x);
+            out
+        };
+        let body = [integer(r), integer(s)].concat();
+        [vec![0x30, body.len() as u8], body].concat()
+    };
+    assert!(Signature::<E>::from_der(&der(&one, &one)).is_some());
+    for invalid in [&order, &max, &[max.clone(), vec![0xff]].concat()] {
+        assert!(Signature::<E>::from_der(&der(invalid, &one)).is_none());
+        assert!(Signature::<E>::from_der(&der(&one, invalid)).is_none());
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: transcript_diff_finds_divergence,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn transcript_diff_finds_divergence<E: Curve>() {
+    use cggmp21::keygen::NonThresholdMsg;
+    use cggmp21::transcript::{self, Transcript};
+    use round_based::{Incoming, MessageType, ProtocolMessage};
+
+    type Msg<E> = NonThresholdMsg<E, SecurityLevel128, sha2::Sha256>;
+
+    let mut rng = DevRng::new();
+    let incoming = |sender: u16, msg_type: MessageType, msg: Msg<E>| Incoming {
+        id: 0,
+        sender,
+        msg_type,
+        msg,
+    };
+    let msgs = (0..3)
+        .map(|_| Msg::<E>::Round2(random_msg_round2::<E>(&mut rng, false)))
+        .collect::<Vec<_>>();
+    let round = msgs[0].round();
+    // Party 2 sends a different message to party 1
+    let equivocated = Msg::<E>::Round2(random_msg_round2::<E>(&mut rng, false));
+
+    let mut transcript0 = Transcript::new();
+    let mut transcript1 = Transcript::new();
+    for (j, msg) in (0u16..).zip(&msgs) {
+        transcript0
+            .record_incoming(&incoming(j, MessageType::Broadcast, msg.clone()))
+            .unwrap();
+        let msg = if j == 2 { &equivocated } else { msg };
+        transcript1
+            .record_incoming(&incoming(j, MessageType::Broadcast, msg.clone()))
+            .unwrap();
+    }
+    // P2P messages are not recorded
+    transcript1
+        .record_incoming(&incoming(0, MessageType::P2P, equivocated.clone()))
+        .unwrap();
+    assert_eq!(transcript0.entries().len(), 3);
+    assert_eq!(transcript1.entries().len(), 3);
+
+    assert!(transcript::diff(&transcript0, &transcript0).is_empty());
+
+    let divergences = transcript::diff(&transcript0, &transcript1);
+    assert_eq!(divergences.len(), 1, "{divergences:?}");
+    assert_eq!(divergences[0].round, round);
+    assert_eq!(divergences[0].sender, 2);
+    assert_eq!(
+        divergences[0].a.as_deref(),
+        Some(transcript0.entries()[2].msg.as_slice())
+    );
+    assert_eq!(
+        divergences[0].b.as_deref(),
+        Some(transcript1.entries()[2].msg.as_slice())
+    );
+
+    // Message missing in the second transcript, transcripts diverged earlier
+    let mut transcript2 = Transcript::new();
+    transcript2.record(round - 1, 1, vec![1, 2, 3]);
+    let divergences = transcript::diff(&transcript2, &transcript0);
+    assert_eq!(divergences.len(), 4, "{divergences:?}");
+    assert_eq!(divergences[0].round, round - 1);
+    assert_eq!(divergences[0].b, None);
+    assert!(divergences[1..].iter().all(|d| d.a.is_none()));
+
+    // Transcript survives serialization
+    let json = serde_json::to_vec(&transcript1).unwrap();
+    let restored: Transcript = serde_json::from_slice(&json).unwrap();
+    assert_eq!(restored, transcript1);
+}
+
+cggmp21_tests::test_suite! {
+    test: points_and_scalars_have_fixed_layout_across_codecs,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn points_and_scalars_have_fixed_layout_across_codecs<E: Curve>() {
+    use cggmp21::signing::msg::MsgRound4;
+
+    let mut rng = DevRng::new();
+
+    // Returns field of the message encoded in json (as hex string) and in cbor (as bytes)
+    fn encoded_field(msg: &impl serde::Serialize, field: &str) -> (String, Vec<u8>) {
+        let json = serde_json::to_value(msg).unwrap();
+        let json = json[field].as_str().unwrap().to_owned();
+
+        let mut cbor = vec![];
+        ciborium::into_writer(msg, &mut cbor).unwrap();
+        let cbor: ciborium::Value = ciborium::from_reader(cbor.as_slice()).unwrap();
+        let cbor = cbor
+            .as_map()
+            .unwrap()
+            .iter()
+            .find(|(k, _)| k.as_text() == Some(field))
+            .and_then(|(_, v)| v.as_bytes())
+            .unwrap()
+            .clone();
+
+        (json, cbor)
+    }
+
+    let msg = random_msg_round2::<E>(&mut rng, false);
+    for (field, expected) in [
+        ("X", msg.X.to_bytes(true).to_vec()),
+        ("sch_commit", msg.sch_commit.0.to_bytes(true).to_vec()),
+    ] {
+        let (json, cbor) = encoded_field(&msg, field);
+        assert_eq!(json, hex::encode(&expected), "{field}");
+        assert_eq!(cbor, expected, "{field}");
+    }
+
+    let msg = MsgRound4::<E> {
+        sigma: Scalar::random(&mut rng),
+        batch: vec![],
+    };
+    let (json, cbor) = encoded_field(&msg, "sigma");
+    let expected = msg.sigma.to_be_bytes().to_vec();
+    assert_eq!(json, hex::encode(&expected));
+    assert_eq!(cbor, expected);
+
+    // Decoding gives back the same message
+    let decoded: MsgRound4<E> =
+        serde_json::from_value(serde_json::to_value(&msg).unwrap()).unwrap();
+    assert_eq!(decoded.sigma, msg.sigma);
+    let mut cbor = vec![];
+    ciborium::into_writer(&msg, &mut cbor).unwrap();
+    let decoded: MsgRound4<E> = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(decoded.sigma, msg.sigma);
+}
@@ -110,6 +110,157 @@ where
         .expect("external verification failed")
 }
 
+cggmp21_tests::test_suite! {
+    test: signing_batch_works,
+    generics: all_curves,
+    suites: {
+        n2_1msg: (None, 2, 1, false),
+        n2_3msgs: (None, 2, 3, false),
+        n3_3msgs_reliable: (None, 3, 3, true),
+        t2n3_2msgs: (Some(2), 3, 2, false),
+        t3n5_4msgs_reliable: (Some(3), 5, 4, true),
+    }
+}
+
+fn signing_batch_works<E>(t: Option<u16>, n: u16, batch_size: usize, reliable_broadcast: bool)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let original_messages_to_sign = iter::repeat_with(|| {
+        let mut msg = [0u8; 100];
+        rng.fill_bytes(&mut msg);
+        msg
+    })
+    .take(batch_size)
+    .collect::<Vec<_>>();
+    let messages_to_sign = original_messages_to_sign
+        .iter()
+        .map(|msg| DataToSign::digest::<Sha256>(msg))
+        .collect::<Vec<_>>();
+
+    // Choose `t` signers to perform signing
+    let t = shares[0].min_signers();
+    let mut participants = (0..n).collect::<Vec<_>>();
+    participants.shuffle(&mut rng);
+    let participants = &participants[..usize::from(t)];
+    println!("Signers: {participants:?}");
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+
+    let sigs = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        let messages_to_sign = &messages_to_sign;
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .enforce_reliable_broadcast(reliable_broadcast)
+                .sign_batch(&mut party_rng, party, messages_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    assert_eq!(sigs.len(), batch_size);
+    let public_key = shares[0].shared_public_key;
+    for ((sig, message_to_sign), original_message_to_sign) in sigs
+        .iter()
+        .zip(&messages_to_sign)
+        .zip(&original_messages_to_sign)
+    {
+        sig.verify(&public_key, message_to_sign)
+            .expect("signature is not valid");
+        E::ExVerifier::verify(&public_key, sig, original_message_to_sign)
+            .expect("external verification failed")
+    }
+
+    // Each signature must be produced with a distinct presignature
+    for (j, sig) in sigs.iter().enumerate() {
+        assert!(sigs[..j].iter().all(|sig2| sig2.r != sig.r));
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_rejects_nested_batch,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+
+fn signing_rejects_nested_batch<E>(t: Option<u16>, n: u16)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::signing::msg::Msg;
+    use cggmp21::testing::adversary;
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let messages_to_sign = iter::repeat_with(|| {
+        let mut msg = [0u8; 100];
+        rng.fill_bytes(&mut msg);
+        DataToSign::digest::<Sha256>(&msg)
+    })
+    .take(2)
+    .collect::<Vec<_>>();
+
+    let t = shares[0].min_signers();
+    let participants = (0..t).collect::<Vec<_>>();
+    let mut signer_rng = iter::repeat_with(|| rng.fork())
+        .take(t.into())
+        .collect::<Vec<_>>();
+
+    // Signer 1 sends a batch of the right size, but smuggles a nested batch into it
+    let mut simulation = round_based::sim::Simulation::with_capacity(t);
+    for ((i, share), signer_rng) in (0..).zip(&shares).zip(&mut signer_rng) {
+        let party = cggmp21::signing(eid, i, &participants, share)
+            .sign_batch_sync(signer_rng, &messages_to_sign);
+        if i == 1 {
+            simulation.add_party(adversary::tamper_sent(party, |msg| match &mut msg.msg {
+                Msg::Round1a(msg) => {
+                    let nested = msg.batch[0].clone();
+                    msg.batch[0].batch.push(nested);
+                    true
+                }
+                _ => false,
+            }));
+        } else {
+            simulation.add_party(adversary::honest(party));
+        }
+    }
+    let outputs = simulation.run().unwrap().into_vec();
+
+    for (i, output) in (0u16..).zip(outputs) {
+        let Some(result) = output else {
+            assert_eq!(i, 1, "only malicious party halts");
+            continue;
+        };
+        let err = result
+            .err()
+            .unwrap_or_else(|| panic!("party {i} must fail"));
+        assert_eq!(err.mismatched_batch_size(), Some(vec![1]), "party {i}");
+    }
+}
+
 cggmp21_tests::test_suite! {
     test: signing_with_presigs,
     generics: all_curves,
@@ -194,6 +345,14 @@ where
     let signature = cggmp21::PartialSignature::combine(&partial_signatures)
         .expect("invalid partial sigantures");
 
+    // Partial signatures can be combined without knowing the message
+    let r = generic_ec::NonZero::from_scalar(partial_signatures[0].r).expect("r is zero");
+    let blind_signature = cggmp21::PartialSignature::combine_blind(&partial_signatures, r)
+        .expect("invalid partial sigantures");
+    assert_eq!(blind_signature, signature);
+    let other_r = generic_ec::NonZero::<generic_ec::Scalar<E>>::random(&mut rng);
+    assert!(cggmp21::PartialSignature::combine_blind(&partial_signatures, other_r).is_none());
+
     #[cfg(feature = "hd-wallet")]
     let public_key = if let Some(path) = &derivation_path {
         generic_ec::NonZero::from_point(
@@ -312,3 +471,1950 @@ where
     E::ExVerifier::verify(&public_key, &sig, &original_message_to_sign)
         .expect("external verification failed")
 }
+
+cggmp21_tests::test_suite! {
+    test: weighted_signing_works,
+    generics: all_curves,
+    suites: {
+        t4w321_s01: (4, &[3, 2, 1], &[0, 1]),
+        t4w321_s02: (4, &[3, 2, 1], &[2, 0]),
+        t4w1213_s13: (4, &[1, 2, 1, 3], &[1, 3]),
+        t4w1213_s012: (4, &[1, 2, 1, 3], &[0, 1, 2]),
+    }
+}
+
+fn weighted_signing_works<E>(t: u16, weights: &[u16], signers: &[u16])
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+    let n: u16 = weights.len().try_into().unwrap();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let sub_shares = round_based::sim::run(n, |i, party| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::keygen::<E>(eid, i, n)
+                .set_weights(t, weights)
+                .start(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    let primes = cggmp21_tests::CACHED_PRIMES
+        .iter::<SecurityLevel128>()
+        .take(n.into())
+        .map(|p| p.split())
+        .collect();
+    let aux_data = cggmp21::trusted_dealer::generate_aux_data_with_primes::<SecurityLevel128, _>(
+        &mut rng, primes, false, false,
+    )
+    .unwrap();
+
+    // Parties without enough weight can't obtain a signing key share
+    let lightest = weights
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, w)| **w)
+        .map(|(j, _)| j)
+        .unwrap();
+    let lightest_u16 = u16::try_from(lightest).unwrap();
+    assert!(cggmp21::key_share::weighted_signing_share(
+        &sub_shares[lightest],
+        &aux_data[lightest],
+        &[lightest_u16]
+    )
+    .is_err());
+
+    let shares = signers
+        .iter()
+        .map(|j| {
+            cggmp21::key_share::weighted_signing_share(
+                &sub_shares[usize::from(*j)],
+                &aux_data[usize::from(*j)],
+                signers,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let public_key = sub_shares[0][0].shared_public_key;
+    assert_eq!(shares[0].shared_public_key(), public_key);
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"weighted signing");
+    let participants = &(0..u16::try_from(signers.len()).unwrap()).collect::<Vec<_>>();
+
+    let sig = round_based::sim::run_with_setup(&shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    sig.verify(&public_key, &message_to_sign)
+        .expect("signature is not valid");
+}
+
+cggmp21_tests::test_suite! {
+    test: deterministic_session_is_reproducible,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn deterministic_session_is_reproducible<E>(t: Option<u16>, n: u16)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let mut original_message_to_sign = [0u8; 100];
+    rng.fill_bytes(&mut original_message_to_sign);
+    let message_to_sign = DataToSign::digest::<Sha256>(&original_message_to_sign);
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = || participants.iter().map(|i| &shares[usize::from(*i)]);
+
+    let mut sign = |seed_domain: &'static [u8]| {
+        round_based::sim::run_with_setup(participants_shares(), |i, party, share| {
+            // Each run uses fresh randomness which must be ignored in deterministic mode
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .set_deterministic_session(seed_domain)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        })
+        .unwrap()
+        .expect_ok()
+        .expect_eq()
+    };
+
+    let sig1 = sign(b"domain");
+    let sig2 = sign(b"domain");
+    let sig3 = sign(b"another domain");
+
+    let public_key = shares[0].shared_public_key;
+    for sig in [&sig1, &sig2, &sig3] {
+        sig.verify(&public_key, &message_to_sign)
+            .expect("signature is not valid");
+    }
+    assert_eq!(sig1, sig2);
+    assert_ne!(sig1.r, sig3.r);
+}
+
+cggmp21_tests::test_suite! {
+    test: nonce_commitments_are_reported,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn nonce_commitments_are_reported<E>(t: Option<u16>, n: u16)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = || participants.iter().map(|i| &shares[usize::from(*i)]);
+
+    // All signers see the same commitments of each other
+    let seen = std::sync::Mutex::new(vec![]);
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let seen_ref = &seen;
+    let sig = round_based::sim::run_with_setup(participants_shares(), |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            let mut hook = |j: u16, commitment: &cggmp21::signing::msg::MsgRound1a| {
+                seen_ref.lock().unwrap().push((i, j, commitment.K.clone()))
+            };
+            cggmp21::signing(eid, i, participants, share)
+                .on_nonce_commitment(&mut hook)
+                .require_nonce_commitment_ack(std::future::ready(true))
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+    sig.verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+
+    let seen = seen.into_inner().unwrap();
+    assert_eq!(seen.len(), usize::from(t) * usize::from(t));
+    for j in 0..t {
+        let commitments_of_j = seen
+            .iter()
+            .filter(|(_, j2, _)| *j2 == j)
+            .map(|(_, _, k)| k)
+            .collect::<Vec<_>>();
+        assert_eq!(commitments_of_j.len(), usize::from(t));
+        assert!(commitments_of_j.iter().all(|k| *k == commitments_of_j[0]));
+    }
+
+    // Signing aborts if commitments are not acknowledged
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let results = round_based::sim::run_with_setup(participants_shares(), |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .require_nonce_commitment_ack(std::future::ready(false))
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap();
+    for result in results {
+        let err = result.unwrap_err();
+        assert!(err.is_nonce_commitments_rejected(), "{err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: aggregate_nonce_is_reported,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn aggregate_nonce_is_reported<E>(t: Option<u16>, n: u16)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = || participants.iter().map(|i| &shares[usize::from(*i)]);
+
+    // All signers see the same nonce, and its x coordinate is `r` component of the signature
+    let seen = std::sync::Mutex::new(vec![]);
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let seen_ref = &seen;
+    let sig = round_based::sim::run_with_setup(participants_shares(), |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            let mut hook =
+                |nonce: &generic_ec::NonZero<Point<E>>| seen_ref.lock().unwrap().push(*nonce);
+            cggmp21::signing(eid, i, participants, share)
+                .on_aggregate_nonce(&mut hook)
+                .require_aggregate_nonce_ack(std::future::ready(true))
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+    sig.verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+
+    let seen = seen.into_inner().unwrap();
+    assert_eq!(seen.len(), usize::from(t));
+    assert!(seen.iter().all(|nonce| *nonce == seen[0]));
+    let r = seen[0].x().expect("nonce is not zero").to_scalar();
+    assert_eq!(r, *sig.r);
+
+    // Signing aborts if nonce is not acknowledged
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let results = round_based::sim::run_with_setup(participants_shares(), |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .require_aggregate_nonce_ack(std::future::ready(false))
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap();
+    for result in results {
+        let err = result.unwrap_err();
+        assert!(err.is_aggregate_nonce_rejected(), "{err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: signature_shares_are_verified_by_coordinator,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t3n5: (Some(3), 5),
+    }
+}
+fn signature_shares_are_verified_by_coordinator<E>(t: Option<u16>, n: u16)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let t = shares[0].min_signers();
+    let mut participants = (0..n).collect::<Vec<_>>();
+    participants.shuffle(&mut rng);
+    let participants = &participants[..usize::from(t)];
+    println!("Signers: {participants:?}");
+
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+    let presigs = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .generate_presignature(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    let mut original_message_to_sign = [0u8; 100];
+    rng.fill_bytes(&mut original_message_to_sign);
+    let message_to_sign = DataToSign::digest::<Sha256>(&original_message_to_sign);
+
+    // Each signer commits to its presignature before the message is known, and later sends its
+    // share to the coordinator
+    let commitments = presigs
+        .iter()
+        .map(|presig| presig.commitment())
+        .collect::<Vec<_>>();
+    let signature_shares = presigs
+        .into_iter()
+        .map(|presig| presig.issue_signature_share(message_to_sign))
+        .collect::<Vec<_>>();
+
+    let public_key = shares[0].shared_public_key;
+    let signature = cggmp21::Signature::from_shares(
+        &public_key,
+        &commitments,
+        &signature_shares,
+        &message_to_sign,
+    )
+    .expect("combine signature shares");
+    signature
+        .verify(&public_key, &message_to_sign)
+        .expect("signature is not valid");
+    E::ExVerifier::verify(&public_key, &signature, &original_message_to_sign)
+        .expect("external verification failed");
+
+    // Coordinator blames the signer that sent an invalid share
+    let cheater = rng.gen_range(0..signature_shares.len());
+    let mut invalid_shares = signature_shares.clone();
+    invalid_shares[cheater].sigma += generic_ec::Scalar::one();
+    let err = cggmp21::Signature::from_shares(
+        &public_key,
+        &commitments,
+        &invalid_shares,
+        &message_to_sign,
+    )
+    .unwrap_err();
+    assert_eq!(err.blame(), [cheater]);
+
+    // Commitments that are inconsistent with the public key are rejected
+    let mut forged_commitments = commitments.clone();
+    forged_commitments[cheater].K = forged_commitments[cheater].K + Point::generator().to_point();
+    let err = cggmp21::Signature::from_shares(
+        &public_key,
+        &forged_commitments,
+        &signature_shares,
+        &message_to_sign,
+    )
+    .unwrap_err();
+    assert!(err.blame().is_empty());
+
+    // Signature shares can't be combined for another public key
+    let other_public_key = *public_key + Point::generator().to_point();
+    let err = cggmp21::Signature::from_shares(
+        &other_public_key,
+        &commitments,
+        &signature_shares,
+        &message_to_sign,
+    )
+    .unwrap_err();
+    assert!(err.blame().is_empty());
+}
+
+cggmp21_tests::test_suite! {
+    test: delegated_contribution_completes_signature,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn delegated_contribution_completes_signature<E>(t: Option<u16>, n: u16)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::signing::RedeemContributionError;
+    use std::time::{Duration, SystemTime};
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+    let mut presigs = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .generate_presignature(&mut party_rng, party)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let other_message = DataToSign::digest::<Sha256>(b"other message");
+    let issued_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let expires_at = issued_at + Duration::from_secs(60);
+
+    let commitments = presigs
+        .iter()
+        .map(|presig| presig.commitment())
+        .collect::<Vec<_>>();
+
+    // Offline signer delegates its contribution, the rest of signers issue shares as usual
+    let contribution = presigs.remove(0).delegate_contribution::<_, Sha256>(
+        &mut rng,
+        &shares[0],
+        message_to_sign,
+        eid,
+        expires_at,
+    );
+    let mut signature_shares = presigs
+        .into_iter()
+        .map(|presig| presig.issue_signature_share(message_to_sign))
+        .collect::<Vec<_>>();
+
+    // Contribution can only be redeemed for the same message and session before it expires
+    let public_share = *shares[0].core.public_shares[0];
+    assert!(matches!(
+        contribution
+            .clone()
+            .redeem_at::<Sha256>(&public_share, &other_message, eid, issued_at),
+        Err(RedeemContributionError::WrongMessage)
+    ));
+    assert!(matches!(
+        contribution.clone().redeem_at::<Sha256>(
+            &public_share,
+            &message_to_sign,
+            ExecutionId::new(b"other eid"),
+            issued_at
+        ),
+        Err(RedeemContributionError::WrongExecutionId)
+    ));
+    assert!(matches!(
+        contribution
+            .clone()
+            .redeem_at::<Sha256>(&public_share, &message_to_sign, eid, expires_at),
+        Err(RedeemContributionError::Expired)
+    ));
+    // Expiration is checked against the system clock, and `expires_at` is in the past
+    assert!(matches!(
+        contribution
+            .clone()
+            .redeem::<Sha256>(&public_share, &message_to_sign, eid),
+        Err(RedeemContributionError::Expired)
+    ));
+
+    // Aggregator can't alter execution ID or extend expiration time
+    let mut tampered = contribution.clone();
+    tampered.execution_id = b"other eid".to_vec();
+    assert!(matches!(
+        tampered.redeem_at::<Sha256>(
+            &public_share,
+            &message_to_sign,
+            ExecutionId::new(b"other eid"),
+            issued_at
+        ),
+        Err(RedeemContributionError::InvalidAuthorization)
+    ));
+    let mut tampered = contribution.clone();
+    tampered.expires_at = u64::MAX;
+    assert!(matches!(
+        tampered.redeem::<Sha256>(&public_share, &message_to_sign, eid),
+        Err(RedeemContributionError::InvalidAuthorization)
+    ));
+
+    // Contribution must be signed by the delegator
+    let other_public_share = *shares[1].core.public_shares[1];
+    assert!(matches!(
+        contribution.clone().redeem_at::<Sha256>(
+            &other_public_share,
+            &message_to_sign,
+            eid,
+            issued_at
+        ),
+        Err(RedeemContributionError::InvalidAuthorization)
+    ));
+
+    let share = contribution
+        .redeem_at::<Sha256>(&public_share, &message_to_sign, eid, issued_at)
+        .expect("redeem contribution");
+    signature_shares.insert(0, share);
+
+    let public_key = shares[0].shared_public_key;
+    let signature = cggmp21::Signature::from_shares(
+        &public_key,
+        &commitments,
+        &signature_shares,
+        &message_to_sign,
+    )
+    .expect("combine signature shares");
+    signature
+        .verify(&public_key, &message_to_sign)
+        .expect("signature is not valid");
+}
+
+cggmp21_tests::test_suite! {
+    test: presignature_pool_hands_out_each_presignature_once,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn presignature_pool_hands_out_each_presignature_once<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::presignature_pool::PresignaturePool;
+    use std::time::Duration;
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+
+    let eids: [[u8; 32]; 2] = [rng.gen(), rng.gen()];
+    let mut generate_presignature = |eid: ExecutionId| {
+        let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+        round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .generate_presignature(&mut party_rng, party)
+                    .await
+            }
+        })
+        .unwrap()
+        .expect_ok()
+        .into_vec()
+        .remove(0)
+    };
+    let presig1 = generate_presignature(ExecutionId::new(&eids[0]));
+    let presig2 = generate_presignature(ExecutionId::new(&eids[1]));
+
+    let mut pool = PresignaturePool::<E>::new(Duration::from_secs(3600));
+    pool.insert(participants, ExecutionId::new(&eids[0]), presig1.clone())
+        .unwrap();
+    pool.insert(participants, ExecutionId::new(&eids[1]), presig2)
+        .unwrap();
+    assert_eq!(pool.available(participants), 2);
+
+    // Pool refuses presignature generated within the same execution ID
+    pool.insert(participants, ExecutionId::new(&eids[0]), presig1.clone())
+        .unwrap_err();
+
+    // Presignatures for another set of signers are not handed out
+    let other_signers = (1..=t).collect::<Vec<_>>();
+    assert!(pool.take(&other_signers).is_none());
+
+    // Each presignature is handed out only once
+    assert!(pool.take(participants).is_some());
+    assert!(pool.take(participants).is_some());
+    assert!(pool.take(participants).is_none());
+    assert!(pool.is_empty());
+
+    // Consumed presignature can't be put back into the pool
+    pool.insert(participants, ExecutionId::new(&eids[0]), presig1.clone())
+        .unwrap_err();
+
+    // Presignatures are evicted after TTL
+    let mut pool = PresignaturePool::<E>::new(Duration::ZERO);
+    pool.insert(participants, ExecutionId::new(&eids[0]), presig1.clone())
+        .unwrap();
+    assert_eq!(pool.evict_expired(), 1);
+    assert!(pool.take(participants).is_none());
+
+    // Evicted presignature is still remembered, even under another execution ID
+    pool.insert(participants, ExecutionId::new(&eids[0]), presig1.clone())
+        .unwrap_err();
+    pool.insert(
+        participants,
+        ExecutionId::new(b"other eid"),
+        presig1.clone(),
+    )
+    .unwrap_err();
+
+    // Until it's explicitly forgotten
+    pool.forget(ExecutionId::new(&eids[0]), &presig1.R);
+    pool.insert(participants, ExecutionId::new(&eids[0]), presig1)
+        .unwrap();
+}
+
+cggmp21_tests::test_suite! {
+    test: presignature_pools_agree_on_presignature,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn presignature_pools_agree_on_presignature<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::presignature_pool::{PresignatureId, PresignaturePool};
+    use std::time::Duration;
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+
+    let eids: [[u8; 32]; 2] = [rng.gen(), rng.gen()];
+    let mut generate_presignatures = |eid: ExecutionId| {
+        let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+        round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .generate_presignature(&mut party_rng, party)
+                    .await
+            }
+        })
+        .unwrap()
+        .expect_ok()
+        .into_vec()
+    };
+    let presigs1 = generate_presignatures(ExecutionId::new(&eids[0]));
+    let presigs2 = generate_presignatures(ExecutionId::new(&eids[1]));
+
+    // Signers put presignatures into their pools in different order
+    let mut pools = iter::repeat_with(|| PresignaturePool::<E>::new(Duration::from_secs(3600)))
+        .take(t.into())
+        .collect::<Vec<_>>();
+    for (j, pool) in pools.iter_mut().enumerate() {
+        let mut presigs = [
+            (ExecutionId::new(&eids[0]), presigs1[j].clone()),
+            (ExecutionId::new(&eids[1]), presigs2[j].clone()),
+        ];
+        if j % 2 == 1 {
+            presigs.reverse();
+        }
+        for (eid, presig) in presigs {
+            pool.insert(participants, eid, presig).unwrap();
+        }
+    }
+
+    // First signer picks a presignature, others take the one with the same id
+    let (id, presig) = pools[0].take(participants).unwrap();
+    assert_eq!(id, PresignatureId::from_eid(ExecutionId::new(&eids[0])));
+    let mut presigs = vec![presig];
+    for pool in &mut pools[1..] {
+        presigs.push(pool.take_by_id(participants, id).unwrap());
+        assert!(pool.take_by_id(participants, id).is_none());
+    }
+    assert!(presigs.iter().all(|presig| presig.R == presigs[0].R));
+
+    let mut message = [0u8; 100];
+    rng.fill_bytes(&mut message);
+    let message = DataToSign::digest::<Sha256>(&message);
+    let partial_signatures = presigs
+        .into_iter()
+        .map(|presig| presig.issue_partial_signature(message))
+        .collect::<Vec<_>>();
+    let signature = cggmp21::PartialSignature::combine(&partial_signatures)
+        .expect("invalid partial signatures");
+    signature
+        .verify(&shares[0].shared_public_key, &message)
+        .expect("signature is not valid");
+}
+
+cggmp21_tests::test_suite! {
+    test: overprovisioned_signing_tolerates_dropped_signer,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn overprovisioned_signing_tolerates_dropped_signer<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::overprovisioning::{quorums, QuorumCombiner};
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let t = shares[0].min_signers();
+
+    // One signer more than needed
+    let candidates = (0..=t).collect::<Vec<_>>();
+    let quorums = quorums(&candidates, t).collect::<Vec<_>>();
+    assert_eq!(quorums.len(), usize::from(t) + 1);
+
+    // Presignature is generated for each quorum within its own execution ID
+    let presignatures = quorums
+        .iter()
+        .map(|quorum| {
+            let eid: [u8; 32] = rng.gen();
+            let eid = ExecutionId::new(&eid);
+            let quorum_shares = quorum.iter().map(|i| &shares[usize::from(*i)]);
+            round_based::sim::run_with_setup(quorum_shares, |i, party, share| {
+                let mut party_rng = rng.fork();
+                async move {
+                    cggmp21::signing(eid, i, quorum, share)
+                        .generate_presignature(&mut party_rng, party)
+                        .await
+                }
+            })
+            .unwrap()
+            .expect_ok()
+            .into_vec()
+        })
+        .collect::<Vec<_>>();
+
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let public_key = shares[0].shared_public_key;
+    let mut combiner = QuorumCombiner::new(public_key.into_inner(), message_to_sign);
+    let quorum_ids = quorums
+        .iter()
+        .zip(&presignatures)
+        .map(|(quorum, presignatures)| {
+            let commitments = presignatures.iter().map(|p| p.commitment()).collect();
+            combiner.add_quorum(quorum, commitments).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // Last candidate drops, everyone else issues shares for all quorums they belong to
+    let dropped = t;
+    let mut any_share = None;
+    for ((quorum, presignatures), &quorum_id) in quorums.iter().zip(presignatures).zip(&quorum_ids)
+    {
+        for (&signer, presignature) in quorum.iter().zip(presignatures) {
+            if signer == dropped {
+                continue;
+            }
+            let share = presignature.issue_signature_share(message_to_sign);
+            any_share.get_or_insert_with(|| share.clone());
+            combiner.receive(quorum_id, signer, share).unwrap();
+        }
+    }
+
+    let result = combiner.try_combine().expect("quorum must complete");
+    assert!(!result.quorum.contains(&dropped));
+    assert_eq!(result.dropped, [dropped]);
+    result
+        .signature
+        .verify(&public_key, &message_to_sign)
+        .expect("signature is not valid");
+
+    // Signer can't submit a share for a quorum it's not a member of
+    let foreign_quorum = quorums
+        .iter()
+        .position(|quorum| !quorum.contains(&dropped))
+        .unwrap();
+    assert!(combiner
+        .receive(quorum_ids[foreign_quorum], dropped, any_share.unwrap())
+        .is_err());
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_scalar_message,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn signing_scalar_message<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // Message representative is given as a scalar, no hashing is applied
+    let scalar = generic_ec::Scalar::<E>::from(0x1234_5678_u64);
+    let message_to_sign = DataToSign::from_scalar(scalar);
+    assert_eq!(message_to_sign.to_scalar(), scalar);
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+    let sig = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    sig.verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+    sig.verify(
+        &shares[0].shared_public_key,
+        &DataToSign::from_scalar(scalar + generic_ec::Scalar::one()),
+    )
+    .expect_err("signature is valid for another message");
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_messages_fit_max_size,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3, 1),
+        t2n3_2msgs: (Some(2), 3, 2),
+    }
+}
+fn signing_messages_fit_max_size<E>(t: Option<u16>, n: u16, batch_size: usize)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    use round_based::state_machine::{ProceedResult, StateMachine};
+
+    /// State machine wrapper that records size of encoded messages received by the party
+    struct RecordSize<'r, SM> {
+        party: SM,
+        max_size: &'r std::cell::Cell<usize>,
+    }
+    impl<SM> StateMachine for RecordSize<'_, SM>
+    where
+        SM: StateMachine,
+        SM::Msg: serde::Serialize,
+    {
+        type Output = SM::Output;
+        type Msg = SM::Msg;
+
+        fn proceed(&mut self) -> ProceedResult<Self::Output, Self::Msg> {
+            self.party.proceed()
+        }
+
+        fn received_msg(
+            &mut self,
+            msg: round_based::Incoming<Self::Msg>,
+        ) -> Result<(), round_based::Incoming<Self::Msg>> {
+            let size = cggmp21::codec::encode_message(&msg.msg, usize::MAX)
+                .unwrap()
+                .len();
+            self.max_size.set(self.max_size.get().max(size));
+            self.party.received_msg(msg)
+        }
+    }
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let messages_to_sign = (0..batch_size)
+        .map(|j| DataToSign::digest::<Sha256>(&j.to_be_bytes()))
+        .collect::<Vec<_>>();
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let mut signer_rng = iter::repeat_with(|| rng.fork())
+        .take(t.into())
+        .collect::<Vec<_>>();
+    let max_size = std::cell::Cell::new(0);
+
+    let mut simulation = round_based::sim::Simulation::with_capacity(t);
+    for ((i, signer_rng), share) in (0..).zip(&mut signer_rng).zip(&shares) {
+        simulation.add_party(RecordSize {
+            party: cggmp21::signing(eid, i, participants, share)
+                .sign_batch_sync(signer_rng, &messages_to_sign),
+            max_size: &max_size,
+        });
+    }
+    simulation.run().unwrap().expect_ok().expect_eq();
+
+    let bound = cggmp21::signing::max_message_size::<E, SecurityLevel128>(batch_size);
+    let max_size = max_size.get();
+    println!("largest message: {max_size} bytes, bound: {bound} bytes");
+    assert!(max_size <= bound);
+    // Bound is not too loose
+    assert!(bound < 2 * max_size);
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_can_be_cancelled,
+    generics: all_curves,
+    suites: {
+        t3n5: (Some(3), 5),
+    }
+}
+fn signing_can_be_cancelled<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+
+    let token = cggmp21::cancellation::CancellationToken::new();
+    token.cancel();
+
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message to sign");
+    let results = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        let token = token.clone();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .set_cancellation(token)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .into_vec();
+
+    for (i, result) in (0u16..).zip(results) {
+        let err = result.unwrap_err();
+        assert!(err.is_cancelled(), "party {i}: {err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_round_timeout,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn signing_round_timeout<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21_tests::{ImmediateTimer, NeverTimer};
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+    let timeout = std::time::Duration::from_secs(10);
+
+    // Timer that never fires doesn't affect the protocol
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message to sign");
+    let sigs = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .set_timer(NeverTimer)
+                .set_round_timeout(timeout)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+    sigs[0]
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .unwrap();
+
+    // Only one signer is online, so it times out waiting for messages of the first round
+    let party = round_based::MpcParty::connected((
+        futures::stream::pending::<Result<round_based::Incoming<_>, std::convert::Infallible>>(),
+        futures::sink::drain(),
+    ));
+    let result = futures::executor::block_on(
+        cggmp21::signing(eid, 0, participants, &shares[0])
+            .set_timer(ImmediateTimer)
+            .set_round_timeout(timeout)
+            .sign(&mut rng, party, message_to_sign),
+    );
+    let err = result.unwrap_err();
+    assert!(err.is_timed_out(), "{err:?}");
+
+    // Round timeout can't be set without a timer
+    let party = round_based::MpcParty::connected((
+        futures::stream::pending::<Result<round_based::Incoming<_>, std::convert::Infallible>>(),
+        futures::sink::drain(),
+    ));
+    let result = futures::executor::block_on(
+        cggmp21::signing(eid, 0, participants, &shares[0])
+            .set_round_timeout(timeout)
+            .sign(&mut rng, party, message_to_sign),
+    );
+    let err = result.unwrap_err();
+    assert!(err.is_timer_not_set(), "{err:?}");
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_checks_expected_public_key,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3, false),
+        #[cfg(feature = "hd-wallet")]
+        t2n3_hd: (Some(2), 3, true),
+    }
+}
+fn signing_checks_expected_public_key<E>(t: Option<u16>, n: u16, hd_wallet: bool)
+where
+    E: Curve + cggmp21_tests::CurveParams,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, hd_wallet)
+        .expect("retrieve cached shares");
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+
+    #[cfg(feature = "hd-wallet")]
+    let derivation_path = hd_wallet.then(|| vec![1u32, 10]);
+    #[cfg(feature = "hd-wallet")]
+    let public_key = match &derivation_path {
+        Some(path) => generic_ec::NonZero::from_point(
+            shares[0]
+                .derive_child_public_key::<E::HdAlgo, _>(path.iter().copied())
+                .unwrap()
+                .public_key,
+        )
+        .unwrap(),
+        None => shares[0].shared_public_key,
+    };
+    #[cfg(not(feature = "hd-wallet"))]
+    let public_key = shares[0].shared_public_key;
+
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message to sign");
+    let mut run_signing = |expected_public_key| {
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+        #[cfg(feature = "hd-wallet")]
+        let derivation_path = &derivation_path;
+        round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+            let mut party_rng = rng.fork();
+            let signing = cggmp21::signing(eid, i, participants, share)
+                .expect_public_key(expected_public_key);
+            #[cfg(feature = "hd-wallet")]
+            let signing = match derivation_path {
+                Some(path) => signing
+                    .set_derivation_path_with_algo::<E::HdAlgo, _>(path.iter().copied())
+                    .unwrap(),
+                None => signing,
+            };
+            async move { signing.sign(&mut party_rng, party, message_to_sign).await }
+        })
+        .unwrap()
+        .into_vec()
+    };
+
+    let sig = run_signing(public_key)
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()[0];
+    sig.verify(&public_key, &message_to_sign)
+        .expect("signature is not valid");
+
+    let another_key =
+        generic_ec::NonZero::from_point(*public_key + Point::generator().to_point()).unwrap();
+    for (i, result) in (0u16..).zip(run_signing(another_key)) {
+        let err = result.unwrap_err();
+        assert!(err.is_wrong_key(), "party {i}: {err:?}");
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: public_key_is_recovered_from_signature,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn public_key_is_recovered_from_signature<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E> + generic_ec::coords::HasAffineXAndParity<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message to sign");
+
+    let sig = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    let public_key = shares[0].shared_public_key;
+    let recovery_id = sig
+        .recovery_id(&public_key, &message_to_sign)
+        .expect("recovery id not found");
+    assert!(recovery_id < 4);
+    let recovered = sig
+        .recover_public_key(recovery_id, &message_to_sign)
+        .unwrap();
+    assert_eq!(recovered, public_key);
+
+    // Another recovery id leads to a different key (or none at all)
+    let other = sig.recover_public_key(recovery_id ^ 1, &message_to_sign);
+    assert!(other.map_or(true, |pk| pk != public_key));
+    assert!(sig.recover_public_key(4, &message_to_sign).is_err());
+
+    // Recovery id doesn't exist if the message doesn't match
+    let another_message = DataToSign::digest::<Sha256>(b"another message");
+    assert_eq!(sig.recovery_id(&public_key, &another_message), None);
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_rejects_invalid_signers_list,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+        n3: (None, 3),
+    }
+}
+fn signing_rejects_invalid_signers_list<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let t = shares[0].min_signers();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message to sign");
+
+    let mut run_signing = |participants: &[u16]| {
+        let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+        round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        })
+        .unwrap()
+        .into_vec()
+    };
+
+    // Last signer is replaced with the first one
+    let mut participants = (0..t).collect::<Vec<_>>();
+    participants[usize::from(t) - 1] = 0;
+    for result in run_signing(&participants) {
+        assert_eq!(result.unwrap_err().duplicate_signer(), Some(0));
+    }
+
+    // List of signers is shorter than threshold
+    for result in run_signing(&participants[..usize::from(t) - 1]) {
+        let err = result.unwrap_err();
+        assert_eq!(err.duplicate_signer(), None);
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: signers_obtain_same_transcript_hash,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn signers_obtain_same_transcript_hash<E: Curve>(t: Option<u16>, n: u16)
+where
+    Point<E>: HasAffineX<E>,
+{
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let eid: [u8; 32] = rng.gen();
+    let other_eid: [u8; 32] = rng.gen();
+
+    let mut sign = |eid: [u8; 32]| {
+        round_based::sim::run_with_setup(&shares[..usize::from(t)], |i, party, share| {
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(ExecutionId::new(&eid), i, participants, share)
+                    .sign_with_transcript(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        })
+        .unwrap()
+        .expect_ok()
+        .expect_eq()
+    };
+
+    let output = sign(eid);
+    output
+        .signature
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+
+    // Another session has another transcript
+    assert!(output.participation_proof.is_none());
+
+    let other_output = sign(other_eid);
+    assert_ne!(output.transcript_hash, other_output.transcript_hash);
+}
+
+cggmp21_tests::test_suite! {
+    test: signers_prove_participation,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn signers_prove_participation<E: Curve>(t: Option<u16>, n: u16)
+where
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::participation::{self, BroadcastSigner, BroadcastVerifier};
+
+    /// Signer for tests: signature is a hash of signer index and data
+    struct TestSigner(u16);
+
+    impl BroadcastSigner for TestSigner {
+        fn sign(&self, data: &[u8]) -> Vec<u8> {
+            use sha2::Digest;
+            Sha256::new()
+                .chain_update(self.0.to_be_bytes())
+                .chain_update(data)
+                .finalize()
+                .to_vec()
+        }
+    }
+
+    impl BroadcastVerifier for TestSigner {
+        fn verify(&self, j: u16, data: &[u8], signature: &[u8]) -> bool {
+            Auditor.verify(j, data, signature)
+        }
+    }
+
+    /// Holds only verification keys of the parties, can't sign
+    struct Auditor;
+
+    impl BroadcastVerifier for Auditor {
+        fn verify(&self, j: u16, data: &[u8], signature: &[u8]) -> bool {
+            TestSigner(j).sign(data) == signature
+        }
+    }
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let t = shares[0].min_signers();
+    let participants = &(n - t..n).collect::<Vec<_>>();
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let output = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        let signer = TestSigner(participants[usize::from(i)]);
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .prove_participation(&signer)
+                .sign_with_transcript(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    output
+        .signature
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+    let proof = output
+        .participation_proof
+        .expect("participation proof is missing");
+    assert_eq!(proof.transcript_hash, output.transcript_hash);
+    participation::verify_participation::<Sha256>(&proof, participants, &Auditor)
+        .expect("participation proof is not valid");
+
+    // Proof doesn't attest another signers set
+    let reversed = participants.iter().rev().copied().collect::<Vec<_>>();
+    assert!(participation::verify_participation::<Sha256>(&proof, &reversed, &Auditor).is_err());
+
+    // Signer with invalid signature is blamed
+    let mut tampered = proof.clone();
+    tampered.signatures[0][0] ^= 1;
+    let err = participation::verify_participation::<Sha256>(&tampered, participants, &Auditor)
+        .unwrap_err();
+    assert_eq!(err.parties, [participants[0]]);
+}
+
+cggmp21_tests::test_suite! {
+    test: round_observer_reports_received_messages,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+    }
+}
+fn round_observer_reports_received_messages<E: Curve>(t: Option<u16>, n: u16)
+where
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::round_observer::RoundProgress;
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let reports = round_based::sim::run_with_setup(&shares[..usize::from(t)], |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            let mut reports = vec![];
+            let mut observer = |progress: RoundProgress| reports.push(progress);
+            cggmp21::signing(eid, i, participants, share)
+                .enforce_reliable_broadcast(false)
+                .set_round_observer(&mut observer)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await?;
+            Ok::<_, cggmp21::signing::SigningError>(reports)
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    for (i, reports) in (0u16..).zip(reports) {
+        for round in ["round 1a", "round 1b", "round 2", "round 3", "round 4"] {
+            let received = reports
+                .iter()
+                .filter(|r| r.round == round)
+                .map(|r| r.received)
+                .collect::<Vec<_>>();
+            assert_eq!(received, (1..t).collect::<Vec<_>>(), "{round}");
+        }
+        assert_eq!(reports.len(), 5 * usize::from(t - 1));
+
+        for report in reports {
+            assert_ne!(report.from, i);
+            assert_eq!(report.expected, t - 1);
+            assert!(1 <= report.received && report.received <= report.expected);
+        }
+    }
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_can_be_resumed,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn signing_can_be_resumed<E: Curve>(t: Option<u16>, n: u16)
+where
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::signing::{resume_signing, SigningState};
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let states = round_based::sim::run_with_setup(&shares[..usize::from(t)], |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign_resumable(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    // Signers are suspended: state is persisted and restored
+    let persisted = states
+        .iter()
+        .map(|state| serde_json::to_vec(state).unwrap())
+        .collect::<Vec<_>>();
+    let restore = || {
+        persisted
+            .iter()
+            .map(|state| serde_json::from_slice::<SigningState<E>>(state).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let states = restore();
+    for state in &states {
+        assert_eq!(state.execution_id().as_bytes(), eid.as_bytes());
+        assert_eq!(state.signers(), participants);
+        assert_eq!(state.message().to_scalar(), message_to_sign.to_scalar());
+    }
+
+    // State can't be resumed in a session with a different execution id
+    let other_eid: [u8; 32] = rng.gen();
+    let other_eid = ExecutionId::new(&other_eid);
+    let results = round_based::sim::run_with_setup(restore(), |_i, party, state| {
+        resume_signing::<E, Sha256, _>(other_eid, state, party)
+    })
+    .unwrap()
+    .into_vec();
+    for result in results {
+        let err = result.unwrap_err();
+        assert!(err.is_execution_id_mismatch(), "{err:?}");
+    }
+
+    let sig = round_based::sim::run_with_setup(states, |_i, party, state| {
+        resume_signing::<E, Sha256, _>(eid, state, party)
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    sig.verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_guard_limits_concurrent_sessions,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn signing_guard_limits_concurrent_sessions<E>(t: Option<u16>, n: u16)
+where
+    E: Curve,
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::signing_guard::SigningGuard;
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let guards = participants
+        .iter()
+        .map(|i| SigningGuard::new(shares[usize::from(*i)].clone(), 1))
+        .collect::<Vec<_>>();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    // Guard refuses to start a session while another one is in flight
+    let permit = guards[0].acquire().unwrap();
+    assert_eq!(guards[0].in_flight(), 1);
+    let err = guards[0].signing(eid, 0, participants).err().unwrap();
+    assert_eq!(err.max_sessions(), 1);
+
+    // Slot is released once the permit is dropped
+    drop(permit);
+    assert_eq!(guards[0].in_flight(), 0);
+
+    // Builder holds the slot until it's dropped
+    let signing = guards[0].signing(eid, 0, participants).unwrap();
+    assert_eq!(guards[0].in_flight(), 1);
+    drop(signing);
+    assert_eq!(guards[0].in_flight(), 0);
+
+    // And so does the protocol future, even if it's never completed
+    let party = round_based::MpcParty::connected((
+        futures::stream::pending::<Result<round_based::Incoming<_>, std::convert::Infallible>>(),
+        futures::sink::drain(),
+    ));
+    let mut signing = Box::pin(guards[0].signing(eid, 0, participants).unwrap().sign(
+        &mut rng,
+        party,
+        DataToSign::digest::<Sha256>(b"message"),
+    ));
+    assert!(futures::FutureExt::now_or_never(signing.as_mut()).is_none());
+    assert_eq!(guards[0].in_flight(), 1);
+    drop(signing);
+    assert_eq!(guards[0].in_flight(), 0);
+
+    let mut original_message_to_sign = [0u8; 100];
+    rng.fill_bytes(&mut original_message_to_sign);
+    let message_to_sign = DataToSign::digest::<Sha256>(&original_message_to_sign);
+
+    let sigs = round_based::sim::run_with_setup(&guards, |i, party, guard| {
+        let mut party_rng = rng.fork();
+        async move {
+            let signing = guard.signing(eid, i, participants).unwrap();
+            // Permit is held by the builder
+            assert_eq!(guard.in_flight(), 1);
+            signing.sign(&mut party_rng, party, message_to_sign).await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .into_vec();
+
+    for guard in &guards {
+        assert_eq!(guard.in_flight(), 0);
+    }
+    sigs[0]
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_with_modexp_backend,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn signing_with_modexp_backend<E: Curve>(t: Option<u16>, n: u16)
+where
+    Point<E>: HasAffineX<E>,
+{
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use cggmp21::modexp::{ModExpBackend, Software};
+    use cggmp21::rug::Integer;
+
+    /// Backend that computes exponentiations in software, and counts them
+    #[derive(Default)]
+    struct Counting(AtomicUsize);
+
+    impl ModExpBackend for Counting {
+        fn pow_mod(
+            &self,
+            base: &Integer,
+            exponent: &Integer,
+            modulus: &Integer,
+        ) -> Option<Integer> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Software.pow_mod(base, exponent, modulus)
+        }
+    }
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let t = shares[0].min_signers();
+    let participants = &(0..t).collect::<Vec<_>>();
+    let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let backend = Counting::default();
+    let backend = &backend;
+    let sig = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .set_modexp_backend(backend)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    sig.verify(&shares[0].shared_public_key, &message_to_sign)
+        .expect("signature is not valid");
+
+    // Each signer encrypts K_i and G_i, then for each other signer computes two homomorphic
+    // multiplications and four encryptions
+    let t = usize::from(t);
+    assert_eq!(backend.0.load(Ordering::Relaxed), t * (2 + 6 * (t - 1)));
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_with_tweaked_key_share,
+    generics: all_curves,
+    suites: {
+        n3: (None, 3),
+        t2n3: (Some(2), 3),
+    }
+}
+fn signing_with_tweaked_key_share<E: Curve>(t: Option<u16>, n: u16)
+where
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::key_share::tweaked_key_share;
+
+    let mut rng = DevRng::new();
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let tweaked_shares = shares
+        .iter()
+        .map(|share| tweaked_key_share::<_, _, Sha256>(share, b"account-1").unwrap())
+        .collect::<Vec<_>>();
+
+    // All parties derive the same key, which is different for different domains
+    let core: &cggmp21::IncompleteKeyShare<E> = shares[0].as_ref();
+    let key_info = core.to_public();
+    let tweaked_key_info = key_info.tweak::<Sha256>(b"account-1").unwrap();
+    let tweaked_pk = tweaked_shares[0].shared_public_key;
+    assert_eq!(tweaked_key_info.shared_public_key, tweaked_pk);
+    assert!(tweaked_shares
+        .iter()
+        .all(|s| s.shared_public_key == tweaked_pk));
+    assert_ne!(tweaked_pk, shares[0].shared_public_key);
+    assert_ne!(
+        key_info
+            .tweak::<Sha256>(b"account-2")
+            .unwrap()
+            .shared_public_key,
+        tweaked_pk
+    );
+
+    // Sign with the last `t` parties, so that it's not only the first party's share that is used
+    let t = tweaked_shares[0].min_signers();
+    let participants = &(n - t..n).collect::<Vec<_>>();
+    let participants_shares = participants
+        .iter()
+        .map(|i| &tweaked_shares[usize::from(*i)]);
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message");
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+
+    let sig = round_based::sim::run_with_setup(participants_shares, |i, party, share| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+        }
+    })
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+
+    sig.verify(&tweaked_pk, &message_to_sign)
+        .expect("signature is not valid under tweaked key");
+    assert!(sig
+        .verify(&shares[0].shared_public_key, &message_to_sign)
+        .is_err());
+}
+
+cggmp21_tests::test_suite! {
+    test: preflight_detects_inconsistent_signers,
+    generics: all_curves,
+    suites: {
+        t2n3: (Some(2), 3),
+    }
+}
+fn preflight_detects_inconsistent_signers<E: Curve>(t: Option<u16>, n: u16)
+where
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::signing::preflight;
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(t, n, false)
+        .expect("retrieve cached shares");
+    let other_key_shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(None, n, false)
+        .expect("retrieve cached shares");
+
+    preflight(&shares).expect("shares are consistent");
+    preflight(&shares[1..]).expect("shares are consistent");
+
+    let err = preflight(&[shares[0].clone(), shares[0].clone()]).unwrap_err();
+    assert!(err.is_duplicate_signer());
+    assert_eq!(err.party(), 0);
+
+    let err = preflight(&[shares[0].clone(), other_key_shares[1].clone()]).unwrap_err();
+    assert!(err.is_wrong_key());
+    assert_eq!(err.party(), 1);
+
+    // Signer 2 carries ring-Pedersen parameters of party 0 that differ from others
+    let mut tampered = shares[2].clone().into_inner();
+    let aux_0 = &mut tampered.aux.parties[0];
+    std::mem::swap(&mut aux_0.s, &mut aux_0.t);
+    let tampered = cggmp21::KeyShare::<E, SecurityLevel128>::validate(tampered).unwrap();
+    let err = preflight(&[shares[0].clone(), shares[1].clone(), tampered]).unwrap_err();
+    assert!(err.is_aux_info_mismatch());
+    assert_eq!(err.party(), 2);
+}
+
+cggmp21_tests::test_suite! {
+    test: batch_verifier_reports_invalid_signatures,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn batch_verifier_reports_invalid_signatures<E: Curve>()
+where
+    Point<E>: HasAffineX<E>,
+    generic_ec::NonZero<Point<E>>: generic_ec::coords::AlwaysHasAffineX<E>,
+{
+    use cggmp21::signing::{BatchVerifier, Signature};
+    use generic_ec::{NonZero, Scalar, SecretScalar};
+
+    // Plain ECDSA signing: $r = (k \cdot G)_x$, $s = k^{-1} (m + r \cdot sk)$
+    fn sign<E: Curve>(
+        rng: &mut DevRng,
+        sk: &NonZero<SecretScalar<E>>,
+        message: &DataToSign<E>,
+    ) -> Signature<E>
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let k = NonZero::<SecretScalar<E>>::random(rng);
+        let nonce: Point<E> = *(Point::generator() * &k);
+        let r = nonce.x().expect("nonce is not zero").to_scalar();
+        let s = k.invert() * (message.to_scalar() + r * sk);
+        Signature {
+            r: NonZero::from_scalar(r).unwrap(),
+            s: NonZero::from_scalar(s).unwrap(),
+        }
+    }
+
+    let mut rng = DevRng::new();
+
+    let mut batch = BatchVerifier::<E>::new();
+    assert!(batch.is_empty());
+    let mut expected_failures = vec![];
+    for i in 0..20u8 {
+        let sk = NonZero::<SecretScalar<E>>::random(&mut rng);
+        let public_key = Point::generator() * &sk;
+        let message = DataToSign::digest::<Sha256>(&[i]);
+        let mut signature = sign(&mut rng, &sk, &message);
+
+        match i % 7 {
+            3 => {
+                // Signature of another message
+                signature = sign(
+                    &mut rng,
+                    &sk,
+                    &DataToSign::digest::<Sha256>(b"another message"),
+                );
+                expected_failures.push(usize::from(i));
+            }
+            5 => {
+                // Tampered `s`
+                signature.s = NonZero::from_scalar(*signature.s + Scalar::one()).unwrap();
+                expected_failures.push(usize::from(i));
+            }
+            _ => (),
+        }
+        batch.push(&public_key, &signature, &message);
+    }
+    assert_eq!(batch.len(), 20);
+    assert_eq!(batch.finalize(), Err(expected_failures));
+
+    // Batch of valid signatures passes
+    let mut batch = BatchVerifier::<E>::default();
+    for i in 0..5u8 {
+        let sk = NonZero::<SecretScalar<E>>::random(&mut rng);
+        let message = DataToSign::digest::<Sha256>(&[i]);
+        let signature = sign(&mut rng, &sk, &message);
+        batch.push(&(Point::generator() * &sk), &signature, &message);
+    }
+    assert_eq!(batch.finalize(), Ok(()));
+
+    // Empty batch passes
+    assert_eq!(BatchVerifier::<E>::new().finalize(), Ok(()));
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_is_send,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn signing_is_send<E: Curve>()
+where
+    Point<E>: HasAffineX<E>,
+{
+    use cggmp21::progress::PerfProfiler;
+    use cggmp21::round_observer::RoundProgress;
+    use cggmp21::signing::SigningArena;
+    use cggmp21_tests::NeverTimer;
+    use futures::{sink::Drain, stream::Pending};
+    use round_based::{Incoming, MpcParty, Outgoing};
+    use std::{convert::Infallible, time::Duration};
+
+    fn assert_send<T: Send>(_: &T) {}
+    /// Party that is never connected to anyone
+    fn party<M>() -> MpcParty<M, (Pending<Result<Incoming<M>, Infallible>>, Drain<Outgoing<M>>)> {
+        MpcParty::connected((futures::stream::pending(), futures::sink::drain()))
+    }
+
+    let mut rng = DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<E, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+    let eid = ExecutionId::new(b"signing is send");
+    let participants = &[0, 1];
+    let message_to_sign = DataToSign::digest::<Sha256>(b"message to sign");
+
+    let mut tracer = PerfProfiler::new();
+    let mut observer = |_: RoundProgress| {};
+    let mut arena = SigningArena::new();
+
+    // Builder and protocol future are `Send` with all the options set, so signing can be spawned
+    // on multi-threaded runtime
+    let signing = cggmp21::signing(eid, 0, participants, &shares[0])
+        .set_progress_tracer(&mut tracer)
+        .set_round_observer(&mut observer)
+        .set_arena(&mut arena)
+        .set_timer(NeverTimer)
+        .set_round_timeout(Duration::from_secs(10))
+        .require_nonce_commitment_ack(async { true })
+        .require_aggregate_nonce_ack(async { true });
+    assert_send(&signing);
+    let signing = signing.sign(&mut rng, party(), message_to_sign);
+    assert_send(&signing);
+    drop(signing);
+
+    let signing = cggmp21::signing(eid, 0, participants, &shares[0])
+        .set_deterministic_session(b"signing is send");
+    let presignature = signing.generate_presignature(&mut rng, party());
+    assert_send(&presignature);
+}
@@ -0,0 +1,87 @@
+//! Pinned test vectors
+//!
+//! Key shares are produced by trusted dealer from a fixed secret key, fixed rng seed and cached
+//! primes, and signing is run in [deterministic session](cggmp21::signing::SigningBuilder::set_deterministic_session)
+//! mode, so the whole transcript is reproducible. Any change to the resulting signature indicates
+//! that the transcript format has changed.
+
+use cggmp21::{
+    generic_ec::{NonZero, Point, Scalar, SecretScalar},
+    key_share::KeyShare,
+    security_level::SecurityLevel128,
+    supported_curves::Secp256k1,
+    DataToSign, ExecutionId, Signature,
+};
+use rand::SeedableRng;
+use sha2::Sha256;
+
+const SECRET_KEY: &str = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const DEALER_SEED: [u8; 32] = *b"cggmp21 test vectors dealer seed";
+const EXECUTION_ID: &[u8] = b"cggmp21 test vectors execution id";
+const SEED_DOMAIN: &[u8] = b"cggmp21 test vectors";
+const MESSAGE: &[u8] = b"cggmp21 test vectors message";
+
+const SHARED_PUBLIC_KEY: &str =
+    "024e3b81af9c2234cad09d679ce6035ed1392347ce64ce405f5dcd36228a25de6e";
+const SIGNATURE: &str = "83ac47e3e7214460034b8259b9af7580ab6cdfe71b9bff2ecb89976c76a2787d749c3778b57a9e29b6bc680355809eae7f4da217838839d31c112a11dbc03ead";
+
+fn key_shares(t: Option<u16>, n: u16) -> Vec<KeyShare<Secp256k1, SecurityLevel128>> {
+    let sk = Scalar::<Secp256k1>::from_be_bytes(hex::decode(SECRET_KEY).unwrap()).unwrap();
+    let sk = NonZero::from_secret_scalar(SecretScalar::new(&mut sk.clone())).unwrap();
+    let primes = cggmp21_tests::CACHED_PRIMES
+        .iter::<SecurityLevel128>()
+        .take(n.into())
+        .map(|p| p.split())
+        .collect();
+
+    let mut rng = rand_dev::DevRng::from_seed(DEALER_SEED);
+    cggmp21::trusted_dealer::builder::<Secp256k1, SecurityLevel128>(n)
+        .set_threshold(t)
+        .set_shared_secret_key(sk)
+        .set_pregenerated_primes(primes)
+        .generate_shares(&mut rng)
+        .unwrap()
+}
+
+#[test]
+fn signing_test_vector() {
+    let shares = key_shares(Some(2), 3);
+    let shared_public_key = shares[0].shared_public_key;
+    assert_eq!(
+        hex::encode(shared_public_key.to_bytes(true)),
+        SHARED_PUBLIC_KEY
+    );
+    assert_eq!(
+        Point::generator() * Scalar::from_be_bytes(hex::decode(SECRET_KEY).unwrap()).unwrap(),
+        *shared_public_key
+    );
+
+    let eid = ExecutionId::new(EXECUTION_ID);
+    let data_to_sign = DataToSign::digest::<Sha256>(MESSAGE);
+    let signers = &[0, 2];
+    let signature = round_based::sim::run_with_setup(
+        signers.iter().map(|j| &shares[usize::from(*j)]),
+        |i, party, share| async move {
+            // Randomness is fully derived from the session seed, rng isn't used
+            let mut rng = rand_dev::DevRng::new();
+            cggmp21::signing(eid, i, signers, share)
+                .set_deterministic_session(SEED_DOMAIN)
+                .sign(&mut rng, party, data_to_sign)
+                .await
+        },
+    )
+    .unwrap()
+    .expect_ok()
+    .expect_eq();
+    signature
+        .verify(&shared_public_key, &data_to_sign)
+        .expect("signature is not valid");
+
+    let mut bytes = [0u8; 64];
+    signature.normalize_s().write_to_slice(&mut bytes);
+    assert_eq!(hex::encode(bytes), SIGNATURE);
+    assert_eq!(
+        Signature::read_from_slice(&hex::decode(SIGNATURE).unwrap()),
+        Some(signature.normalize_s())
+    );
+}
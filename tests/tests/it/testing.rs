@@ -0,0 +1,64 @@
+use cggmp21::key_share::AnyKeyShare;
+use cggmp21::{
+    define_security_level, security_level::SecurityLevel128, supported_curves::Secp256k1,
+    DataToSign,
+};
+use rand_dev::DevRng;
+use sha2::Sha256;
+
+/// Dummy security level that makes simulation fast
+#[derive(Clone)]
+struct DummyLevel;
+define_security_level!(DummyLevel {
+    security_bits = 32,
+    epsilon = 64,
+    ell = 128,
+    ell_prime = 128,
+    m = 128,
+    q = (cggmp21::rug::Integer::ONE.clone() << 128) - 1,
+});
+
+#[test]
+fn simulated_keygen_works() {
+    let mut rng = DevRng::new();
+
+    for (t, n) in [(None, 2), (Some(2), 3)] {
+        let shares =
+            cggmp21::testing::simulate_keygen::<Secp256k1, DummyLevel, _>(&mut rng, t, n).unwrap();
+        assert_eq!(shares.len(), usize::from(n));
+        for (i, share) in (0u16..).zip(&shares) {
+            assert_eq!(share.core.i, i);
+            assert_eq!(share.min_signers(), t.unwrap_or(n));
+            assert_eq!(share.shared_public_key, shares[0].shared_public_key);
+        }
+    }
+}
+
+#[test]
+fn simulated_signing_works() {
+    let mut rng = DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .unwrap();
+
+    let data_to_sign = DataToSign::digest::<Sha256>(b"simulated signing");
+    let signature =
+        cggmp21::testing::simulate_signing(&mut rng, &shares[1..], data_to_sign).unwrap();
+    signature
+        .verify(&shares[0].shared_public_key, &data_to_sign)
+        .unwrap();
+}
+
+#[test]
+fn simulated_signing_reports_error() {
+    let mut rng = DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .unwrap();
+
+    // Three signers can't sign with 2-out-of-3 key
+    let data_to_sign = DataToSign::digest::<Sha256>(b"simulated signing");
+    let err = cggmp21::testing::simulate_signing(&mut rng, &shares, data_to_sign).unwrap_err();
+    assert!(err.signing_error().is_some(), "{err:?}");
+    assert_eq!(err.failed_party(), Some(0), "{err:?}");
+}
@@ -72,3 +72,613 @@ fn trusted_dealer_generates_correct_shares<E: Curve>() {
         }
     }
 }
+
+#[test]
+fn aux_info_verification_blames_malformed_party() {
+    let mut rng = DevRng::new();
+    let shares = trusted_dealer::builder::<cggmp21::supported_curves::Secp256k1, DummyLevel>(3)
+        .generate_shares(&mut rng)
+        .unwrap();
+    let aux = shares[0].aux.clone();
+    aux.verify().unwrap();
+
+    // t = 1 is not a valid ring-pedersen parameter
+    let mut tampered = aux.clone();
+    tampered.parties[1].t = cggmp21::rug::Integer::from(1);
+    assert_eq!(tampered.verify().unwrap_err().party(), 1);
+
+    // s is not coprime with N
+    let mut tampered = aux.clone();
+    tampered.parties[2].s = shares[2].aux.p.clone();
+    assert_eq!(tampered.verify().unwrap_err().party(), 2);
+
+    // N is even
+    let mut tampered = aux;
+    tampered.parties[0].N *= 2u8;
+    assert_eq!(tampered.verify().unwrap_err().party(), 0);
+}
+
+#[test]
+fn memory_footprint_accounts_for_precomputations() {
+    let mut rng = DevRng::new();
+    let n = 3;
+    let shares = trusted_dealer::builder::<cggmp21::supported_curves::Secp256k1, DummyLevel>(n)
+        .set_threshold(Some(2))
+        .generate_shares(&mut rng)
+        .unwrap();
+    let report = shares[0].memory_footprint();
+
+    assert_eq!(report.parties.len(), usize::from(n));
+    for party in &report.parties {
+        assert_eq!(party.multiexp, Default::default());
+        assert_eq!(party.crt, Default::default());
+        // 256 bits modulus with some allocation overhead
+        assert_eq!(party.modulus.serialized, 32);
+        assert!(party.modulus.in_memory >= party.modulus.serialized);
+    }
+    // Public shares are counted in compressed form
+    assert_eq!(report.public_shares.serialized, usize::from(n) * 33);
+    assert_eq!(report.secret_share.serialized, 32);
+
+    let mut share = shares[0].clone().into_inner();
+    share.aux.precompute_multiexp_tables().unwrap();
+    share.aux.precompute_crt(share.core.i).unwrap();
+    let report2 = share.memory_footprint();
+
+    let multiexp = report2
+        .parties
+        .iter()
+        .map(|p| p.multiexp.serialized)
+        .sum::<usize>();
+    assert_eq!(multiexp, share.aux.multiexp_tables_size());
+    assert!(report2.parties[0].crt.serialized > 0);
+    assert_eq!(report2.parties[1].crt, Default::default());
+    assert!(report2.total().in_memory > report.total().in_memory + multiexp);
+    assert!(report2.total().serialized > report.total().serialized + multiexp);
+}
+
+#[test]
+fn same_key_ignores_party_specific_data() {
+    type E = cggmp21::supported_curves::Secp256k1;
+    let mut rng = DevRng::new();
+    let sk = NonZero::<SecretScalar<E>>::random(&mut rng);
+
+    // Core shares: all shares of one key are of the same key, but shares of another
+    // dealing are not, even though the secret key is the same
+    let core_shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .set_shared_secret_key(sk.clone())
+        .generate_core_shares(&mut rng)
+        .unwrap();
+    let other_core_shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .set_shared_secret_key(sk)
+        .generate_core_shares(&mut rng)
+        .unwrap();
+    for a in &core_shares {
+        for b in &core_shares {
+            assert!(a.same_key(b));
+        }
+        for b in &other_core_shares {
+            assert!(!a.same_key(b));
+        }
+    }
+
+    // Full shares: aux info must be the same as well
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .generate_shares(&mut rng)
+        .unwrap();
+    for a in &shares {
+        for b in &shares {
+            assert!(a.same_key(b));
+        }
+    }
+    let mut other_aux = shares[1].clone().into_inner();
+    other_aux.aux.parties[0].s += 1;
+    assert!(!shares[0].same_key(&other_aux));
+}
+
+#[test]
+fn public_key_share_has_no_secret() {
+    type E = cggmp21::supported_curves::Secp256k1;
+    let mut rng = DevRng::new();
+
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .generate_core_shares(&mut rng)
+        .unwrap();
+
+    let public = shares[0].to_public();
+    assert!(public.same_key(&shares[1].to_public()));
+    assert_eq!(public.shared_public_key, shares[0].shared_public_key());
+    assert_eq!(public.n(), 3);
+    assert_eq!(public.min_signers(), 2);
+
+    // Serialized public share doesn't contain the secret share
+    let serialized = serde_json::to_value(&public).unwrap();
+    let fields = serialized.as_object().unwrap();
+    assert!(!fields.contains_key("x"));
+    assert!(!fields.contains_key("i"));
+    let restored: cggmp21::key_share::PublicKeyShare<E> =
+        serde_json::from_value(serialized).unwrap();
+    assert!(restored.same_key(&public));
+}
+
+#[test]
+fn commitment_polynomial_matches_public_shares() {
+    use generic_ec_zkp::polynomial::Polynomial;
+    type E = cggmp21::supported_curves::Secp256k1;
+    let mut rng = DevRng::new();
+
+    for (t, n) in [(2, 3), (3, 5), (5, 5)] {
+        let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+            .set_threshold(Some(t))
+            .generate_core_shares(&mut rng)
+            .unwrap();
+
+        let commitment = shares[0].commitment_polynomial().unwrap();
+        assert_eq!(commitment.len(), usize::from(t));
+        assert_eq!(commitment[0], *shares[0].shared_public_key);
+
+        let commitment = Polynomial::from_coefs(commitment);
+        for j in 0..n {
+            let preimage_j = shares[0].share_preimage(j).unwrap();
+            let public_share_j: Point<E> = commitment.value(&Scalar::from(preimage_j));
+            assert_eq!(public_share_j, *shares[0].public_shares[usize::from(j)]);
+        }
+    }
+
+    // Additive shares are not polynomial
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(None)
+        .generate_core_shares(&mut rng)
+        .unwrap();
+    assert!(shares[0].commitment_polynomial().is_none());
+}
+
+#[test]
+fn key_share_reports_parties_count_and_threshold() {
+    use cggmp21::key_share::AnyKeyShare;
+    type E = cggmp21::supported_curves::Secp256k1;
+    let mut rng = DevRng::new();
+
+    for (t, n) in [(None, 3), (Some(2), 3), (Some(5), 5)] {
+        let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+            .set_threshold(t)
+            .generate_shares(&mut rng)
+            .unwrap();
+        assert_eq!(shares[0].parties_count(), n);
+        assert_eq!(shares[0].threshold(), t);
+        assert_eq!(shares[0].min_signers(), t.unwrap_or(n));
+    }
+}
+
+#[test]
+fn valid_signer_sets_enumerates_all_quorums() {
+    use cggmp21::key_share::AnyKeyShare;
+    type E = cggmp21::supported_curves::Secp256k1;
+    let mut rng = DevRng::new();
+
+    for (t, n, expected_amount) in [
+        (None, 3, 1),
+        (Some(2), 3, 3),
+        (Some(3), 5, 10),
+        (Some(7), 7, 1),
+    ] {
+        let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+            .set_threshold(t)
+            .generate_core_shares(&mut rng)
+            .unwrap();
+        let t = shares[0].min_signers();
+
+        let sets = shares[0].valid_signer_sets().collect::<Vec<_>>();
+        assert_eq!(sets.len(), expected_amount, "t={t} n={n}");
+        for set in &sets {
+            assert_eq!(set.len(), usize::from(t));
+            assert!(set.windows(2).all(|w| w[0] < w[1]));
+            assert!(set.iter().all(|j| *j < n));
+        }
+        // Sets are distinct and come in lexicographic order
+        assert!(sets.windows(2).all(|w| w[0] < w[1]));
+
+        // Any of the sets can reconstruct the key
+        for set in &sets {
+            let quorum = set
+                .iter()
+                .map(|j| shares[usize::from(*j)].clone())
+                .collect::<Vec<_>>();
+            let sk = reconstruct_secret_key(&quorum).unwrap();
+            assert_eq!(Point::generator() * sk, shares[0].shared_public_key);
+        }
+    }
+}
+
+#[cfg(feature = "hd-wallet")]
+cggmp21_tests::test_suite! {
+    test: range_derived_public_keys_match_single_derivation,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+#[cfg(feature = "hd-wallet")]
+fn range_derived_public_keys_match_single_derivation<E: cggmp21_tests::CurveParams>() {
+    let mut rng = DevRng::new();
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .hd_wallet(true)
+        .generate_shares(&mut rng)
+        .unwrap();
+    let xpub = shares[0].extended_public_key().unwrap();
+
+    let range = 20..36;
+    let public_keys =
+        cggmp21::hd::derive_child_public_keys_with_algo::<E::HdAlgo, E>(&xpub, range.clone())
+            .unwrap();
+    assert_eq!(public_keys.len(), range.len());
+    for (index, public_key) in range.zip(public_keys) {
+        let expected = shares[0]
+            .derive_child_public_key::<E::HdAlgo, _>([index])
+            .unwrap()
+            .public_key;
+        assert_eq!(public_key, expected, "index {index}");
+    }
+
+    let hardened = (1 << 31) - 1..(1 << 31) + 1;
+    assert!(
+        cggmp21::hd::derive_child_public_keys_with_algo::<E::HdAlgo, E>(&xpub, hardened).is_err()
+    );
+}
+
+#[cfg(feature = "hd-wallet")]
+cggmp21_tests::test_suite! {
+    test: combined_chain_code_derives_child,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+#[cfg(feature = "hd-wallet")]
+fn combined_chain_code_derives_child<E: cggmp21_tests::CurveParams>() {
+    let mut rng = DevRng::new();
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .hd_wallet(true)
+        .generate_shares(&mut rng)
+        .unwrap();
+    let chain_code = shares[0].chain_code.unwrap();
+    let public_key = *shares[0].shared_public_key;
+
+    let xpub = chain_code.extended_public_key(public_key);
+    assert_eq!(xpub.public_key, public_key);
+    assert_eq!(&xpub.chain_code, chain_code.as_bytes());
+    assert_eq!(chain_code.to_string(), hex::encode(chain_code.as_bytes()));
+
+    let index = 7.try_into().unwrap();
+    let child = shares[0]
+        .derive_child_public_key::<E::HdAlgo, _>([index])
+        .unwrap();
+    let child_chain_code = chain_code.derive_child::<E, E::HdAlgo>(public_key, index);
+    assert_eq!(child_chain_code.into_inner(), child.chain_code);
+}
+
+cggmp21_tests::test_suite! {
+    test: signing_refuses_level_incompatible_with_curve,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn signing_refuses_level_incompatible_with_curve<E: Curve>()
+where
+    Point<E>: generic_ec::coords::HasAffineX<E>,
+{
+    use cggmp21::security_level::{is_compatible_with_curve, SecurityLevel128};
+    assert!(is_compatible_with_curve::<SecurityLevel128, E>());
+    // `DummyLevel` has ell = 128 which is smaller than the curve order
+    assert!(!is_compatible_with_curve::<DummyLevel, E>());
+
+    let mut rng = DevRng::new();
+    let shares = trusted_dealer::builder::<E, DummyLevel>(2)
+        .generate_shares(&mut rng)
+        .unwrap();
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = cggmp21::ExecutionId::new(&eid);
+    let message = cggmp21::signing::DataToSign::digest::<sha2::Sha256>(b"message");
+
+    // Signing must fail before it sends or receives anything, so we run only one party
+    let share = &shares[0];
+    let result = round_based::sim::run(1, |_, party| {
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, 0, &[0, 1], share)
+                .sign(&mut party_rng, party, message)
+                .await
+        }
+    })
+    .unwrap()
+    .into_vec()
+    .remove(0);
+
+    assert!(result.unwrap_err().is_incompatible_level_for_curve());
+}
+
+cggmp21_tests::test_suite! {
+    test: rogue_key_is_detected,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn rogue_key_is_detected<E: Curve>() {
+    use cggmp21::key_share::{IncompleteKeyShare, Validate};
+
+    let mut rng = DevRng::new();
+    for t in [None, Some(2), Some(3)] {
+        let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+            .set_threshold(t)
+            .generate_shares(&mut rng)
+            .unwrap();
+        for share in &shares {
+            let core: &IncompleteKeyShare<E> = share.as_ref();
+            core.verify_no_rogue_key().unwrap();
+        }
+    }
+
+    // Additive key where contributions of parties 1 and 2 cancel out, so party 0 alone
+    // controls the key. Such key share is consistent, but it's not secure.
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .generate_shares(&mut rng)
+        .unwrap();
+    let core: &IncompleteKeyShare<E> = shares[1].as_ref();
+    let mut rogue = core.clone().into_inner();
+    let rogue_public_key = Point::generator() * NonZero::<SecretScalar<E>>::random(&mut rng);
+    let public_share_1 = rogue.key_info.public_shares[1];
+    rogue.key_info.shared_public_key = rogue_public_key;
+    rogue.key_info.public_shares = vec![rogue_public_key, public_share_1, -public_share_1];
+    let rogue = rogue.validate().unwrap();
+
+    assert!(rogue.verify_no_rogue_key().is_err());
+}
+
+cggmp21_tests::test_suite! {
+    test: validate_all_reports_every_problem,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn validate_all_reports_every_problem<E: Curve>() {
+    use cggmp21::key_share::{IncompleteKeyShare, Validate};
+
+    let mut rng = DevRng::new();
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .generate_shares(&mut rng)
+        .unwrap();
+    let core: &IncompleteKeyShare<E> = shares[0].as_ref();
+    let valid = core.clone().into_inner().validate_all().unwrap();
+    assert_eq!(valid.public_shares, core.public_shares);
+
+    // Secret share doesn't match public share, threshold exceeds amount of parties
+    let mut corrupted = core.clone().into_inner();
+    corrupted.x = NonZero::<SecretScalar<E>>::random(&mut rng);
+    corrupted.key_info.vss_setup.as_mut().unwrap().min_signers = 4;
+    assert!(corrupted.clone().validate().is_err());
+    let Err(problems) = corrupted.validate_all() else {
+        panic!("corrupted share is valid")
+    };
+    assert_eq!(problems.len(), 2, "{problems:?}");
+
+    // Secret share doesn't match public share, public shares don't match public key
+    let mut corrupted = core.clone().into_inner();
+    corrupted.x = NonZero::<SecretScalar<E>>::random(&mut rng);
+    corrupted.key_info.shared_public_key =
+        Point::generator() * NonZero::<SecretScalar<E>>::random(&mut rng);
+    let Err(problems) = corrupted.validate_all() else {
+        panic!("corrupted share is valid")
+    };
+    assert_eq!(problems.len(), 2, "{problems:?}");
+}
+
+cggmp21_tests::test_suite! {
+    test: additive_shares_sum_up_to_secret_key,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn additive_shares_sum_up_to_secret_key<E: Curve>() {
+    use cggmp21::key_share::IncompleteKeyShare;
+
+    let mut rng = DevRng::new();
+    for (t, n, signers) in [
+        (None, 3, &[2, 0, 1][..]),
+        (Some(2), 3, &[2, 0][..]),
+        (Some(2), 3, &[0, 1, 2][..]),
+        (Some(3), 5, &[4, 1, 3][..]),
+    ] {
+        let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+            .set_threshold(t)
+            .generate_shares(&mut rng)
+            .unwrap();
+
+        let mut sk = Scalar::<E>::zero();
+        let mut pk = Point::<E>::zero();
+        for &j in signers {
+            let core: &IncompleteKeyShare<E> = shares[usize::from(j)].as_ref();
+            let x_j = core.additive_share_for(signers).unwrap();
+            let pub_x_j = core.public_additive_share_for(j, signers).unwrap();
+            assert_eq!(Point::generator() * &x_j, pub_x_j);
+            sk += x_j.as_ref();
+            pk += pub_x_j;
+        }
+        assert_eq!(Point::generator() * sk, shares[0].core.shared_public_key);
+        assert_eq!(pk, shares[0].core.shared_public_key);
+    }
+
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .generate_shares(&mut rng)
+        .unwrap();
+    let core: &IncompleteKeyShare<E> = shares[0].as_ref();
+    // Not enough signers, party is not a signer, duplicate and out of bounds signers
+    assert!(core.additive_share_for(&[0]).is_err());
+    assert!(core.additive_share_for(&[1, 2]).is_err());
+    assert!(core.additive_share_for(&[0, 0]).is_err());
+    assert!(core.additive_share_for(&[0, 3]).is_err());
+}
+
+cggmp21_tests::test_suite! {
+    test: two_party_additive_shares,
+    generics: all_curves,
+    suites: {
+        test: (),
+    }
+}
+fn two_party_additive_shares<E: Curve>() {
+    use cggmp21::key_share::IncompleteKeyShare;
+
+    let mut rng = DevRng::new();
+    for (t, n, i, j) in [(Some(2), 5, 3, 1), (Some(2), 2, 0, 1), (None, 2, 1, 0)] {
+        let shares = trusted_dealer::builder::<E, DummyLevel>(n)
+            .set_threshold(t)
+            .generate_shares(&mut rng)
+            .unwrap();
+        let core_i: &IncompleteKeyShare<E> = shares[usize::from(i)].as_ref();
+        let core_j: &IncompleteKeyShare<E> = shares[usize::from(j)].as_ref();
+
+        let share_i = core_i.two_party_additive_share(j).unwrap();
+        let share_j = core_j.two_party_additive_share(i).unwrap();
+        assert_eq!(share_i.public_share, share_j.counterparty_public_share);
+        assert_eq!(share_i.counterparty_public_share, share_j.public_share);
+        assert_eq!(Point::generator() * &share_i.x, share_i.public_share);
+        assert_eq!(
+            Point::generator() * (share_i.x.as_ref() + share_j.x.as_ref()),
+            core_i.shared_public_key
+        );
+    }
+
+    let shares = trusted_dealer::builder::<E, DummyLevel>(4)
+        .set_threshold(Some(3))
+        .generate_shares(&mut rng)
+        .unwrap();
+    let core: &IncompleteKeyShare<E> = shares[0].as_ref();
+    // Threshold is too high, counterparty is local party or out of bounds
+    assert!(core.two_party_additive_share(1).is_err());
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .generate_shares(&mut rng)
+        .unwrap();
+    let core: &IncompleteKeyShare<E> = shares[0].as_ref();
+    assert!(core.two_party_additive_share(0).is_err());
+    assert!(core.two_party_additive_share(3).is_err());
+}
+
+#[test]
+fn aux_info_exposes_public_params() {
+    let mut rng = DevRng::new();
+    let shares = trusted_dealer::builder::<cggmp21::supported_curves::Secp256k1, DummyLevel>(3)
+        .generate_shares(&mut rng)
+        .unwrap();
+    for share in &shares {
+        for j in 0..3 {
+            let params = share.aux.party_public_params(j).unwrap();
+            let aux_j = &shares[usize::from(j)].aux;
+            assert_eq!(*params.N, cggmp21::rug::Integer::from(&aux_j.p * &aux_j.q));
+            assert_eq!(params.s, &share.aux.parties[usize::from(j)].s);
+            assert_eq!(params.t, &share.aux.parties[usize::from(j)].t);
+        }
+        assert!(share.aux.party_public_params(3).is_none());
+    }
+}
+
+#[test]
+fn key_share_carries_metadata() {
+    type E = cggmp21::supported_curves::Secp256k1;
+    let mut rng = DevRng::new();
+
+    let shares = trusted_dealer::builder::<E, DummyLevel>(3)
+        .set_threshold(Some(2))
+        .generate_shares(&mut rng)
+        .unwrap();
+    assert!(shares[0].metadata().is_empty());
+
+    // Key share without metadata is serialized as before
+    let serialized = serde_json::to_value(&shares[0]).unwrap();
+    assert!(!serialized.as_object().unwrap().contains_key("metadata"));
+
+    let mut share = shares[0].clone().into_inner().with_metadata(
+        [("label".to_owned(), "treasury".to_owned())]
+            .into_iter()
+            .collect(),
+    );
+    assert_eq!(share.set_metadata_field("policy", "p-1"), None);
+    assert_eq!(
+        share.set_metadata_field("policy", "p-2"),
+        Some("p-1".to_owned())
+    );
+    let share = cggmp21::KeyShare::<E, DummyLevel>::validate(share).unwrap();
+    assert_eq!(share.metadata()["label"], "treasury");
+    assert_eq!(share.metadata()["policy"], "p-2");
+
+    // Metadata doesn't affect the key
+    assert!(share.same_key(&shares[1]));
+
+    // Metadata of validated key share can be modified in place
+    let share = share
+        .modify(|share| {
+            share.set_metadata_field("label", "cold storage");
+        })
+        .unwrap();
+    assert_eq!(share.metadata()["label"], "cold storage");
+    assert_eq!(share.metadata()["policy"], "p-2");
+
+    let serialized = serde_json::to_vec(&share).unwrap();
+    let deserialized: cggmp21::KeyShare<E, DummyLevel> =
+        serde_json::from_slice(&serialized).unwrap();
+    assert_eq!(deserialized.metadata(), share.metadata());
+}
+
+#[test]
+fn compact_key_share_expands_back() {
+    type E = cggmp21::supported_curves::Secp256k1;
+    let mut rng = DevRng::new();
+
+    for t in [None, Some(2), Some(5)] {
+        let shares = trusted_dealer::builder::<E, DummyLevel>(7)
+            .set_threshold(t)
+            .generate_core_shares(&mut rng)
+            .unwrap();
+
+        for share in &shares {
+            let compact = share.compact();
+            assert_eq!(compact.is_compressed(), t.is_some());
+            assert_eq!(compact.i(), share.i);
+            assert_eq!(compact.n(), Some(7));
+            assert_eq!(compact.shared_public_key(), share.shared_public_key);
+            for j in 0..7 {
+                assert_eq!(
+                    compact.public_share(j),
+                    Some(share.public_shares[usize::from(j)])
+                );
+            }
+            assert_eq!(compact.public_share(7), None);
+
+            // Round-trip through serialization
+            let serialized = serde_json::to_vec(&compact).unwrap();
+            let compact: cggmp21::key_share::CompactIncompleteKeyShare<E> =
+                serde_json::from_slice(&serialized).unwrap();
+
+            let expanded = compact.expand().unwrap();
+            assert_eq!(expanded.i, share.i);
+            assert_eq!(
+                AsRef::<Scalar<E>>::as_ref(&expanded.x),
+                AsRef::<Scalar<E>>::as_ref(&share.x)
+            );
+            assert_eq!(expanded.public_shares, share.public_shares);
+            assert!(expanded.same_key(share));
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! JavaScript bindings for key generation
+//!
+//! Exposes threshold key generation over secp256k1 curve to JavaScript via [`wasm_bindgen`].
+//! Instead of async API, protocol is driven step by step: transport feeds incoming messages
+//! into [`Keygen`] and sends out whatever [`Keygen::next_outgoing`] returns, so it fits
+//! callback-based transports (e.g. WebSockets) used in the browser. Messages and the resulting
+//! key share are serialized as JSON.
+//!
+//! Randomness is sourced from `crypto.getRandomValues`.
+//!
+//! Signing, aux info generation and key refresh are not exposed as they rely on GMP which
+//! doesn't compile to `wasm32-unknown-unknown`.
+//!
+//! ## Example
+//! ```js
+//! const keygen = new Keygen(eid, i, t, n);
+//! const flush = () => {
+//!     let msg;
+//!     while ((msg = keygen.next_outgoing()) !== undefined) {
+//!         send(msg.recipient, msg.msg);
+//!     }
+//!     if (keygen.is_finished()) {
+//!         onKeyShare(keygen.output());
+//!     }
+//! };
+//! onMessage((sender, isBroadcast, msg) => {
+//!     keygen.feed_incoming(sender, isBroadcast, msg);
+//!     flush();
+//! });
+//! flush();
+//! ```
+
+use std::collections::VecDeque;
+
+use cggmp21_keygen::{security_level::SecurityLevel128, ExecutionId, KeygenError};
+use generic_ec::curves::Secp256k1;
+use key_share::CoreKeyShare;
+use rand_core::OsRng;
+use round_based::{
+    state_machine::{ProceedResult, StateMachine},
+    Incoming, MessageDestination, MessageType,
+};
+use sha2::Sha256;
+use wasm_bindgen::prelude::*;
+
+type Msg = cggmp21_keygen::ThresholdMsg<Secp256k1, SecurityLevel128, Sha256>;
+type Output = Result<CoreKeyShare<Secp256k1>, KeygenError>;
+
+/// Threshold key generation
+#[wasm_bindgen]
+pub struct Keygen {
+    state_machine: Box<dyn StateMachine<Output = Output, Msg = Msg>>,
+    incomings: VecDeque<Incoming<Msg>>,
+    next_msg_id: round_based::MsgId,
+    awaits_msg: bool,
+    output: Option<Output>,
+}
+
+/// Message that needs to be sent to other parties
+#[wasm_bindgen(getter_with_clone)]
+pub struct OutgoingMessage {
+    /// Index of recipient, or `undefined` if message needs to be broadcasted
+    pub recipient: Option<u16>,
+    /// Message serialized as JSON
+    pub msg: String,
+}
+
+#[wasm_bindgen]
+impl Keygen {
+    /// Starts key generation
+    ///
+    /// `eid` is [execution ID](ExecutionId), `i` is index of local party, `t` is threshold and
+    /// `n` is amount of parties.
+    #[wasm_bindgen(constructor)]
+    pub fn new(eid: Vec<u8>, i: u16, t: u16, n: u16) -> Keygen {
+        let state_machine = round_based::state_machine::wrap_protocol(move |party| async move {
+            let eid = ExecutionId::new(&eid);
+            cggmp21_keygen::keygen::<Secp256k1>(eid, i, n)
+                .set_threshold(t)
+                .start(&mut OsRng, party)
+                .await
+        });
+        Self {
+            state_machine: Box::new(state_machine),
+            incomings: VecDeque::new(),
+            next_msg_id: 0,
+            awaits_msg: false,
+            output: None,
+        }
+    }
+
+    /// Saves message received from party `sender`
+    ///
+    /// Message is processed on the next call to [`next_outgoing`](Self::next_outgoing)
+    pub fn feed_incoming(
+        &mut self,
+        sender: u16,
+        is_broadcast: bool,
+        msg: &str,
+    ) -> Result<(), JsError> {
+        let msg: Msg = serde_json::from_str(msg)?;
+        self.incomings.push_back(Incoming {
+            id: self.next_msg_id,
+            sender,
+            msg_type: if is_broadcast {
+                MessageType::Broadcast
+            } else {
+                MessageType::P2P
+            },
+            msg,
+        });
+        self.next_msg_id += 1;
+        Ok(())
+    }
+
+    /// Proceeds the protocol until it has a message to send
+    ///
+    /// Returns `undefined` when protocol can't proceed until more messages are received,
+    /// or when it's finished.
+    pub fn next_outgoing(&mut self) -> Result<Option<OutgoingMessage>, JsError> {
+        if self.output.is_some() {
+            return Ok(None);
+        }
+        loop {
+            if self.awaits_msg {
+                let Some(incoming) = self.incomings.pop_front() else {
+                    return Ok(None);
+                };
+                self.state_machine
+                    .received_msg(incoming)
+                    .map_err(|_| JsError::new("state machine didn't accept the message"))?;
+                self.awaits_msg = false;
+            }
+            match self.state_machine.proceed() {
+                ProceedResult::SendMsg(outgoing) => {
+                    return Ok(Some(OutgoingMessage {
+                        recipient: match outgoing.recipient {
+                            MessageDestination::AllParties => None,
+                            MessageDestination::OneParty(j) => Some(j),
+                        },
+                        msg: serde_json::to_string(&outgoing.msg)?,
+                    }))
+                }
+                ProceedResult::NeedsOneMoreMessage => self.awaits_msg = true,
+                ProceedResult::Output(output) => {
+                    self.output = Some(output);
+                    return Ok(None);
+                }
+                ProceedResult::Yielded => {}
+                ProceedResult::Error(err) => return Err(JsError::new(&err.to_string())),
+            }
+        }
+    }
+
+    /// Indicates whether protocol is finished
+    pub fn is_finished(&self) -> bool {
+        self.output.is_some()
+    }
+
+    /// Returns key share serialized as JSON
+    ///
+    /// Returns error if protocol is not finished yet or if it was aborted
+    pub fn output(&self) -> Result<String, JsError> {
+        match &self.output {
+            Some(Ok(key_share)) => Ok(serde_json::to_string(key_share)?),
+            Some(Err(err)) => Err(JsError::new(&err.to_string())),
+            None => Err(JsError::new("protocol is not finished")),
+        }
+    }
+}